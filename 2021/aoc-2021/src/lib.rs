@@ -0,0 +1,91 @@
+//! The solutions of 2021, compiled from the sources of the day crates, such that every host (the runner, the C
+//! interface and the WebAssembly bindings) registers the same solvers.
+
+#![allow(dead_code)]
+
+use aoc_common::Registry;
+
+#[path = "../../day01/src/main.rs"]
+mod day01;
+#[path = "../../day02/src/main.rs"]
+mod day02;
+#[path = "../../day03/src/main.rs"]
+mod day03;
+#[path = "../../day04/src/main.rs"]
+mod day04;
+#[path = "../../day05/src/main.rs"]
+mod day05;
+#[path = "../../day06/src/main.rs"]
+mod day06;
+#[path = "../../day07/src/main.rs"]
+mod day07;
+#[path = "../../day08/src/main.rs"]
+mod day08;
+#[path = "../../day09/src/main.rs"]
+mod day09;
+#[path = "../../day10/src/main.rs"]
+mod day10;
+#[path = "../../day11/src/main.rs"]
+mod day11;
+#[path = "../../day12/src/main.rs"]
+mod day12;
+#[path = "../../day13/src/main.rs"]
+mod day13;
+#[path = "../../day14/src/main.rs"]
+mod day14;
+#[path = "../../day15/src/main.rs"]
+mod day15;
+#[path = "../../day16/src/main.rs"]
+mod day16;
+#[path = "../../day17/src/main.rs"]
+mod day17;
+#[path = "../../day18/src/main.rs"]
+mod day18;
+#[path = "../../day19/src/main.rs"]
+mod day19;
+#[path = "../../day20/src/main.rs"]
+mod day20;
+#[path = "../../day21/src/main.rs"]
+mod day21;
+#[path = "../../day22/src/main.rs"]
+mod day22;
+#[path = "../../day23/src/main.rs"]
+mod day23;
+#[path = "../../day24/src/main.rs"]
+mod day24;
+#[path = "../../day25/src/main.rs"]
+mod day25;
+
+pub mod submarine;
+
+/// The year of the puzzles.
+pub const YEAR: u16 = 2021;
+
+/// Registers the solvers of all days of the year.
+pub fn register_2021(registry: &mut Registry) {
+    registry.register::<day01::Day01>(YEAR, 1);
+    registry.register::<day02::Day02>(YEAR, 2);
+    registry.register::<day03::Day03>(YEAR, 3);
+    registry.register::<day04::Day04>(YEAR, 4);
+    registry.register::<day05::Day05>(YEAR, 5);
+    registry.register::<day06::Day06>(YEAR, 6);
+    registry.register::<day07::Day07>(YEAR, 7);
+    registry.register::<day08::Day08>(YEAR, 8);
+    registry.register::<day09::Day09>(YEAR, 9);
+    registry.register::<day10::Day10>(YEAR, 10);
+    registry.register::<day11::Day11>(YEAR, 11);
+    registry.register::<day12::Day12>(YEAR, 12);
+    registry.register::<day13::Day13>(YEAR, 13);
+    registry.register::<day14::Day14>(YEAR, 14);
+    registry.register::<day15::Day15>(YEAR, 15);
+    registry.register::<day16::Day16>(YEAR, 16);
+    registry.register::<day17::Day17>(YEAR, 17);
+    registry.register::<day18::Day18>(YEAR, 18);
+    registry.register::<day19::Day19>(YEAR, 19);
+    registry.register::<day20::Day20>(YEAR, 20);
+    registry.register::<day21::Day21>(YEAR, 21);
+    registry.register::<day22::Day22>(YEAR, 22);
+    registry.register::<day23::Day23>(YEAR, 23);
+    registry.register::<day24::Day24>(YEAR, 24);
+    registry.register::<day25::Day25>(YEAR, 25);
+}
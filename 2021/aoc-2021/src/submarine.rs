@@ -0,0 +1,122 @@
+//! Combines the depth measurements of the sea floor (day 1) and the course of the submarine (day 2) into a
+//! single picture of the trip, drawn as an SVG image.
+//!
+//! Neither puzzle says how far apart the measurements of the sea floor are, so we assume one measurement per
+//! unit of horizontal distance. This happens to line up well: there are 2000 measurements, and the course
+//! travels about as far. The course is the one of part 1, as the aimed course of part 2 ends up hundreds of
+//! times deeper than the sea floor.
+
+use crate::{day01, day02};
+use aoc_common::Point;
+use std::io::Write;
+
+/// The width of the image in pixels.
+const WIDTH: f64 = 1000.0;
+
+/// The height of the image in pixels, excluding the caption.
+const HEIGHT: f64 = 400.0;
+
+/// The height of the caption below the image in pixels.
+const CAPTION_HEIGHT: f64 = 24.0;
+
+/// The depths of the sea floor and the positions of the submarine along its course.
+pub struct Trip {
+    /// The depth of the sea floor at every unit of horizontal distance.
+    pub sea_floor: Vec<usize>,
+
+    /// The positions of the submarine after every move, starting at the surface.
+    pub course: Vec<Point>,
+}
+
+impl Trip {
+    /// Reads the depth measurements of day 1 and the course of day 2 from the provided files.
+    pub fn read(depths_file: &str, course_file: &str) -> aoc_common::Result<Self> {
+        let sea_floor = day01::parse_input(depths_file)?;
+        let moves = day02::parse_input(course_file)?;
+
+        let mut course = Vec::with_capacity(moves.len() + 1);
+        course.push(Point(0, 0));
+        for step in moves.iter() {
+            course.push(step.traverse1(course[course.len() - 1]));
+        }
+
+        Ok(Self { sea_floor, course })
+    }
+
+    /// Gets the number of moves that end at or below the sea floor.
+    pub fn groundings(&self) -> usize {
+        self.course
+            .iter()
+            .filter(|p| self.sea_floor.get(p.0).is_some_and(|&floor| p.1 >= floor))
+            .count()
+    }
+
+    /// Draws the sea floor and the course of the submarine as an SVG image, with the surface at the top.
+    pub fn write_svg(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let max_x = self
+            .course
+            .iter()
+            .map(|p| p.0)
+            .chain([self.sea_floor.len()])
+            .max()
+            .unwrap_or(0)
+            .max(1) as f64;
+        let max_depth = self
+            .course
+            .iter()
+            .map(|p| p.1)
+            .chain(self.sea_floor.iter().copied())
+            .max()
+            .unwrap_or(0)
+            .max(1) as f64;
+
+        let x = |x: usize| x as f64 / max_x * WIDTH;
+        let y = |depth: usize| depth as f64 / max_depth * HEIGHT;
+
+        writeln!(
+            writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"14\">",
+            WIDTH,
+            HEIGHT + CAPTION_HEIGHT
+        )?;
+        writeln!(
+            writer,
+            "<rect width=\"{}\" height=\"{}\" fill=\"#0b3d5c\"/>",
+            WIDTH, HEIGHT
+        )?;
+
+        // The sea floor, closed along the bottom of the image.
+        write!(writer, "<polygon fill=\"#c2a36b\" points=\"0,{}", HEIGHT)?;
+        for (index, &depth) in self.sea_floor.iter().enumerate() {
+            write!(writer, " {:.1},{:.1}", x(index), y(depth))?;
+        }
+        writeln!(writer, " {:.1},{}\"/>", x(self.sea_floor.len()), HEIGHT)?;
+
+        write!(
+            writer,
+            "<polyline fill=\"none\" stroke=\"#ffd400\" stroke-width=\"2\" points=\""
+        )?;
+        for (index, position) in self.course.iter().enumerate() {
+            if index > 0 {
+                write!(writer, " ")?;
+            }
+            write!(writer, "{:.1},{:.1}", x(position.0), y(position.1))?;
+        }
+        writeln!(writer, "\"/>")?;
+
+        let end = self.course[self.course.len() - 1];
+        writeln!(
+            writer,
+            "<text x=\"4\" y=\"{}\">{} depth measurements, {} moves, ending {} units ahead at depth {}, touching the sea floor {} times</text>",
+            HEIGHT + CAPTION_HEIGHT - 6.0,
+            self.sea_floor.len(),
+            self.course.len() - 1,
+            end.0,
+            end.1,
+            self.groundings()
+        )?;
+        writeln!(writer, "</svg>")?;
+
+        writer.flush()
+    }
+}
@@ -0,0 +1,37 @@
+/* C declarations of the aoc-ffi library, see aoc-ffi/src/lib.rs for the documentation. */
+
+#ifndef AOC_H
+#define AOC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* There is no solver for the requested day. */
+#define AOC_NO_SOLVER (-1)
+
+/* The input is not valid UTF-8, could not be parsed, or the part is not 1 or 2. */
+#define AOC_INVALID_INPUT (-2)
+
+/* The solver panicked. */
+#define AOC_PANICKED (-3)
+
+/*
+ * Solves one part (1 or 2) of the puzzle of the provided day, for the len bytes of puzzle input text at
+ * input_ptr. The answer is written to out_buf as a zero terminated string, truncated to out_len bytes
+ * (including the terminator) if needed.
+ *
+ * Returns the length of the full answer in bytes, or a negative error code, in which case a description
+ * of the error is written to out_buf instead.
+ */
+ptrdiff_t aoc_solve(uint16_t year, uint8_t day, uint8_t part, const uint8_t *input_ptr, size_t len,
+                    char *out_buf, size_t out_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
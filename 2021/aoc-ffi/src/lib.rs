@@ -0,0 +1,99 @@
+//! A C interface to the solvers of all days, for calling them from other languages and benchmark harnesses.
+//! The matching declarations are in `include/aoc.h`.
+
+use aoc_common::Registry;
+
+/// Returned by [`aoc_solve`] if there is no solver for the requested day.
+pub const AOC_NO_SOLVER: isize = -1;
+
+/// Returned by [`aoc_solve`] if the input is not valid UTF-8, could not be parsed, or the part is not 1 or 2.
+pub const AOC_INVALID_INPUT: isize = -2;
+
+/// Returned by [`aoc_solve`] if the solver panicked.
+pub const AOC_PANICKED: isize = -3;
+
+/// Builds a registry containing the solvers of all days.
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    aoc_2021::register_2021(&mut registry);
+    registry
+}
+
+/// Solves one part of a puzzle, and returns the answer or an error code along with a message.
+fn solve(year: u16, day: u8, part: u8, input: &[u8]) -> Result<String, (isize, String)> {
+    let registry = registry();
+    let registration = registry.get(year, day).ok_or_else(|| {
+        (
+            AOC_NO_SOLVER,
+            format!("No solver registered for {} day {}.", year, day),
+        )
+    })?;
+
+    let input = std::str::from_utf8(input)
+        .map_err(|error| (AOC_INVALID_INPUT, format!("Input is not UTF-8: {}.", error)))?;
+
+    (registration.solve)(input, part)
+        .map(|answer| answer.to_string())
+        .map_err(|error| (AOC_INVALID_INPUT, error.to_string()))
+}
+
+/// Copies as much of the provided text as fits into the buffer, followed by a terminating zero.
+///
+/// # Safety
+///
+/// `buf` must be null or point to at least `buf_len` writable bytes.
+unsafe fn write_c_string(text: &str, buf: *mut u8, buf_len: usize) {
+    if buf.is_null() || buf_len == 0 {
+        return;
+    }
+
+    let count = text.len().min(buf_len - 1);
+    std::ptr::copy_nonoverlapping(text.as_ptr(), buf, count);
+    *buf.add(count) = 0;
+}
+
+/// Solves one part (1 or 2) of the puzzle of the provided day, for the `len` bytes of puzzle input text at
+/// `input_ptr`. The answer is written to `out_buf` as a zero terminated string, truncated to `out_len` bytes
+/// (including the terminator) if needed.
+///
+/// Returns the length of the full answer in bytes, which may exceed `out_len - 1` if the answer was
+/// truncated. On failure, a negative error code ([`AOC_NO_SOLVER`], [`AOC_INVALID_INPUT`] or
+/// [`AOC_PANICKED`]) is returned and a description of the error is written to `out_buf` instead.
+///
+/// # Safety
+///
+/// `input_ptr` must point to at least `len` readable bytes (or may be null if `len` is zero), and `out_buf`
+/// must be null or point to at least `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    year: u16,
+    day: u8,
+    part: u8,
+    input_ptr: *const u8,
+    len: usize,
+    out_buf: *mut u8,
+    out_len: usize,
+) -> isize {
+    let input = if len == 0 {
+        &[][..]
+    } else {
+        std::slice::from_raw_parts(input_ptr, len)
+    };
+
+    // Unwinding into the caller is undefined behavior, so a panicking solver must not escape.
+    let result = match std::panic::catch_unwind(|| solve(year, day, part, input)) {
+        Ok(result) => result,
+        Err(_) => Err((AOC_PANICKED, "The solver panicked.".to_string())),
+    };
+
+    match result {
+        Ok(answer) => {
+            write_c_string(&answer, out_buf, out_len);
+            answer.len() as isize
+        }
+        Err((code, message)) => {
+            write_c_string(&message, out_buf, out_len);
+            code
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! WebAssembly bindings to the solvers of all days, for embedding them in a browser page. Build with
+//! `wasm-pack build aoc-wasm --target web` and call the exported functions from JavaScript:
+//!
+//! ```js
+//! import init, { solve } from "./pkg/aoc_wasm.js";
+//! await init();
+//! console.log(solve(2021, 1, 1, "199\n200\n208\n"));
+//! ```
+
+use aoc_common::Registry;
+use wasm_bindgen::prelude::*;
+
+/// Builds a registry containing the solvers of all days.
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    aoc_2021::register_2021(&mut registry);
+    registry
+}
+
+/// Solves one part (1 or 2) of the puzzle of the provided day for the provided input text, and returns the
+/// answer. Throws an error if there is no solver for the day, or the input could not be parsed.
+#[wasm_bindgen]
+pub fn solve(year: u16, day: u8, part: u8, input: &str) -> Result<String, JsError> {
+    let registry = registry();
+    let registration = registry
+        .get(year, day)
+        .ok_or_else(|| JsError::new(&format!("No solver registered for {} day {}.", year, day)))?;
+
+    (registration.solve)(input, part)
+        .map(|answer| answer.to_string())
+        .map_err(|error| JsError::new(&error.to_string()))
+}
+
+/// Lists the days that have a solver, as `year-day` pairs (e.g. `2021-1`).
+#[wasm_bindgen]
+pub fn days() -> Vec<JsValue> {
+    registry()
+        .iter()
+        .map(|x| JsValue::from_str(&format!("{}-{}", x.year, x.day)))
+        .collect()
+}
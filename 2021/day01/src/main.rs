@@ -1,43 +1,252 @@
-use std::{fs::File, io::{BufReader, BufRead}, time::Instant};
+use aoc_common::{input_path, parse_lines, Answer, Budget, DayReport, Isa, JsonReport, Solver};
+use std::{fs::File, io::{BufReader, BufRead}, time::{Duration, Instant}};
 
 
-fn part1(lines: &Vec<usize>) -> usize {
-    lines.iter()
-        .skip(1)
-        .enumerate()
-        .map(|(i, line)| if lines[i] < *line { 1 } else { 0 })
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Vec<usize>> {
+    parse_lines(reader, |line| line.parse::<usize>()
+        .map_err(|_| format!("Expected a depth, but found {}.", line)))
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Vec<usize>> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+pub fn part1(lines: &[usize]) -> usize {
+    count_gap_increases(lines, 1)
+}
+
+/// Two consecutive windows of three depths share two of them, so the later window has the larger sum exactly
+/// when its last depth is larger than the first depth of the earlier window.
+pub fn part2(lines: &[usize]) -> usize {
+    count_gap_increases(lines, 3)
+}
+
+/// Counts how often a depth is larger than the depth `gap` measurements before it, using the widest vectors
+/// the processor supports.
+pub fn count_gap_increases(depths: &[usize], gap: usize) -> usize {
+    match aoc_common::isa() {
+        #[cfg(target_arch = "x86_64")]
+        // Safety: AVX2 is only selected if the processor supports it.
+        Isa::Avx2 => unsafe { count_gap_increases_avx2(depths, gap) },
+        _ => count_gap_increases_scalar(depths, gap),
+    }
+}
+
+#[inline(always)]
+fn count_gap_increases_scalar(depths: &[usize], gap: usize) -> usize {
+    depths.iter()
+        .zip(depths.get(gap..).unwrap_or(&[]))
+        .map(|(previous, current)| (previous < current) as usize)
         .sum()
 }
 
-fn part2(lines: &Vec<usize>) -> usize {
-    let sums: Vec<usize> = lines.iter()
-        .skip(2)
-        .enumerate()
-        .map(|(i, line)| lines[i] + lines[i + 1] + *line)
-        .collect();
+/// The same loop as [`count_gap_increases_scalar`], compiled with AVX2 such that it compares 4 depths at once.
+///
+/// # Safety
+///
+/// The processor must support AVX2.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn count_gap_increases_avx2(depths: &[usize], gap: usize) -> usize {
+    count_gap_increases_scalar(depths, gap)
+}
+
+/// Counts how often the sum of a window of `window` depths is larger than the sum of the window
+/// `stride` depths before it. Only every `decimation`-th window is compared to its predecessor,
+/// starting with the first one. Part 1 is a window of 1, part 2 a window of 3, both with a stride
+/// and decimation of 1.
+pub fn count_increases(depths: &[usize], window: usize, stride: usize, decimation: usize) -> usize {
+    assert!(
+        window > 0 && stride > 0 && decimation > 0,
+        "Window, stride and decimation must be positive."
+    );
+
+    if depths.len() < window {
+        return 0;
+    }
+
+    // Compute all window sums with a rolling sum.
+    let mut sums = Vec::with_capacity(depths.len() - window + 1);
+    let mut sum: usize = depths[..window].iter().sum();
+    sums.push(sum);
+    for i in window..depths.len() {
+        sum = sum + depths[i] - depths[i - window];
+        sums.push(sum);
+    }
 
     sums.iter()
-        .skip(1)
-        .enumerate()
-        .map(|(i, line)| if sums[i] < *line { 1 } else { 0 })
-        .sum()
+        .skip(stride)
+        .step_by(decimation)
+        .zip(sums.iter().step_by(decimation))
+        .filter(|(current, previous)| current > previous)
+        .count()
+}
+
+/// Computes the same result as [`count_increases`], but sums every window from scratch.
+pub fn count_increases_naive(
+    depths: &[usize],
+    window: usize,
+    stride: usize,
+    decimation: usize,
+) -> usize {
+    let window_sum = |start: usize| depths[start..start + window].iter().sum::<usize>();
+
+    let mut count = 0;
+    let mut start = 0;
+    while start + stride + window <= depths.len() {
+        if window_sum(start + stride) > window_sum(start) {
+            count += 1;
+        }
+        start += decimation;
+    }
+    count
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day01;
+
+impl Solver for Day01 {
+    type Input = Vec<usize>;
+
+    const BUDGET: Budget = Budget {
+        parse: Some(Duration::from_millis(1)),
+        part1: Some(Duration::from_micros(20)),
+        part2: Some(Duration::from_micros(30)),
+    };
+
+    fn parse(file: &str) -> aoc_common::Result<Vec<usize>> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Vec<usize>> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Vec<usize>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Vec<usize>) -> Answer {
+        part2(input).into()
+    }
 }
 
 fn main() -> std::io::Result<()> {
-    let file = File::open("input.txt")?;
-    let lines: Vec<usize> = BufReader::new(file).lines()
-        .map(|x| x.unwrap().parse::<usize>().unwrap())
-        .collect();
-    
-    let now = Instant::now();
-    let result1 = part1(&lines);
-    let elapsed1 = now.elapsed();
-
-    let now = Instant::now();
-    let result2 = part2(&lines);
-    let elapsed2 = now.elapsed();
-
-    println!("{} (time: {})", result1, elapsed1.as_nanos());
-    println!("{} (time: {})", result2, elapsed2.as_nanos());
+    let mut window = None;
+    let mut stride = 1;
+    let mut decimation = 1;
+    let mut json = false;
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut number = |name: &str| {
+            args.next()
+                .and_then(|x| x.parse::<usize>().ok())
+                .unwrap_or_else(|| panic!("Expected a number after {}.", name))
+        };
+
+        match arg.as_str() {
+            "--window" => window = Some(number("--window")),
+            "--stride" => stride = number("--stride"),
+            "--decimate" => decimation = number("--decimate"),
+            "--format" => json = match args.next().as_deref() {
+                Some("text") => false,
+                Some("json") => true,
+                _ => panic!("Expected text or json after --format."),
+            },
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    let file = file.unwrap_or_else(|| input_path("input.txt"));
+    let lines = parse_input(&file)?;
+
+    // Querying the processor is slow the first time, which should not count towards part 1.
+    aoc_common::isa();
+    let increases = window.map(|window| count_increases(&lines, window, stride, decimation));
+
+    if json {
+        // The same shape as the runner's JSON output, with the window statistics in the analysis field.
+        let mut analysis = String::from("{\"window\":");
+        match (window, increases) {
+            (Some(window), Some(increases)) => analysis += &format!(
+                "{{\"size\":{},\"stride\":{},\"decimation\":{},\"increases\":{}}}",
+                window, stride, decimation, increases
+            ),
+            _ => analysis += "null",
+        }
+        analysis += "}";
+
+        let report = JsonReport {
+            days: vec![DayReport {
+                year: 2021,
+                day: 1,
+                run: aoc_common::run::<Day01>(&file)?,
+                analysis: Some(analysis),
+            }],
+            ..JsonReport::default()
+        };
+        report.write_json(&mut std::io::stdout().lock())?;
+    } else {
+        let now = Instant::now();
+        let result1 = part1(&lines);
+        let elapsed1 = now.elapsed();
+
+        let now = Instant::now();
+        let result2 = part2(&lines);
+        let elapsed2 = now.elapsed();
+
+        println!("{} (time: {})", result1, elapsed1.as_nanos());
+        println!("{} (time: {})", result2, elapsed2.as_nanos());
+
+        if let Some(increases) = increases {
+            println!(
+                "Increases (window {}, stride {}, every {} windows): {}",
+                window.unwrap(),
+                stride,
+                decimation,
+                increases
+            );
+        }
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compares [`count_increases`] against [`count_increases_naive`] for small windows, strides and
+    /// decimations.
+    fn check_windows(depths: &[usize]) {
+        for window in 1..=5 {
+            for stride in 1..=5 {
+                for decimation in 1..=3 {
+                    assert_eq!(
+                        count_increases(depths, window, stride, decimation),
+                        count_increases_naive(depths, window, stride, decimation),
+                        "window {}, stride {}, every {} windows", window, stride, decimation
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rolling_sums_agree_with_naive_sums_on_sample() {
+        check_windows(&parse_reader(include_str!("../input2.txt").as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn rolling_sums_agree_with_naive_sums_on_input() {
+        check_windows(&parse_reader(include_str!("../input.txt").as_bytes()).unwrap());
+    }
+}
+
+// parse (real)            time:   [107.18 us 109.12 us 111.62 us]
+// part 1 (real)           time:   [1.8354 us 1.9028 us 1.9654 us]
+// part 2 (real)           time:   [2.6745 us 2.7816 us 2.8805 us]
@@ -1,3 +1,4 @@
+use aoc_common::{input_path, parse_lines, Answer, Error, Explain, Narrator, Rng, Solver};
 use std::{fs::File, io::{BufReader, BufRead}, time::Instant};
 
 
@@ -5,7 +6,55 @@ const BIT_LENGTH: usize = 12;
 const BIT_MASK: usize = (1 << BIT_LENGTH) - 1;
 
 
-fn part1(input: &Vec<u16>) -> usize {
+/// Describes a single filtering decision made while applying a bit criteria.
+pub struct FilterStep {
+    /// The index of the bit that was considered.
+    pub bit_index: usize,
+
+    /// The number of remaining values with a 0 at the bit index.
+    pub zeroes: usize,
+
+    /// The number of remaining values with a 1 at the bit index.
+    pub ones: usize,
+
+    /// The bit value that values needed to have in order to be kept.
+    pub kept_bit: u8,
+
+    /// The number of values that were kept.
+    pub kept: usize,
+}
+
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Vec<u16>> {
+    let input = parse_lines(reader, |line| u16::from_str_radix(line, 2)
+        .map_err(|_| format!("Expected a binary number, but found {}.", line)))?;
+
+    // The ratings are read from the last remaining number, so there has to be at least one.
+    if input.is_empty() {
+        return Err(Error::at_line(1, "Expected a binary number."));
+    }
+
+    Ok(input)
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Vec<u16>> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+
+/// Generates a pseudo-random diagnostic report with the provided number of values, determined by
+/// the provided seed.
+pub fn generate(count: usize, seed: u64) -> Vec<u16> {
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|_| (rng.next_usize() & BIT_MASK) as u16)
+        .collect()
+}
+
+
+pub fn part1(input: &[u16]) -> usize {
     let mut gamma: usize = 0;
 
     for i in 0..BIT_LENGTH {
@@ -31,53 +80,128 @@ fn part1(input: &Vec<u16>) -> usize {
 }
 
 
-fn part2(input: &Vec<u16>) -> usize {
-    let oxygen = do_filter(&input, |a, b| a > b);
-    let co2 = do_filter(&input, |a, b| a <= b);
+pub fn part2(input: &[u16]) -> usize {
+    let oxygen = do_filter(input, OXYGEN_CRITERIA, None);
+    let co2 = do_filter(input, CO2_CRITERIA, None);
 
     oxygen * co2
 }
 
 
-fn do_filter(input: &Vec<u16>, criteria: fn(usize, usize) -> bool) -> usize {
-    let mut working_set = input.clone();
-    let mut set0 = Vec::with_capacity(working_set.len());
-    let mut set1 = Vec::with_capacity(working_set.len());
+/// Keeps the values with the most common bit, preferring ones on equal counts.
+pub const OXYGEN_CRITERIA: fn(usize, usize) -> bool = |a, b| a > b;
+
+/// Keeps the values with the least common bit, preferring zeroes on equal counts.
+pub const CO2_CRITERIA: fn(usize, usize) -> bool = |a, b| a <= b;
+
+
+/// Filters the values using the bit criteria. The criteria receives the number of zeroes and ones
+/// at the current bit index, and returns `true` if the values with a zero should be kept. If a
+/// trace is provided, every filtering decision is recorded in it.
+///
+/// Rather than copying values around, the remaining values are tracked as indices into the input.
+/// This list of indices is filtered in place, and is the only buffer that is allocated.
+pub fn do_filter(
+    input: &[u16],
+    criteria: fn(usize, usize) -> bool,
+    mut trace: Option<&mut Vec<FilterStep>>,
+) -> usize {
+    let mut remaining: Vec<usize> = (0..input.len()).collect();
 
     for i in (0..BIT_LENGTH).rev() {
-        if working_set.len() == 1 {
+        if remaining.len() == 1 {
             break;
         }
 
-        for &x in &working_set {
-            if x & (1 << i) == 0 {
-                set0.push(x);
-            } else {
-                set1.push(x);
-            }
+        let zeroes = remaining
+            .iter()
+            .filter(|&&index| input[index] & (1 << i) == 0)
+            .count();
+        let ones = remaining.len() - zeroes;
+
+        let keep_zeroes = criteria(zeroes, ones);
+        remaining.retain(|&index| (input[index] & (1 << i) == 0) == keep_zeroes);
+
+        if let Some(trace) = trace.as_mut() {
+            trace.push(FilterStep {
+                bit_index: i,
+                zeroes,
+                ones,
+                kept_bit: if keep_zeroes { 0 } else { 1 },
+                kept: remaining.len(),
+            });
         }
+    }
+
+    input[remaining[0]] as usize
+}
 
-        working_set.clear();
-        working_set.extend(if criteria(set0.len(), set1.len()) {
-            &set0
-        } else {
-            &set1
-        });
 
-        set0.clear();
-        set1.clear();
+/// Describes every filtering decision made while determining the ratings of part 2.
+pub fn explain(input: &[u16], sink: &mut dyn Explain) -> std::io::Result<()> {
+    let ratings = [
+        ("Oxygen generator", OXYGEN_CRITERIA),
+        ("CO2 scrubber", CO2_CRITERIA),
+    ];
+
+    for (name, criteria) in ratings {
+        let mut trace = Vec::new();
+        let rating = do_filter(input, criteria, Some(&mut trace));
+
+        aoc_common::explain!(sink, "{} rating:", name)?;
+        for step in trace {
+            aoc_common::explain!(
+                sink,
+                "  bit {:>2}: {:>4} zeroes, {:>4} ones -> keep {}s ({} remaining)",
+                step.bit_index, step.zeroes, step.ones, step.kept_bit, step.kept
+            )?;
+        }
+        aoc_common::explain!(sink, "  result: {:0width$b} ({})", rating, rating, width = BIT_LENGTH)?;
     }
 
-    working_set[0] as usize
+    Ok(())
 }
 
 
+/// The solution of this day, as it is registered with the runner.
+pub struct Day03;
+
+impl Solver for Day03 {
+    type Input = Vec<u16>;
+
+    fn parse(file: &str) -> aoc_common::Result<Vec<u16>> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Vec<u16>> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Vec<u16>) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Vec<u16>) -> Answer {
+        part2(input).into()
+    }
+
+    fn explain(input: &Vec<u16>, sink: &mut dyn Explain) -> std::io::Result<()> {
+        explain(input, sink)
+    }
+}
+
 fn main() -> std::io::Result<()> {
-    let file = File::open("input.txt")?;
-    let input: Vec<u16> = BufReader::new(file)
-        .lines()
-        .map(|x| u16::from_str_radix(x.unwrap().as_str(), 2).unwrap())
-        .collect();
+    let mut explain = false;
+    let mut file = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--explain" => explain = true,
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
     
     let now = Instant::now();
     let result1 = part1(&input);
@@ -89,8 +213,17 @@ fn main() -> std::io::Result<()> {
 
     println!("Part1: {} (time: {})", result1, elapsed1.as_nanos());
     println!("Part2: {} (time: {})", result2, elapsed2.as_nanos());
+
+    if explain {
+        self::explain(&input, &mut Narrator::new(std::io::stdout().lock()))?;
+    }
+
     Ok(())
 }
 
 // Part1: 2035764 (time: 2600)
-// Part2: 2817661 (time: 17500)
\ No newline at end of file
+// Part2: 2817661 (time: 17500)
+//
+// Benchmarked (filtering index lists in place):
+// generated 10M values/part 2
+//                         time:   [343.32 ms 346.40 ms 349.54 ms]
\ No newline at end of file
@@ -1,11 +1,50 @@
 #![allow(dead_code)]
 
+use aoc_common::Rng;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 #[path = "../src/main.rs"]
 mod main;
 
+/// Generates an input with the provided number of boards, each filled with distinct numbers from the draw
+/// order. A fixed seed keeps the generated input the same between runs.
+fn generate_input(board_count: usize) -> main::Input {
+    let mut rng = Rng::new(0x2545_f491_4f6c_dd1d);
+    let mut next = move |bound: usize| (rng.next_u64() % bound as u64) as usize;
+
+    // Shuffle the numbers 0..100 to get the draw order, and use the first 25 of a fresh shuffle per board.
+    let mut shuffled = |numbers: &mut Vec<u8>| {
+        for i in (1..numbers.len()).rev() {
+            numbers.swap(i, next(i + 1));
+        }
+    };
+
+    let mut order: Vec<u8> = (0..100).collect();
+    shuffled(&mut order);
+
+    let mut numbers: Vec<u8> = (0..100).collect();
+    let boards = (0..board_count)
+        .map(|_| {
+            shuffled(&mut numbers);
+            let mut board = main::Board::default();
+            let cells = board.grid.len();
+            board.grid.copy_from_slice(&numbers[..cells]);
+            board
+        })
+        .collect();
+
+    main::Input { order, boards }
+}
+
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
         b.iter(|| main::part1(black_box(&input)))
@@ -25,7 +64,14 @@ fn bench_main(c: &mut Criterion) {
         let input = main::parse_input("input.txt").unwrap();
         b.iter(|| main::part2(black_box(&input)))
     });
+
+    let input = generate_input(100_000);
+    let mut group = c.benchmark_group("100000 boards");
+    group.sample_size(10);
+    group.bench_function("part 1", |b| b.iter(|| main::part1(black_box(&input))));
+    group.bench_function("part 2", |b| b.iter(|| main::part2(black_box(&input))));
+    group.finish();
 }
 
 criterion_group!(benches, bench_main);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -1,40 +1,99 @@
+use aoc_common::{input_path, Answer, Error, Explain, GridFormatter, Narrator, Solver};
 use std::{
+    collections::VecDeque,
+    fmt::Display,
     fs::File,
-    io::{BufRead, BufReader, Lines},
+    io::{BufRead, BufReader, Write},
     time::Instant,
 };
 
 const BOARD_WIDTH: usize = 5;
 
-const ENDING_MASKS: [u32; BOARD_WIDTH * 2] = [
-    0b11111_00000_00000_00000_00000,
-    0b00000_11111_00000_00000_00000,
-    0b00000_00000_11111_00000_00000,
-    0b00000_00000_00000_11111_00000,
-    0b00000_00000_00000_00000_11111,
-    0b10000_10000_10000_10000_10000,
-    0b01000_01000_01000_01000_01000,
-    0b00100_00100_00100_00100_00100,
-    0b00010_00010_00010_00010_00010,
-    0b00001_00001_00001_00001_00001,
-];
+/// For every cell, the masks of the row and column that contain it. Only these two lines can be completed
+/// by marking the cell.
+const LINE_MASKS: [(u32, u32); BOARD_WIDTH * BOARD_WIDTH] = line_masks();
+
+const fn line_masks() -> [(u32, u32); BOARD_WIDTH * BOARD_WIDTH] {
+    let row = (1 << BOARD_WIDTH) - 1;
+    let mut column = 0;
+    let mut y = 0;
+    while y < BOARD_WIDTH {
+        column |= 1 << (y * BOARD_WIDTH);
+        y += 1;
+    }
+
+    let mut result = [(0, 0); BOARD_WIDTH * BOARD_WIDTH];
+    let mut i = 0;
+    while i < result.len() {
+        result[i] = (
+            row << (i / BOARD_WIDTH * BOARD_WIDTH),
+            column << (i % BOARD_WIDTH),
+        );
+        i += 1;
+    }
+    result
+}
 
 pub struct Input {
     pub order: Vec<u8>,
     pub boards: Vec<Board>,
 }
 
+#[derive(Default, Clone, Copy)]
 pub struct Board {
     pub grid: [u8; BOARD_WIDTH * BOARD_WIDTH],
+
+    /// The cells that have been marked, one bit per cell.
+    pub marked: u32,
 }
 
-impl Board {
-    pub fn new() -> Board {
-        Board {
-            grid: [0u8; BOARD_WIDTH * BOARD_WIDTH],
-        }
-    }
+/// Represents a full row or column on a board.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Line {
+    /// The row with the provided Y coordinate.
+    Row(usize),
+
+    /// The column with the provided X coordinate.
+    Column(usize),
+}
+
+/// Represents a board that got a full row or column after a draw.
+#[derive(Clone, Copy, Debug)]
+pub struct Win {
+    /// The index of the winning board.
+    pub board: usize,
+
+    /// The number that was drawn.
+    pub number: u8,
+
+    /// The row or column that was completed by the draw.
+    pub line: Line,
+
+    /// The final score of the board.
+    pub score: usize,
+
+    /// The cells of the board that were marked when it won, one bit per cell.
+    pub marked: u32,
+}
+
+/// Keeps track of the markings of a set of boards while numbers are being drawn.
+pub struct Game {
+    /// The boards that are being played, including their markings.
+    boards: Vec<Board>,
 
+    /// Indicates for every board whether it has won already.
+    finished: Vec<bool>,
+}
+
+/// An iterator that draws numbers from a source of numbers, and yields the wins in the order
+/// they happen.
+pub struct Wins<I> {
+    game: Game,
+    draws: I,
+    pending: VecDeque<Win>,
+}
+
+impl Board {
     pub fn get(&self, x: usize, y: usize) -> u8 {
         self.grid[y * BOARD_WIDTH + x]
     }
@@ -43,127 +102,425 @@ impl Board {
         self.grid[y * BOARD_WIDTH + x] = value;
     }
 
-    pub fn update_and_get_score(&self, number: u8, marking: &mut u32) -> Option<usize> {
-        self.grid
-            .iter()
-            .position(|&x| x == number) // Search the grid for the number.
-            .map_or(None, |index| {
-                // Update marking.
-                let new_marking = *marking | (1 << index);
-                *marking = new_marking;
-
-                // Check if there is any winning row/col and calculate score.
-                ENDING_MASKS.iter().find_map(|&ending| {
-                    if (new_marking & ending) != ending {
-                        // This row/col is not fully marked, no score can be assigned.
-                        None
-                    } else {
-                        // Sum all unmarked fields.
-                        let s: usize = (0..self.grid.len())
-                            .filter(|&i| ((new_marking >> i) & 1) == 0)
-                            .map(|i| self.grid[i] as usize)
-                            .sum();
-
-                        // Calculate final score.
-                        Some(s * (number as usize))
-                    }
-                })
-            })
+    /// Gets the numbers on the provided row or column.
+    pub fn numbers_on(&self, line: Line) -> [u8; BOARD_WIDTH] {
+        match line {
+            Line::Row(y) => std::array::from_fn(|x| self.get(x, y)),
+            Line::Column(x) => std::array::from_fn(|y| self.get(x, y)),
+        }
+    }
+
+    /// Marks the number on the board, and returns the completed line and the final score if this made the
+    /// board win.
+    pub fn mark(&mut self, number: u8) -> Option<(Line, usize)> {
+        // Search the grid for the number.
+        let index = self.grid.iter().position(|&x| x == number)?;
+        self.marked |= 1 << index;
+
+        // Only the row and column of the marked cell can have been completed.
+        let (row, column) = LINE_MASKS[index];
+        let line = if self.marked & row == row {
+            Line::Row(index / BOARD_WIDTH)
+        } else if self.marked & column == column {
+            Line::Column(index % BOARD_WIDTH)
+        } else {
+            return None;
+        };
+
+        Some((line, self.unmarked_sum() * (number as usize)))
+    }
+
+    /// Sums all numbers on the board that are not marked.
+    pub fn unmarked_sum(&self) -> usize {
+        (0..self.grid.len())
+            .filter(|&i| ((self.marked >> i) & 1) == 0)
+            .map(|i| self.grid[i] as usize)
+            .sum()
     }
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
-    let mut lines = BufReader::new(file).lines();
+impl Display for Line {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Line::Row(y) => write!(f, "row {}", y),
+            Line::Column(x) => write!(f, "column {}", x),
+        }
+    }
+}
 
-    let order: Vec<u8> = lines
-        .next()
-        .expect("Expected random order of numbers.")?
-        .split(',')
-        .map(|x| x.parse::<u8>().expect("Expected a number in order."))
-        .collect();
+impl Display for Board {
+    /// Draws the grid of the board, with an asterisk in front of every marked number.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        GridFormatter::values(BOARD_WIDTH, BOARD_WIDTH, |x, y| {
+            let index = y * BOARD_WIDTH + x;
+            if (self.marked >> index) & 1 == 1 {
+                format!("*{}", self.grid[index])
+            } else {
+                self.grid[index].to_string()
+            }
+        })
+        .fmt(f)
+    }
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let lines = reader.lines();
 
+    let mut line_count = 0;
+    let mut order = None;
     let mut boards = Vec::new();
-    while lines.next().is_some() {
-        let board = parse_board(&mut lines)?;
-        boards.push(board);
-    }
-
-    Ok(Input {
-        order: order,
-        boards: boards,
-    })
-}
-
-fn parse_board(lines: &mut Lines<BufReader<File>>) -> std::io::Result<Board> {
-    let mut result = Board::new();
-
-    for y in 0..BOARD_WIDTH {
-        let line: Vec<u8> = lines
-            .next()
-            .expect("Expected line of numbers")?
-            .split(' ')
-            .filter_map(|x| {
-                if x.is_empty() {
-                    None
-                } else {
-                    Some(x.parse::<u8>().expect("Expected a number in board."))
-                }
-            })
-            .collect();
+    let mut board = Board::default();
+    let mut row_count = 0;
+
+    // Lines are recognized by their contents rather than their position, such that the draw order
+    // may appear anywhere in the file (as long as it is not in the middle of a board).
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        line_count = index + 1;
+
+        // Tolerate Windows line endings and trailing whitespace.
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            // Boards are separated by blank lines, so a board should never be cut off by one.
+            if row_count != 0 {
+                return Err(Error::at_line(index + 1, "Incomplete board."));
+            }
+        } else if line.contains(',') {
+            if order.is_some() || row_count != 0 {
+                return Err(Error::at_line(index + 1, "Unexpected draw order."));
+            }
+
+            order = Some(
+                line.split(',')
+                    .map(|x| {
+                        x.trim().parse::<u8>().map_err(|_| {
+                            Error::at_line(
+                                index + 1,
+                                format!("Expected a number in the order, but found {}.", x.trim()),
+                            )
+                        })
+                    })
+                    .collect::<aoc_common::Result<Vec<u8>>>()?,
+            );
+        } else {
+            let row: Vec<u8> = line
+                .split_whitespace()
+                .map(|x| {
+                    x.parse::<u8>().map_err(|_| {
+                        Error::at_line(
+                            index + 1,
+                            format!("Expected a number in the board, but found {}.", x),
+                        )
+                    })
+                })
+                .collect::<aoc_common::Result<_>>()?;
 
-        for x in 0..BOARD_WIDTH {
-            result.set(x, y, line[x]);
+            if row.len() != BOARD_WIDTH {
+                return Err(Error::at_line(index + 1, "Expected a row of 5 numbers."));
+            }
+
+            for (x, &value) in row.iter().enumerate() {
+                board.set(x, row_count, value);
+            }
+
+            row_count += 1;
+            if row_count == BOARD_WIDTH {
+                boards.push(std::mem::take(&mut board));
+                row_count = 0;
+            }
         }
     }
 
-    Ok(result)
+    if row_count != 0 {
+        return Err(Error::at_line(
+            line_count,
+            "Incomplete board at the end of the file.",
+        ));
+    }
+
+    let order =
+        order.ok_or_else(|| Error::at_line(line_count, "Expected a random order of numbers."))?;
+
+    Ok(Input { order, boards })
 }
 
-pub fn part1(input: &Input) -> usize {
-    let mut markings = vec![0u32; input.boards.len()];
-
-    input
-        .order
-        .iter()
-        .find_map(|&x| {
-            input
-                .boards
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+impl Game {
+    /// Starts a new game with copies of the provided boards, with all markings cleared.
+    pub fn new(boards: &[Board]) -> Self {
+        Self {
+            boards: boards
                 .iter()
-                .enumerate()
-                .find_map(|(i, b)| b.update_and_get_score(x, &mut markings[i]))
-        })
+                .map(|&board| Board { marked: 0, ..board })
+                .collect(),
+            finished: vec![false; boards.len()],
+        }
+    }
+
+    /// Marks the number on all boards that have not won yet, and appends any new wins to the
+    /// provided buffer in board order.
+    pub fn draw(&mut self, number: u8, wins: &mut VecDeque<Win>) {
+        for i in 0..self.boards.len() {
+            if self.finished[i] {
+                continue;
+            }
+
+            if let Some((line, score)) = self.boards[i].mark(number) {
+                self.finished[i] = true;
+                wins.push_back(Win {
+                    board: i,
+                    number,
+                    line,
+                    score,
+                    marked: self.boards[i].marked,
+                });
+            }
+        }
+    }
+
+    /// Consumes the game, and plays it with the numbers drawn from the provided source.
+    /// Numbers are only drawn when the next win is requested.
+    pub fn play<I>(self, draws: I) -> Wins<I::IntoIter>
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        Wins {
+            game: self,
+            draws: draws.into_iter(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<I> Iterator for Wins<I>
+where
+    I: Iterator<Item = u8>,
+{
+    type Item = Win;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            let number = self.draws.next()?;
+            self.game.draw(number, &mut self.pending);
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+pub fn part1(input: &Input) -> usize {
+    Game::new(&input.boards)
+        .play(input.order.iter().copied())
+        .next()
         .unwrap()
+        .score
 }
 
 pub fn part2(input: &Input) -> usize {
-    let mut finished: Vec<bool> = vec![false; input.boards.len()];
-    let mut markings = vec![0u32; input.boards.len()];
+    Game::new(&input.boards)
+        .play(input.order.iter().copied())
+        .last()
+        .unwrap()
+        .score
+}
 
-    let mut last = 0;
+/// Plays the boards in the input with numbers read from the standard input, and announces
+/// every win as soon as it happens.
+fn play_live(input: &Input) {
+    let draws = std::io::stdin()
+        .lock()
+        .lines()
+        .map_while(|line| line.ok())
+        .flat_map(|line| {
+            line.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|x| !x.is_empty())
+                .filter_map(|x| match x.parse::<u8>() {
+                    Ok(number) => Some(number),
+                    Err(_) => {
+                        eprintln!("Ignoring invalid number {}.", x);
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
 
-    input.order.iter().for_each(|&n| {
-        for i in 0..input.boards.len() {
-            if finished[i] {
-                continue;
-            }
+    let mut remaining = input.boards.len();
+    for win in Game::new(&input.boards).play(draws) {
+        remaining -= 1;
+        println!(
+            "Board {} wins after drawing {} with a score of {} ({} boards remaining).",
+            win.board, win.number, win.score, remaining
+        );
+    }
+}
+
+/// Describes every win in the order it happens, including the row or column that was completed and the
+/// markings of the board at that time. The first and last win determine the solutions to part 1 and 2
+/// respectively.
+pub fn explain(input: &Input, sink: &mut dyn Explain) -> std::io::Result<()> {
+    let mut first = None;
+    let mut last = None;
 
-            let result = input.boards[i].update_and_get_score(n, &mut markings[i]);
-            if let Some(score) = result {
-                finished[i] = true;
-                last = score;
+    for win in Game::new(&input.boards).play(input.order.iter().copied()) {
+        let board = &input.boards[win.board];
+        aoc_common::explain!(
+            sink,
+            "Board {} wins after drawing {}, completing {} ({}), with a score of {}.",
+            win.board,
+            win.number,
+            win.line,
+            board.numbers_on(win.line).map(|x| x.to_string()).join(" "),
+            win.score
+        )?;
+        aoc_common::explain!(
+            sink,
+            "{}",
+            Board {
+                marked: win.marked,
+                ..*board
             }
-        }
-    });
+        )?;
+
+        first.get_or_insert(win.board);
+        last = Some(win.board);
+    }
+
+    if let (Some(first), Some(last)) = (first, last) {
+        aoc_common::explain!(
+            sink,
+            "Solution 1 is the score of board {}, the first to win.",
+            first
+        )?;
+        aoc_common::explain!(
+            sink,
+            "Solution 2 is the score of board {}, the last to win.",
+            last
+        )?;
+    }
+
+    Ok(())
+}
 
-    last
+/// Writes the boards and the full draw timeline as newline delimited JSON. Every board is written as a
+/// `board` event first, followed by one `draw` event per number in the draw order, listing the boards
+/// that won with that number.
+pub fn export_ndjson(input: &Input, writer: &mut impl Write) -> std::io::Result<()> {
+    for (index, board) in input.boards.iter().enumerate() {
+        let rows: Vec<String> = board
+            .grid
+            .chunks(BOARD_WIDTH)
+            .map(|row| {
+                let numbers: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+                format!("[{}]", numbers.join(","))
+            })
+            .collect();
+
+        writeln!(
+            writer,
+            "{{\"event\":\"board\",\"board\":{},\"rows\":[{}]}}",
+            index,
+            rows.join(",")
+        )?;
+    }
+
+    let mut game = Game::new(&input.boards);
+    let mut wins = VecDeque::new();
+    let mut remaining = input.boards.len();
+
+    for (index, &number) in input.order.iter().enumerate() {
+        wins.clear();
+        game.draw(number, &mut wins);
+        remaining -= wins.len();
+
+        let wins: Vec<String> = wins
+            .iter()
+            .map(|win| format!("{{\"board\":{},\"score\":{}}}", win.board, win.score))
+            .collect();
+
+        writeln!(
+            writer,
+            "{{\"event\":\"draw\",\"draw\":{},\"number\":{},\"wins\":[{}],\"remaining\":{}}}",
+            index,
+            number,
+            wins.join(","),
+            remaining
+        )?;
+    }
+
+    writer.flush()
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day04;
+
+impl Solver for Day04 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+
+    fn explain(input: &Input, sink: &mut dyn Explain) -> std::io::Result<()> {
+        explain(input, sink)
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    let mut live = false;
+    let mut ndjson = false;
+    let mut explain = false;
+
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--live" => live = true,
+            "--explain" => explain = true,
+            "--format" => match args.next().as_deref() {
+                Some("ndjson") => ndjson = true,
+                Some("text") => ndjson = false,
+                _ => panic!("Expected text or ndjson after --format."),
+            },
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
     let time_parse = now.elapsed();
 
+    if live {
+        play_live(&input);
+        return Ok(());
+    }
+
+    if ndjson {
+        return export_ndjson(&input, &mut std::io::stdout().lock());
+    }
+
+    if explain {
+        return self::explain(&input, &mut Narrator::new(std::io::stdout().lock()));
+    }
+
     let now = Instant::now();
     let result1 = part1(&input);
     let time1 = now.elapsed();
@@ -182,3 +539,12 @@ fn main() -> std::io::Result<()> {
 // Parse: (time: 175us)
 // Solution 1: 58838 (time: 14us)
 // Solution 2: 6256 (time: 102us)
+//
+// Benchmarked (generated input, markings in a separate vector and checked against all 10 line masks):
+// 100000 boards/part 1    time:   [15.684 ms 15.839 ms 16.099 ms]
+// 100000 boards/part 2    time:   [126.84 ms 127.83 ms 128.58 ms]
+//
+// Benchmarked (generated input, markings inside the boards and only checking the row and column of the
+// marked cell):
+// 100000 boards/part 1    time:   [13.774 ms 14.495 ms 14.980 ms]
+// 100000 boards/part 2    time:   [93.285 ms 97.988 ms 108.16 ms]
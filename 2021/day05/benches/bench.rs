@@ -6,6 +6,14 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
         b.iter(|| main::part1(black_box(&input)))
@@ -25,6 +33,21 @@ fn bench_main(c: &mut Criterion) {
         let input = main::parse_input("input.txt").unwrap();
         b.iter(|| main::part2(black_box(&input)))
     });
+
+    c.bench_function("part 1 (real, sweep)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::part1_sweep(black_box(&input)))
+    });
+
+    c.bench_function("part 2 (real, sweep)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::part2_sweep(black_box(&input)))
+    });
+
+    c.bench_function("part 2 (generated 500, 10^9 range, sweep)", |b| {
+        let input = main::Input::generate(500, 1_000_000_000, 1337);
+        b.iter(|| main::part2_sweep(black_box(&input)))
+    });
 }
 
 criterion_group!(benches, bench_main);
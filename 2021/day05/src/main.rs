@@ -1,4 +1,8 @@
+use aoc_common::{
+    byte_lines, input_path, read_literal, Answer, Error, GridFormatter, Point, Rng, Solver,
+};
 use std::{
+    collections::{HashMap, HashSet},
     fmt::Display,
     fs::File,
     io::{BufRead, BufReader},
@@ -17,10 +21,6 @@ pub struct Input {
     lines: Vec<LineSegment>,
 }
 
-/// Represents an xy-coordinate within a diagram.
-#[derive(PartialEq, PartialOrd)]
-pub struct Point(usize, usize);
-
 /// Represents a line within a diagram.
 pub struct LineSegment {
     /// The starting point. The X component of this coordinate is guaranteed
@@ -32,48 +32,158 @@ pub struct LineSegment {
     pub end: Point,
 }
 
-/// Represents a diagram in which line segments are drawn.
-pub struct Diagram {
-    /// Gets the raw data stored in the diagram.
-    grid: [u8; DIAGRAM_WIDTH * DIAGRAM_HEIGHT],
+/// The formats in which a line segment can be described in the input.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SegmentFormat {
+    /// The two end points of the segment, in the format `"x1,y1 -> x2,y2"`.
+    EndPoints,
+
+    /// The starting point, a compass direction and a length, in the format `"x,y dir len"`. The direction
+    /// is one of `N`, `NE`, `E`, `SE`, `S`, `SW`, `W` or `NW`, where north is towards the top of the diagram
+    /// (decreasing Y). The length is the number of steps taken from the starting point.
+    Direction,
 }
 
-impl Point {
-    /// Parses an XY coordinate from a string slice. The string must be in the format "x,y".
-    pub fn from_str(s: &str) -> Point {
-        let mut split = s.split(',');
+/// An iterator over all integer points on a line segment, from its start to its end point (inclusive).
+/// Points are produced using Bresenham's line algorithm, which only uses integer arithmetic and reduces to
+/// simple steps for horizontal, vertical and diagonal lines.
+pub struct SegmentPoints {
+    /// The next point to produce.
+    current: (isize, isize),
 
-        let x = split
-            .next()
-            .expect("Expected an X component.")
-            .parse::<usize>()
-            .expect("Could not parse X component.");
-        let y = split
-            .next()
-            .expect("Expected an Y component.")
-            .parse::<usize>()
-            .expect("Could not parse Y component.");
+    /// The last point to produce.
+    end: (isize, isize),
 
-        Point(x, y)
-    }
+    /// The absolute difference in X, and the negated absolute difference in Y between the end points.
+    delta: (isize, isize),
+
+    /// The direction to step in along both axes.
+    step: (isize, isize),
+
+    /// The accumulated error term.
+    error: isize,
+
+    /// A value indicating whether the end point was produced already.
+    done: bool,
 }
 
-impl Display for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "(x: {}, y: {})", self.0, self.1)
-    }
+/// Selects how overlapping points are counted.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Algorithm {
+    /// Draw all line segments in a [`Diagram`], and count the cells that are covered twice.
+    Grid,
+
+    /// Count overlaps directly from the line segments with [`count_overlaps_sweep`], without materializing
+    /// a grid. This works for arbitrarily large coordinates.
+    Sweep,
+}
+
+/// The orientation of the infinite line a segment lies on.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Orientation {
+    /// `y = c`, parametrized by `x`.
+    Horizontal,
+
+    /// `x = c`, parametrized by `y`.
+    Vertical,
+
+    /// `x - y = c`, parametrized by `x`.
+    DiagonalDown,
+
+    /// `x + y = c`, parametrized by `x`.
+    DiagonalUp,
+}
+
+/// A line segment described by the infinite line it lies on, and the range it covers on that line.
+struct LineRange {
+    /// The line the segment lies on.
+    line: (Orientation, i64),
+
+    /// The first parameter value on the line that is covered.
+    low: i64,
+
+    /// The last parameter value on the line that is covered.
+    high: i64,
+}
+
+/// Represents a diagram in which line segments are drawn.
+pub struct Diagram {
+    /// Gets the raw data stored in the diagram.
+    grid: [u8; DIAGRAM_WIDTH * DIAGRAM_HEIGHT],
 }
 
 impl LineSegment {
     /// Parses a line segment from a string slice. The string must be in the format `"x1,y1 -> x2,y2"`.
     /// This function ensures that the starting point of the line segment is never to the right of
     /// the ending point.
-    pub fn from_str(s: &str) -> LineSegment {
-        let mut split = s.split(" -> ");
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<LineSegment, String> {
+        let (start, end) = s
+            .split_once(" -> ")
+            .ok_or_else(|| format!("Expected a start and end location, but found {}.", s))?;
+
+        Ok(LineSegment::new(
+            start.parse::<Point>()?,
+            end.parse::<Point>()?,
+        ))
+    }
 
-        let start = Point::from_str(split.next().expect("Expected start location."));
-        let end = Point::from_str(split.next().expect("Expected end location."));
+    /// Parses a line segment in the format `"x1,y1 -> x2,y2"` directly from the bytes of a line, without
+    /// allocating. Returns [`None`] if the line is not in this format.
+    pub fn parse_bytes(mut s: &[u8]) -> Option<LineSegment> {
+        let start = Point::read(&mut s)?;
+        read_literal(&mut s, b" -> ")?;
+        let end = Point::read(&mut s)?;
+        s.is_empty().then(|| LineSegment::new(start, end))
+    }
 
+    /// Parses a line segment from a string slice in the format `"x,y dir len"`, see [`SegmentFormat::Direction`].
+    pub fn from_direction_str(s: &str) -> Result<LineSegment, String> {
+        let mut split = s.split_whitespace();
+
+        let start = split
+            .next()
+            .ok_or("Expected a start location.")?
+            .parse::<Point>()?;
+        let (dx, dy) = match split.next().ok_or("Expected a direction.")? {
+            "N" => (0, -1),
+            "NE" => (1, -1),
+            "E" => (1, 0),
+            "SE" => (1, 1),
+            "S" => (0, 1),
+            "SW" => (-1, 1),
+            "W" => (-1, 0),
+            "NW" => (-1, -1),
+            x => return Err(format!("Unknown direction {}.", x)),
+        };
+        let length = split.next().ok_or("Expected a length.")?;
+        let length = length
+            .parse::<isize>()
+            .map_err(|_| format!("Expected a length, but found {}.", length))?;
+
+        let end = Point(
+            usize::try_from(start.0 as isize + dx * length)
+                .map_err(|_| "Segment ends left of the diagram.")?,
+            usize::try_from(start.1 as isize + dy * length)
+                .map_err(|_| "Segment ends above the diagram.")?,
+        );
+
+        Ok(LineSegment::new(start, end))
+    }
+
+    /// Parses a line segment from a string slice in any of the supported formats, which is detected
+    /// automatically.
+    pub fn parse(s: &str) -> Result<LineSegment, String> {
+        match SegmentFormat::detect(s) {
+            Some(SegmentFormat::EndPoints) => LineSegment::from_str(s),
+            Some(SegmentFormat::Direction) => LineSegment::from_direction_str(s),
+            None => Err(format!("Unrecognized line segment {}.", s)),
+        }
+    }
+
+    /// Creates a new line segment between the two points, such that the starting point of the line segment
+    /// is never to the right of the ending point.
+    pub fn new(start: Point, end: Point) -> LineSegment {
         if start < end {
             LineSegment { start, end }
         } else {
@@ -104,33 +214,158 @@ impl LineSegment {
         self.start.1 > self.end.1
     }
 
+    /// Gets an iterator over all points on the line segment, starting at the start point and ending at
+    /// the end point.
+    pub fn points(&self) -> SegmentPoints {
+        SegmentPoints::new(&self.start, &self.end)
+    }
+
     /// Draws the line segment in the provided diagram, and returns the number of times the line
     /// has introduced a new crossing point.
     pub fn cover(&self, diagram: &mut Diagram) -> usize {
-        if self.is_horizontal() {
-            (self.start.0..=self.end.0)
-                .filter(|&x| diagram.cover(Point(x, self.start.1)))
-                .count()
-        } else if self.is_vertical() {
-            (self.start.1..=self.end.1)
-                .filter(|&y| diagram.cover(Point(self.start.0, y)))
-                .count()
+        self.points().filter(|&point| diagram.cover(point)).count()
+    }
+}
+
+impl Orientation {
+    /// Gets the coefficients `(a, b)` of the line equation `a * x + b * y = c`.
+    fn coefficients(&self) -> (i64, i64) {
+        match self {
+            Orientation::Horizontal => (0, 1),
+            Orientation::Vertical => (1, 0),
+            Orientation::DiagonalDown => (1, -1),
+            Orientation::DiagonalUp => (1, 1),
+        }
+    }
+
+    /// Gets the line with this orientation that goes through the provided point.
+    fn line_through(&self, point: (i64, i64)) -> (Orientation, i64) {
+        let (a, b) = self.coefficients();
+        (*self, a * point.0 + b * point.1)
+    }
+
+    /// Gets the parameter of the provided point on a line with this orientation.
+    fn parameter(&self, point: (i64, i64)) -> i64 {
+        match self {
+            Orientation::Vertical => point.1,
+            _ => point.0,
+        }
+    }
+}
+
+impl LineRange {
+    /// Describes the provided line segment as a range on a line.
+    fn from_segment(segment: &LineSegment) -> Self {
+        let orientation = if segment.is_horizontal() {
+            Orientation::Horizontal
+        } else if segment.is_vertical() {
+            Orientation::Vertical
+        } else if segment.is_diagonal_down() {
+            Orientation::DiagonalDown
+        } else {
+            Orientation::DiagonalUp
+        };
+
+        let start = (segment.start.0 as i64, segment.start.1 as i64);
+        let end = (segment.end.0 as i64, segment.end.1 as i64);
+        let (low, high) = (orientation.parameter(start), orientation.parameter(end));
+
+        Self {
+            line: orientation.line_through(start),
+            low: low.min(high),
+            high: low.max(high),
+        }
+    }
+
+    /// Determines whether the provided point on the line is covered by the range.
+    fn contains(&self, point: (i64, i64)) -> bool {
+        (self.low..=self.high).contains(&self.line.0.parameter(point))
+    }
+
+    /// Gets the integer point where the lines of both ranges cross, if both ranges contain it. Returns
+    /// [`None`] for parallel lines, or if the lines cross in between integer points.
+    fn crossing(&self, other: &LineRange) -> Option<(i64, i64)> {
+        let ((a1, b1), c1) = (self.line.0.coefficients(), self.line.1);
+        let ((a2, b2), c2) = (other.line.0.coefficients(), other.line.1);
+
+        let determinant = a1 * b2 - a2 * b1;
+        if determinant == 0 {
+            return None;
+        }
+
+        let x = c1 * b2 - c2 * b1;
+        let y = a1 * c2 - a2 * c1;
+        if x % determinant != 0 || y % determinant != 0 {
+            return None;
+        }
+
+        let point = (x / determinant, y / determinant);
+        if self.contains(point) && other.contains(point) {
+            Some(point)
+        } else {
+            None
+        }
+    }
+}
+
+impl SegmentFormat {
+    /// Detects the format of a line segment described by the provided string slice, or returns [`None`] if
+    /// it is in neither of the supported formats.
+    pub fn detect(s: &str) -> Option<SegmentFormat> {
+        if s.contains(" -> ") {
+            Some(SegmentFormat::EndPoints)
+        } else if s.split_whitespace().count() == 3 {
+            Some(SegmentFormat::Direction)
+        } else {
+            None
+        }
+    }
+}
+
+impl SegmentPoints {
+    /// Creates a new iterator over the points between the two provided points (inclusive).
+    pub fn new(start: &Point, end: &Point) -> Self {
+        let current = (start.0 as isize, start.1 as isize);
+        let end = (end.0 as isize, end.1 as isize);
+        let delta = ((end.0 - current.0).abs(), -(end.1 - current.1).abs());
+
+        Self {
+            current,
+            end,
+            delta,
+            step: ((end.0 - current.0).signum(), (end.1 - current.1).signum()),
+            error: delta.0 + delta.1,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for SegmentPoints {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = Point(self.current.0 as usize, self.current.1 as usize);
+
+        if self.current == self.end {
+            self.done = true;
         } else {
-            let length = self.end.0 - self.start.0;
-            (0..=length)
-                .filter(|&i| {
-                    let point = Point(
-                        self.start.0 + i,
-                        if self.is_diagonal_down() {
-                            self.start.1 + i
-                        } else {
-                            self.start.1 - i
-                        },
-                    );
-                    diagram.cover(point)
-                })
-                .count()
+            // Step along the axes for which the error term allows it.
+            let error2 = 2 * self.error;
+            if error2 >= self.delta.1 {
+                self.error += self.delta.1;
+                self.current.0 += self.step.0;
+            }
+            if error2 <= self.delta.0 {
+                self.error += self.delta.0;
+                self.current.1 += self.step.1;
+            }
         }
+
+        Some(result)
     }
 }
 
@@ -140,6 +375,12 @@ impl Display for LineSegment {
     }
 }
 
+impl Default for Diagram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Diagram {
     /// Initializes a new empty diagram.
     pub fn new() -> Diagram {
@@ -163,33 +404,93 @@ impl Diagram {
 
 impl Display for Diagram {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..DIAGRAM_HEIGHT {
-            for x in 0..DIAGRAM_WIDTH {
-                let cell = self.get(Point(x, y));
-                if cell == 0 {
-                    write!(f, ".")?;
-                } else {
-                    write!(f, "{}", cell)?;
+        GridFormatter::sparse(DIAGRAM_WIDTH, DIAGRAM_HEIGHT, |x, y| self.get(Point(x, y))).fmt(f)
+    }
+}
+
+impl std::str::FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grid" => Ok(Algorithm::Grid),
+            "sweep" => Ok(Algorithm::Sweep),
+            _ => Err(format!("Unknown algorithm {}.", s)),
+        }
+    }
+}
+
+impl Input {
+    /// Creates a pseudo-random input with the provided number of horizontal, vertical and diagonal line
+    /// segments, with all coordinates below `max_coordinate`, determined by the provided seed.
+    pub fn generate(line_count: usize, max_coordinate: usize, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut next = move || rng.next_usize();
+
+        let mut lines = Vec::with_capacity(line_count);
+        for _ in 0..line_count {
+            let start = Point(next() % max_coordinate, next() % max_coordinate);
+            let end = match next() % 4 {
+                0 => Point(next() % max_coordinate, start.1),
+                1 => Point(start.0, next() % max_coordinate),
+                2 => {
+                    let length = next() % (max_coordinate - start.0.max(start.1));
+                    Point(start.0 + length, start.1 + length)
                 }
-            }
-            writeln!(f)?;
+                _ => {
+                    let length = next() % (max_coordinate - start.0).min(start.1 + 1);
+                    Point(start.0 + length, start.1 - length)
+                }
+            };
+            lines.push(LineSegment::new(start, end));
         }
 
-        Ok(())
+        Self { lines }
     }
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(mut reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let lines: Vec<LineSegment> = byte_lines(&data)
+        .enumerate()
+        .map(|(index, line)| {
+            let segment = match LineSegment::parse_bytes(line) {
+                Some(segment) => segment,
+                // Other formats (and malformed lines) go through the slower string parser.
+                None => std::str::from_utf8(line)
+                    .map_err(|_| "Expected a line of text.".to_string())
+                    .and_then(LineSegment::parse)
+                    .map_err(|x| Error::at_line(index + 1, x))?,
+            };
+
+            // The start never lies to the right of the end, but it can lie below it.
+            if segment.end.0 >= DIAGRAM_WIDTH
+                || segment.start.1.max(segment.end.1) >= DIAGRAM_HEIGHT
+            {
+                return Err(Error::at_line(
+                    index + 1,
+                    format!(
+                        "Segment from {} to {} does not fit in the diagram.",
+                        segment.start, segment.end
+                    ),
+                ));
+            }
 
-    let lines: Vec<LineSegment> = BufReader::new(file)
-        .lines()
-        .map(|s| LineSegment::from_str(s.expect("Expected line").as_str()))
-        .collect();
+            Ok(segment)
+        })
+        .collect::<aoc_common::Result<_>>()?;
 
     Ok(Input { lines })
 }
 
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
 pub fn part1(input: &Input) -> usize {
     let mut diagram = Diagram::new();
 
@@ -207,17 +508,180 @@ pub fn part2(input: &Input) -> usize {
     input.lines.iter().map(|l| l.cover(&mut diagram)).sum()
 }
 
+/// Counts the points that are covered by at least two of the provided line segments, without drawing
+/// them in a grid. The running time only depends on the number of segments, not on their coordinates.
+///
+/// Every segment lies on a horizontal, vertical or diagonal line. Points covered twice are either part
+/// of an overlap between segments on the same line, or a crossing of segments on different lines:
+/// - Overlaps are found per line by sweeping over the segment end points as events, and keeping track
+///   of how many segments are active.
+/// - Crossings are found by intersecting every pair of segments on different lines. A crossing that is
+///   not part of any overlap adds one point, and a crossing that is part of overlaps on several lines
+///   was counted once for each of them.
+pub fn count_overlaps_sweep(lines: &[LineSegment], include_diagonals: bool) -> usize {
+    let ranges: Vec<LineRange> = lines
+        .iter()
+        .filter(|l| include_diagonals || l.is_horizontal() || l.is_vertical())
+        .map(LineRange::from_segment)
+        .collect();
+
+    // Collect the start and end events of the segments on every line.
+    let mut events: HashMap<(Orientation, i64), Vec<(i64, isize)>> = HashMap::new();
+    for range in ranges.iter() {
+        let line_events = events.entry(range.line).or_default();
+        line_events.push((range.low, 1));
+        line_events.push((range.high + 1, -1));
+    }
+
+    // Sweep over every line, and record the ranges where at least two segments are active.
+    let mut count = 0;
+    let mut overlaps: HashMap<(Orientation, i64), Vec<(i64, i64)>> = HashMap::new();
+    for (line, mut line_events) in events {
+        line_events.sort_unstable();
+
+        let mut active = 0;
+        let mut overlap_start = 0;
+        for (position, delta) in line_events {
+            let was_overlapping = active >= 2;
+            active += delta;
+            if !was_overlapping && active >= 2 {
+                overlap_start = position;
+            } else if was_overlapping && active < 2 && position > overlap_start {
+                overlaps
+                    .entry(line)
+                    .or_default()
+                    .push((overlap_start, position - 1));
+                count += (position - overlap_start) as usize;
+            }
+        }
+    }
+
+    // Find all points where segments on different lines cross.
+    let mut crossings = HashSet::new();
+    for (i, range) in ranges.iter().enumerate() {
+        for other in ranges[i + 1..].iter() {
+            if let Some(point) = range.crossing(other) {
+                crossings.insert(point);
+            }
+        }
+    }
+
+    // Correct the count for every crossing, depending on the number of overlaps it is part of.
+    for point in crossings {
+        let covering_overlaps = [
+            Orientation::Horizontal,
+            Orientation::Vertical,
+            Orientation::DiagonalDown,
+            Orientation::DiagonalUp,
+        ]
+        .iter()
+        .filter(|orientation| {
+            let line = orientation.line_through(point);
+            let parameter = orientation.parameter(point);
+            overlaps.get(&line).is_some_and(|ranges| {
+                // Overlaps on a line are produced in order, and are disjoint.
+                let index = ranges.partition_point(|&(_, high)| high < parameter);
+                index < ranges.len() && ranges[index].0 <= parameter
+            })
+        })
+        .count();
+
+        match covering_overlaps {
+            0 => count += 1,
+            n => count -= n - 1,
+        }
+    }
+
+    count
+}
+
+pub fn part1_sweep(input: &Input) -> usize {
+    count_overlaps_sweep(&input.lines, false)
+}
+
+pub fn part2_sweep(input: &Input) -> usize {
+    count_overlaps_sweep(&input.lines, true)
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day05;
+
+impl Solver for Day05 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
 fn main() -> std::io::Result<()> {
+    let mut algorithm = Algorithm::Grid;
+    let mut generate = None;
+    let mut seed = aoc_common::seed();
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--algorithm" => {
+                algorithm = args
+                    .next()
+                    .expect("Expected grid or sweep after --algorithm.")
+                    .parse()
+                    .unwrap()
+            }
+            "--generate" => {
+                let line_count = args
+                    .next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .expect("Expected a line count after --generate.");
+                let max_coordinate = args
+                    .next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .expect("Expected a maximum coordinate after the line count.");
+                generate = Some((line_count, max_coordinate))
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --seed.")
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let input = match generate {
+        Some((line_count, max_coordinate)) => Input::generate(line_count, max_coordinate, seed),
+        None => parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?,
+    };
     let time_parse = now.elapsed();
 
     let now = Instant::now();
-    let result1 = part1(&input);
+    let result1 = match algorithm {
+        Algorithm::Grid => part1(&input),
+        Algorithm::Sweep => part1_sweep(&input),
+    };
     let time1 = now.elapsed();
 
     let now = Instant::now();
-    let result2 = part2(&input);
+    let result2 = match algorithm {
+        Algorithm::Grid => part2(&input),
+        Algorithm::Sweep => part2_sweep(&input),
+    };
     let time2 = now.elapsed();
 
     println!("Parse: (time: {}us)", time_parse.as_micros());
@@ -227,6 +691,40 @@ fn main() -> std::io::Result<()> {
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the sweep line algorithm agrees with drawing the line segments in a diagram, naming the
+    /// provided input on failure.
+    fn check_sweep(input: &Input, name: &str) {
+        assert_eq!(part1_sweep(input), part1(input), "part 1 of {}", name);
+        assert_eq!(part2_sweep(input), part2(input), "part 2 of {}", name);
+    }
+
+    #[test]
+    fn sweep_agrees_with_grid_on_sample() {
+        check_sweep(
+            &parse_reader(include_bytes!("../input2.txt").as_slice()).unwrap(),
+            "the sample",
+        );
+    }
+
+    #[test]
+    fn sweep_agrees_with_grid_on_generated_inputs() {
+        for seed in 1..=20 {
+            check_sweep(
+                &Input::generate(500, DIAGRAM_WIDTH.min(DIAGRAM_HEIGHT), seed),
+                &format!("seed {}", seed),
+            );
+        }
+    }
+}
+
 // Parse: (time: 181us)
 // Solution 1: 6007 (time: 835us)
 // Solution 2: 19349 (time: 938us)
+//
+// Parsing from bytes with read_uint instead of splitting strings:
+// parse (real), before    time:   [90.538 µs 91.541 µs 92.652 µs]
+// parse (real), after     time:   [56.361 µs 56.669 µs 57.024 µs]
@@ -6,6 +6,14 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
         b.iter(|| main::part1(black_box(&input)))
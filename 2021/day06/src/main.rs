@@ -1,6 +1,8 @@
+use aoc_common::{input_path, Answer, Error, GrowthStats, LinearSim, Solver, SparseMatrix};
 use std::{
+    fmt::Display,
     fs::File,
-    io::{BufReader, Read},
+    io::{BufRead, BufReader},
     time::Instant,
 };
 
@@ -17,72 +19,252 @@ use std::{
 //   => If we iterate all days,  "resetting" timers of the previous fish doesn't affect
 //      final outcome, because after 7 days we end up at the same value anyways.
 //
-// => We can implement the entire thing as a simple feedback shift register that just increases
-//    the number of fish at (t+7)%9 by the number of fish with timer (t % 9).
-//    Total fish count is then just sum of all counts.
+// => The counts of one day are a linear function of the counts of the day before: every fish at
+//    timer t > 0 moves to t-1, and every fish at timer 0 moves to timer 6 and spawns one at timer 8.
+//    We can implement the entire thing as a LinearSim over the 9 counts, and the total fish count is
+//    then just the sum of all counts.
+
+/// The number of distinct timer values a fish can have.
+const TIMER_COUNT: usize = 9;
+
+/// The maximum number of fish in the initial state, such that the number of fish after 256 days still fits
+/// in 64 bits.
+const MAX_FISH: usize = 1_000_000_000;
 
 pub struct Input {
-    initial_state: Vec<usize>,
+    /// The number of fish for every timer value in the initial state.
+    initial_state: [usize; TIMER_COUNT],
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
-
+/// Parses the initial state of the fish. The input either lists the timer of every fish (`3,4,3,1,2`),
+/// or the number of fish per timer value (`1:1,2:1,3:2,4:1`). The format is detected automatically.
+pub fn parse_reader(mut reader: impl BufRead) -> aoc_common::Result<Input> {
     let mut buf = String::new();
-    BufReader::new(file).read_to_string(&mut buf)?;
+    reader.read_to_string(&mut buf)?;
+
+    let mut initial_state = [0usize; TIMER_COUNT];
+
+    // Tolerate trailing line endings (LF or CRLF) and whitespace.
+    let number = |x: &str| {
+        x.trim()
+            .parse::<usize>()
+            .map_err(|_| Error::at_line(1, format!("Expected a number, but found {}.", x.trim())))
+    };
+
+    let mut total = 0usize;
+    for item in buf.trim_end().split(',').map(|x| x.trim()) {
+        let (timer, count) = match item.split_once(':') {
+            Some((timer, count)) => (number(timer)?, number(count)?),
+            None => (number(item)?, 1),
+        };
 
-    let numbers: Vec<usize> = buf
-        .split(',')
-        .map(|x| x.parse::<usize>().expect("Expected number"))
-        .collect();
+        if timer >= TIMER_COUNT {
+            return Err(Error::at_line(
+                1,
+                format!("Timer value {} is out of range.", timer),
+            ));
+        }
 
-    Ok(Input {
-        initial_state: numbers,
-    })
+        total = total.saturating_add(count);
+        if total > MAX_FISH {
+            return Err(Error::at_line(
+                1,
+                format!("Expected at most {} fish.", MAX_FISH),
+            ));
+        }
+
+        initial_state[timer] += count;
+    }
+
+    Ok(Input { initial_state })
 }
 
-pub fn simulate(input: &Input, days: usize) -> usize {
-    let mut fish_counts = [0usize; 9];
+/// Indicates that the number of fish no longer fits in 64 bits.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Overflow;
 
-    for &timer in input.initial_state.iter() {
-        fish_counts[timer] += 1;
+impl Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The number of fish no longer fits in 64 bits")
     }
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Builds the matrix that maps the number of fish per timer value of one day onto those of the next day.
+pub fn transition() -> SparseMatrix {
+    let ageing = (1..TIMER_COUNT).map(|timer| (timer - 1, timer, 1));
+    let spawning = [(6, 0, 1), (8, 0, 1)];
+    SparseMatrix::from_entries(TIMER_COUNT, ageing.chain(spawning))
+}
+
+/// Creates the simulation of the fish, starting at the initial state.
+fn fish_sim(input: &Input) -> LinearSim {
+    let initial_state = input.initial_state.iter().map(|&x| x as u64).collect();
+    LinearSim::new(initial_state, transition())
+}
+
+/// Gets the total number of fish in a state of the simulation, or an [`Overflow`] if the counts per timer
+/// value or their sum no longer fit in 64 bits.
+fn total(sim: &LinearSim) -> Result<usize, Overflow> {
+    if sim.overflowed() {
+        return Err(Overflow);
+    }
+
+    sim.state()
+        .iter()
+        .try_fold(0usize, |acc, &x| acc.checked_add(x as usize))
+        .ok_or(Overflow)
+}
+
+/// Simulates the provided number of days, and returns the total number of fish. The population grows
+/// exponentially, and no longer fits in 64 bits after roughly 450 days, which is reported as an [`Overflow`].
+pub fn simulate(input: &Input, days: usize) -> Result<usize, Overflow> {
+    let mut sim = fish_sim(input);
+    sim.step(days);
+    total(&sim)
+}
+
+/// Simulates the provided number of days, and returns the total number of fish after every day, starting
+/// with the initial state. Fails as soon as the number of fish no longer fits in 64 bits.
+pub fn simulate_trace(input: &Input, days: usize) -> Result<Vec<usize>, Overflow> {
+    let mut sim = fish_sim(input);
+    let mut trace = Vec::with_capacity(days + 1);
+    trace.push(total(&sim)?);
 
-    for day in 0..days {
-        fish_counts[(day + 7) % 9] += fish_counts[day % 9];
+    for _ in 0..days {
+        sim.step(1);
+        trace.push(total(&sim)?);
     }
 
-    fish_counts.iter().sum()
+    Ok(trace)
 }
 
-pub fn part1(input: &Input) -> usize {
-    simulate(&input, 80)
+pub fn part1(input: &Input) -> Result<usize, Overflow> {
+    simulate(input, 80)
 }
 
-pub fn part2(input: &Input) -> usize {
-    simulate(&input, 256)
+pub fn part2(input: &Input) -> Result<usize, Overflow> {
+    simulate(input, 256)
     // 0
 }
 
+/// The solution of this day, as it is registered with the runner.
+pub struct Day06;
+
+impl Solver for Day06 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
 fn main() -> std::io::Result<()> {
+    let mut growth_days = None;
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--growth" => {
+                growth_days = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a number of days after --growth."),
+                )
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
     let time_parse = now.elapsed();
+
+    if let Some(days) = growth_days {
+        match simulate_trace(&input, days) {
+            Ok(trace) => {
+                return GrowthStats::from_trace(&trace).write_json(&mut std::io::stdout().lock())
+            }
+            Err(error) => {
+                eprintln!("{} within {} days.", error, days);
+                std::process::exit(1);
+            }
+        }
+    }
+
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
     let now = Instant::now();
     let result1 = part1(&input);
     let time1 = now.elapsed();
-    println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
+    println!(
+        "Solution 1: {} (time: {}us)",
+        Answer::from(result1),
+        time1.as_micros()
+    );
 
     let now = Instant::now();
     let result2 = part2(&input);
     let time2 = now.elapsed();
-    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+    println!(
+        "Solution 2: {} (time: {}us)",
+        Answer::from(result2),
+        time2.as_micros()
+    );
 
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Generates the initial state of a school with at most 1000 fish per timer value.
+    fn school() -> impl Strategy<Value = [usize; TIMER_COUNT]> {
+        prop::array::uniform9(0..=1000usize)
+    }
+
+    proptest! {
+        /// The number of fish never decreases from one day to the next.
+        #[test]
+        fn fish_counts_never_decrease(initial_state in school()) {
+            let input = Input { initial_state };
+            let trace = simulate_trace(&input, 256).unwrap();
+            for day in 1..trace.len() {
+                prop_assert!(trace[day] >= trace[day - 1], "fewer fish on day {}", day);
+            }
+        }
+
+        /// The trace agrees with simulating the days at once.
+        #[test]
+        fn trace_agrees_with_simulate(initial_state in school()) {
+            let input = Input { initial_state };
+            let trace = simulate_trace(&input, 256).unwrap();
+            for days in [0, 18, 80, 256] {
+                prop_assert_eq!(simulate(&input, days), Ok(trace[days]));
+            }
+        }
+    }
+}
+
 // Parse: (time: 139us)
 // Solution 1: 394994 (time: 0us)
 // Solution 2: 1765974267455 (time: 0us)
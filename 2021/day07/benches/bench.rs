@@ -6,6 +6,14 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
         b.iter(|| main::part1(black_box(&input)))
@@ -25,7 +33,30 @@ fn bench_main(c: &mut Criterion) {
         let input = main::parse_input("input.txt").unwrap();
         b.iter(|| main::part2(black_box(&input)))
     });
+
+    for crab_count in [1_000_000, 10_000_000] {
+        let input = main::Input::generate(crab_count, 2000, 1337);
+        let mut group = c.benchmark_group(format!("part 2 (generated {})", crab_count));
+        group.sample_size(10);
+        for (name, strategy) in [
+            ("serial", main::Strategy::Serial),
+            #[cfg(feature = "parallel")]
+            ("parallel", main::Strategy::Parallel),
+            ("histogram", main::Strategy::Histogram),
+        ] {
+            group.bench_function(name, |b| {
+                b.iter(|| {
+                    main::get_minimum_fuel_with(
+                        black_box(&input),
+                        main::triangular_fuel_cost,
+                        strategy,
+                    )
+                })
+            });
+        }
+        group.finish();
+    }
 }
 
 criterion_group!(benches, bench_main);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -1,52 +1,215 @@
+use aoc_common::{input_path, Answer, Error, Rng, Solver};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::{
     fs::File,
-    io::{BufReader, Read},
+    io::{BufRead, BufReader},
+    ops::RangeInclusive,
     time::Instant,
 };
 
+/// The maximum distance of a crab from position 0.
+const MAX_POSITION: isize = 1_000_000;
+
 pub struct Input {
     positions: Vec<isize>,
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
+/// Selects how the total fuel cost of a candidate position is computed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Strategy {
+    /// Sum the cost of every crab one after the other.
+    Serial,
+
+    /// Sum the cost of the crabs in parallel.
+    #[cfg(feature = "parallel")]
+    Parallel,
+
+    /// Count the crabs per position once, and sum the cost per position weighted by the number of crabs.
+    Histogram,
+}
+
+/// The number of crabs at every position between the leftmost and rightmost crab.
+pub struct Histogram {
+    /// The position of the leftmost crab.
+    min: isize,
+
+    /// The number of crabs at every position, starting at [`Histogram::min`].
+    counts: Vec<usize>,
+}
+
+impl Input {
+    /// Creates a pseudo-random fleet of crabs at positions from 0 up to (but excluding) `max_position`,
+    /// determined by the provided seed.
+    pub fn generate(crab_count: usize, max_position: usize, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut next = move || rng.next_usize();
+
+        Self {
+            positions: (0..crab_count)
+                .map(|_| (next() % max_position.max(1)) as isize)
+                .collect(),
+        }
+    }
+}
+
+impl Histogram {
+    /// Counts the crabs at every position.
+    pub fn new(positions: &[isize]) -> Self {
+        let min = positions.iter().copied().min().unwrap_or(0);
+        let max = positions.iter().copied().max().unwrap_or(0);
+
+        let mut counts = vec![0; (max - min + 1) as usize];
+        for &position in positions {
+            counts[(position - min) as usize] += 1;
+        }
+
+        Self { min, counts }
+    }
 
+    /// Computes the total fuel needed to align all crabs at the provided position.
+    pub fn total_cost(&self, dest: isize, fuel_cost: fn(isize) -> isize) -> isize {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(i, &count)| count as isize * fuel_cost((self.min + i as isize - dest).abs()))
+            .sum()
+    }
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "serial" => Ok(Strategy::Serial),
+            #[cfg(feature = "parallel")]
+            "parallel" => Ok(Strategy::Parallel),
+            #[cfg(not(feature = "parallel"))]
+            "parallel" => Err("The parallel strategy requires the parallel feature.".to_string()),
+            "histogram" => Ok(Strategy::Histogram),
+            _ => Err(format!("Unknown strategy {}.", s)),
+        }
+    }
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(mut reader: impl BufRead) -> aoc_common::Result<Input> {
     let mut buf = String::new();
-    BufReader::new(file).read_to_string(&mut buf)?;
+    reader.read_to_string(&mut buf)?;
 
+    // Tolerate trailing line endings (LF or CRLF) and whitespace. Positions are bounded, such that the
+    // fuel costs of part 2 cannot overflow.
     let numbers: Vec<isize> = buf
+        .trim_end()
         .split(',')
-        .map(|x| x.parse::<isize>().expect("Expected number"))
-        .collect();
+        .map(|x| match x.trim().parse::<isize>() {
+            Ok(position) if position.abs() <= MAX_POSITION => Ok(position),
+            _ => Err(Error::at_line(
+                1,
+                format!(
+                    "Expected a position from -{0} to {0}, but found {1}.",
+                    MAX_POSITION,
+                    x.trim()
+                ),
+            )),
+        })
+        .collect::<aoc_common::Result<_>>()?;
 
-    Ok(Input {
-        positions: numbers,
-    })
+    Ok(Input { positions: numbers })
 }
 
-fn get_minimum_fuel_binary(input: &Input, fuel_cost: fn(isize) -> isize) -> isize {
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
 
-    fn get_total_cost(input: &Input, dest: isize, fuel_cost: fn(isize) -> isize) -> isize {
-        input
-            .positions
-            .iter()
-            .map(|&pos| fuel_cost((pos - dest).abs()))
-            .sum()
+/// The fuel cost of moving a crab over a distance in part 1.
+pub fn linear_fuel_cost(distance: isize) -> isize {
+    distance
+}
+
+/// The fuel cost of moving a crab over a distance in part 2.
+pub fn triangular_fuel_cost(distance: isize) -> isize {
+    distance * (distance + 1) / 2
+}
+
+/// Computes the total fuel needed to align all crabs at the provided position.
+fn get_total_cost(input: &Input, dest: isize, fuel_cost: fn(isize) -> isize) -> isize {
+    input
+        .positions
+        .iter()
+        .map(|&pos| fuel_cost((pos - dest).abs()))
+        .sum()
+}
+
+/// Computes the same result as [`get_total_cost`], but sums the costs of the crabs in parallel.
+#[cfg(feature = "parallel")]
+fn get_total_cost_parallel(input: &Input, dest: isize, fuel_cost: fn(isize) -> isize) -> isize {
+    input
+        .positions
+        .par_iter()
+        .map(|&pos| fuel_cost((pos - dest).abs()))
+        .sum()
+}
+
+/// Computes the total fuel needed to align all crabs at every position in the provided range, as a list
+/// of `(position, fuel)` pairs.
+pub fn fuel_curve(
+    input: &Input,
+    range: RangeInclusive<isize>,
+    fuel_cost: fn(isize) -> isize,
+) -> Vec<(isize, isize)> {
+    range
+        .map(|pos| (pos, get_total_cost(input, pos, fuel_cost)))
+        .collect()
+}
+
+/// Finds the cheapest position to align all crabs at, and returns it together with the fuel that is
+/// needed, as a `(position, fuel)` pair.
+pub fn get_minimum_fuel_binary(input: &Input, fuel_cost: fn(isize) -> isize) -> (isize, isize) {
+    get_minimum_fuel_with(input, fuel_cost, Strategy::Serial)
+}
+
+/// Finds the cheapest position to align all crabs at, computing the total fuel of every candidate position
+/// with the provided strategy.
+pub fn get_minimum_fuel_with(
+    input: &Input,
+    fuel_cost: fn(isize) -> isize,
+    strategy: Strategy,
+) -> (isize, isize) {
+    let start = input.positions.iter().sum::<isize>() / input.positions.len() as isize;
+
+    match strategy {
+        Strategy::Serial => descend(start, |dest| get_total_cost(input, dest, fuel_cost)),
+        #[cfg(feature = "parallel")]
+        Strategy::Parallel => descend(start, |dest| {
+            get_total_cost_parallel(input, dest, fuel_cost)
+        }),
+        Strategy::Histogram => {
+            let histogram = Histogram::new(&input.positions);
+            descend(start, |dest| histogram.total_cost(dest, fuel_cost))
+        }
     }
+}
 
+/// Walks from the provided start position towards the position with the lowest total cost, and returns it
+/// as a `(position, fuel)` pair.
+fn descend(start: isize, total_cost: impl Fn(isize) -> isize) -> (isize, isize) {
     // Key observation is that if you'd plot the total cost based on position, then you
     // get a graph where the only local minimum == the global minimum (a sink).
     //
-    //  => We can do something similar to a binary search. Start in the middle, and check 
-    //     by going left and right of the current candidate position which direction will 
+    //  => We can do something similar to a binary search. Start in the middle, and check
+    //     by going left and right of the current candidate position which direction will
     //     decrease the total cost. Stop when both will result in an increase.
 
-    let mut mid_pos = input.positions.iter().sum::<isize>() / input.positions.len() as isize;
-    let mut mid_fuel = get_total_cost(&input, mid_pos, fuel_cost);
+    let mut mid_pos = start;
+    let mut mid_fuel = total_cost(mid_pos);
 
     loop {
-        let left_fuel = get_total_cost(&input, mid_pos - 1, fuel_cost);
-        let right_fuel = get_total_cost(&input, mid_pos + 1, fuel_cost);
+        let left_fuel = total_cost(mid_pos - 1);
+        let right_fuel = total_cost(mid_pos + 1);
 
         if left_fuel < mid_fuel {
             mid_fuel = left_fuel;
@@ -55,38 +218,141 @@ fn get_minimum_fuel_binary(input: &Input, fuel_cost: fn(isize) -> isize) -> isiz
             mid_fuel = right_fuel;
             mid_pos += 1;
         } else {
-            return mid_fuel;
+            return (mid_pos, mid_fuel);
         }
     }
 }
 
 pub fn part1(input: &Input) -> isize {
-    get_minimum_fuel_binary(&input, |distance| distance)
+    get_minimum_fuel_binary(input, linear_fuel_cost).1
 }
 
 pub fn part2(input: &Input) -> isize {
-    get_minimum_fuel_binary(&input, |distance| distance * (distance + 1) / 2)
+    get_minimum_fuel_binary(input, triangular_fuel_cost).1
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day07;
+
+impl Solver for Day07 {
+    type Input = Input;
+
+    #[cfg(feature = "parallel")]
+    const STRATEGIES: &'static [&'static str] = &["serial", "parallel", "histogram"];
+    #[cfg(not(feature = "parallel"))]
+    const STRATEGIES: &'static [&'static str] = &["serial", "histogram"];
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        let strategy = aoc_common::strategy(2021, 7, Strategy::Serial);
+        (get_minimum_fuel_with(input, linear_fuel_cost, strategy).1 as usize).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        let strategy = aoc_common::strategy(2021, 7, Strategy::Serial);
+        (get_minimum_fuel_with(input, triangular_fuel_cost, strategy).1 as usize).into()
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    let mut curve = false;
+    let mut strategy = Strategy::Serial;
+    let mut generate_crabs = None;
+    let mut seed = aoc_common::seed();
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fuel-curve" => curve = true,
+            "--strategy" => {
+                strategy = args
+                    .next()
+                    .expect("Expected serial, parallel or histogram after --strategy.")
+                    .parse()
+                    .unwrap()
+            }
+            "--generate" => {
+                generate_crabs = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a crab count after --generate."),
+                )
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --seed.")
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let input = match generate_crabs {
+        Some(crab_count) => Input::generate(crab_count, 2000, seed),
+        None => parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?,
+    };
     let time_parse = now.elapsed();
+
+    if curve {
+        // Print the cost landscape of both parts as tab separated values, ready for plotting.
+        let min = *input.positions.iter().min().unwrap_or(&0);
+        let max = *input.positions.iter().max().unwrap_or(&0);
+        let curve1 = fuel_curve(&input, min..=max, linear_fuel_cost);
+        let curve2 = fuel_curve(&input, min..=max, triangular_fuel_cost);
+
+        println!("position\tfuel 1\tfuel 2");
+        for (&(position, fuel1), &(_, fuel2)) in curve1.iter().zip(curve2.iter()) {
+            println!("{}\t{}\t{}", position, fuel1, fuel2);
+        }
+
+        return Ok(());
+    }
+
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
     let now = Instant::now();
-    let result1 = part1(&input);
+    let (position1, result1) = get_minimum_fuel_with(&input, linear_fuel_cost, strategy);
     let time1 = now.elapsed();
-    println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
+    println!(
+        "Solution 1: {} at position {} (time: {}us)",
+        result1,
+        position1,
+        time1.as_micros()
+    );
 
     let now = Instant::now();
-    let result2 = part2(&input);
+    let (position2, result2) = get_minimum_fuel_with(&input, triangular_fuel_cost, strategy);
     let time2 = now.elapsed();
-    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+    println!(
+        "Solution 2: {} at position {} (time: {}us)",
+        result2,
+        position2,
+        time2.as_micros()
+    );
 
     Ok(())
 }
 
 // Parse: (time: 117us)
 // Solution 1: 348996 (time: 69us)
-// Solution 2: 98231647 (time: 5us)
\ No newline at end of file
+// Solution 2: 98231647 (time: 5us)
+//
+// Benchmarked on generated fleets (single core machine, so the parallel sum only shows the overhead of rayon):
+// part 2 (generated 1000000)/serial       time:   [13.769 ms 14.759 ms 15.603 ms]
+// part 2 (generated 1000000)/parallel     time:   [14.799 ms 15.063 ms 15.554 ms]
+// part 2 (generated 1000000)/histogram    time:   [4.2451 ms 4.2630 ms 4.2767 ms]
+// part 2 (generated 10000000)/serial      time:   [160.12 ms 163.37 ms 165.93 ms]
+// part 2 (generated 10000000)/parallel    time:   [167.74 ms 169.40 ms 170.92 ms]
+// part 2 (generated 10000000)/histogram   time:   [57.681 ms 60.057 ms 62.193 ms]
+// The histogram is dominated by counting the crabs once, after which every probe only costs one pass over
+// the 2000 positions. 10^8 crabs solve in about 0.6s per part with the histogram.
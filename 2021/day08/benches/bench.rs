@@ -6,6 +6,14 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
         b.iter(|| main::part1(black_box(&input)))
@@ -25,7 +33,29 @@ fn bench_main(c: &mut Criterion) {
         let input = main::parse_input("input.txt").unwrap();
         b.iter(|| main::part2(black_box(&input)))
     });
+
+    #[cfg(feature = "parallel")]
+    c.bench_function("part 2 (real, parallel)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::part2_parallel(black_box(&input)))
+    });
+
+    for entry_count in [1_000, 10_000, 100_000, 1_000_000] {
+        let input = main::Input::generate(entry_count, 1337);
+        // Without the parallel feature, only the serial strategy is left.
+        #[allow(clippy::single_element_loop)]
+        for (name, strategy) in [
+            ("serial", main::Strategy::Serial),
+            #[cfg(feature = "parallel")]
+            ("parallel", main::Strategy::Parallel),
+        ] {
+            c.bench_function(
+                &format!("part 2 (generated {}, {})", entry_count, name),
+                |b| b.iter(|| main::part2_with(black_box(&input), strategy)),
+            );
+        }
+    }
 }
 
 criterion_group!(benches, bench_main);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
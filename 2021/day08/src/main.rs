@@ -1,4 +1,8 @@
+use aoc_common::{byte_lines, input_path, Answer, Error, Explain, Isa, Narrator, Rng, Solver};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::{
+    fmt::Display,
     fs::File,
     io::{BufRead, BufReader},
     time::Instant,
@@ -19,11 +23,77 @@ pub struct Entry {
     outputs: [WeightedSignal; 4],
 }
 
+/// Errors that can occur while parsing an entry.
+#[derive(Debug)]
+pub enum EntryError {
+    /// Indicates the ` | ` delimiter between patterns and outputs is missing.
+    MissingDelimiter,
+
+    /// Indicates the entry did not contain exactly 10 signal patterns.
+    InvalidPatternCount(usize),
+
+    /// Indicates the entry did not contain exactly 4 output values.
+    InvalidOutputCount(usize),
+
+    /// Indicates a signal contained a character that is not a wire (`a` to `g`).
+    InvalidSignal(String),
+
+    /// Indicates the signal patterns cannot represent all 10 digits.
+    InvalidPatternSet,
+
+    /// Indicates a signal pattern occurs more than once.
+    DuplicatePattern(String),
+
+    /// Indicates the signal patterns are not the 10 digits under a single wiring.
+    InconsistentPatterns,
+
+    /// Indicates an output is not one of the signal patterns.
+    UnknownOutput(String),
+}
+
+/// Represents an input line that could not be parsed into an entry.
+#[derive(Debug)]
+pub struct SkippedLine {
+    /// The line number (starting at 1).
+    pub line: usize,
+
+    /// The reason the line was skipped.
+    pub error: EntryError,
+}
+
 /// Represents the input for the puzzle.
 pub struct Input {
     entries: Vec<Entry>,
+
+    /// The lines that were skipped because they did not contain a valid entry.
+    skipped: Vec<SkippedLine>,
 }
 
+/// Selects how the entries are processed in part 2.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Strategy {
+    /// Deduce the outputs of all entries one after the other.
+    Serial,
+
+    /// Deduce the outputs of the entries in parallel.
+    #[cfg(feature = "parallel")]
+    Parallel,
+}
+
+/// The segments that are lit for every digit, with segment `a` in the lowest bit.
+const DIGIT_SIGNALS: [Signal; 10] = [
+    0b1110111, // abcefg
+    0b0100100, // cf
+    0b1011101, // acdeg
+    0b1101101, // acdfg
+    0b0101110, // bcdf
+    0b1101011, // abdfg
+    0b1111011, // abdefg
+    0b0100101, // acf
+    0b1111111, // abcdefg
+    0b1101111, // abcdfg
+];
+
 /// A structure that keeps track of known signal patterns to their corresponding digits.
 pub struct SignalMapping {
     /// A mapping from signals to digits.
@@ -33,6 +103,55 @@ pub struct SignalMapping {
     known_signals: [Signal; 10],
 }
 
+/// The wiring of an entry, mapping every scrambled wire onto the segment it is connected to. This can decode
+/// any signal pattern that was observed with the same wiring, not just the outputs of the entry.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WireMap {
+    /// For every wire (`a` to `g`), the index of the segment it lights up.
+    segments: [u8; 7],
+}
+
+/// The reason a signal pattern was assigned to a digit during deduction.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rule {
+    /// The pattern is the only one with its number of segments (digits 1, 4, 7 and 8).
+    UniqueWeight,
+
+    /// The pattern has 6 segments, but not all segments of 1 (digit 6).
+    LacksSegmentOf1,
+
+    /// The pattern has 6 segments and all segments of 1, but not all segments of 4 (digit 0).
+    LacksSegmentOf4,
+
+    /// The pattern has 6 segments and all segments of 1 and 4 (digit 9).
+    HasSegmentsOf1And4,
+
+    /// The pattern has 5 segments and all segments of 1 (digit 3).
+    HasSegmentsOf1,
+
+    /// The pattern has 5 segments and 5 is already known, so it is the only one left (digit 2).
+    FiveIsKnown,
+
+    /// The pattern has 5 segments and shares 4 of them with 6 (digit 2).
+    SharesFourWith6,
+
+    /// The pattern has 5 segments and shares all of them with 6 (digit 5).
+    SharesFiveWith6,
+}
+
+/// A single step in the deduction of the wire configuration of an entry.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Deduction {
+    /// The signal pattern that was identified.
+    pub signal: Signal,
+
+    /// The digit the signal pattern was assigned to.
+    pub digit: usize,
+
+    /// The reason for the assignment.
+    pub rule: Rule,
+}
+
 impl SignalMapping {
     /// Initializes a new empty signal mapping.
     pub fn new() -> Self {
@@ -44,8 +163,8 @@ impl SignalMapping {
 
     /// Assigns a signal to a digit.
     pub fn assign(&mut self, signal: Signal, digit: usize) {
-        self.mapping[signal as usize] = digit as usize;
-        self.known_signals[digit as usize] = signal;
+        self.mapping[signal as usize] = digit;
+        self.known_signals[digit] = signal;
     }
 
     /// Determines whether the provided digit is already mapped to a signal pattern.
@@ -62,28 +181,132 @@ impl SignalMapping {
     }
 }
 
-impl Entry {
-    /// Parses an input entry from a string slice. 
-    /// The slice should be in the format: `<patterns> | <outputs>`.
-    pub fn from_str(s: &str) -> Self {
+impl WireMap {
+    /// Derives the wiring from a mapping in which all 10 digits are known, or returns [`None`] if the mapping
+    /// is not the result of connecting the wires to the segments one-to-one.
+    ///
+    /// Every segment is lit for a unique set of digits, so a wire is connected to the segment that is lit for
+    /// exactly the digits whose patterns contain the wire.
+    pub fn from_mapping(mapping: &SignalMapping) -> Option<Self> {
+        /// For every wire, the set of digits whose signals contain it.
+        fn digits_per_wire(signals: &[Signal; 10]) -> [u16; 7] {
+            let mut result = [0u16; 7];
+            for (digit, &signal) in signals.iter().enumerate() {
+                for (wire, digits) in result.iter_mut().enumerate() {
+                    if signal & (1 << wire) != 0 {
+                        *digits |= 1 << digit;
+                    }
+                }
+            }
+            result
+        }
+
+        let segment_digits = digits_per_wire(&DIGIT_SIGNALS);
+        let wire_digits = digits_per_wire(&mapping.known_signals);
 
-        /// Parses a single signal from a string slice.
-        fn parse_signal(s: &str) -> WeightedSignal {
-            let mut result = 0;
+        let mut segments = [0u8; 7];
+        let mut used = 0u8;
+        for (segment, digits) in segments.iter_mut().zip(wire_digits) {
+            *segment = segment_digits.iter().position(|&x| x == digits)? as u8;
+            used |= 1 << *segment;
+        }
 
-            for c in s.as_bytes() {
-                result |= 1 << (c - 97);
+        // Two wires that are lit for the same digits would be connected to the same segment.
+        (used == 0b1111111).then_some(Self { segments })
+    }
+
+    /// Derives the wiring directly from the 10 distinct patterns of an entry, without deducing which digit
+    /// every pattern shows first. Returns [`None`] if the patterns are not the 10 digits under any wiring.
+    ///
+    /// Most segments are lit in a unique number of digits: `b` in 6, `e` in 4 and `f` in 9. Segments `a` and
+    /// `c` are both lit in 8 digits, but only `c` is part of 1, and `d` and `g` are both lit in 7 digits, but
+    /// only `d` is part of 4.
+    pub fn from_patterns(patterns: &[WeightedSignal; 10]) -> Option<Self> {
+        let one = patterns.iter().find(|x| x.1 == 2)?.0;
+        let four = patterns.iter().find(|x| x.1 == 4)?.0;
+
+        let mut counts = [0u8; 7];
+        for &(signal, _) in patterns {
+            for (wire, count) in counts.iter_mut().enumerate() {
+                *count += (signal >> wire) & 1;
             }
+        }
 
-            (result, s.len())
+        let mut segments = [0u8; 7];
+        let mut used = 0u8;
+        for (wire, segment) in segments.iter_mut().enumerate() {
+            let bit = 1 << wire;
+            *segment = match (counts[wire], one & bit != 0, four & bit != 0) {
+                (8, false, _) => 0,
+                (6, _, _) => 1,
+                (8, true, _) => 2,
+                (7, _, true) => 3,
+                (4, _, _) => 4,
+                (9, _, _) => 5,
+                (7, _, false) => 6,
+                _ => return None,
+            };
+            used |= 1 << *segment;
         }
 
-        /// Parses a list of signals from a string slice.
-        fn parse_signals<const N: usize>(s: &str, buf: &mut [WeightedSignal; N]) {
-            let mut split = s.split(' ');
-            for i in 0..N {
-                buf[i] = parse_signal(split.next().expect("Expected component"));
+        // Distinct patterns that all show a digit under a one-to-one wiring show every digit once.
+        let digits = DIGIT_SIGNALS.iter().fold(0u128, |acc, &x| acc | (1 << x));
+        let wire_map = Self { segments };
+        (used == 0b1111111
+            && patterns
+                .iter()
+                .all(|x| digits & (1 << wire_map.translate(x.0)) != 0))
+        .then_some(wire_map)
+    }
+
+    /// Gets the segment the provided wire (0 for `a` to 6 for `g`) is connected to.
+    pub fn segment(&self, wire: usize) -> usize {
+        self.segments[wire] as usize
+    }
+
+    /// Decodes a signal pattern into the digit it shows, or returns [`None`] if the lit segments do not form a
+    /// digit.
+    pub fn decode(&self, signal: Signal) -> Option<u8> {
+        let segments = self.translate(signal);
+        DIGIT_SIGNALS
+            .iter()
+            .position(|&x| x == segments)
+            .map(|x| x as u8)
+    }
+
+    /// Translates a signal pattern into the segments that it lights up.
+    fn translate(&self, signal: Signal) -> Signal {
+        (0..7)
+            .filter(|&wire| signal & (1 << wire) != 0)
+            .fold(0, |acc, wire| acc | (1 << self.segments[wire]))
+    }
+}
+
+impl Default for SignalMapping {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Entry {
+    /// Parses an input entry from a string slice.
+    /// The slice should be in the format: `<patterns> | <outputs>`.
+    pub fn parse(s: &str) -> Result<Self, EntryError> {
+        /// Parses a list of signals from a string slice into the buffer, and returns the number
+        /// of signals that were found.
+        fn parse_signals<const N: usize>(
+            s: &str,
+            buf: &mut [WeightedSignal; N],
+        ) -> Result<usize, EntryError> {
+            let mut count = 0;
+            for component in s.split(' ').filter(|x| !x.is_empty()) {
+                if count < N {
+                    let signal = parse_signal(component)?;
+                    buf[count] = (signal, get_weight(signal));
+                }
+                count += 1;
             }
+            Ok(count)
         }
 
         let mut patterns = [(0u8, 0usize); 10];
@@ -91,43 +314,147 @@ impl Entry {
 
         let mut delimeter_split = s.split(" | ");
 
-        parse_signals(
-            delimeter_split.next().expect("Expected signal patterns."),
+        let pattern_count = parse_signals(
+            delimeter_split.next().ok_or(EntryError::MissingDelimiter)?,
             &mut patterns,
-        );
-        parse_signals(
-            delimeter_split.next().expect("Expected output values."),
+        )?;
+        if pattern_count != patterns.len() {
+            return Err(EntryError::InvalidPatternCount(pattern_count));
+        }
+
+        let output_count = parse_signals(
+            delimeter_split.next().ok_or(EntryError::MissingDelimiter)?,
             &mut outputs,
-        );
+        )?;
+        if output_count != outputs.len() {
+            return Err(EntryError::InvalidOutputCount(output_count));
+        }
+
+        let entry = Self { patterns, outputs };
+        entry.validate()?;
+        Ok(entry)
+    }
+
+    /// Parses an input entry directly from the bytes of a line in a single pass, without the intermediate
+    /// string splits of [`Entry::parse`]. Returns [`None`] if the line is not a valid entry, in which case
+    /// [`Entry::parse`] can be used to find out why.
+    pub fn parse_bytes(line: &[u8]) -> Option<Self> {
+        /// Reads exactly `N` space separated signals into the buffer.
+        fn read_signals<const N: usize>(s: &[u8], buf: &mut [WeightedSignal; N]) -> Option<()> {
+            let mut count = 0;
+            for component in s.split(|&b| b == b' ').filter(|x| !x.is_empty()) {
+                let mut signal = 0;
+                for &c in component {
+                    if !(b'a'..=b'g').contains(&c) {
+                        return None;
+                    }
+                    signal |= 1 << (c - b'a');
+                }
+
+                *buf.get_mut(count)? = (signal, get_weight(signal));
+                count += 1;
+            }
+            (count == N).then_some(())
+        }
+
+        let delimiter = line.windows(3).position(|x| x == b" | ")?;
+
+        let mut patterns = [(0u8, 0usize); 10];
+        let mut outputs = [(0u8, 0usize); 4];
+        read_signals(&line[..delimiter], &mut patterns)?;
+        read_signals(&line[delimiter + 3..], &mut outputs)?;
+
+        let entry = Self { patterns, outputs };
+        entry.validate().ok()?;
+        Some(entry)
+    }
+
+    /// Checks that the patterns are 10 distinct signals that show the 10 digits under a single wiring, and
+    /// that every output is one of them. Deduction relies on this, and would silently decode anything else
+    /// into wrong digits.
+    fn validate(&self) -> Result<(), EntryError> {
+        if !is_complete_pattern_set(&self.patterns) {
+            return Err(EntryError::InvalidPatternSet);
+        }
+
+        let mut seen = 0u128;
+        for &(signal, _) in self.patterns.iter() {
+            if seen & (1 << signal) != 0 {
+                return Err(EntryError::DuplicatePattern(signal_to_string(signal)));
+            }
+            seen |= 1 << signal;
+        }
+
+        if WireMap::from_patterns(&self.patterns).is_none() {
+            return Err(EntryError::InconsistentPatterns);
+        }
 
-        Self { patterns, outputs }
+        match self
+            .outputs
+            .iter()
+            .find(|&&(signal, _)| seen & (1 << signal) == 0)
+        {
+            Some(&(signal, _)) => Err(EntryError::UnknownOutput(signal_to_string(signal))),
+            None => Ok(()),
+        }
     }
 
-    /// Deduces the digits 1, 4, 7 and 8 from the configuration, and returns a list 
+    /// Deduces the digits 1, 4, 7 and 8 from the configuration, and returns a list
     /// of (partially) parsed numbers from the output.
+    #[inline]
     pub fn deduce_digits_1478(&self) -> [Option<usize>; 4] {
         let mut result = [None; 4];
-        for i in 0..result.len() {
-            result[i] = get_number_by_weight(self.outputs[i].1 as usize);
+        for (digit, output) in result.iter_mut().zip(self.outputs.iter()) {
+            *digit = get_number_by_weight(output.1);
         }
         result
     }
 
-    /// Deduces the full wire configuration, and returns the final number indicated 
+    /// Deduces the full wire configuration, and returns the final number indicated
     /// by the output digits.
+    #[inline]
     pub fn deduce_output(&self) -> usize {
+        self.deduce_mapping(|_| {}).get_number(&self.outputs)
+    }
+
+    /// Deduces the full wire configuration, and returns it as a table that can decode any signal pattern
+    /// observed with this entry's wiring. Returns [`EntryError::InconsistentPatterns`] if the deduced digits
+    /// do not follow from a single wiring.
+    pub fn wire_map(&self) -> Result<WireMap, EntryError> {
+        WireMap::from_mapping(&self.deduce_mapping(|_| {})).ok_or(EntryError::InconsistentPatterns)
+    }
+
+    /// Deduces the full wire configuration, and returns every step that was taken to get there,
+    /// in the order they were taken.
+    pub fn explain(&self) -> Vec<Deduction> {
+        let mut steps = Vec::with_capacity(self.patterns.len());
+        self.deduce_mapping(|step| steps.push(step));
+        steps
+    }
+
+    /// Deduces the full wire configuration, and reports every assignment of a signal pattern to a
+    /// digit to the provided callback.
+    fn deduce_mapping(&self, mut record: impl FnMut(Deduction)) -> SignalMapping {
         let mut mapping = SignalMapping::new();
+        let mut assign = |mapping: &mut SignalMapping, signal, digit, rule| {
+            mapping.assign(signal, digit);
+            record(Deduction {
+                signal,
+                digit,
+                rule,
+            });
+        };
 
         let mut i = 0;
         let mut j = 0;
         let mut weight5 = [0u8; 3];
         let mut weight6 = [0u8; 3];
 
-        // Find the digits 1, 4, 7, 8 first, and presort the unknown signals based on 
+        // Find the digits 1, 4, 7, 8 first, and presort the unknown signals based on
         // their hamming weight.
         for signal in self.patterns {
             match get_number_by_weight(signal.1) {
-                Some(x) => mapping.assign(signal.0, x),
+                Some(x) => assign(&mut mapping, signal.0, x, Rule::UniqueWeight),
                 None => match signal.1 {
                     5 => {
                         weight5[i] = signal.0;
@@ -142,17 +469,17 @@ impl Entry {
             };
         }
 
-        // For signals with weight 6, it can only be the digits 0, 6 or 9. 
+        // For signals with weight 6, it can only be the digits 0, 6 or 9.
         // - 6 is the only digit that does not have all segments from 1.
         // - 0 is the only one that doesn't have the middle segment, which is present in 4.
         // - 9 remains if both of these conditions are not met.
         for signal in weight6 {
             if (signal & mapping.known_signals[1]) != mapping.known_signals[1] {
-                mapping.assign(signal, 6);
+                assign(&mut mapping, signal, 6, Rule::LacksSegmentOf1);
             } else if (signal & mapping.known_signals[4]) != mapping.known_signals[4] {
-                mapping.assign(signal, 0);
+                assign(&mut mapping, signal, 0, Rule::LacksSegmentOf4);
             } else {
-                mapping.assign(signal, 9);
+                assign(&mut mapping, signal, 9, Rule::HasSegmentsOf1And4);
             }
         }
 
@@ -161,22 +488,58 @@ impl Entry {
         // - 2 has fewer segments in common with 6 than 5.
         for signal in weight5 {
             if (signal & mapping.known_signals[1]) == mapping.known_signals[1] {
-                mapping.assign(signal, 3);
-            } else if mapping.has_known_signal(5) || get_weight(signal & mapping.known_signals[6]) == 4 {
-                mapping.assign(signal, 2);
+                assign(&mut mapping, signal, 3, Rule::HasSegmentsOf1);
+            } else if mapping.has_known_signal(5) {
+                assign(&mut mapping, signal, 2, Rule::FiveIsKnown);
+            } else if get_weight(signal & mapping.known_signals[6]) == 4 {
+                assign(&mut mapping, signal, 2, Rule::SharesFourWith6);
             } else {
-                mapping.assign(signal, 5);
+                assign(&mut mapping, signal, 5, Rule::SharesFiveWith6);
             }
         }
 
-        // All digit patterns are matched, get final output.
-        mapping.get_number(&self.outputs)
+        // All digit patterns are matched.
+        mapping
     }
 }
 
-/// Computes the number of bits set in a 7-bit number.
+/// Parses a single signal pattern from the wires (`a` to `g`) that are part of it.
+pub fn parse_signal(s: &str) -> Result<Signal, EntryError> {
+    let mut result = 0;
+
+    for c in s.as_bytes() {
+        if !(b'a'..=b'g').contains(c) {
+            return Err(EntryError::InvalidSignal(s.to_string()));
+        }
+        result |= 1 << (c - 97);
+    }
+
+    Ok(result)
+}
+
+/// Determines whether the patterns consist of exactly one pattern for each of the digits 1, 4, 7 and 8, and
+/// three patterns each of weight 5 and weight 6, which deduction relies on.
+fn is_complete_pattern_set(patterns: &[WeightedSignal; 10]) -> bool {
+    let mut weight_counts = [0usize; 8];
+    for pattern in patterns {
+        weight_counts[get_weight(pattern.0)] += 1;
+    }
+    weight_counts == [0, 0, 1, 1, 1, 3, 3, 1]
+}
+
+/// Computes the number of bits set in a 7-bit number. This is a single instruction in code that is compiled
+/// for [`Isa::Avx2`], which includes POPCNT.
+#[inline(always)]
 fn get_weight(x: u8) -> usize {
-    (0..7).fold(0, |acc, i| acc + ((x >> i) & 1)) as usize
+    x.count_ones() as usize
+}
+
+/// Formats a signal pattern as the wires (`a` to `g`) that are part of it.
+fn signal_to_string(signal: Signal) -> String {
+    (0..7)
+        .filter(|i| signal & (1 << i) != 0)
+        .map(|i| (b'a' + i) as char)
+        .collect()
 }
 
 /// Guesses the digit based on the provided hamming weight. This only works for the digits 1, 4, 7 and 8 because
@@ -191,42 +554,412 @@ fn get_number_by_weight(weight: usize) -> Option<usize> {
     }
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
-    let lines = BufReader::new(file).lines();
+impl Display for EntryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryError::MissingDelimiter => write!(f, "missing ' | ' delimiter"),
+            EntryError::InvalidPatternCount(x) => write!(f, "expected 10 patterns, found {}", x),
+            EntryError::InvalidOutputCount(x) => write!(f, "expected 4 outputs, found {}", x),
+            EntryError::InvalidSignal(x) => write!(f, "invalid signal '{}'", x),
+            EntryError::InvalidPatternSet => write!(f, "patterns do not form all 10 digits"),
+            EntryError::DuplicatePattern(x) => write!(f, "pattern '{}' occurs more than once", x),
+            EntryError::InconsistentPatterns => {
+                write!(
+                    f,
+                    "patterns do not form the 10 digits under a single wiring"
+                )
+            }
+            EntryError::UnknownOutput(x) => write!(f, "output '{}' is not one of the patterns", x),
+        }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rule::UniqueWeight => write!(f, "the only pattern with this many segments"),
+            Rule::LacksSegmentOf1 => write!(f, "6 segments, missing a segment of 1"),
+            Rule::LacksSegmentOf4 => write!(f, "6 segments, all of 1, missing a segment of 4"),
+            Rule::HasSegmentsOf1And4 => write!(f, "6 segments, all of 1 and 4"),
+            Rule::HasSegmentsOf1 => write!(f, "5 segments, all of 1"),
+            Rule::FiveIsKnown => write!(f, "5 segments, not all of 1, and 5 is already known"),
+            Rule::SharesFourWith6 => write!(f, "5 segments, 4 in common with 6"),
+            Rule::SharesFiveWith6 => write!(f, "5 segments, all in common with 6"),
+        }
+    }
+}
+
+impl Display for Deduction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<7} is {} ({})",
+            signal_to_string(self.signal),
+            self.digit,
+            self.rule
+        )
+    }
+}
+
+impl Display for WireMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for wire in 0..self.segments.len() {
+            if wire > 0 {
+                write!(f, " ")?;
+            }
+            write!(
+                f,
+                "{}->{}",
+                (b'a' + wire as u8) as char,
+                (b'a' + self.segments[wire]) as char
+            )?;
+        }
+        Ok(())
+    }
+}
 
-    let entries: Vec<Entry> = lines
-        .map(|line| Entry::from_str(line.expect("Expected entry").as_str()))
-        .collect();
+impl Display for SkippedLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
 
-    Ok(Input { entries })
+impl std::str::FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "serial" => Ok(Strategy::Serial),
+            #[cfg(feature = "parallel")]
+            "parallel" => Ok(Strategy::Parallel),
+            #[cfg(not(feature = "parallel"))]
+            "parallel" => Err("The parallel strategy requires the parallel feature.".to_string()),
+            _ => Err(format!("Unknown strategy {}.", s)),
+        }
+    }
+}
+
+impl Input {
+    /// Creates a pseudo-random input with the provided number of entries, determined by the provided seed.
+    /// Every entry has its wires scrambled by a random permutation, and shows four random digits.
+    pub fn generate(entry_count: usize, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut next = move || rng.next_usize();
+
+        let mut entries = Vec::with_capacity(entry_count);
+        for _ in 0..entry_count {
+            // Fisher-Yates shuffle of the wires.
+            let mut wires = [0, 1, 2, 3, 4, 5, 6];
+            for i in (1..wires.len()).rev() {
+                wires.swap(i, next() % (i + 1));
+            }
+
+            let scramble = |signal: Signal| {
+                let scrambled = (0..7)
+                    .filter(|&i| signal & (1 << i) != 0)
+                    .fold(0, |acc, i| acc | (1 << wires[i]));
+                (scrambled, get_weight(scrambled))
+            };
+
+            let mut patterns = DIGIT_SIGNALS.map(scramble);
+            for i in (1..patterns.len()).rev() {
+                patterns.swap(i, next() % (i + 1));
+            }
+
+            let outputs = [(); 4].map(|_| scramble(DIGIT_SIGNALS[next() % 10]));
+            entries.push(Entry { patterns, outputs });
+        }
+
+        Self {
+            entries,
+            skipped: Vec::new(),
+        }
+    }
+}
+
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_input_with(file, false)
+}
+
+/// Parses the input from the provided reader. If `lenient` is set, invalid entries are skipped and reported
+/// in [`Input::skipped`]. Otherwise, the first invalid entry results in an error.
+pub fn parse_reader_with(mut reader: impl BufRead, lenient: bool) -> aoc_common::Result<Input> {
+    // Read everything at once and split it in place, rather than allocating a string for every line.
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+
+    // Trailing whitespace and Windows line endings are stripped by byte_lines.
+    for (i, line) in byte_lines(&data).enumerate() {
+        if let Some(entry) = Entry::parse_bytes(line) {
+            entries.push(entry);
+            continue;
+        }
+
+        // Invalid lines go through the string parser, which explains what is wrong with them.
+        let line = std::str::from_utf8(line)
+            .map_err(|_| Error::at_line(i + 1, "Expected a line of text."))?;
+
+        match Entry::parse(line) {
+            Ok(entry) => entries.push(entry),
+            Err(error) => {
+                let skipped_line = SkippedLine { line: i + 1, error };
+                if !lenient {
+                    return Err(Error::at_line(i + 1, skipped_line.error.to_string()));
+                }
+                skipped.push(skipped_line);
+            }
+        }
+    }
+
+    Ok(Input { entries, skipped })
+}
+
+/// Parses the input file, see [`parse_reader_with`].
+pub fn parse_input_with(file: &str, lenient: bool) -> aoc_common::Result<Input> {
+    parse_reader_with(BufReader::new(File::open(file)?), lenient)
 }
 
 pub fn part1(input: &Input) -> usize {
-    input
-        .entries
+    match aoc_common::isa() {
+        #[cfg(target_arch = "x86_64")]
+        // Safety: AVX2 is only selected if the processor supports it.
+        Isa::Avx2 => unsafe { count_digits_1478_avx2(&input.entries) },
+        _ => count_digits_1478(&input.entries),
+    }
+}
+
+#[inline(always)]
+fn count_digits_1478(entries: &[Entry]) -> usize {
+    entries
         .iter()
         .map(|e| e.deduce_digits_1478().iter().filter_map(|&x| x).count())
         .sum()
 }
 
+/// The same loop as [`count_digits_1478`], compiled with AVX2 such that the outputs of several entries are
+/// checked at once.
+///
+/// # Safety
+///
+/// The processor must support AVX2.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn count_digits_1478_avx2(entries: &[Entry]) -> usize {
+    count_digits_1478(entries)
+}
+
 pub fn part2(input: &Input) -> usize {
-    input.entries.iter().map(|e| e.deduce_output()).sum()
+    match aoc_common::isa() {
+        #[cfg(target_arch = "x86_64")]
+        // Safety: AVX2 and POPCNT are only selected if the processor supports them.
+        Isa::Avx2 => unsafe { sum_outputs_avx2(&input.entries) },
+        _ => sum_outputs(&input.entries),
+    }
+}
+
+#[inline(always)]
+fn sum_outputs(entries: &[Entry]) -> usize {
+    entries.iter().map(|e| e.deduce_output()).sum()
+}
+
+/// The same loop as [`sum_outputs`], compiled with AVX2 and POPCNT such that the weights of the signals that
+/// share segments are counted with a single instruction.
+///
+/// # Safety
+///
+/// The processor must support AVX2 and POPCNT.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,popcnt")]
+unsafe fn sum_outputs_avx2(entries: &[Entry]) -> usize {
+    sum_outputs(entries)
+}
+
+/// Computes the same result as [`part2`], but deduces the outputs of the entries in parallel.
+#[cfg(feature = "parallel")]
+pub fn part2_parallel(input: &Input) -> usize {
+    input.entries.par_iter().map(|e| e.deduce_output()).sum()
+}
+
+/// Computes the result of part 2 using the provided strategy.
+pub fn part2_with(input: &Input, strategy: Strategy) -> usize {
+    match strategy {
+        Strategy::Serial => part2(input),
+        #[cfg(feature = "parallel")]
+        Strategy::Parallel => part2_parallel(input),
+    }
+}
+
+/// Describes the deduction steps of every entry in the input, followed by the decoded wiring and the number
+/// shown on its outputs.
+pub fn explain(input: &Input, sink: &mut dyn Explain) -> std::io::Result<()> {
+    for (index, entry) in input.entries.iter().enumerate() {
+        aoc_common::explain!(sink, "Entry {}:", index + 1)?;
+        for step in entry.explain() {
+            aoc_common::explain!(sink, "  {}", step)?;
+        }
+
+        match entry.wire_map() {
+            Ok(wire_map) => aoc_common::explain!(sink, "  Wiring: {}", wire_map)?,
+            Err(error) => aoc_common::explain!(sink, "  Wiring: {}", error)?,
+        }
+        aoc_common::explain!(
+            sink,
+            "  Output: {} = {}",
+            entry
+                .outputs
+                .map(|output| signal_to_string(output.0))
+                .join(" "),
+            entry.deduce_output()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Decodes an additional signal pattern with the wiring of the provided entry (starting at 1), and prints the
+/// digit it shows.
+fn print_decoded(input: &Input, entry: usize, pattern: &str) {
+    let wire_map = match entry.checked_sub(1).and_then(|x| input.entries.get(x)) {
+        Some(entry) => entry.wire_map(),
+        None => {
+            println!("Entry {}: no such entry.", entry);
+            return;
+        }
+    };
+    let wire_map = match wire_map {
+        Ok(wire_map) => wire_map,
+        Err(error) => {
+            println!("Entry {}: {}", entry, error);
+            return;
+        }
+    };
+
+    match parse_signal(pattern).map(|signal| wire_map.decode(signal)) {
+        Ok(Some(digit)) => println!("Entry {}: {} = {}", entry, pattern, digit),
+        Ok(None) => println!("Entry {}: {} is not a digit", entry, pattern),
+        Err(error) => println!("Entry {}: {}", entry, error),
+    }
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day08;
+
+impl Solver for Day08 {
+    type Input = Input;
+
+    #[cfg(feature = "parallel")]
+    const STRATEGIES: &'static [&'static str] = &["serial", "parallel"];
+    #[cfg(not(feature = "parallel"))]
+    const STRATEGIES: &'static [&'static str] = &["serial"];
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader_with(input.as_bytes(), false)
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2_with(input, aoc_common::strategy(2021, 8, Strategy::Serial)).into()
+    }
+
+    fn explain(input: &Input, sink: &mut dyn Explain) -> std::io::Result<()> {
+        explain(input, sink)
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    let mut lenient = false;
+    let mut explain = false;
+    let mut strategy = Strategy::Serial;
+    let mut generate_entries = None;
+    let mut decode = Vec::new();
+    let mut seed = aoc_common::seed();
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lenient" => lenient = true,
+            "--explain" => explain = true,
+            "--strategy" => {
+                strategy = args
+                    .next()
+                    .expect("Expected serial or parallel after --strategy.")
+                    .parse()
+                    .unwrap()
+            }
+            "--generate" => {
+                generate_entries = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected an entry count after --generate."),
+                )
+            }
+            "--decode" => {
+                let entry = args
+                    .next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .expect("Expected an entry number after --decode.");
+                let pattern = args
+                    .next()
+                    .expect("Expected a signal pattern after the entry number of --decode.");
+                decode.push((entry, pattern));
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --seed.")
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    // Querying the processor is slow the first time, which should not count towards any phase.
+    aoc_common::isa();
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let input = match generate_entries {
+        Some(entry_count) => Input::generate(entry_count, seed),
+        None => parse_input_with(&file.unwrap_or_else(|| input_path("input.txt")), lenient)?,
+    };
     let time_parse = now.elapsed();
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
+    if !input.skipped.is_empty() {
+        println!("Skipped {} invalid entries:", input.skipped.len());
+        for skipped in input.skipped.iter() {
+            println!("  {}", skipped);
+        }
+    }
+
+    if explain {
+        return self::explain(&input, &mut Narrator::new(std::io::stdout().lock()));
+    }
+
+    if !decode.is_empty() {
+        for (entry, pattern) in decode {
+            print_decoded(&input, entry, &pattern);
+        }
+        return Ok(());
+    }
+
     let now = Instant::now();
     let result1 = part1(&input);
     let time1 = now.elapsed();
     println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
 
     let now = Instant::now();
-    let result2 = part2(&input);
+    let result2 = part2_with(&input, strategy);
     let time2 = now.elapsed();
     println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
 
@@ -236,3 +969,20 @@ fn main() -> std::io::Result<()> {
 // Parse: (time: 262us)
 // Solution 1: 416 (time: 0us)
 // Solution 2: 1043697 (time: 28us)
+//
+// Benchmarked on generated inputs (single core machine, so this only shows the overhead of rayon):
+// part 2 (generated 1000, serial)       time:   [90.741 us 91.521 us 92.380 us]
+// part 2 (generated 1000, parallel)     time:   [94.904 us 95.975 us 97.031 us]
+// part 2 (generated 10000, serial)      time:   [1.1673 ms 1.1794 ms 1.1914 ms]
+// part 2 (generated 10000, parallel)    time:   [1.1954 ms 1.2019 ms 1.2086 ms]
+// part 2 (generated 100000, serial)     time:   [10.880 ms 10.976 ms 11.078 ms]
+// part 2 (generated 100000, parallel)   time:   [10.682 ms 10.792 ms 10.916 ms]
+// part 2 (generated 1000000, serial)    time:   [118.32 ms 119.24 ms 120.44 ms]
+// part 2 (generated 1000000, parallel)  time:   [115.36 ms 115.93 ms 116.51 ms]
+// With a single worker, rayon only adds overhead, which disappears in the noise from around 100k entries
+// on. Where the parallel version starts to pay off on multiple cores is undetermined, as these numbers say
+// nothing about it.
+//
+// Parsing entries from bytes in a single pass, without a string per line:
+// parse (real), before    time:   [142.69 µs 147.64 µs 153.17 µs]
+// parse (real), after     time:   [88.804 µs 92.568 µs 96.404 µs]
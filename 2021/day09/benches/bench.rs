@@ -6,6 +6,14 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
         b.iter(|| main::part1(black_box(&input)))
@@ -25,6 +33,41 @@ fn bench_main(c: &mut Criterion) {
         let input = main::parse_input("input.txt").unwrap();
         b.iter(|| main::part2(black_box(&input)))
     });
+
+    c.bench_function("part 2 (generated 1000x1000)", |b| {
+        let map = main::HeightMap::generate(1000, 1000, 1337);
+        b.iter(|| main::multiply_largest_basins(black_box(&map), main::Connectivity::Orthogonal))
+    });
+
+    c.bench_function("part 1 (real, array grid)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        let map = main::ArrayGrid::<100, 100>::from_map(&input.map).unwrap();
+        b.iter(|| main::sum_risk_levels(black_box(&map), main::Connectivity::Orthogonal))
+    });
+
+    c.bench_function("part 2 (real, array grid)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        let map = main::ArrayGrid::<100, 100>::from_map(&input.map).unwrap();
+        b.iter(|| main::multiply_largest_basins(black_box(&map), main::Connectivity::Orthogonal))
+    });
+
+    for (name, edge_policy) in [
+        ("clamp", main::EdgePolicy::Clamp),
+        ("wrap", main::EdgePolicy::Wrap),
+        ("constant", main::EdgePolicy::Constant(9)),
+    ] {
+        c.bench_function(&format!("gaussian 3x3 (real, {})", name), |b| {
+            let input = main::parse_input("input.txt").unwrap();
+            let kernel = main::Kernel::gaussian();
+            b.iter(|| main::convolve(black_box(&input.map), &kernel, edge_policy))
+        });
+
+        c.bench_function(&format!("box 5x5 (generated 1000x1000, {})", name), |b| {
+            let map = main::HeightMap::generate(1000, 1000, 1337);
+            let kernel = main::Kernel::box_blur(5);
+            b.iter(|| main::convolve(black_box(&map), &kernel, edge_policy))
+        });
+    }
 }
 
 criterion_group!(benches, bench_main);
@@ -1,131 +1,120 @@
+use aoc_common::{input_path, top_k, Answer, Budget, Error, GridFormatter, Point, Rng, Solver};
 use std::{
     fmt::Display,
     fs::File,
     io::{BufRead, BufReader},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
-const MAP_WIDTH: usize = 100;
-const MAP_HEIGHT: usize = 100;
 const MAX_HEIGHT: u8 = 9;
 
-/// Represents a position within a height map.
-#[derive(Clone, Copy)]
-pub struct Vector2(usize, usize);
+/// The width of the height map in the official puzzle input.
+pub const ARRAY_MAP_WIDTH: usize = 100;
 
-/// Represents a height map in the form of u8 elements.
-pub struct HeightMap {
-    grid: [u8; MAP_WIDTH * MAP_HEIGHT],
-}
+/// The height of the height map in the official puzzle input.
+pub const ARRAY_MAP_HEIGHT: usize = 100;
 
-/// Provides heights for neighbouring cells.
-pub struct Neighbours {
-    pub top: u8,
-    pub right: u8,
-    pub bottom: u8,
-    pub left: u8,
-}
+/// Provides access to the heights in a height map, independent of how the heights are stored.
+pub trait HeightGrid {
+    /// Gets the width of the map.
+    fn width(&self) -> usize;
 
-/// Represents the input for the puzzle.
-pub struct Input {
-    map: HeightMap,
-}
+    /// Gets the height of the map.
+    fn height(&self) -> usize;
 
-impl Vector2 {
-    /// Translates the index into a position on a height map.
-    pub fn from_index(index: usize) -> Self {
-        Self(index % MAP_WIDTH, index / MAP_WIDTH)
-    }
-
-    /// Translates the position into an index within the raw grid of a height map.
-    pub fn to_index(&self) -> usize {
-        self.1 * MAP_WIDTH + self.0
-    }
-}
+    /// Gets the height at the provided position.
+    fn get(&self, location: Point) -> u8;
 
-impl HeightMap {
-    /// Creates a new height map, that is initialized with the max height on every cell.
-    pub fn new() -> Self {
-        Self {
-            grid: [MAX_HEIGHT; MAP_WIDTH * MAP_HEIGHT],
-        }
+    /// Translates the position into an index of a cell in the map.
+    fn index_of(&self, location: Point) -> usize {
+        location.1 * self.width() + location.0
     }
 
-    /// Gets the height at the provided position.
-    pub fn get(&self, location: Vector2) -> u8 {
-        self.grid[location.to_index()]
+    /// Translates the index of a cell in the map into a position.
+    fn position_of(&self, index: usize) -> Point {
+        Point(index % self.width(), index / self.width())
     }
 
-    /// Updates the height at the provided position.
-    pub fn set(&mut self, location: Vector2, height: u8) {
-        self.grid[location.to_index()] = height;
+    /// Gets the positions and heights of all neighbours of the provided location that are within
+    /// the bounds of the map.
+    fn get_neighbours(
+        &self,
+        location: Point,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = (Point, u8)> + '_ {
+        connectivity.offsets().iter().filter_map(move |&(dx, dy)| {
+            let x = location
+                .0
+                .checked_add_signed(dx)
+                .filter(|&x| x < self.width())?;
+            let y = location
+                .1
+                .checked_add_signed(dy)
+                .filter(|&y| y < self.height())?;
+            let neighbour = Point(x, y);
+            Some((neighbour, self.get(neighbour)))
+        })
     }
 
-    /// Gets the heights of all neighbours of the provided location.
-    pub fn get_neighbours(&self, location: Vector2) -> Neighbours {
-        let top = if location.1 > 0 {
-            self.get(Vector2(location.0, location.1 - 1))
-        } else {
-            MAX_HEIGHT
-        };
-
-        let right = if location.0 < MAP_WIDTH - 1 {
-            self.get(Vector2(location.0 + 1, location.1))
-        } else {
-            MAX_HEIGHT
-        };
+    /// Determines whether the provided location is a low point.
+    fn is_low_point(&self, location: Point, connectivity: Connectivity) -> bool {
+        let height = self.get(location);
+        if height == MAX_HEIGHT {
+            return false;
+        }
 
-        let bottom = if location.1 < MAP_HEIGHT - 1 {
-            self.get(Vector2(location.0, location.1 + 1))
-        } else {
-            MAX_HEIGHT
-        };
+        self.get_neighbours(location, connectivity)
+            .all(|(_, neighbour)| height < neighbour)
+    }
 
-        let left = if location.0 > 0 {
-            self.get(Vector2(location.0 - 1, location.1))
-        } else {
-            MAX_HEIGHT
-        };
+    /// Gets the neighbour that water flows to from the provided location, following the steepest descent.
+    /// This is the lowest neighbour that is strictly lower than the location itself. Ties are broken by
+    /// taking the first neighbour in the order of [`Connectivity::offsets`]. Returns [`None`] if no
+    /// neighbour is lower, i.e. the location is a low point (or the bottom of a plateau).
+    fn drain_step(&self, location: Point, connectivity: Connectivity) -> Option<Point> {
+        let height = self.get(location);
+        self.get_neighbours(location, connectivity)
+            .filter(|&(_, neighbour)| neighbour < height)
+            .min_by_key(|&(_, neighbour)| neighbour)
+            .map(|(neighbour, _)| neighbour)
+    }
 
-        Neighbours {
-            top,
-            right,
-            bottom,
-            left,
+    /// Follows the steepest descent from the provided location until no lower neighbour is left, and
+    /// returns all visited positions, starting with the location itself. Cells with the value
+    /// [`MAX_HEIGHT`] are not part of any basin, and only produce the location itself.
+    fn drain_path(&self, from: Point, connectivity: Connectivity) -> Vec<Point> {
+        let mut path = vec![from];
+        if self.get(from) == MAX_HEIGHT {
+            return path;
         }
-    }
 
-    /// Determines whether the provided location is a low point.
-    pub fn is_low_point(&self, location: Vector2) -> bool {
-        let height = self.get(location);
-        if height == MAX_HEIGHT {
-            return false;
+        // Every step goes strictly down, so this always terminates.
+        let mut current = from;
+        while let Some(next) = self.drain_step(current, connectivity) {
+            path.push(next);
+            current = next;
         }
 
-        let neighbours = self.get_neighbours(location);
-        height < neighbours.top
-            && height < neighbours.right
-            && height < neighbours.bottom
-            && height < neighbours.left
+        path
     }
 
     /// Computes the risk level for the provided risk level.
-    pub fn get_risk_level(&self, location: Vector2) -> usize {
+    fn get_risk_level(&self, location: Point) -> usize {
         (self.get(location) + 1) as usize
     }
 
     /// Computes the size of the basin, starting at the provided location.
     /// This location does NOT have to be a low point. It returns [`None`] if the
     /// cell was already visited or if the cell has the value [`MAX_HEIGHT`].
-    pub fn get_basin_size(
+    fn get_basin_size(
         &self,
-        location: Vector2,
+        location: Point,
+        connectivity: Connectivity,
         visited: &mut [bool],
-        agenda: &mut Vec<Vector2>,
+        agenda: &mut Vec<Point>,
     ) -> Option<usize> {
-
         // Short circuit if possible.
-        if visited[location.to_index()] || self.get(location) == MAX_HEIGHT {
+        if visited[self.index_of(location)] || self.get(location) == MAX_HEIGHT {
             return None;
         }
 
@@ -133,9 +122,8 @@ impl HeightMap {
 
         // Perform DFS.
         agenda.push(location);
-        while !agenda.is_empty() {
-            let location = agenda.pop().unwrap();
-            let index = location.to_index();
+        while let Some(location) = agenda.pop() {
+            let index = self.index_of(location);
 
             if visited[index] {
                 continue;
@@ -144,112 +132,562 @@ impl HeightMap {
             visited[index] = true;
             size += 1;
 
-            let neighbours = self.get_neighbours(location);
-            if neighbours.left != MAX_HEIGHT {
-                agenda.push(Vector2(location.0 - 1, location.1))
-            }
-            if neighbours.right != MAX_HEIGHT {
-                agenda.push(Vector2(location.0 + 1, location.1))
-            }
-            if neighbours.top != MAX_HEIGHT {
-                agenda.push(Vector2(location.0, location.1 - 1))
-            }
-            if neighbours.bottom != MAX_HEIGHT {
-                agenda.push(Vector2(location.0, location.1 + 1))
-            }
+            agenda.extend(
+                self.get_neighbours(location, connectivity)
+                    .filter(|&(_, height)| height != MAX_HEIGHT)
+                    .map(|(neighbour, _)| neighbour),
+            );
         }
 
         Some(size)
     }
 }
 
-impl Display for HeightMap {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..MAP_HEIGHT {
-            for x in 0..MAP_WIDTH {
-                write!(f, "{}", self.get(Vector2(x, y)))?;
-            }
-            writeln!(f)?;
+/// Represents a height map in the form of u8 elements, with a size determined at run-time.
+pub struct HeightMap {
+    width: usize,
+    height: usize,
+    grid: Vec<u8>,
+}
+
+/// Represents a height map in the form of u8 elements, with a size determined at compile-time.
+pub struct ArrayGrid<const W: usize, const H: usize> {
+    grid: [[u8; W]; H],
+}
+
+/// Selects how the height map is stored while solving the puzzle.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GridStrategy {
+    /// Use the [`HeightMap`] as parsed, sized at run-time.
+    Dynamic,
+
+    /// Copy the map into an [`ArrayGrid`] sized for the official puzzle input.
+    Array,
+}
+
+/// Describes which cells are considered adjacent to each other.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Connectivity {
+    /// Cells share an edge (up, down, left, right).
+    Orthogonal,
+
+    /// Cells share an edge or a corner.
+    EightWay,
+
+    /// Cells are hexagons laid out in axial coordinates, where X is the `q` axis and Y the `r` axis.
+    HexAxial,
+}
+
+/// A square matrix of weights that can be slid over a height map, with the center of the matrix on
+/// the cell that is being computed.
+pub struct Kernel {
+    /// The width and height of the kernel. This is always an odd number.
+    size: usize,
+
+    /// The weights in the kernel, stored row by row.
+    weights: Vec<f64>,
+}
+
+/// Describes what height is used for cells outside of the map during a convolution.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EdgePolicy {
+    /// Use the height of the nearest cell on the border of the map.
+    Clamp,
+
+    /// Wrap around to the opposite side of the map.
+    Wrap,
+
+    /// Use a fixed height.
+    Constant(u8),
+}
+
+/// Represents the input for the puzzle.
+pub struct Input {
+    pub map: HeightMap,
+}
+
+impl Connectivity {
+    /// Gets the relative offsets of all cells adjacent to a cell.
+    pub fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Orthogonal => &[(0, -1), (1, 0), (0, 1), (-1, 0)],
+            Connectivity::EightWay => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+            ],
+            Connectivity::HexAxial => &[(0, -1), (1, -1), (1, 0), (0, 1), (-1, 1), (-1, 0)],
+        }
+    }
+}
+
+impl std::str::FromStr for GridStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dynamic" => Ok(GridStrategy::Dynamic),
+            "array" => Ok(GridStrategy::Array),
+            _ => Err(format!("Unknown grid strategy {}.", s)),
+        }
+    }
+}
+
+impl std::str::FromStr for Connectivity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "orthogonal" => Ok(Connectivity::Orthogonal),
+            "8-way" => Ok(Connectivity::EightWay),
+            "hex" => Ok(Connectivity::HexAxial),
+            _ => Err(format!("Unknown connectivity {}.", s)),
+        }
+    }
+}
+
+impl HeightMap {
+    /// Creates a new height map, that is initialized with the max height on every cell.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            grid: vec![MAX_HEIGHT; width * height],
+        }
+    }
+
+    /// Creates a new height map of the provided size with pseudo-random heights, determined by the
+    /// provided seed.
+    pub fn generate(width: usize, height: usize, seed: u64) -> Self {
+        let mut result = Self::new(width, height);
+
+        let mut rng = Rng::new(seed);
+        for cell in result.grid.iter_mut() {
+            *cell = (rng.next_u64() % (MAX_HEIGHT as u64 + 1)) as u8;
+        }
+
+        result
+    }
+
+    /// Updates the height at the provided position.
+    pub fn set(&mut self, location: Point, height: u8) {
+        let index = self.index_of(location);
+        self.grid[index] = height;
+    }
+}
+
+impl HeightGrid for HeightMap {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get(&self, location: Point) -> u8 {
+        self.grid[self.index_of(location)]
+    }
+}
+
+impl<const W: usize, const H: usize> ArrayGrid<W, H> {
+    /// Copies the heights of the provided map into a new grid. Returns [`None`] if the map is not
+    /// exactly of size `W` by `H`.
+    pub fn from_map(map: &HeightMap) -> Option<Self> {
+        if map.width() != W || map.height() != H {
+            return None;
+        }
+
+        let mut grid = [[MAX_HEIGHT; W]; H];
+        for (y, row) in grid.iter_mut().enumerate() {
+            row.copy_from_slice(&map.grid[y * W..(y + 1) * W]);
         }
 
-        Ok(())
+        Some(Self { grid })
     }
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
-    let lines = BufReader::new(file).lines();
+impl<const W: usize, const H: usize> HeightGrid for ArrayGrid<W, H> {
+    fn width(&self) -> usize {
+        W
+    }
+
+    fn height(&self) -> usize {
+        H
+    }
+
+    fn get(&self, location: Point) -> u8 {
+        self.grid[location.1][location.0]
+    }
+}
+
+impl HeightMap {
+    /// Computes a smoothed copy of the height map by convolving it with the provided kernel. Cells
+    /// outside of the map take the height of the nearest cell on the border. The result is stored
+    /// row by row.
+    pub fn smooth(&self, kernel: &Kernel) -> Vec<f64> {
+        convolve(self, kernel, EdgePolicy::Clamp)
+    }
+
+    /// Computes the gradient `(dx, dy)` of every cell using central differences, stored row by row.
+    /// Cells on the border use the nearest cell inside the map for their missing neighbour.
+    pub fn gradient(&self) -> Vec<(f64, f64)> {
+        let dx = convolve(self, &Kernel::central_difference_x(), EdgePolicy::Clamp);
+        let dy = convolve(self, &Kernel::central_difference_y(), EdgePolicy::Clamp);
+        dx.into_iter().zip(dy).collect()
+    }
+}
+
+impl Kernel {
+    /// Creates a new kernel from the provided weights, stored row by row. The number of weights must be
+    /// the square of an odd number.
+    pub fn new(weights: Vec<f64>) -> Self {
+        let size = (weights.len() as f64).sqrt() as usize;
+        assert!(
+            size * size == weights.len() && size % 2 == 1,
+            "A kernel must be a square with an odd size."
+        );
+        Self { size, weights }
+    }
+
+    /// Creates a kernel of the provided (odd) size that averages all cells it covers.
+    pub fn box_blur(size: usize) -> Self {
+        let count = size * size;
+        Self::new(vec![1.0 / count as f64; count])
+    }
+
+    /// Creates a 3x3 kernel that approximates a gaussian blur.
+    pub fn gaussian() -> Self {
+        Self::new(
+            [1.0, 2.0, 1.0, 2.0, 4.0, 2.0, 1.0, 2.0, 1.0]
+                .iter()
+                .map(|x| x / 16.0)
+                .collect(),
+        )
+    }
+
+    /// Creates a 3x3 kernel that computes the central difference along the X axis.
+    pub fn central_difference_x() -> Self {
+        Self::new(vec![0.0, 0.0, 0.0, -0.5, 0.0, 0.5, 0.0, 0.0, 0.0])
+    }
+
+    /// Creates a 3x3 kernel that computes the central difference along the Y axis.
+    pub fn central_difference_y() -> Self {
+        Self::new(vec![0.0, -0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0])
+    }
+
+    /// Gets the width and height of the kernel.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Gets the weight at the provided offset from the center of the kernel.
+    pub fn get(&self, dx: isize, dy: isize) -> f64 {
+        let radius = (self.size / 2) as isize;
+        self.weights[((dy + radius) * self.size as isize + dx + radius) as usize]
+    }
+}
+
+impl EdgePolicy {
+    /// Gets the height at the provided position, which may lie outside of the map.
+    pub fn sample(&self, map: &impl HeightGrid, x: isize, y: isize) -> u8 {
+        let width = map.width() as isize;
+        let height = map.height() as isize;
+
+        if (0..width).contains(&x) && (0..height).contains(&y) {
+            return map.get(Point(x as usize, y as usize));
+        }
+
+        match self {
+            EdgePolicy::Clamp => map.get(Point(
+                x.clamp(0, width - 1) as usize,
+                y.clamp(0, height - 1) as usize,
+            )),
+            EdgePolicy::Wrap => map.get(Point(
+                x.rem_euclid(width) as usize,
+                y.rem_euclid(height) as usize,
+            )),
+            EdgePolicy::Constant(value) => *value,
+        }
+    }
+}
+
+impl std::str::FromStr for EdgePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clamp" => Ok(EdgePolicy::Clamp),
+            "wrap" => Ok(EdgePolicy::Wrap),
+            _ => s
+                .strip_prefix("constant:")
+                .and_then(|x| x.parse::<u8>().ok())
+                .map(EdgePolicy::Constant)
+                .ok_or_else(|| format!("Unknown edge policy {}.", s)),
+        }
+    }
+}
+
+/// Slides the kernel over every cell of the map, and computes the weighted sum of the heights it
+/// covers. The kernel is applied as-is (it is not flipped). Cells outside of the map are looked up
+/// according to the edge policy. The result is stored row by row.
+pub fn convolve(map: &impl HeightGrid, kernel: &Kernel, edge_policy: EdgePolicy) -> Vec<f64> {
+    let radius = (kernel.size() / 2) as isize;
+    let mut result = Vec::with_capacity(map.width() * map.height());
+
+    for y in 0..map.height() as isize {
+        for x in 0..map.width() as isize {
+            let mut sum = 0.0;
+
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let height = edge_policy.sample(map, x + dx, y + dy);
+                    sum += kernel.get(dx, dy) * height as f64;
+                }
+            }
+
+            result.push(sum);
+        }
+    }
+
+    result
+}
+
+impl Display for HeightMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        GridFormatter::values(self.width, self.height, |x, y| self.get(Point(x, y))).fmt(f)
+    }
+}
 
-    let mut map = HeightMap::new();
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let lines = reader
+        .lines()
+        .map(|line| line.map(|x| x.trim_end().to_string()))
+        .collect::<std::io::Result<Vec<String>>>()?;
+
+    let width = lines.first().map_or(0, |x| x.len());
+    let mut map = HeightMap::new(width, lines.len());
+
+    for (y, line) in lines.iter().enumerate() {
+        if line.len() != width {
+            return Err(Error::at_line(
+                y + 1,
+                format!(
+                    "Expected a row of {} heights, but found {}.",
+                    width,
+                    line.len()
+                ),
+            ));
+        }
 
-    lines.enumerate().for_each(|(y, line)| {
-        line.expect("Expected height map line")
-            .as_bytes()
-            .iter()
-            .map(|&b| b - 0x30)
-            .enumerate()
-            .for_each(|(x, h)| map.set(Vector2(x, y), h));
-    });
+        for (x, &b) in line.as_bytes().iter().enumerate() {
+            if !b.is_ascii_digit() {
+                return Err(Error::at_column(
+                    y + 1,
+                    x + 1,
+                    format!("Expected a height, but found '{}'.", b.escape_ascii()),
+                ));
+            }
+            map.set(Point(x, y), b - b'0');
+        }
+    }
 
     Ok(Input { map })
 }
 
-pub fn part1(input: &Input) -> usize {
-    (0..MAP_HEIGHT).map(|y| {
-        (0..MAP_WIDTH)
-            .filter_map(|x| {
-                let pos = Vector2(x, y);
-                if input.map.is_low_point(pos) {
-                    Some(input.map.get_risk_level(pos))
-                } else {
-                    None
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Renders the map with an arrow on every cell pointing in the direction water drains to, see
+/// [`HeightGrid::drain_step`]. Cells without a lower neighbour show their height, and cells with the value
+/// [`MAX_HEIGHT`] are shown as `#`.
+pub fn render_drainage(map: &impl HeightGrid, connectivity: Connectivity) -> String {
+    GridFormatter::custom(map.width(), map.height(), |x, y| {
+        let location = Point(x, y);
+        let height = map.get(location);
+        if height == MAX_HEIGHT {
+            return '#';
+        }
+
+        match map.drain_step(location, connectivity) {
+            None => (b'0' + height) as char,
+            Some(next) => {
+                let dx = next.0 as isize - location.0 as isize;
+                let dy = next.1 as isize - location.1 as isize;
+                match (dx, dy) {
+                    (0, -1) => '↑',
+                    (1, -1) => '↗',
+                    (1, 0) => '→',
+                    (1, 1) => '↘',
+                    (0, 1) => '↓',
+                    (-1, 1) => '↙',
+                    (-1, 0) => '←',
+                    _ => '↖',
                 }
-            })
-            .sum::<usize>()
+            }
+        }
     })
-    .sum()
+    .to_string()
+}
+
+/// Sums the risk levels of all low points in the map.
+pub fn sum_risk_levels(map: &impl HeightGrid, connectivity: Connectivity) -> usize {
+    (0..map.height())
+        .map(|y| {
+            (0..map.width())
+                .filter_map(|x| {
+                    let pos = Point(x, y);
+                    if map.is_low_point(pos, connectivity) {
+                        Some(map.get_risk_level(pos))
+                    } else {
+                        None
+                    }
+                })
+                .sum::<usize>()
+        })
+        .sum()
+}
+
+/// Multiplies the sizes of the three largest basins in the map.
+pub fn multiply_largest_basins(map: &impl HeightGrid, connectivity: Connectivity) -> usize {
+    let cell_count = map.width() * map.height();
+    let mut visited = vec![false; cell_count];
+    let mut agenda = Vec::with_capacity(cell_count);
+
+    let mut sizes: Vec<usize> = (0..cell_count)
+        .filter_map(|i| {
+            map.get_basin_size(map.position_of(i), connectivity, &mut visited, &mut agenda)
+        })
+        .collect();
+
+    // Maps with fewer than three basins count the missing basins as empty.
+    top_k(&mut sizes, 3).map_or(0, |top| top.iter().product())
+}
+
+pub fn part1(input: &Input) -> usize {
+    sum_risk_levels(&input.map, Connectivity::Orthogonal)
 }
 
 pub fn part2(input: &Input) -> usize {
-    let mut visited = [false; MAP_WIDTH * MAP_HEIGHT];
-    let mut agenda = Vec::with_capacity(MAP_WIDTH * MAP_HEIGHT);
-    let mut top = [0usize; 3];
+    multiply_largest_basins(&input.map, Connectivity::Orthogonal)
+}
 
-    (0..MAP_WIDTH * MAP_HEIGHT)
-        .filter_map(|i|
-            input.map.get_basin_size(Vector2::from_index(i), &mut visited, &mut agenda)
-        )
-        .for_each(|size| {
-            if size >= top[0] {
-                top[2] = top[1];
-                top[1] = top[0];
-                top[0] = size;
-            } else if size >= top[1] {
-                top[2] = top[1];
-                top[1] = size;
-            } else if size > top[2] {
-                top[2] = size;
-            }
-        });
+/// The solution of this day, as it is registered with the runner.
+pub struct Day09;
+
+impl Solver for Day09 {
+    type Input = Input;
 
-    top.iter().product()
+    const BUDGET: Budget = Budget {
+        parse: None,
+        part1: Some(Duration::from_micros(500)),
+        part2: Some(Duration::from_millis(3)),
+    };
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    let mut connectivity = Connectivity::Orthogonal;
+    let mut strategy = GridStrategy::Dynamic;
+    let mut drainage = false;
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--connectivity" => {
+                connectivity = args
+                    .next()
+                    .expect("Expected orthogonal, 8-way or hex after --connectivity.")
+                    .parse()
+                    .unwrap()
+            }
+            "--grid" => {
+                strategy = args
+                    .next()
+                    .expect("Expected dynamic or array after --grid.")
+                    .parse()
+                    .unwrap()
+            }
+            "--drainage" => drainage = true,
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
     let time_parse = now.elapsed();
+
+    if drainage {
+        print!("{}", render_drainage(&input.map, connectivity));
+        return Ok(());
+    }
+
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
+    match strategy {
+        GridStrategy::Dynamic => solve(&input.map, connectivity),
+        GridStrategy::Array => {
+            let map = ArrayGrid::<ARRAY_MAP_WIDTH, ARRAY_MAP_HEIGHT>::from_map(&input.map)
+                .expect("The array grid strategy requires a 100x100 map.");
+            solve(&map, connectivity)
+        }
+    }
+
+    Ok(())
+}
+
+/// Solves both parts using the provided height map, and prints the results.
+fn solve(map: &impl HeightGrid, connectivity: Connectivity) {
     let now = Instant::now();
-    let result1 = part1(&input);
+    let result1 = sum_risk_levels(map, connectivity);
     let time1 = now.elapsed();
     println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
 
     let now = Instant::now();
-    let result2 = part2(&input);
+    let result2 = multiply_largest_basins(map, connectivity);
     let time2 = now.elapsed();
     println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
-
-    Ok(())
 }
+
+// Benchmarked (run-time sized HeightMap vs. compile-time sized ArrayGrid<100, 100>):
+// part 1 (real)           time:   [43.436 us 45.239 us 47.083 us]
+// part 2 (real)           time:   [316.70 us 323.44 us 329.55 us]
+// part 1 (real, array grid)
+//                         time:   [31.804 us 34.927 us 38.132 us]
+// part 2 (real, array grid)
+//                         time:   [283.92 us 296.97 us 312.05 us]
+//
+// Dynamic sizing costs roughly 25% in part 1 (bounds come from fields instead of constants) and
+// under 10% in part 2, where the flood fill dominates.
+//
+// Benchmarked (three largest basins by selection):
+// part 2 (real)           time:   [244.92 us 248.92 us 254.01 us]
+// part 2 (generated 1000x1000)
+//                         time:   [26.990 ms 27.963 ms 29.221 ms]
+//
+// The previous hand-rolled top 3 ran part 2 (real) in [200.37 us 208.95 us 219.08 us]. It was already
+// linear and needed no allocation, so collecting the sizes for selection is slightly slower here.
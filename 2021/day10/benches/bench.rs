@@ -6,26 +6,90 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
-        b.iter(|| main::part1(black_box(&input)))
+        b.iter(|| main::part1(black_box(&input), None))
     });
 
     c.bench_function("part 2 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
-        b.iter(|| main::part2(black_box(&input)))
+        b.iter(|| main::part2(black_box(&input), None))
     });
 
     c.bench_function("part 1 (real)", |b| {
         let input = main::parse_input("input.txt").unwrap();
-        b.iter(|| main::part1(black_box(&input)))
+        b.iter(|| main::part1(black_box(&input), None))
     });
 
     c.bench_function("part 2 (real)", |b| {
         let input = main::parse_input("input.txt").unwrap();
-        b.iter(|| main::part2(black_box(&input)))
+        b.iter(|| main::part2(black_box(&input), None))
+    });
+
+    c.bench_function("part 1 (real, scratch)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        let mut scratch = main::DiagnoseScratch::default();
+        b.iter(|| main::part1(black_box(&input), Some(&mut scratch)))
+    });
+
+    c.bench_function("part 2 (real, scratch)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        let mut scratch = main::DiagnoseScratch::default();
+        b.iter(|| main::part2(black_box(&input), Some(&mut scratch)))
+    });
+
+    let mut group = c.benchmark_group("generated 1M lines");
+    group.sample_size(10);
+    let input = main::generate(1_000_000, 1337);
+    group.bench_function("part 1", |b| {
+        b.iter(|| main::part1(black_box(&input), None))
+    });
+    group.bench_function("part 2", |b| {
+        b.iter(|| main::part2(black_box(&input), None))
+    });
+    #[cfg(feature = "parallel")]
+    group.bench_function("part 1 (parallel)", |b| {
+        b.iter(|| main::part1_parallel(black_box(&input)))
+    });
+    #[cfg(feature = "parallel")]
+    group.bench_function("part 2 (parallel)", |b| {
+        b.iter(|| main::part2_parallel(black_box(&input)))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("incomplete line of 1M characters");
+    group.sample_size(10);
+    let input = main::generate_incomplete(1_000_000, 1337);
+    group.bench_function("part 2", |b| {
+        b.iter(|| main::part2(black_box(&input), None))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("median of 10M scores");
+    group.sample_size(10);
+    let scores: Vec<usize> = (0..10_000_000usize)
+        .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 20)
+        .collect();
+    group.bench_function("sort", |b| {
+        b.iter(|| {
+            let mut scores = scores.clone();
+            scores.sort();
+            scores[scores.len() / 2]
+        })
+    });
+    group.bench_function("select", |b| {
+        b.iter(|| aoc_common::median(&mut scores.clone()))
     });
+    group.finish();
 }
 
 criterion_group!(benches, bench_main);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -1,3 +1,8 @@
+use aoc_common::{
+    input_path, median, Answer, Error, Explain, Narrator, Rng, Scanner, Scratch, Solver,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::{
     fs::File,
     io::{BufRead, BufReader},
@@ -8,100 +13,423 @@ pub struct Input {
     lines: Vec<String>,
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
-    let lines = BufReader::new(file).lines().map(|x| x.unwrap()).collect();
+/// The result of checking the syntax of a single line.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Diagnosis {
+    /// The line contains an illegal closing character.
+    Corrupted(IllegalCharacter),
+
+    /// The line is not corrupted, with the score of the characters needed to complete it.
+    Incomplete(u128),
+}
+
+/// Describes the first closing character in a line that does not match the last unclosed character.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IllegalCharacter {
+    /// The index of the character within the line.
+    pub position: usize,
+
+    /// The closing character that would have closed the last unclosed character.
+    pub expected: char,
+
+    /// The closing character that was found instead.
+    pub found: char,
+
+    /// The syntax error score of the character that was found.
+    pub score: usize,
+}
+
+/// Indicates the completion score of a line does not fit in 128 bits. Every unclosed character multiplies
+/// the score by 5, so this happens for lines with more than 55 unclosed characters.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScoreOverflow {
+    /// The number of characters needed to complete the line.
+    pub unclosed: usize,
+}
+
+/// Describes why the middle completion score of the input cannot be determined.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompletionError {
+    /// The completion score of a line does not fit in 128 bits.
+    Overflow(ScoreOverflow),
+
+    /// None of the lines is incomplete, so there are no completion scores.
+    NoIncompleteLines,
+}
+
+impl From<ScoreOverflow> for CompletionError {
+    fn from(error: ScoreOverflow) -> Self {
+        Self::Overflow(error)
+    }
+}
+
+impl std::fmt::Display for IllegalCharacter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Expected {}, but found {} instead at position {}",
+            self.expected, self.found, self.position
+        )
+    }
+}
+
+impl std::fmt::Display for ScoreOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The completion score of a line with {} unclosed characters does not fit in 128 bits",
+            self.unclosed
+        )
+    }
+}
+
+impl std::fmt::Display for CompletionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overflow(error) => error.fmt(f),
+            Self::NoIncompleteLines => write!(f, "None of the lines is incomplete"),
+        }
+    }
+}
+
+/// The opening characters of the four kinds of chunks, indexed by kind.
+const OPENING: [u8; 4] = *b"([{<";
+
+/// The closing characters of the four kinds of chunks, indexed by kind.
+const CLOSING: [u8; 4] = *b")]}>";
+
+/// The syntax error scores of the closing characters, indexed by kind.
+const ERROR_SCORES: [usize; 4] = [3, 57, 1197, 25137];
+
+/// A single character in a line, along with the kind of chunk it opens or closes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Bracket {
+    Open(u8),
+    Close(u8),
+}
+
+/// Classifies the provided character, or returns [`None`] if it is not a bracket.
+fn bracket(c: u8) -> Option<Bracket> {
+    match c {
+        b'(' => Some(Bracket::Open(0)),
+        b'[' => Some(Bracket::Open(1)),
+        b'{' => Some(Bracket::Open(2)),
+        b'<' => Some(Bracket::Open(3)),
+        b')' => Some(Bracket::Close(0)),
+        b']' => Some(Bracket::Close(1)),
+        b'}' => Some(Bracket::Close(2)),
+        b'>' => Some(Bracket::Close(3)),
+        _ => None,
+    }
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut lines = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?.trim_end().to_string();
+
+        let mut scanner = Scanner::new(line.as_bytes());
+        while !scanner.is_eof() {
+            scanner
+                .expect("a bracket", bracket)
+                .map_err(|error| error.at_line(index + 1))?;
+        }
+
+        lines.push(line);
+    }
+
+    if lines.is_empty() {
+        return Err(Error::at_line(1, "Expected a line of brackets."));
+    }
+
     Ok(Input { lines })
 }
 
-pub fn part1(input: &Input) -> usize {
-    let mut stack = Vec::with_capacity(input.lines[0].len());
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Checks the syntax of a single line, using the provided stack as scratch space. Returns an error if the
+/// line is incomplete, but its completion score is too large to be represented.
+///
+/// # Panics
+///
+/// Panics if the line contains characters other than brackets. Lines read by [`parse_input`] are checked
+/// for this up front.
+pub fn diagnose(line: &str, stack: &mut Vec<u8>) -> Result<Diagnosis, ScoreOverflow> {
+    stack.clear();
+
+    let mut scanner = Scanner::new(line.as_bytes());
+    while let Some(c) = scanner.next() {
+        match bracket(c).expect("Expected a bracket.") {
+            Bracket::Open(kind) => stack.push(kind),
+            Bracket::Close(kind) => match stack.pop() {
+                Some(open) if open != kind => {
+                    return Ok(Diagnosis::Corrupted(IllegalCharacter {
+                        position: scanner.position() - 1,
+                        expected: CLOSING[open as usize] as char,
+                        found: c as char,
+                        score: ERROR_SCORES[kind as usize],
+                    }));
+                }
+                _ => {}
+            },
+        }
+    }
+
+    let score = stack.iter().rev().try_fold(0u128, |acc, &kind| {
+        acc.checked_mul(5)?.checked_add(kind as u128 + 1)
+    });
+
+    score.map(Diagnosis::Incomplete).ok_or(ScoreOverflow {
+        unclosed: stack.len(),
+    })
+}
+
+/// Generates a pseudo-random input with the provided number of lines, determined by the provided seed.
+/// Roughly half of the lines are corrupted, the other half are incomplete.
+pub fn generate(line_count: usize, seed: u64) -> Input {
+    let mut rng = Rng::new(seed);
+    let mut next = move || rng.next_u64();
+
+    let mut lines = Vec::with_capacity(line_count);
+    let mut stack = Vec::new();
+
+    for _ in 0..line_count {
+        let corrupt = next() % 2 == 0;
+        let mut line = String::with_capacity(100);
+        stack.clear();
+
+        for i in 0..90 {
+            let r = next();
+            if stack.is_empty() || r % 3 != 0 {
+                let bracket = (r >> 8) as usize % 4;
+                stack.push(bracket);
+                line.push(OPENING[bracket] as char);
+            } else {
+                let bracket = stack.pop().unwrap();
+                if corrupt && i > 45 {
+                    // Close with the wrong bracket to corrupt the line.
+                    line.push(CLOSING[(bracket + 1) % 4] as char);
+                    break;
+                }
+                line.push(CLOSING[bracket] as char);
+            }
+        }
+
+        lines.push(line);
+    }
+
+    Input { lines }
+}
+
+/// Generates a pseudo-random input consisting of a single incomplete line of the provided length, without
+/// any closing characters. This is useful for stress testing the completion scores.
+pub fn generate_incomplete(length: usize, seed: u64) -> Input {
+    let mut rng = Rng::new(seed);
+    let line = (0..length)
+        .map(|_| OPENING[(rng.next_u64() >> 8) as usize % 4] as char)
+        .collect();
+
+    Input { lines: vec![line] }
+}
+
+/// The buffers that [`part1`] and [`part2`] reuse between runs.
+#[derive(Default)]
+pub struct DiagnoseScratch {
+    /// The stack of unclosed brackets of the current line.
+    stack: Vec<u8>,
+
+    /// The completion scores of the incomplete lines.
+    scores: Vec<u128>,
+}
+
+pub fn part1(input: &Input, scratch: Option<&mut DiagnoseScratch>) -> usize {
+    let mut owned = DiagnoseScratch::default();
+    let DiagnoseScratch { stack, .. } = scratch.unwrap_or(&mut owned);
 
     input
         .lines
         .iter()
-        .filter_map(|line| {
-            stack.clear();
-
-            line.chars().find_map(|c| {
-                let e = match c {
-                    ')' => Some(('(', 3)),
-                    ']' => Some(('[', 57)),
-                    '}' => Some(('{', 1197)),
-                    '>' => Some(('<', 25137)),
-                    _ => None,
-                };
-
-                if let Some(expected) = e {
-                    if let Some(actual) = stack.pop() {
-                        if expected.0 != actual {
-                            return Some(expected.1);
-                        }
-                    }
-                } else {
-                    stack.push(c);
-                }
-
-                None
-            })
+        .filter_map(|line| match diagnose(line, stack) {
+            Ok(Diagnosis::Corrupted(illegal)) => Some(illegal.score),
+            Ok(Diagnosis::Incomplete(_)) | Err(_) => None,
         })
         .sum()
 }
 
-pub fn part2(input: &Input) -> usize {
-    let mut stack = Vec::with_capacity(input.lines[0].len());
+pub fn part2(
+    input: &Input,
+    scratch: Option<&mut DiagnoseScratch>,
+) -> Result<u128, CompletionError> {
+    let mut owned = DiagnoseScratch::default();
+    let DiagnoseScratch { stack, scores } = scratch.unwrap_or(&mut owned);
+
+    scores.clear();
+    for line in input.lines.iter() {
+        if let Diagnosis::Incomplete(score) = diagnose(line, stack)? {
+            scores.push(score);
+        }
+    }
 
-    let mut scores: Vec<usize> = input
+    median(scores).ok_or(CompletionError::NoIncompleteLines)
+}
+
+/// Computes the same result as [`part1`], but diagnoses lines in parallel.
+#[cfg(feature = "parallel")]
+pub fn part1_parallel(input: &Input) -> usize {
+    input
         .lines
-        .iter()
-        .filter_map(|line| {
-            stack.clear();
-
-            for c in line.chars() {
-                let e = match c {
-                    ')' => Some('('),
-                    ']' => Some('['),
-                    '}' => Some('{'),
-                    '>' => Some('<'),
-                    _ => None,
-                };
-
-                if let Some(expected) = e {
-                    if let Some(actual) = stack.pop() {
-                        if expected != actual {
-                            return None;
-                        }
-                    }
-                } else {
-                    stack.push(c);
-                }
-            }
+        .par_iter()
+        .map_init(Vec::new, |stack, line| match diagnose(line, stack) {
+            Ok(Diagnosis::Corrupted(illegal)) => illegal.score,
+            Ok(Diagnosis::Incomplete(_)) | Err(_) => 0,
+        })
+        .sum()
+}
 
-            Some(stack.iter().rev().fold(0, |acc, c| {
-                let score = match c {
-                    '(' => 1,
-                    '[' => 2,
-                    '{' => 3,
-                    '<' => 4,
-                    _ => unreachable!(),
-                };
-
-                acc * 5 + score
-            }))
+/// Computes the same result as [`part2`], but diagnoses lines in parallel.
+#[cfg(feature = "parallel")]
+pub fn part2_parallel(input: &Input) -> Result<u128, CompletionError> {
+    let mut scores = input
+        .lines
+        .par_iter()
+        .map_init(Vec::new, |stack, line| match diagnose(line, stack) {
+            Ok(Diagnosis::Corrupted(_)) => None,
+            Ok(Diagnosis::Incomplete(score)) => Some(Ok(score)),
+            Err(error) => Some(Err(error)),
         })
-        .collect();
+        .flatten()
+        .collect::<Result<Vec<u128>, ScoreOverflow>>()?;
+
+    median(&mut scores).ok_or(CompletionError::NoIncompleteLines)
+}
 
-    scores.sort();
+/// Describes the diagnosis of every line in the input. For corrupted lines, this includes the first illegal
+/// character and its syntax error score.
+pub fn explain(input: &Input, sink: &mut dyn Explain) -> std::io::Result<()> {
+    let mut stack = Vec::new();
 
-    scores[scores.len() / 2]
+    for (index, line) in input.lines.iter().enumerate() {
+        match diagnose(line, &mut stack) {
+            Ok(Diagnosis::Corrupted(illegal)) => aoc_common::explain!(
+                sink,
+                "Line {}: corrupted. {} (score: {}).",
+                index + 1,
+                illegal,
+                illegal.score
+            )?,
+            Ok(Diagnosis::Incomplete(score)) => aoc_common::explain!(
+                sink,
+                "Line {}: incomplete, {} unclosed characters (completion score: {}).",
+                index + 1,
+                stack.len(),
+                score
+            )?,
+            Err(error) => aoc_common::explain!(sink, "Line {}: incomplete. {}.", index + 1, error)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day10;
+
+impl Solver for Day10 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input, None).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input, None).into()
+    }
+
+    fn part1_with(input: &Input, scratch: &mut Scratch) -> Answer {
+        part1(input, Some(scratch.get())).into()
+    }
+
+    fn part2_with(input: &Input, scratch: &mut Scratch) -> Answer {
+        part2(input, Some(scratch.get())).into()
+    }
+
+    fn explain(input: &Input, sink: &mut dyn Explain) -> std::io::Result<()> {
+        explain(input, sink)
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    let mut parallel = false;
+    let mut explain = false;
+    let mut stress_length = None;
+    let mut seed = aoc_common::seed();
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--parallel" => parallel = true,
+            "--explain" => explain = true,
+            "--stress" => {
+                stress_length = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a line length after --stress."),
+                )
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --seed.")
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    type Solver1 = fn(&Input) -> usize;
+    type Solver2 = fn(&Input) -> Result<u128, CompletionError>;
+    #[cfg(feature = "parallel")]
+    let (part1, part2): (Solver1, Solver2) = if parallel {
+        (part1_parallel, part2_parallel)
+    } else {
+        (|x| part1(x, None), |x| part2(x, None))
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    let (part1, part2): (Solver1, Solver2) = {
+        assert!(
+            !parallel,
+            "The --parallel flag requires the parallel feature."
+        );
+        (|x| part1(x, None), |x| part2(x, None))
+    };
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let input = match stress_length {
+        Some(length) => generate_incomplete(length, seed),
+        None => parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?,
+    };
     let time_parse = now.elapsed();
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
+    if explain {
+        return self::explain(&input, &mut Narrator::new(std::io::stdout().lock()));
+    }
+
     let now = Instant::now();
     let result1 = part1(&input);
     let time1 = now.elapsed();
@@ -110,11 +438,50 @@ fn main() -> std::io::Result<()> {
     let now = Instant::now();
     let result2 = part2(&input);
     let time2 = now.elapsed();
-    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+    match result2 {
+        Ok(score) => println!("Solution 2: {} (time: {}us)", score, time2.as_micros()),
+        Err(error) => println!("Solution 2: {} (time: {}us)", error, time2.as_micros()),
+    }
 
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the provided lines.
+    fn parse(input: &str) -> Input {
+        parse_reader(input.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn part2_of_example() {
+        let input = parse(include_str!("../input2.txt"));
+        assert_eq!(part2(&input, None), Ok(288957));
+    }
+
+    #[test]
+    fn part2_without_incomplete_lines_is_an_error() {
+        let input = parse("(]\n");
+        assert_eq!(part2(&input, None), Err(CompletionError::NoIncompleteLines));
+    }
+}
+
 // Parse: (time: 83us)
 // Solution 1: 389589 (time: 48us)
 // Solution 2: 1190420163 (time: 62us)
+//
+// Benchmarked (generated 1M lines, on a single core machine, so no parallel speedup is possible):
+// part 1                  time:   [712.99 ms 717.20 ms 722.25 ms]
+// part 2                  time:   [742.33 ms 757.33 ms 772.55 ms]
+// part 1 (parallel)       time:   [748.35 ms 785.45 ms 829.89 ms]
+// part 2 (parallel)       time:   [826.06 ms 837.74 ms 850.03 ms]
+
+// Benchmarked (median by selection instead of sorting):
+// generated 1M lines/part 2
+//                         time:   [640.65 ms 651.66 ms 664.17 ms]
+// median of 10M scores/sort
+//                         time:   [354.22 ms 358.18 ms 364.16 ms]
+// median of 10M scores/select
+//                         time:   [58.553 ms 60.976 ms 63.428 ms]
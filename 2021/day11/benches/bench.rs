@@ -6,26 +6,56 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
-        b.iter(|| main::part1(black_box(&input)))
+        b.iter(|| main::part1(black_box(&input), None))
     });
 
     c.bench_function("part 2 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
-        b.iter(|| main::part2(black_box(&input)))
+        b.iter(|| main::part2(black_box(&input), None))
     });
 
     c.bench_function("part 1 (real)", |b| {
         let input = main::parse_input("input.txt").unwrap();
-        b.iter(|| main::part1(black_box(&input)))
+        b.iter(|| main::part1(black_box(&input), None))
     });
 
     c.bench_function("part 2 (real)", |b| {
         let input = main::parse_input("input.txt").unwrap();
-        b.iter(|| main::part2(black_box(&input)))
+        b.iter(|| main::part2(black_box(&input), None))
+    });
+
+    c.bench_function("part 1 (real, scratch)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        let mut scratch = main::StepScratch::default();
+        b.iter(|| main::part1(black_box(&input), Some(&mut scratch)))
+    });
+
+    c.bench_function("part 2 (real, scratch)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        let mut scratch = main::StepScratch::default();
+        b.iter(|| main::part2(black_box(&input), Some(&mut scratch)))
+    });
+
+    c.bench_function("10000 steps (real)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::count_flashes(black_box(&input.map), 10000, None))
+    });
+
+    c.bench_function("100 steps (generated 250x250)", |b| {
+        let map = main::EnergyMap::generate(250, 250, 1337);
+        b.iter(|| main::count_flashes(black_box(&map), 100, None))
     });
 }
 
 criterion_group!(benches, bench_main);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -1,184 +1,651 @@
+use aoc_common::{
+    input_path, Answer, Budget, Error, GridFormatter, Isa, Rng, Scratch, Solver, Vector2,
+};
 use std::{
     fmt::Display,
     fs::File,
-    io::{BufRead, BufReader},
-    time::Instant,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
 };
 
-const MAP_WIDTH: usize = 10;
-const MAP_HEIGHT: usize = 10;
+/// The number of neighbours every cell has in the precomputed neighbour table.
+const NEIGHBOUR_COUNT: usize = 8;
+
+/// The energy level that marks a cell as flashed during a step. It is never a real energy level.
+const FLASHED: u8 = u8::MAX;
+
+/// The number of cells that are increased before they are checked for flashes in step 1, which is one AVX2
+/// register of energy levels.
+const LANES: usize = 32;
+
+/// The rules that determine when octopuses flash and what happens to their energy levels.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rules {
+    /// Octopuses with an energy level above this value flash.
+    pub threshold: u8,
+
+    /// The energy level of an octopus after it flashed.
+    pub reset: u8,
 
-#[derive(Clone, Copy)]
-pub struct Vector2(isize, isize);
+    /// The energy every octopus gains in every step, and for every adjacent flash.
+    pub increment: u8,
+}
+
+impl Default for Rules {
+    /// The rules of the puzzle: flash above 9, reset to 0 and gain 1 energy at a time.
+    fn default() -> Self {
+        Self {
+            threshold: 9,
+            reset: 0,
+            increment: 1,
+        }
+    }
+}
 
-impl Vector2 {
-    /// Translates the index into a position on an energy map.
-    pub fn from_index(index: usize) -> Self {
-        Self((index % MAP_WIDTH) as isize, (index / MAP_WIDTH) as isize)
+impl Rules {
+    /// Checks that energy levels cannot overflow under these rules. An octopus can gain energy from the
+    /// step itself and from all 8 neighbours before it flashes.
+    pub fn validate(&self) -> Result<(), String> {
+        let max_level = self.threshold as usize + 9 * self.increment as usize;
+        if self.increment == 0 {
+            Err(String::from("The increment must be at least 1."))
+        } else if max_level >= FLASHED as usize {
+            Err(format!(
+                "Energy levels could reach {}, which does not fit in a byte.",
+                max_level
+            ))
+        } else if self.reset > self.threshold {
+            Err(String::from(
+                "The reset level must not exceed the threshold.",
+            ))
+        } else {
+            Ok(())
+        }
     }
+}
 
-    /// Translates the position into an index within the raw grid of an energy map.
-    pub fn to_index(&self) -> usize {
-        self.1 as usize * MAP_WIDTH + self.0 as usize
+impl std::str::FromStr for Rules {
+    type Err = String;
+
+    /// Parses rules from a flat JSON object, e.g. `{"threshold": 9, "reset": 0, "increment": 1}`. Missing
+    /// fields keep their default value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let body = s
+            .trim()
+            .strip_prefix('{')
+            .and_then(|x| x.strip_suffix('}'))
+            .ok_or_else(|| format!("Expected a JSON object, but found {}.", s))?;
+
+        let mut rules = Rules::default();
+        for field in body.split(',').filter(|x| !x.trim().is_empty()) {
+            let (key, value) = field
+                .split_once(':')
+                .ok_or_else(|| format!("Expected a key and a value in {}.", field.trim()))?;
+            let value = value
+                .trim()
+                .parse::<u8>()
+                .map_err(|_| format!("Expected a number from 0 to 255 in {}.", field.trim()))?;
+
+            match key.trim().trim_matches('"') {
+                "threshold" => rules.threshold = value,
+                "reset" => rules.reset = value,
+                "increment" => rules.increment = value,
+                x => return Err(format!("Unknown rule {}.", x)),
+            }
+        }
+
+        rules.validate()?;
+        Ok(rules)
     }
 }
 
-#[derive(Clone)]
 pub struct EnergyMap {
-    grid: [u8; MAP_WIDTH * MAP_HEIGHT],
+    /// The width of the map.
+    width: usize,
+
+    /// The height of the map.
+    height: usize,
+
+    /// The energy levels of all cells, followed by one sentinel cell that is always marked as flashed during a
+    /// step.
+    grid: Vec<u8>,
+
+    /// For every cell, the indices of all its neighbours. Cells on the border of the map refer to
+    /// the sentinel cell for their missing neighbours.
+    neighbours: Vec<[usize; NEIGHBOUR_COUNT]>,
+
+    /// The rules the map is simulated with.
+    rules: Rules,
+}
+
+impl Clone for EnergyMap {
+    fn clone(&self) -> Self {
+        Self {
+            width: self.width,
+            height: self.height,
+            grid: self.grid.clone(),
+            neighbours: self.neighbours.clone(),
+            rules: self.rules,
+        }
+    }
+
+    /// Copies the provided map into the buffers of this map, without allocating if they are large enough.
+    fn clone_from(&mut self, source: &Self) {
+        self.width = source.width;
+        self.height = source.height;
+        self.grid.clone_from(&source.grid);
+        self.neighbours.clone_from(&source.neighbours);
+        self.rules = source.rules;
+    }
+}
+
+/// The buffers that [`part1`] and [`part2`] reuse between runs.
+#[derive(Default)]
+pub struct StepScratch {
+    /// The agenda of cells that are about to flash.
+    agenda: Vec<usize>,
+
+    /// The copy of the map that is simulated.
+    map: Option<EnergyMap>,
+}
+
+impl StepScratch {
+    /// Simulates a copy of the provided map one step at a time, like [`EnergyMap::steps`], but in the buffers
+    /// of this scratch space.
+    fn steps<'a>(&'a mut self, map: &EnergyMap) -> impl Iterator<Item = usize> + 'a {
+        match &mut self.map {
+            Some(copy) => copy.clone_from(map),
+            None => self.map = Some(map.clone()),
+        }
+
+        let copy = self.map.as_mut().unwrap();
+        let agenda = &mut self.agenda;
+        std::iter::repeat_with(move || copy.step_reuse_stack(agenda))
+    }
+}
+
+/// An iterator that simulates a map one step at a time, and produces the number of flashes in every step.
+pub struct Steps {
+    /// The current state of the map.
+    map: EnergyMap,
+
+    /// The agenda that is reused across steps.
+    agenda: Vec<usize>,
+}
+
+impl Iterator for Steps {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.map.step_reuse_stack(&mut self.agenda))
+    }
 }
 
 impl EnergyMap {
-    fn new() -> Self {
+    /// Creates a new map of the provided size, where every cell has an energy level of 0.
+    pub fn new(width: usize, height: usize) -> Self {
+        let sentinel = width * height;
+
+        // Precompute the neighbours of every cell once, so stepping does not need to do any
+        // coordinate math or bounds checks.
+        let mut neighbours = vec![[sentinel; NEIGHBOUR_COUNT]; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let mut count = 0;
+                for dy in -1..=1isize {
+                    for dx in -1..=1isize {
+                        if dy == 0 && dx == 0 {
+                            continue;
+                        }
+
+                        let (nx, ny) = (x as isize + dx, y as isize + dy);
+                        if nx >= 0 && nx < width as isize && ny >= 0 && ny < height as isize {
+                            neighbours[y * width + x][count] = ny as usize * width + nx as usize;
+                        }
+                        count += 1;
+                    }
+                }
+            }
+        }
+
         Self {
-            grid: [0u8; MAP_WIDTH * MAP_HEIGHT],
+            width,
+            height,
+            grid: vec![0u8; width * height + 1],
+            neighbours,
+            rules: Rules::default(),
+        }
+    }
+
+    /// Gets the rules the map is simulated with.
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Changes the rules the map is simulated with. The rules must be valid, see [`Rules::validate`].
+    pub fn set_rules(&mut self, rules: Rules) {
+        debug_assert!(rules.validate().is_ok());
+        self.rules = rules;
+    }
+
+    /// Creates a new map of the provided size with pseudo-random energy levels, determined by the
+    /// provided seed.
+    pub fn generate(width: usize, height: usize, seed: u64) -> Self {
+        let mut result = Self::new(width, height);
+
+        let mut rng = Rng::new(seed);
+        for i in 0..width * height {
+            result.grid[i] = (rng.next_u64() % 10) as u8;
         }
+
+        result
+    }
+
+    /// Gets the total number of cells in the map.
+    pub fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Determines whether the map has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     pub fn get(&self, location: Vector2) -> u8 {
-        self.grid[location.to_index()]
+        self.grid[location.1 as usize * self.width + location.0 as usize]
     }
 
     pub fn set(&mut self, location: Vector2, value: u8) {
-        self.grid[location.to_index()] = value;
+        self.grid[location.1 as usize * self.width + location.0 as usize] = value;
+    }
+
+    /// Gets an infinite iterator over the number of flashes in every step, starting from the current state.
+    /// The map itself is not modified.
+    pub fn steps(&self) -> Steps {
+        Steps {
+            map: self.clone(),
+            agenda: Vec::with_capacity(self.len()),
+        }
     }
 
     pub fn step(&mut self) -> usize {
-        let mut agenda = Vec::with_capacity(MAP_WIDTH * MAP_HEIGHT);
+        let mut agenda = Vec::with_capacity(self.len());
         self.step_reuse_stack(&mut agenda)
     }
 
-    pub fn step_reuse_stack(&mut self, agenda: &mut Vec<Vector2>) -> usize {
-        // Step 1: Increase all energy levels.
-        for i in 0..self.grid.len() {
-            self.grid[i] += 1;
-
-            // If we are flashing after the increase, store the position for processing.
-            if self.grid[i] > 9 {
-                agenda.push(Vector2::from_index(i));
-            }
+    pub fn step_reuse_stack(&mut self, agenda: &mut Vec<usize>) -> usize {
+        let len = self.len();
+        let Rules {
+            threshold,
+            reset,
+            increment,
+        } = self.rules;
+
+        // Cells that flashed are marked such that they do not gain energy anymore in this step. With a reset
+        // level of 0 they can be reset right away, because every other cell has gained energy in step 1, so
+        // only cells that flashed are 0. Otherwise, they are marked separately and reset at the end.
+        let marker = if reset == 0 { 0 } else { FLASHED };
+        self.grid[len] = marker;
+
+        // Step 1: Increase all energy levels (excluding the sentinel).
+        let levels = &mut self.grid[..len];
+        match aoc_common::isa() {
+            #[cfg(target_arch = "x86_64")]
+            // Safety: AVX2 is only selected if the processor supports it.
+            Isa::Avx2 => unsafe { increase_levels_avx2(levels, increment, threshold, agenda) },
+            _ => increase_levels(levels, increment, threshold, agenda),
         }
 
         let mut count = 0;
 
         // Step 2: Flash and ripple through DFS.
-        while !agenda.is_empty() {
-            // Get current position to process.
-            let pos = agenda.pop().unwrap();
-
-            // If we are not flashing, just ignore.
-            if self.get(pos) <= 9 {
+        while let Some(index) = agenda.pop() {
+            // If we are not flashing (or flashed already), just ignore.
+            let level = self.grid[index];
+            if level <= threshold || level == marker {
                 continue;
             }
 
-            // We are flashing at this position, reset to 0.
-            self.set(pos, 0);
+            // We are flashing at this position, mark it as flashed.
+            self.grid[index] = marker;
 
             // Register that we flashed.
             count += 1;
 
-            // Schedule neighbours for processing.
-            for dy in -1..=1 {
-                // Check if we go out of bounds in Y direction.
-                let pos_y = pos.1 + dy;
-                if pos_y < 0 || pos_y >= MAP_HEIGHT as isize {
-                    continue;
-                }
+            // Schedule neighbours for processing. Missing neighbours refer to the sentinel, which
+            // is always marked as flashed and is therefore never scheduled.
+            for &neighbour in self.neighbours[index].iter() {
+                let level = &mut self.grid[neighbour];
 
-                for dx in -1..=1 {
-                    // Check if we go out of bounds in X direction.
-                    let pos_x = pos.0 + dx;
-                    if pos_x < 0 || pos_x >= MAP_HEIGHT as isize || (dy == 0 && dx == 0) {
-                        continue;
-                    }
-
-                    // Schedule if the neighbour level isn't reset before.
-                    let new_pos = Vector2(pos_x, pos_y);
-                    let level = self.get(new_pos);
-
-                    if level > 0 {
-                        self.set(new_pos, level + 1);
-                        agenda.push(new_pos);
-                    }
+                // Schedule if the neighbour did not flash before.
+                if *level != marker {
+                    *level += increment;
+                    agenda.push(neighbour);
                 }
             }
         }
 
+        // Step 3: Reset the energy levels of everything that flashed, if this did not happen already.
+        if marker != reset && count > 0 {
+            for level in self.grid[..len].iter_mut().filter(|x| **x == marker) {
+                *level = reset;
+            }
+        }
+
         count
     }
 }
 
-impl Display for EnergyMap {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..MAP_WIDTH {
-            for x in 0..MAP_HEIGHT {
-                write!(f, "{:>3}", self.get(Vector2(x as isize, y as isize)))?;
-            }
+/// Increases all energy levels, and pushes the indices of the cells that flash onto the agenda. The levels
+/// are increased and checked a chunk at a time, such that chunks without flashes are skipped without branching
+/// on every cell.
+#[inline(always)]
+fn increase_levels(levels: &mut [u8], increment: u8, threshold: u8, agenda: &mut Vec<usize>) {
+    for (index, chunk) in levels.chunks_mut(LANES).enumerate() {
+        let mut max = 0;
+        for level in chunk.iter_mut() {
+            *level += increment;
+            max = max.max(*level);
+        }
 
-            writeln!(f)?;
+        // If we are flashing after the increase, store the position for processing.
+        if max > threshold {
+            for (i, &level) in chunk.iter().enumerate() {
+                if level > threshold {
+                    agenda.push(index * LANES + i);
+                }
+            }
         }
+    }
+}
 
-        Ok(())
+/// The same loop as [`increase_levels`], compiled with AVX2 such that every chunk is increased and checked
+/// with a few vector instructions.
+///
+/// # Safety
+///
+/// The processor must support AVX2.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn increase_levels_avx2(
+    levels: &mut [u8],
+    increment: u8,
+    threshold: u8,
+    agenda: &mut Vec<usize>,
+) {
+    increase_levels(levels, increment, threshold, agenda)
+}
+
+impl Display for EnergyMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        GridFormatter::values(self.width, self.height, |x, y| {
+            self.get(Vector2(x as isize, y as isize))
+        })
+        .fmt(f)
     }
 }
 
 pub struct Input {
-    map: EnergyMap,
+    pub map: EnergyMap,
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
-    let mut map = EnergyMap::new();
-    BufReader::new(file)
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let lines = reader
         .lines()
-        .enumerate()
-        .for_each(|(y, line)| {
-            line.expect("Expected a line")
-                .as_bytes()
-                .iter()
-                .enumerate()
-                .for_each(|(x, &b)| map.set(Vector2(x as isize, y as isize), b - 0x30))
-        });
+        .map(|line| line.map(|x| x.trim_end().to_string()))
+        .collect::<std::io::Result<Vec<String>>>()?;
+
+    let width = lines.first().map_or(0, |x| x.len());
+    let mut map = EnergyMap::new(width, lines.len());
+    for (y, line) in lines.iter().enumerate() {
+        if line.len() != width {
+            return Err(Error::at_line(
+                y + 1,
+                format!(
+                    "Expected a row of {} energy levels, but found {}.",
+                    width,
+                    line.len()
+                ),
+            ));
+        }
+
+        for (x, &b) in line.as_bytes().iter().enumerate() {
+            if !b.is_ascii_digit() {
+                return Err(Error::at_column(
+                    y + 1,
+                    x + 1,
+                    format!(
+                        "Expected an energy level, but found '{}'.",
+                        b.escape_ascii()
+                    ),
+                ));
+            }
+            map.set(Vector2(x as isize, y as isize), b - b'0');
+        }
+    }
 
     Ok(Input { map })
 }
 
-pub fn part1(input: &Input) -> usize {
-    let mut agenda = Vec::with_capacity(MAP_WIDTH * MAP_HEIGHT);
-    let mut map = input.map.clone();
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Simulates the provided number of steps, and returns the total number of flashes.
+pub fn count_flashes(map: &EnergyMap, steps: usize, scratch: Option<&mut StepScratch>) -> usize {
+    let mut owned = StepScratch::default();
+    scratch.unwrap_or(&mut owned).steps(map).take(steps).sum()
+}
+
+/// Writes the number of flashes in each of the provided number of steps to a CSV file, together with
+/// the running total.
+pub fn export_series(path: &Path, map: &EnergyMap, steps: usize) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "step,flashes,total")?;
+
+    let mut total = 0;
+    for (step, flashes) in map.steps().take(steps).enumerate() {
+        total += flashes;
+        writeln!(writer, "{},{},{}", step + 1, flashes, total)?;
+    }
+
+    writer.flush()
+}
 
-    (0..100).map(|_| map.step_reuse_stack(&mut agenda)).sum()
+pub fn part1(input: &Input, scratch: Option<&mut StepScratch>) -> usize {
+    count_flashes(&input.map, 100, scratch)
 }
 
-pub fn part2(input: &Input) -> usize {
-    let mut agenda = Vec::with_capacity(MAP_WIDTH * MAP_HEIGHT);
-    let mut map = input.map.clone();
+/// Finds the first step (starting at 1) in which all octopuses flash, giving up after the provided number of
+/// steps. Under the puzzle rules this always happens eventually, but not necessarily under other rules.
+pub fn find_synchronized_step(
+    map: &EnergyMap,
+    max_steps: usize,
+    scratch: Option<&mut StepScratch>,
+) -> Option<usize> {
+    let len = map.len();
+    let mut owned = StepScratch::default();
+    let steps = scratch.unwrap_or(&mut owned).steps(map);
+    let index = steps.take(max_steps).position(|flashes| flashes == len);
+    index.map(|x| x + 1)
+}
 
-    (0..)
-        .position(|_| map.step_reuse_stack(&mut agenda) == MAP_WIDTH * MAP_HEIGHT)
-        .unwrap()
-        + 1
+pub fn part2(input: &Input, scratch: Option<&mut StepScratch>) -> usize {
+    find_synchronized_step(&input.map, usize::MAX, scratch).unwrap()
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day11;
+
+impl Solver for Day11 {
+    type Input = Input;
+
+    const BUDGET: Budget = Budget {
+        parse: None,
+        part1: Some(Duration::from_micros(400)),
+        part2: Some(Duration::from_millis(2)),
+    };
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input, None).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input, None).into()
+    }
+
+    fn part1_with(input: &Input, scratch: &mut Scratch) -> Answer {
+        part1(input, Some(scratch.get())).into()
+    }
+
+    fn part2_with(input: &Input, scratch: &mut Scratch) -> Answer {
+        part2(input, Some(scratch.get())).into()
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    let mut series = None;
+    let mut series_steps = 100;
+    let mut rules = Rules::default();
+    let mut max_steps = 1_000_000;
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--series" => series = Some(args.next().expect("Expected a file path after --series.")),
+            "--steps" => {
+                series_steps = args
+                    .next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .expect("Expected a number after --steps.")
+            }
+            "--rules" => {
+                rules = args
+                    .next()
+                    .expect("Expected a JSON object after --rules.")
+                    .parse()
+                    .unwrap()
+            }
+            "--threshold" => {
+                rules.threshold = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --threshold.")
+            }
+            "--reset" => {
+                rules.reset = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --reset.")
+            }
+            "--increment" => {
+                rules.increment = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --increment.")
+            }
+            "--max-steps" => {
+                max_steps = args
+                    .next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .expect("Expected a number after --max-steps.")
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    if let Err(error) = rules.validate() {
+        panic!("Invalid rules: {}", error);
+    }
+
+    // Querying the processor is slow the first time, which should not count towards any phase.
+    aoc_common::isa();
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let mut input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    input.map.set_rules(rules);
     let time_parse = now.elapsed();
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
     let now = Instant::now();
-    let result1 = part1(&input);
+    let result1 = part1(&input, None);
     let time1 = now.elapsed();
     println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
 
     let now = Instant::now();
-    let result2 = part2(&input);
+    let result2 = find_synchronized_step(&input.map, max_steps, None);
     let time2 = now.elapsed();
-    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+    match result2 {
+        Some(step) => println!("Solution 2: {} (time: {}us)", step, time2.as_micros()),
+        None => println!(
+            "Solution 2: no synchronized flash within {} steps (time: {}us)",
+            max_steps,
+            time2.as_micros()
+        ),
+    }
+
+    if let Some(path) = series {
+        export_series(Path::new(&path), &input.map, series_steps)?;
+        println!("Series: {} steps written to {}", series_steps, path);
+    }
 
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The total number of flashes after 10 and 100 steps of the example in the puzzle description.
+    const EXAMPLE_TOTALS: [(usize, usize); 2] = [(10, 204), (100, 1656)];
+
+    /// Parses the example in the puzzle description.
+    fn example() -> Input {
+        parse_reader(include_bytes!("../input2.txt").as_slice()).unwrap()
+    }
+
+    #[test]
+    fn example_totals_match() {
+        let input = example();
+        for (steps, expected) in EXAMPLE_TOTALS {
+            assert_eq!(
+                count_flashes(&input.map, steps, None),
+                expected,
+                "total after {} steps",
+                steps
+            );
+        }
+    }
+
+    #[test]
+    fn example_synchronizes() {
+        assert_eq!(part2(&example(), None), 195);
+    }
+}
+
 // Parse: (time: 125us)
 // Solution 1: 1673 (time: 73us)
 // Solution 2: 279 (time: 183us)
+//
+// Benchmarked (precomputed neighbour table):
+// part 1 (real)           time:   [35.498 us 36.927 us 38.394 us]
+// part 2 (real)           time:   [150.01 us 152.32 us 154.78 us]
+// 10000 steps (real)      time:   [2.8646 ms 2.9200 ms 2.9762 ms]
+// 100 steps (generated 250x250)
+//                         time:   [47.519 ms 48.398 ms 49.354 ms]
+//
+// Benchmarked (nested dy/dx loops, before):
+// part 1 (real)           time:   [58.959 us 62.040 us 65.384 us]
+// part 2 (real)           time:   [190.86 us 198.86 us 209.02 us]
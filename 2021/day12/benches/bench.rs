@@ -6,26 +6,56 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
-        b.iter(|| main::part1(black_box(&input)))
+        b.iter(|| main::part1(black_box(&input), None))
     });
 
     c.bench_function("part 2 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
-        b.iter(|| main::part2(black_box(&input)))
+        b.iter(|| main::part2(black_box(&input), None))
     });
 
     c.bench_function("part 1 (real)", |b| {
         let input = main::parse_input("input.txt").unwrap();
-        b.iter(|| main::part1(black_box(&input)))
+        b.iter(|| main::part1(black_box(&input), None))
     });
 
     c.bench_function("part 2 (real)", |b| {
         let input = main::parse_input("input.txt").unwrap();
-        b.iter(|| main::part2(black_box(&input)))
+        b.iter(|| main::part2(black_box(&input), None))
+    });
+
+    c.bench_function("part 1 (real, scratch)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        let mut scratch = main::PathScratch::default();
+        b.iter(|| main::part1(black_box(&input), Some(&mut scratch)))
+    });
+
+    c.bench_function("part 2 (real, scratch)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        let mut scratch = main::PathScratch::default();
+        b.iter(|| main::part2(black_box(&input), Some(&mut scratch)))
+    });
+
+    c.bench_function("part 1 (real, contracted)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::part1_contracted(black_box(&input)))
+    });
+
+    c.bench_function("part 2 (real, contracted)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::part2_contracted(black_box(&input)))
     });
 }
 
 criterion_group!(benches, bench_main);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
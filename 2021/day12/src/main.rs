@@ -1,8 +1,13 @@
+use aoc_common::{
+    input_path,
+    pathfinding::{self, SearchProblem, SearchStats},
+    tracing, Answer, Error, Rng, Scratch, Solver,
+};
 use std::{
-    collections::HashMap,
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
     fs::File,
-    io::{BufRead, BufReader},
-    str::FromStr,
+    io::{BufRead, BufReader, BufWriter, Write},
     time::Instant,
 };
 
@@ -16,6 +21,9 @@ pub struct Node {
 
     /// A collection of neighbours adjacent to this node.
     pub neighbours: Vec<usize>,
+
+    /// The weights of the edges to every neighbour, in the same order as [`Node::neighbours`].
+    pub weights: Vec<usize>,
 }
 
 /// The special ID for the start node.
@@ -24,10 +32,17 @@ pub const NODE_ID_START: usize = 0;
 /// The special ID for the end node.
 pub const NODE_ID_END: usize = 1;
 
+/// The weight assigned to edges that do not specify one explicitly.
+pub const DEFAULT_EDGE_WEIGHT: usize = 1;
+
 /// An undirected graph.
+#[derive(Default)]
 pub struct Graph {
     /// The nodes in the graph.
     pub nodes: Vec<Node>,
+
+    /// A mapping from node names to their IDs, for the nodes that were given a name.
+    pub node_ids: HashMap<String, usize>,
 }
 
 impl Node {
@@ -37,6 +52,7 @@ impl Node {
             id,
             is_large,
             neighbours: Vec::new(),
+            weights: Vec::new(),
         }
     }
 
@@ -52,78 +68,382 @@ impl Node {
 }
 
 impl Graph {
-    /// Creates a new empty undirected graph.
-    pub fn new() -> Self {
-        Self { nodes: Vec::new() }
-    }
-
     /// Adds a node to the graph, and returns the ID of the newly generated node.
     pub fn add_node(&mut self, is_large: bool) -> usize {
         self.nodes.push(Node::new(self.nodes.len(), is_large));
         self.nodes.len() - 1
     }
 
-    /// Connects two nodes together based on their IDs.
-    pub fn connect(&mut self, origin_id: usize, target_id: usize) {
+    /// Adds a node with the provided name to the graph, and returns the ID of the newly generated node.
+    pub fn add_named_node(&mut self, name: String, is_large: bool) -> usize {
+        let node_id = self.add_node(is_large);
+        self.node_ids.insert(name, node_id);
+        node_id
+    }
+
+    /// Gets the ID of the node with the provided name, or [`None`] if no such node exists.
+    pub fn node_id(&self, name: &str) -> Option<usize> {
+        self.node_ids.get(name).copied()
+    }
+
+    /// Gets the IDs of the node with the name `start` and of the nodes named in `ends`, or [`None`] if any of
+    /// the names is unknown.
+    pub fn endpoint_ids(&self, start: &str, ends: &[&str]) -> Option<(usize, Vec<usize>)> {
+        let start_id = self.node_id(start)?;
+        let end_ids = ends
+            .iter()
+            .map(|name| self.node_id(name))
+            .collect::<Option<Vec<usize>>>()?;
+        Some((start_id, end_ids))
+    }
+
+    /// Counts the distinct paths from the node with the name `start` to any of the nodes named in `ends`,
+    /// following the same small cave rules as the puzzle. Returns [`None`] if any of the names is unknown.
+    pub fn count_paths_between(
+        &self,
+        start: &str,
+        ends: &[&str],
+        allow_small_twice: bool,
+    ) -> Option<usize> {
+        let (start_id, end_ids) = self.endpoint_ids(start, ends)?;
+
+        Some(find_distinct_paths(
+            self,
+            start_id,
+            &end_ids,
+            allow_small_twice,
+            &mut PathScratch::default(),
+            &mut SearchStats::new(),
+        ))
+    }
+
+    /// Connects two nodes together based on their IDs, using the provided edge weight.
+    pub fn connect(&mut self, origin_id: usize, target_id: usize, weight: usize) {
         self.nodes[origin_id].neighbours.push(target_id);
+        self.nodes[origin_id].weights.push(weight);
         self.nodes[target_id].neighbours.push(origin_id);
+        self.nodes[target_id].weights.push(weight);
+    }
+
+    /// Creates a new pseudo-random cave system with the provided number of nodes (including the start
+    /// and end nodes), determined by the provided seed. Every node is connected to at least one earlier
+    /// node, and receives `extra_edges` additional random connections. Large caves are never connected
+    /// to each other, as that would result in an infinite amount of paths.
+    pub fn generate(node_count: usize, extra_edges: usize, seed: u64) -> Self {
+        assert!(
+            node_count >= 2,
+            "A cave system needs at least a start and end node."
+        );
+
+        let mut rng = Rng::new(seed);
+        let mut next = move || rng.next_usize();
+
+        let mut graph = Graph::default();
+        graph.add_named_node(String::from("start"), false);
+        graph.add_named_node(String::from("end"), false);
+        for i in 2..node_count {
+            // Roughly one in four caves is a large cave.
+            let node_id = graph.add_node(next() % 4 == 0);
+
+            // Make sure the graph stays connected.
+            let mut target_id = next() % i;
+            if graph.nodes[node_id].is_large && graph.nodes[target_id].is_large {
+                target_id = NODE_ID_START;
+            }
+            graph.connect(node_id, target_id, DEFAULT_EDGE_WEIGHT);
+        }
+
+        for origin_id in 0..node_count {
+            for _ in 0..extra_edges {
+                let target_id = next() % node_count;
+                if target_id == origin_id
+                    || (graph.nodes[origin_id].is_large && graph.nodes[target_id].is_large)
+                    || graph.edge_weight(origin_id, target_id).is_some()
+                {
+                    continue;
+                }
+                graph.connect(origin_id, target_id, DEFAULT_EDGE_WEIGHT);
+            }
+        }
+
+        graph
+    }
+
+    /// Sorts the neighbours of every node by their names, such that exploring the graph no longer depends on
+    /// the order in which the edges appeared in the input. Unnamed nodes are sorted after all named nodes, by
+    /// their ID.
+    pub fn sort_neighbours(&mut self) {
+        // Rank the nodes in the order they should appear in, as the names cannot be borrowed while sorting.
+        let names = self.node_names();
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by_key(|&id| (names[id].is_none(), names[id], id));
+        let mut rank = vec![0; order.len()];
+        for (i, &id) in order.iter().enumerate() {
+            rank[id] = i;
+        }
+
+        for node in self.nodes.iter_mut() {
+            let mut edges: Vec<(usize, usize)> = node
+                .neighbours
+                .iter()
+                .copied()
+                .zip(node.weights.iter().copied())
+                .collect();
+            edges.sort_by_key(|&(id, _)| rank[id]);
+
+            node.neighbours = edges.iter().map(|&(id, _)| id).collect();
+            node.weights = edges.iter().map(|&(_, weight)| weight).collect();
+        }
+    }
+
+    /// Gets the name of every node, indexed by node ID, or [`None`] for nodes that were not given a name.
+    pub fn node_names(&self) -> Vec<Option<&str>> {
+        let mut names = vec![None; self.nodes.len()];
+        for (name, &id) in self.node_ids.iter() {
+            names[id] = Some(name.as_str());
+        }
+        names
+    }
+
+    /// Gets the weight of the edge between the two nodes, or [`None`] if they are not connected.
+    pub fn edge_weight(&self, origin_id: usize, target_id: usize) -> Option<usize> {
+        let origin = &self.nodes[origin_id];
+        origin
+            .neighbours
+            .iter()
+            .position(|&x| x == target_id)
+            .map(|i| origin.weights[i])
+    }
+
+    /// Gets every undirected edge in the graph exactly once, as `(origin, target, weight)` triples with
+    /// `origin <= target`.
+    pub fn edges(&self) -> Vec<(usize, usize, usize)> {
+        let mut edges = Vec::with_capacity(self.edge_count());
+        for node in self.nodes.iter() {
+            // An edge from a node to itself is stored twice in its own list of neighbours.
+            let mut skip_loop = false;
+            for (&target_id, &weight) in node.neighbours.iter().zip(node.weights.iter()) {
+                if target_id == node.id {
+                    skip_loop = !skip_loop;
+                    if !skip_loop {
+                        continue;
+                    }
+                } else if target_id < node.id {
+                    continue;
+                }
+                edges.push((node.id, target_id, weight));
+            }
+        }
+        edges
+    }
+
+    /// Gets the number of undirected edges in the graph.
+    pub fn edge_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|node| node.neighbours.len())
+            .sum::<usize>()
+            / 2
+    }
+
+    /// Counts the nodes per degree, indexed by the degree.
+    pub fn degree_histogram(&self) -> Vec<usize> {
+        let max_degree = self
+            .nodes
+            .iter()
+            .map(|node| node.neighbours.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut counts = vec![0; max_degree + 1];
+        for node in self.nodes.iter() {
+            counts[node.neighbours.len()] += 1;
+        }
+        counts
+    }
+
+    /// Gets every pair of large caves that are connected to each other. Paths could go back and forth
+    /// between such a pair forever, so the graph only has a finite number of paths if there are none.
+    pub fn adjacent_large_caves(&self) -> Vec<(usize, usize)> {
+        self.edges()
+            .into_iter()
+            .filter(|&(origin_id, target_id, _)| {
+                self.nodes[origin_id].is_large && self.nodes[target_id].is_large
+            })
+            .map(|(origin_id, target_id, _)| (origin_id, target_id))
+            .collect()
     }
 }
 
+/// Writes the graph as a JSON document, containing every node, every undirected edge and statistics on the
+/// degrees of the nodes. Nodes that were not given a name have a `null` name.
+pub fn export_json(graph: &Graph, writer: &mut impl Write) -> std::io::Result<()> {
+    let names = graph.node_names();
+
+    let nodes: Vec<String> = graph
+        .nodes
+        .iter()
+        .map(|node| {
+            format!(
+                "{{\"id\":{},\"name\":{},\"large\":{},\"degree\":{}}}",
+                node.id,
+                match names[node.id] {
+                    Some(name) => format!("\"{}\"", name),
+                    None => String::from("null"),
+                },
+                node.is_large,
+                node.neighbours.len()
+            )
+        })
+        .collect();
+
+    let edges: Vec<String> = graph
+        .edges()
+        .iter()
+        .map(|(origin_id, target_id, weight)| {
+            format!(
+                "{{\"from\":{},\"to\":{},\"weight\":{}}}",
+                origin_id, target_id, weight
+            )
+        })
+        .collect();
+
+    let histogram: Vec<String> = graph
+        .degree_histogram()
+        .iter()
+        .map(|x| x.to_string())
+        .collect();
+
+    writeln!(
+        writer,
+        "{{\"nodes\":[{}],\"edges\":[{}],\"edge_count\":{},\"degree_histogram\":[{}]}}",
+        nodes.join(","),
+        edges.join(","),
+        graph.edge_count(),
+        histogram.join(",")
+    )
+}
+
+/// Prints a warning for every pair of adjacent large caves, and returns `true` if there were none.
+fn check_structure(graph: &Graph) -> bool {
+    let names = graph.node_names();
+    let display_name = |node_id: usize| match names[node_id] {
+        Some(name) => name.to_string(),
+        None => format!("#{}", node_id),
+    };
+
+    let pairs = graph.adjacent_large_caves();
+    for &(origin_id, target_id) in pairs.iter() {
+        eprintln!(
+            "Warning: large caves {} and {} are connected, so there are infinitely many paths.",
+            display_name(origin_id),
+            display_name(target_id)
+        );
+    }
+    pairs.is_empty()
+}
+
 /// The puzzle input.
 pub struct Input {
     /// The graph that was stored in the input file.
     graph: Graph,
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let mut graph = Graph::new();
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    parse_reader_with(reader, false)
+}
+
+/// Parses the puzzle input from the provided reader. Two connected large caves are an error, unless
+/// `allow_adjacent_large` is set, in which case they can be reported with [`Graph::adjacent_large_caves`].
+pub fn parse_reader_with(
+    reader: impl BufRead,
+    allow_adjacent_large: bool,
+) -> aoc_common::Result<Input> {
+    let mut graph = Graph::default();
 
     // We map names to IDs, this allows for faster lookup later during exploration.
-    let mut node_ids = HashMap::new();
-    node_ids.insert(String::from_str("start").unwrap(), graph.add_node(false));
-    node_ids.insert(String::from_str("end").unwrap(), graph.add_node(false));
+    graph.add_named_node(String::from("start"), false);
+    graph.add_named_node(String::from("end"), false);
+
+    // Parse all lines in the input.
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let invalid = |message: String| Error::at_line(index + 1, message);
 
-    // Parse all lines in the file.
-    let file = File::open(file)?;
-    BufReader::new(file).lines().for_each(|line| {
-        // Split the line into two parts.
-        let line = line.expect("Expected a line");
-        let mut split = line.split('-');
+        // Split the line into the individual names of the nodes, and the optional weight of the edge
+        // (e.g. `a-b:3`).
+        let (origin_name, target) = line
+            .trim_end()
+            .split_once('-')
+            .ok_or_else(|| invalid(format!("Expected an edge, but found {}.", line.trim_end())))?;
+        let (target_name, weight) = match target.split_once(':') {
+            Some((name, weight)) => (
+                name,
+                weight.parse::<usize>().map_err(|_| {
+                    invalid(format!(
+                        "Expected a numerical edge weight, but found {}.",
+                        weight
+                    ))
+                })?,
+            ),
+            None => (target, DEFAULT_EDGE_WEIGHT),
+        };
 
-        // Get the individiual names of the nodes.
-        let origin_name = String::from_str(split.next().expect("Expected origin node.")).unwrap();
-        let target_name = String::from_str(split.next().expect("Expected target node.")).unwrap();
+        for name in [origin_name, target_name] {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(invalid(format!(
+                    "Expected a cave name, but found '{}'.",
+                    name
+                )));
+            }
+        }
 
         // Convert them to IDs, and add them if they weren't added yet.
-        let origin_id = get_or_add_node(&mut graph, &mut node_ids, origin_name);
-        let target_id = get_or_add_node(&mut graph, &mut node_ids, target_name);
+        let origin_id = get_or_add_node(&mut graph, String::from(origin_name));
+        let target_id = get_or_add_node(&mut graph, String::from(target_name));
+
+        // Paths could go back and forth between two connected large caves forever.
+        if !allow_adjacent_large
+            && graph.nodes[origin_id].is_large
+            && graph.nodes[target_id].is_large
+        {
+            return Err(invalid(format!(
+                "Large caves {} and {} are connected, so there are infinitely many paths.",
+                origin_name, target_name
+            )));
+        }
 
         // Connect the two nodes.
-        graph.connect(origin_id, target_id);
-    });
+        graph.connect(origin_id, target_id, weight);
+    }
 
     /// Gets the (new) ID of the node with the provided name.
     /// This function will allocate a new node in the graph if the name was not known yet.
-    fn get_or_add_node(
-        graph: &mut Graph,
-        node_ids: &mut HashMap<String, usize>,
-        name: String,
-    ) -> usize {
-        if let Some(&node_id) = node_ids.get(&name) {
+    fn get_or_add_node(graph: &mut Graph, name: String) -> usize {
+        if let Some(node_id) = graph.node_id(&name) {
             return node_id;
         }
 
-        let is_large = name.chars().nth(0).unwrap().is_uppercase();
-        let node_id = graph.add_node(is_large);
-        node_ids.insert(name, node_id);
-        node_id
+        let is_large = name.chars().next().unwrap().is_uppercase();
+        graph.add_named_node(name, is_large)
     }
 
     Ok(Input { graph })
 }
 
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_input_with(file, false)
+}
+
+/// Parses the puzzle input from the provided file, see [`parse_reader_with`].
+pub fn parse_input_with(file: &str, allow_adjacent_large: bool) -> aoc_common::Result<Input> {
+    parse_reader_with(BufReader::new(File::open(file)?), allow_adjacent_large)
+}
+
 /// Represents a tree structure that stores all explored paths in a [`Graph`].
+#[derive(Default)]
 struct PathTree {
     /// The path nodes making up the tree.
     nodes: Vec<PathNode>,
@@ -143,25 +463,13 @@ struct PathNode {
 }
 
 impl PathTree {
-    /// Creates a new path tree with one root node.
-    pub fn new() -> Self {
-        Self {
-            nodes: vec![PathNode {
-                node_id: ROOT_PATH_ID,
-                previous_path_id: ROOT_PATH_ID,
-            }],
-        }
-    }
-
-    /// Creates a new path tree with one root node. The path tree will be able to
-    /// contain `capacity` elements without reallocating.
-    pub fn with_capacity(capacity: usize) -> Self {
-        let mut nodes = Vec::with_capacity(capacity);
-        nodes.push(PathNode {
+    /// Removes all paths from the tree except for the root node, but keeps the allocated nodes for reuse.
+    pub fn reset(&mut self) {
+        self.nodes.clear();
+        self.nodes.push(PathNode {
             node_id: ROOT_PATH_ID,
             previous_path_id: ROOT_PATH_ID,
         });
-        Self { nodes }
     }
 
     /// Registers a new path in the path tree, originating from the provided path.
@@ -191,83 +499,969 @@ impl PathTree {
     }
 }
 
-fn find_distinct_paths(graph: &Graph, allow_small_twice: bool) -> usize {
-    // Paths counter.
-    let mut count = 0;
+/// The buffers that [`part1`] and [`part2`] reuse between runs.
+#[derive(Default)]
+pub struct PathScratch {
+    /// The paths that are yet to be explored, as the node to explore, whether a small cave was visited twice
+    /// already, and the path that led to the node.
+    agenda: Vec<(usize, bool, usize)>,
 
-    // Exploration tree.
-    let mut path_tree = PathTree::with_capacity(graph.nodes.len());
+    /// The explored paths.
+    path_tree: PathTree,
+}
 
-    // Allocate agenda and schedule starting node to be processed first..
-    let mut agenda = Vec::with_capacity(graph.nodes.len());
-    agenda.push((
-        NODE_ID_START,
-        false,
-        path_tree.add_path(NODE_ID_START, ROOT_PATH_ID),
-    ));
+/// Counting the distinct paths of a [`Graph`] as a [`SearchProblem`]. Every state is the node to explore, whether a
+/// small cave was visited twice already, and the path that led to the node, stored in a [`PathTree`].
+struct CavePaths<'a> {
+    /// The graph to search through.
+    graph: &'a Graph,
 
-    while !agenda.is_empty() {
-        let (node_id, twice, path_id) = agenda.pop().unwrap();
+    /// The node that all paths start in.
+    start_id: usize,
 
-        // If we found the end, register it and don't explore this path any further.
-        if node_id == NODE_ID_END {
-            count += 1;
-            continue;
-        }
+    /// The nodes that paths end in.
+    end_ids: &'a [usize],
+
+    /// A value indicating whether a single small cave may be visited twice.
+    allow_small_twice: bool,
+
+    /// The explored paths.
+    path_tree: &'a mut PathTree,
+}
+
+impl SearchProblem for CavePaths<'_> {
+    type State = (usize, bool, usize);
+
+    fn start(&mut self) -> Self::State {
+        (
+            self.start_id,
+            false,
+            self.path_tree.add_path(self.start_id, ROOT_PATH_ID),
+        )
+    }
+
+    fn is_goal(&self, state: &Self::State) -> bool {
+        self.end_ids.contains(&state.0)
+    }
+
+    fn successors(&mut self, state: &Self::State, mut visit: impl FnMut(Self::State, usize)) {
+        let &(node_id, twice, path_id) = state;
 
         // Explore this new path.
-        let new_path_id = path_tree.add_path(node_id, path_id);
+        let new_path_id = self.path_tree.add_path(node_id, path_id);
 
         // Look for neighbours.
-        for &neighbour_id in graph.nodes[node_id].neighbours.iter() {
-            let neighbour_node = &graph.nodes[neighbour_id];
+        let node = &self.graph.nodes[node_id];
+        for (&neighbour_id, &weight) in node.neighbours.iter().zip(node.weights.iter()) {
+            let neighbour_node = &self.graph.nodes[neighbour_id];
 
             // Did we traverse this cave already? If we did, we can only do that if the cave is large.
-            if !neighbour_node.is_large && path_tree.path_contains_node(new_path_id, neighbour_id) {
+            if !neighbour_node.is_large
+                && self.path_tree.path_contains_node(new_path_id, neighbour_id)
+            {
                 // Part 2: We are actually allowed to traverse a small cave once, but only once!
-                if allow_small_twice
-                    && !neighbour_node.is_start()
-                    && !neighbour_node.is_end()
+                if self.allow_small_twice
+                    && neighbour_id != self.start_id
+                    && !self.end_ids.contains(&neighbour_id)
                     && !twice
                 {
-                    agenda.push((neighbour_id, true, new_path_id));
+                    visit((neighbour_id, true, new_path_id), weight);
                 }
             } else {
-                agenda.push((neighbour_id, twice, new_path_id));
+                visit((neighbour_id, twice, new_path_id), weight);
             }
         }
     }
+}
+
+/// Counts the distinct paths from the start node to any of the end nodes. A path stops as soon as it
+/// reaches one of the end nodes. Neither the start node nor the end nodes can be visited twice.
+fn find_distinct_paths(
+    graph: &Graph,
+    start_id: usize,
+    end_ids: &[usize],
+    allow_small_twice: bool,
+    scratch: &mut PathScratch,
+    stats: &mut SearchStats,
+) -> usize {
+    let _span = tracing::debug_span!("dfs", allow_small_twice).entered();
+
+    // Exploration tree.
+    let PathScratch { agenda, path_tree } = scratch;
+    path_tree.reset();
 
+    let mut problem = CavePaths {
+        graph,
+        start_id,
+        end_ids,
+        allow_small_twice,
+        path_tree,
+    };
+    let count = pathfinding::count_paths(&mut problem, agenda, stats);
+
+    tracing::debug!(
+        count,
+        explored = problem.path_tree.nodes.len(),
+        "enumerated paths"
+    );
     count
 }
 
-pub fn part1(input: &Input) -> usize {
-    find_distinct_paths(&input.graph, false)
+/// Counts the paths of [`part1`] or [`part2`], recording what the search did in the provided statistics.
+pub fn count_paths_with_stats(
+    input: &Input,
+    allow_small_twice: bool,
+    stats: &mut SearchStats,
+) -> usize {
+    find_distinct_paths(
+        &input.graph,
+        NODE_ID_START,
+        &[NODE_ID_END],
+        allow_small_twice,
+        &mut PathScratch::default(),
+        stats,
+    )
+}
+
+/// Calls `visit` with every distinct path from the start node to any of the end nodes, as the IDs of the
+/// nodes along it, following the same small cave rules as [`find_distinct_paths`]. Paths are produced
+/// depth-first, in the order of the neighbour lists of the nodes.
+pub fn for_each_path(
+    graph: &Graph,
+    start_id: usize,
+    end_ids: &[usize],
+    allow_small_twice: bool,
+    mut visit: impl FnMut(&[usize]),
+) {
+    fn explore(
+        graph: &Graph,
+        path: &mut Vec<usize>,
+        end_ids: &[usize],
+        twice: bool,
+        allow_small_twice: bool,
+        visit: &mut impl FnMut(&[usize]),
+    ) {
+        let node_id = *path.last().unwrap();
+        if end_ids.contains(&node_id) {
+            visit(path);
+            return;
+        }
+
+        for &neighbour_id in graph.nodes[node_id].neighbours.iter() {
+            let mut twice = twice;
+
+            // Small caves can only be visited again once, and never the start.
+            if !graph.nodes[neighbour_id].is_large && path.contains(&neighbour_id) {
+                if !allow_small_twice || twice || neighbour_id == path[0] {
+                    continue;
+                }
+                twice = true;
+            }
+
+            path.push(neighbour_id);
+            explore(graph, path, end_ids, twice, allow_small_twice, visit);
+            path.pop();
+        }
+    }
+
+    let mut path = vec![start_id];
+    explore(
+        graph,
+        &mut path,
+        end_ids,
+        false,
+        allow_small_twice,
+        &mut visit,
+    );
+}
+
+/// Writes every distinct path from the start node to any of the end nodes on its own line, with the node
+/// names separated by commas. Unnamed nodes are written as their ID.
+fn dump_paths(
+    graph: &Graph,
+    start_id: usize,
+    end_ids: &[usize],
+    allow_small_twice: bool,
+    writer: &mut impl Write,
+) -> std::io::Result<()> {
+    let names = graph.node_names();
+    let mut result = Ok(());
+    for_each_path(graph, start_id, end_ids, allow_small_twice, |path| {
+        if result.is_ok() {
+            let path: Vec<String> = path
+                .iter()
+                .map(|&id| names[id].map_or_else(|| id.to_string(), String::from))
+                .collect();
+            result = writeln!(writer, "{}", path.join(","));
+        }
+    });
+    result?;
+    writer.flush()
+}
+
+/// Finds the cost of the cheapest path from the start node to any of the end nodes, following the same small
+/// cave rules as [`find_distinct_paths`]. Returns [`None`] if no such path exists.
+pub fn find_shortest_weighted_path(
+    graph: &Graph,
+    start_id: usize,
+    end_ids: &[usize],
+    allow_small_twice: bool,
+) -> Option<usize> {
+    // Small caves visited so far are stored as a bitmask, so we can only deal with 64 nodes.
+    assert!(
+        graph.nodes.len() <= 64,
+        "Only graphs of up to 64 nodes are supported."
+    );
+
+    // A state is a combination of the current node, the small caves that were visited, and whether
+    // a small cave was visited twice already. Since caves can be revisited, a state needs to keep
+    // track of all this, and we do Dijkstra on these states rather than the nodes themselves.
+    let mut settled = HashSet::new();
+    let mut agenda = BinaryHeap::new();
+    agenda.push(Reverse((0, start_id, 1u64 << start_id, false)));
+
+    while let Some(Reverse((cost, node_id, visited, twice))) = agenda.pop() {
+        // Are we there yet?
+        if end_ids.contains(&node_id) {
+            return Some(cost);
+        }
+
+        // Did we process this state before with a cheaper cost?
+        if !settled.insert((node_id, visited, twice)) {
+            continue;
+        }
+
+        let node = &graph.nodes[node_id];
+        for (&neighbour_id, &weight) in node.neighbours.iter().zip(node.weights.iter()) {
+            let neighbour_node = &graph.nodes[neighbour_id];
+            let new_cost = cost + weight;
+
+            if neighbour_node.is_large {
+                agenda.push(Reverse((new_cost, neighbour_id, visited, twice)));
+            } else if visited & (1 << neighbour_id) == 0 {
+                let new_visited = visited | (1 << neighbour_id);
+                agenda.push(Reverse((new_cost, neighbour_id, new_visited, twice)));
+            } else if allow_small_twice
+                && neighbour_id != start_id
+                && !end_ids.contains(&neighbour_id)
+                && !twice
+            {
+                agenda.push(Reverse((new_cost, neighbour_id, visited, true)));
+            }
+        }
+    }
+
+    None
+}
+
+/// A graph that only contains the small caves of another graph, along with the start and end nodes of the
+/// paths to count. Since large caves can never be adjacent, every hop through any other large cave connects
+/// two of the remaining caves directly, and only the number of ways to get from one to another matters for
+/// counting paths.
+pub struct ContractedGraph {
+    /// The IDs of the remaining caves in the original graph, indexed by their ID in the contracted graph.
+    pub node_ids: Vec<usize>,
+
+    /// For every remaining cave, the caves that can be reached in one step (either directly, or through
+    /// exactly one large cave), together with the number of distinct ways to get there.
+    pub transitions: Vec<Vec<(usize, usize)>>,
+
+    /// The ID in the contracted graph of the node that all paths start in.
+    start: usize,
+
+    /// The IDs in the contracted graph of the nodes that paths end in, as a bitmask.
+    ends: u64,
+
+    /// The IDs in the contracted graph of the remaining large caves, as a bitmask. Only the start and end
+    /// nodes can be large caves.
+    large: u64,
+}
+
+impl ContractedGraph {
+    /// Contracts all large caves in the provided graph, except for the start and end nodes. Large caves are
+    /// not allowed to be adjacent, as that would result in an infinite amount of paths.
+    pub fn from_graph(graph: &Graph, start_id: usize, end_ids: &[usize]) -> Self {
+        let is_kept =
+            |node: &Node| !node.is_large || node.id == start_id || end_ids.contains(&node.id);
+
+        // Visited caves are stored as a bitmask during counting, so we can only deal with 64 of them.
+        let node_ids: Vec<usize> = graph
+            .nodes
+            .iter()
+            .filter(|node| is_kept(node))
+            .map(|node| node.id)
+            .collect();
+        assert!(
+            node_ids.len() <= 64,
+            "Only graphs of up to 64 small caves are supported."
+        );
+
+        let mut compact_ids = vec![usize::MAX; graph.nodes.len()];
+        for (compact_id, &node_id) in node_ids.iter().enumerate() {
+            compact_ids[node_id] = compact_id;
+        }
+
+        let mut transitions = Vec::with_capacity(node_ids.len());
+        for &node_id in node_ids.iter() {
+            let mut multiplicities = vec![0; node_ids.len()];
+            for &neighbour_id in graph.nodes[node_id].neighbours.iter() {
+                let neighbour_node = &graph.nodes[neighbour_id];
+                if is_kept(neighbour_node) {
+                    multiplicities[compact_ids[neighbour_id]] += 1;
+                    continue;
+                }
+
+                // Hop through the large cave. This includes hopping straight back to the current cave.
+                for &target_id in neighbour_node.neighbours.iter() {
+                    assert!(
+                        is_kept(&graph.nodes[target_id]),
+                        "Large caves cannot be adjacent."
+                    );
+                    multiplicities[compact_ids[target_id]] += 1;
+                }
+            }
+
+            transitions.push(
+                multiplicities
+                    .into_iter()
+                    .enumerate()
+                    .filter(|&(_, count)| count > 0)
+                    .collect(),
+            );
+        }
+
+        let ends = end_ids
+            .iter()
+            .fold(0u64, |mask, &node_id| mask | 1 << compact_ids[node_id]);
+        let large = node_ids
+            .iter()
+            .enumerate()
+            .filter(|&(_, &node_id)| graph.nodes[node_id].is_large)
+            .fold(0u64, |mask, (compact_id, _)| mask | 1 << compact_id);
+
+        Self {
+            start: compact_ids[start_id],
+            ends,
+            large,
+            node_ids,
+            transitions,
+        }
+    }
+
+    /// Counts the distinct paths from the start node to any of the end nodes, following the same small cave
+    /// rules as [`find_distinct_paths`].
+    pub fn count_paths(&self, allow_small_twice: bool) -> usize {
+        if self.ends & (1 << self.start) != 0 {
+            return 1;
+        }
+
+        let mut cache = HashMap::new();
+        self.count_paths_from(
+            self.start,
+            self.visit(0, self.start),
+            false,
+            allow_small_twice,
+            &mut cache,
+        )
+    }
+
+    /// Marks the provided cave as visited if it is a small cave. Large caves can be visited any number of
+    /// times.
+    fn visit(&self, visited: u64, node: usize) -> u64 {
+        visited | ((1 << node) & !self.large)
+    }
+
+    /// Counts the distinct paths from the provided cave to any of the end nodes, given the caves visited so
+    /// far. The number of remaining paths only depends on the current cave, the visited caves, and whether
+    /// a cave was visited twice already, so results are cached on those.
+    fn count_paths_from(
+        &self,
+        node: usize,
+        visited: u64,
+        twice: bool,
+        allow_small_twice: bool,
+        cache: &mut HashMap<(usize, u64, bool), usize>,
+    ) -> usize {
+        if let Some(&count) = cache.get(&(node, visited, twice)) {
+            return count;
+        }
+
+        let mut count = 0;
+        for &(target, multiplicity) in self.transitions[node].iter() {
+            let paths = if self.ends & (1 << target) != 0 {
+                1
+            } else if visited & (1 << target) == 0 {
+                let new_visited = self.visit(visited, target);
+                self.count_paths_from(target, new_visited, twice, allow_small_twice, cache)
+            } else if allow_small_twice && target != self.start && !twice {
+                self.count_paths_from(target, visited, true, allow_small_twice, cache)
+            } else {
+                0
+            };
+
+            count += multiplicity * paths;
+        }
+
+        cache.insert((node, visited, twice), count);
+        count
+    }
+}
+
+/// An approximation of the number of distinct paths in a graph.
+#[derive(Debug, Clone, Copy)]
+pub struct PathCountEstimate {
+    /// The estimated number of paths.
+    pub mean: f64,
+
+    /// The lower bound of the 95% confidence interval.
+    pub low: f64,
+
+    /// The upper bound of the 95% confidence interval.
+    pub high: f64,
+
+    /// The number of random walks the estimate is based on.
+    pub samples: usize,
 }
 
-pub fn part2(input: &Input) -> usize {
-    find_distinct_paths(&input.graph, true)
+/// Estimates the number of distinct paths from the start node to any of the end nodes, following the same
+/// small cave rules as [`find_distinct_paths`], without enumerating them.
+///
+/// Every sample is a random walk that picks uniformly among the allowed moves, and is weighted by the
+/// product of the number of choices it had along the way (Knuth's estimator). Walks that get stuck count
+/// as zero paths. The average weight is an unbiased estimate of the true path count.
+pub fn estimate_path_count(
+    graph: &Graph,
+    start_id: usize,
+    end_ids: &[usize],
+    allow_small_twice: bool,
+    samples: usize,
+    seed: u64,
+) -> PathCountEstimate {
+    let mut rng = Rng::new(seed);
+    let mut next = move || rng.next_usize();
+
+    let mut visited = vec![false; graph.nodes.len()];
+    let mut candidates = Vec::new();
+
+    let mut sum = 0.0;
+    let mut sum_squares = 0.0;
+
+    for _ in 0..samples {
+        visited.iter_mut().for_each(|x| *x = false);
+        visited[start_id] = true;
+
+        let mut node_id = start_id;
+        let mut twice = false;
+        let mut weight = 1.0;
+
+        while !end_ids.contains(&node_id) {
+            // Collect all moves that are allowed from the current cave.
+            candidates.clear();
+            for &neighbour_id in graph.nodes[node_id].neighbours.iter() {
+                let neighbour_node = &graph.nodes[neighbour_id];
+
+                if neighbour_node.is_large || !visited[neighbour_id] {
+                    candidates.push((neighbour_id, twice));
+                } else if allow_small_twice
+                    && neighbour_id != start_id
+                    && !end_ids.contains(&neighbour_id)
+                    && !twice
+                {
+                    candidates.push((neighbour_id, true));
+                }
+            }
+
+            // A dead end contributes no paths.
+            if candidates.is_empty() {
+                weight = 0.0;
+                break;
+            }
+
+            let (neighbour_id, new_twice) = candidates[next() % candidates.len()];
+            weight *= candidates.len() as f64;
+            visited[neighbour_id] = true;
+            node_id = neighbour_id;
+            twice = new_twice;
+        }
+
+        sum += weight;
+        sum_squares += weight * weight;
+    }
+
+    let n = samples as f64;
+    let mean = sum / n;
+    let variance = if samples > 1 {
+        (sum_squares - n * mean * mean).max(0.0) / (n - 1.0)
+    } else {
+        0.0
+    };
+    let margin = 1.96 * (variance / n).sqrt();
+
+    PathCountEstimate {
+        mean,
+        low: (mean - margin).max(0.0),
+        high: mean + margin,
+        samples,
+    }
+}
+
+pub fn part1(input: &Input, scratch: Option<&mut PathScratch>) -> usize {
+    let mut owned = PathScratch::default();
+    let scratch = scratch.unwrap_or(&mut owned);
+    find_distinct_paths(
+        &input.graph,
+        NODE_ID_START,
+        &[NODE_ID_END],
+        false,
+        scratch,
+        &mut SearchStats::new(),
+    )
+}
+
+pub fn part2(input: &Input, scratch: Option<&mut PathScratch>) -> usize {
+    let mut owned = PathScratch::default();
+    let scratch = scratch.unwrap_or(&mut owned);
+    find_distinct_paths(
+        &input.graph,
+        NODE_ID_START,
+        &[NODE_ID_END],
+        true,
+        scratch,
+        &mut SearchStats::new(),
+    )
+}
+
+pub fn part1_contracted(input: &Input) -> usize {
+    ContractedGraph::from_graph(&input.graph, NODE_ID_START, &[NODE_ID_END]).count_paths(false)
+}
+
+pub fn part2_contracted(input: &Input) -> usize {
+    ContractedGraph::from_graph(&input.graph, NODE_ID_START, &[NODE_ID_END]).count_paths(true)
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day12;
+
+impl Solver for Day12 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input, None).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input, None).into()
+    }
+
+    fn part1_with(input: &Input, scratch: &mut Scratch) -> Answer {
+        part1(input, Some(scratch.get())).into()
+    }
+
+    fn part2_with(input: &Input, scratch: &mut Scratch) -> Answer {
+        part2(input, Some(scratch.get())).into()
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    aoc_common::init_tracing();
+
+    let mut shortest_path = false;
+    let mut contract = false;
+    let mut estimate_samples = None;
+    let mut generate_nodes = None;
+    let mut between = None;
+    let mut seed = aoc_common::seed();
+    let mut sorted = false;
+    let mut dump = None;
+    let mut json = false;
+    let mut check = false;
+    let mut search_stats = false;
+    let mut frontier_plot = None;
+
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--shortest-path" => shortest_path = true,
+            "--contract" => contract = true,
+            "--sorted" => sorted = true,
+            "--check-structure" => check = true,
+            "--search-stats" => search_stats = true,
+            "--frontier-plot" => {
+                frontier_plot = Some(
+                    args.next()
+                        .expect("Expected a file path after --frontier-plot."),
+                )
+            }
+            "--format" => match args.next().as_deref() {
+                Some("json") => json = true,
+                Some("text") => json = false,
+                _ => panic!("Expected text or json after --format."),
+            },
+            "--dump-paths" => {
+                dump = match args.next().as_deref() {
+                    Some("1") => Some(false),
+                    Some("2") => Some(true),
+                    _ => panic!("Expected 1 or 2 after --dump-paths."),
+                }
+            }
+            "--estimate" => {
+                estimate_samples = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a sample count after --estimate."),
+                )
+            }
+            "--generate" => {
+                generate_nodes = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a node count after --generate."),
+                )
+            }
+            "--between" => {
+                let start = args.next().expect("Expected a start node after --between.");
+                let ends = args
+                    .next()
+                    .expect("Expected comma separated end nodes after the start node.");
+                between = Some((start, ends))
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|x| x.parse::<u64>().ok())
+                    .expect("Expected a number after --seed.")
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let mut input = match generate_nodes {
+        Some(node_count) => Input {
+            graph: Graph::generate(node_count, 2, seed),
+        },
+        // The structure check reports connected large caves itself, rather than failing to parse them.
+        None => parse_input_with(&file.unwrap_or_else(|| input_path("input.txt")), check)?,
+    };
+    if sorted {
+        input.graph.sort_neighbours();
+    }
     let time_parse = now.elapsed();
+
+    if json {
+        return export_json(&input.graph, &mut std::io::stdout().lock());
+    }
+
+    // Counting paths would never finish if large caves are adjacent, so validate the graph before searching.
+    if check && !check_structure(&input.graph) {
+        std::process::exit(1);
+    }
+
+    // Search between the requested nodes, which default to the start and end node.
+    let (start_id, end_ids) = match &between {
+        Some((start, ends)) => input
+            .graph
+            .endpoint_ids(start, &ends.split(',').collect::<Vec<_>>())
+            .unwrap_or_else(|| panic!("Unknown node name in --between {} {}.", start, ends)),
+        None => (NODE_ID_START, vec![NODE_ID_END]),
+    };
+
+    if let Some(allow_small_twice) = dump {
+        return dump_paths(
+            &input.graph,
+            start_id,
+            &end_ids,
+            allow_small_twice,
+            &mut BufWriter::new(std::io::stdout().lock()),
+        );
+    }
+
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
+    if let Some(samples) = estimate_samples {
+        for (rules, allow_small_twice) in [("part 1", false), ("part 2", true)] {
+            let now = Instant::now();
+            let estimate = estimate_path_count(
+                &input.graph,
+                start_id,
+                &end_ids,
+                allow_small_twice,
+                samples,
+                seed,
+            );
+            let time = now.elapsed();
+            println!(
+                "Estimated paths ({} rules): {:.0} (95% CI: {:.0} to {:.0}, {} samples) (time: {}us)",
+                rules,
+                estimate.mean,
+                estimate.low,
+                estimate.high,
+                estimate.samples,
+                time.as_micros()
+            );
+        }
+
+        return Ok(());
+    }
+
+    if shortest_path {
+        for (rules, allow_small_twice) in [("part 1", false), ("part 2", true)] {
+            let now = Instant::now();
+            let result =
+                find_shortest_weighted_path(&input.graph, start_id, &end_ids, allow_small_twice);
+            let time = now.elapsed();
+            match result {
+                Some(cost) => println!(
+                    "Shortest path ({} rules): {} (time: {}us)",
+                    rules,
+                    cost,
+                    time.as_micros()
+                ),
+                None => println!("Shortest path ({} rules): no path exists", rules),
+            }
+        }
+
+        return Ok(());
+    }
+
+    if let Some((start, ends)) = between {
+        let ends: Vec<&str> = ends.split(',').collect();
+        for (rules, allow_small_twice) in [("part 1", false), ("part 2", true)] {
+            let now = Instant::now();
+            let count = if contract {
+                ContractedGraph::from_graph(&input.graph, start_id, &end_ids)
+                    .count_paths(allow_small_twice)
+            } else {
+                find_distinct_paths(
+                    &input.graph,
+                    start_id,
+                    &end_ids,
+                    allow_small_twice,
+                    &mut PathScratch::default(),
+                    &mut SearchStats::new(),
+                )
+            };
+            let time = now.elapsed();
+            println!(
+                "Paths from {} to {} ({} rules): {} (time: {}us)",
+                start,
+                ends.join(" or "),
+                rules,
+                count,
+                time.as_micros()
+            );
+        }
+
+        return Ok(());
+    }
+
     let now = Instant::now();
-    let result1 = part1(&input);
+    let result1 = if contract {
+        part1_contracted(&input)
+    } else {
+        part1(&input, None)
+    };
     let time1 = now.elapsed();
     println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
 
     let now = Instant::now();
-    let result2 = part2(&input);
+    let result2 = if contract {
+        part2_contracted(&input)
+    } else {
+        part2(&input, None)
+    };
     let time2 = now.elapsed();
     println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
 
+    // Search again with instrumentation, such that it does not slow down the timed runs above.
+    if search_stats {
+        for (rules, allow_small_twice) in [("part 1", false), ("part 2", true)] {
+            let mut stats = SearchStats::new();
+            count_paths_with_stats(&input, allow_small_twice, &mut stats);
+            println!(
+                "Search ({} rules): {} states expanded, at most {} on the frontier",
+                rules, stats.expanded, stats.max_frontier
+            );
+        }
+    }
+
+    if let Some(path) = frontier_plot {
+        let mut stats = SearchStats::recording();
+        count_paths_with_stats(&input, true, &mut stats);
+        stats.write_frontier_svg(&mut BufWriter::new(File::create(&path)?))?;
+        println!("Frontier of part 2 plotted to {}", path);
+    }
+
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The larger examples in the puzzle description, next to the one in `input2.txt`.
+    const EXAMPLES: [&str; 2] = [
+        "dc-end\nHN-start\nstart-kj\ndc-start\ndc-HN\nLN-dc\nHN-end\nkj-sa\nkj-HN\nkj-dc\n",
+        "fs-end\nhe-DX\nfs-he\nstart-DX\npj-DX\nend-zg\nzg-sl\nzg-pj\npj-he\nRW-he\nfs-DX\npj-RW\n\
+         zg-RW\nstart-pj\nhe-WI\nzg-he\npj-fs\nstart-RW\n",
+    ];
+
+    /// Parses the examples in the puzzle description.
+    fn examples() -> Vec<Graph> {
+        [include_str!("../input2.txt")]
+            .into_iter()
+            .chain(EXAMPLES)
+            .map(|example| parse_reader(example.as_bytes()).unwrap().graph)
+            .collect()
+    }
+
+    /// Checks that the contracted graph and [`for_each_path`] count the same paths between the provided nodes
+    /// as the unoptimized search, under the rules of both parts.
+    fn check_contraction(graph: &Graph, start_id: usize, end_ids: &[usize], name: &str) {
+        let contracted = ContractedGraph::from_graph(graph, start_id, end_ids);
+        for allow_small_twice in [false, true] {
+            let expected = find_distinct_paths(
+                graph,
+                start_id,
+                end_ids,
+                allow_small_twice,
+                &mut PathScratch::default(),
+                &mut SearchStats::new(),
+            );
+            assert_eq!(
+                contracted.count_paths(allow_small_twice),
+                expected,
+                "{}, allow_small_twice = {}",
+                name,
+                allow_small_twice
+            );
+
+            let mut enumerated = 0;
+            for_each_path(graph, start_id, end_ids, allow_small_twice, |_| {
+                enumerated += 1
+            });
+            assert_eq!(
+                enumerated, expected,
+                "{}, allow_small_twice = {}",
+                name, allow_small_twice
+            );
+        }
+    }
+
+    #[test]
+    fn examples_match_published_counts() {
+        let expected = [(10, 36), (19, 103), (226, 3509)];
+        for (graph, (part1, part2)) in examples().iter().zip(expected) {
+            assert_eq!(
+                graph.count_paths_between("start", &["end"], false),
+                Some(part1)
+            );
+            assert_eq!(
+                graph.count_paths_between("start", &["end"], true),
+                Some(part2)
+            );
+        }
+    }
+
+    /// Parses the provided lines, and sorts the neighbours of the resulting graph.
+    fn parse_sorted(lines: &[&str]) -> Graph {
+        let mut graph = parse_reader(lines.join("\n").as_bytes()).unwrap().graph;
+        graph.sort_neighbours();
+        graph
+    }
+
+    /// Gets the output of `--dump-paths` for the provided graph.
+    fn dumped_paths(graph: &Graph, allow_small_twice: bool) -> String {
+        let mut output = Vec::new();
+        dump_paths(
+            graph,
+            NODE_ID_START,
+            &[NODE_ID_END],
+            allow_small_twice,
+            &mut output,
+        )
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn sorted_paths_do_not_depend_on_line_order() {
+        let inputs = [include_str!("../input2.txt")].into_iter().chain(EXAMPLES);
+        for (index, input) in inputs.enumerate() {
+            let mut lines: Vec<&str> = input.lines().collect();
+            let graph = parse_sorted(&lines);
+            let expected =
+                [false, true].map(|allow_small_twice| dumped_paths(&graph, allow_small_twice));
+
+            let mut rng = Rng::new(aoc_common::DEFAULT_SEED);
+            for round in 1..=10 {
+                // Fisher-Yates shuffle of the lines.
+                for i in (1..lines.len()).rev() {
+                    lines.swap(i, rng.next_usize() % (i + 1));
+                }
+
+                let graph = parse_sorted(&lines);
+                for (allow_small_twice, expected) in [false, true].into_iter().zip(expected.iter())
+                {
+                    assert_eq!(
+                        &dumped_paths(&graph, allow_small_twice),
+                        expected,
+                        "example {}, round {}, allow_small_twice = {}",
+                        index + 1,
+                        round,
+                        allow_small_twice
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn contraction_agrees_on_examples() {
+        for (index, graph) in examples().iter().enumerate() {
+            check_contraction(
+                graph,
+                NODE_ID_START,
+                &[NODE_ID_END],
+                &format!("example {}", index + 1),
+            );
+        }
+    }
+
+    #[test]
+    fn contraction_agrees_on_generated_graphs() {
+        for seed in 1..=20 {
+            check_contraction(
+                &Graph::generate(10, 2, seed),
+                NODE_ID_START,
+                &[NODE_ID_END],
+                &format!("seed {}", seed),
+            );
+        }
+    }
+
+    #[test]
+    fn contraction_agrees_between_other_nodes() {
+        for seed in 1..=20 {
+            // Pick endpoints among all caves, such that large caves are among them as well.
+            let graph = Graph::generate(10, 2, seed);
+            let start_id = seed as usize % 10;
+            let end_ids = [(start_id + 3) % 10, (start_id + 7) % 10];
+            check_contraction(&graph, start_id, &end_ids, &format!("seed {}", seed));
+        }
+    }
+}
+
 // Parse: (time: 149us)
 // Solution 1: 3576 (time: 1286us)
 // Solution 2: 84271 (time: 21737us)
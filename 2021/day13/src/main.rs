@@ -1,89 +1,222 @@
+use aoc_common::{
+    input_path, Answer, Budget, Error, Explain, GridFormatter, Narrator, Point, Solver,
+};
 use std::{
     collections::HashSet,
     fs::File,
-    io::{BufRead, BufReader},
-    time::Instant,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
 };
 
 const WORD_LENGTH: usize = 8;
-const LETTER_SIZE: Vector2 = Vector2(5, 6);
+const LETTER_SIZE: Point = Point(5, 6);
 const WORD_STRIDE: usize = LETTER_SIZE.0 * WORD_LENGTH;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Vector2(usize, usize);
+/// The size of the translation tables of part 2, which bounds all coordinates and fold positions.
+const SHEET_SIZE: usize = 1500;
+
+/// The largest sheet that is drawn when explaining the folds. Larger sheets are only described by their size.
+const MAX_DRAWN_SIZE: Point = Point(2 * WORD_STRIDE, 4 * LETTER_SIZE.1);
+
+/// The bits of a letter hash that can be part of a glyph. The last column of every letter is the blank
+/// spacing between letters, which is excluded such that it does not turn into ink when inverting.
+const GLYPH_MASK: u32 = 0b01111_01111_01111_01111_01111_01111;
 
 #[repr(u8)]
-#[derive(PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum Axis {
     X,
     Y,
 }
 
+#[derive(Debug)]
 pub struct Fold {
     axis: Axis,
     position: usize,
 }
 
+#[derive(Debug)]
 pub struct Input {
-    points: Vec<Vector2>,
+    points: Vec<Point>,
     folds: Vec<Fold>,
 }
 
-impl Vector2 {
-    pub fn from_str(s: &str) -> Self {
-        let mut split = s.split(',');
-        let x = split
-            .next()
-            .expect("Expected X component.")
-            .parse::<usize>()
-            .expect("X component needs to be an integer.");
-        let y = split
-            .next()
-            .expect("Expected Y component.")
-            .parse::<usize>()
-            .expect("Y component needs to be an integer.");
-        Self(x, y)
+/// Describes how the letters are drawn by the points that remain after folding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    /// The points are the ink of the letters.
+    Dots,
+
+    /// The points are the holes around the letters, i.e. the letters are drawn by the missing points.
+    Holes,
+
+    /// Read the letters both ways, and pick the interpretation that recognizes the most letters.
+    Auto,
+}
+
+/// Describes a point that does not land on the remaining sheet after a fold. This happens when the point
+/// lies on the fold line, or when it is further away from the fold line than the edge of the sheet on the
+/// other side, such that it would end up at a negative coordinate.
+#[derive(Debug)]
+pub struct FoldError {
+    /// The original point in the input.
+    pub point: Point,
+
+    /// The index of the fold that moved the point off the sheet.
+    pub fold_index: usize,
+
+    /// The location of the point right before the offending fold was applied.
+    pub location: Point,
+}
+
+impl std::fmt::Display for FoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Point {},{} (at {},{}) does not land on the sheet after fold {}",
+            self.point.0,
+            self.point.1,
+            self.location.0,
+            self.location.1,
+            self.fold_index + 1
+        )
+    }
+}
+
+impl std::str::FromStr for Polarity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dots" => Ok(Polarity::Dots),
+            "holes" => Ok(Polarity::Holes),
+            "auto" => Ok(Polarity::Auto),
+            _ => Err(format!("Unknown polarity {}.", s)),
+        }
     }
 }
 
+/// Parses a point in the format "x,y", whose coordinates must lie on the sheet.
+pub fn parse_point(s: &str) -> Result<Point, String> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| format!("Expected a point, but found {}.", s))?;
+    let component = |x: &str| match x.parse::<usize>() {
+        Ok(value) if value < SHEET_SIZE => Ok(value),
+        _ => Err(format!(
+            "Expected a coordinate below {}, but found {}.",
+            SHEET_SIZE, x
+        )),
+    };
+    Ok(Point(component(x)?, component(y)?))
+}
+
 impl Fold {
-    pub fn from_str(s: &str) -> Self {
-        let equals_index = s.find('=').expect("Expected '='");
-        let axis = match s.chars().nth("fold along ".len()).unwrap() {
-            'x' => Axis::X,
-            'y' => Axis::Y,
-            _ => panic!("Unexpected axis."),
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        let (axis, position) = s
+            .strip_prefix("fold along ")
+            .and_then(|x| x.split_once('='))
+            .ok_or_else(|| format!("Expected a fold, but found {}.", s))?;
+
+        let axis = match axis {
+            "x" => Axis::X,
+            "y" => Axis::Y,
+            _ => return Err(format!("Unknown axis {}.", axis)),
+        };
+
+        // Folds mirror the coordinates up to twice their position.
+        let position = match position.parse::<usize>() {
+            Ok(value) if value > 0 && 2 * value < SHEET_SIZE => value,
+            _ => {
+                return Err(format!(
+                    "Expected a fold position from 1 to {}, but found {}.",
+                    (SHEET_SIZE - 1) / 2,
+                    position
+                ))
+            }
+        };
+        Ok(Self { axis, position })
+    }
+
+    /// Maps the provided point to its location after applying the fold. Points that would not land on the
+    /// remaining sheet are clamped to its edges.
+    pub fn apply(&self, point: Point) -> Point {
+        let fold = |x: usize| {
+            if x > self.position {
+                self.position.saturating_sub(x - self.position)
+            } else {
+                x.min(self.position.saturating_sub(1))
+            }
         };
 
-        let position = s[equals_index + 1..]
-            .parse::<usize>()
-            .expect("Expected numerical position");
-        Self { axis, position }
+        if self.axis == Axis::X {
+            Point(fold(point.0), point.1)
+        } else {
+            Point(point.0, fold(point.1))
+        }
+    }
+
+    /// Maps the provided point to its location after applying the fold, or returns [`None`] if the point
+    /// lies on the fold line or would end up at a negative coordinate.
+    pub fn try_apply(&self, point: Point) -> Option<Point> {
+        let x = if self.axis == Axis::X {
+            point.0
+        } else {
+            point.1
+        };
+        if x == self.position || x > 2 * self.position {
+            None
+        } else {
+            Some(self.apply(point))
+        }
     }
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
-    let lines = BufReader::new(file).lines();
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let lines = reader.lines();
 
     let mut points = Vec::new();
     let mut folds = Vec::new();
+    let mut line_count = 0;
+
+    // Lines are recognized by their contents rather than the section they are in, such that inputs
+    // with the folds listed first (or without a separating blank line) are still accepted.
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        line_count = index + 1;
+
+        // Tolerate Windows line endings and trailing whitespace.
+        let line = line.trim_end();
 
-    let mut is_parsing_points = true;
-    for line in lines {
-        let line = line.unwrap();
         if line.is_empty() {
-            is_parsing_points = false;
-        } else if is_parsing_points {
-            points.push(Vector2::from_str(line.as_str()));
+            continue;
+        } else if line.starts_with("fold along ") {
+            folds.push(Fold::from_str(line).map_err(|x| Error::at_line(index + 1, x))?);
+        } else if line.contains(',') {
+            points.push(parse_point(line).map_err(|x| Error::at_line(index + 1, x))?);
         } else {
-            folds.push(Fold::from_str(line.as_str()));
+            return Err(Error::at_line(
+                index + 1,
+                format!("Expected a point or a fold, but found {}.", line),
+            ));
         }
     }
 
+    if folds.is_empty() {
+        return Err(Error::at_line(line_count, "Expected at least one fold."));
+    }
+
     Ok(Input { points, folds })
 }
 
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
 pub fn part1(input: &Input) -> usize {
     // Lazy implementation...
 
@@ -91,27 +224,18 @@ pub fn part1(input: &Input) -> usize {
 
     let mut remaining = HashSet::new();
     for &point in input.points.iter() {
-        let new_point = if fold.axis == Axis::X {
-            if point.0 > fold.position {
-                Vector2(fold.position - (point.0 - fold.position), point.1)
-            } else {
-                point
-            }
-        } else {
-            if point.1 > fold.position {
-                Vector2(point.0, fold.position - (point.1 - fold.position))
-            } else {
-                point
-            }
-        };
-
-        remaining.insert(new_point);
+        remaining.insert(fold.apply(point));
     }
 
     remaining.len()
 }
 
 pub fn part2(input: &Input) -> String {
+    part2_with(input, Polarity::Auto)
+}
+
+/// Computes the result of part 2, reading the letters with the provided polarity.
+pub fn part2_with(input: &Input, polarity: Polarity) -> String {
     // Step 1: Folding:
     //  Key observation 1:
     //  A fold on the X axis only affects the X coordinate of all points, and same for Y.
@@ -166,15 +290,15 @@ pub fn part2(input: &Input) -> String {
     //  => Final hash for A is 0b01001_01001_01111_01001_01001_00110
 
     // Set up translation tables.
-    let mut x_translations = [0u8; 1500];
-    let mut y_translations = [0u8; 1500];
+    let mut x_translations = [0u8; SHEET_SIZE];
+    let mut y_translations = [0u8; SHEET_SIZE];
 
     // Initialize identity mappings.
-    for i in 0..WORD_STRIDE {
-        x_translations[i] = i as u8;
+    for (i, x) in x_translations.iter_mut().take(WORD_STRIDE).enumerate() {
+        *x = i as u8;
     }
-    for i in 0..LETTER_SIZE.1 {
-        y_translations[i] = i as u8;
+    for (i, y) in y_translations.iter_mut().take(LETTER_SIZE.1).enumerate() {
+        *y = i as u8;
     }
 
     // Apply all folds in reverse order.
@@ -194,7 +318,7 @@ pub fn part2(input: &Input) -> String {
     let translated_points = input
         .points
         .iter()
-        .map(|p| Vector2(x_translations[p.0] as usize, y_translations[p.1] as usize));
+        .map(|p| Point(x_translations[p.0] as usize, y_translations[p.1] as usize));
 
     // "Draw" letters (aka construct letter hashes).
     let mut letter_hashes = [0u32; WORD_LENGTH];
@@ -206,13 +330,35 @@ pub fn part2(input: &Input) -> String {
         letter_hashes[letter_index] |= 1 << bit_index;
     });
 
-    // OCR
-    let mut result = String::with_capacity(WORD_LENGTH);
-    for i in 0..letter_hashes.len() {
-        result.push(hash_to_letter(letter_hashes[i]).unwrap_or('?'));
-    }
+    read_letters(&letter_hashes, polarity)
+}
 
-    result
+/// Reads the letter hashes as text, with unrecognized letters turned into `?`. For [`Polarity::Auto`],
+/// both interpretations are tried, and the one with the most recognized letters is returned, preferring
+/// dots if they are equally good.
+fn read_letters(letter_hashes: &[u32; WORD_LENGTH], polarity: Polarity) -> String {
+    let read = |invert: bool| -> String {
+        letter_hashes
+            .iter()
+            .map(|&hash| if invert { !hash & GLYPH_MASK } else { hash })
+            .map(|hash| hash_to_letter(hash).unwrap_or('?'))
+            .collect()
+    };
+
+    match polarity {
+        Polarity::Dots => read(false),
+        Polarity::Holes => read(true),
+        Polarity::Auto => {
+            let dots = read(false);
+            let holes = read(true);
+            let recognized = |x: &String| x.chars().filter(|&c| c != '?').count();
+            if recognized(&holes) > recognized(&dots) {
+                holes
+            } else {
+                dots
+            }
+        }
+    }
 }
 
 fn hash_to_letter(hash: u32) -> Option<char> {
@@ -232,29 +378,299 @@ fn hash_to_letter(hash: u32) -> Option<char> {
     }
 }
 
+/// Follows every point through all folds in the input, and reports the points that do not land on the
+/// remaining sheet. Every point is reported at most once, for the first fold it fails.
+pub fn check_folds(input: &Input) -> Vec<FoldError> {
+    let mut errors = Vec::new();
+
+    for &point in input.points.iter() {
+        let mut location = point;
+        for (fold_index, fold) in input.folds.iter().enumerate() {
+            match fold.try_apply(location) {
+                Some(new_location) => location = new_location,
+                None => {
+                    errors.push(FoldError {
+                        point,
+                        fold_index,
+                        location,
+                    });
+                    break;
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+/// Applies all folds in the input, and returns the distinct points that remain on the paper.
+/// The points are sorted in reading order (top to bottom, left to right).
+pub fn fold_all(input: &Input) -> Vec<Point> {
+    let mut result: Vec<Point> = input
+        .points
+        .iter()
+        .map(|&p| input.folds.iter().fold(p, |acc, fold| fold.apply(acc)))
+        .collect();
+
+    result.sort_by_key(|p| (p.1, p.0));
+    result.dedup();
+    result
+}
+
+/// Describes the points that remain after every fold in the input, drawing the sheet once it is small enough.
+pub fn explain(input: &Input, sink: &mut dyn Explain) -> std::io::Result<()> {
+    let mut points: HashSet<Point> = input.points.iter().copied().collect();
+    let mut size = Point(
+        points.iter().map(|p| p.0 + 1).max().unwrap_or(0),
+        points.iter().map(|p| p.1 + 1).max().unwrap_or(0),
+    );
+
+    for (index, fold) in input.folds.iter().enumerate() {
+        points = points.iter().map(|&p| fold.apply(p)).collect();
+        let axis = if fold.axis == Axis::X {
+            size.0 = fold.position;
+            'x'
+        } else {
+            size.1 = fold.position;
+            'y'
+        };
+
+        aoc_common::explain!(
+            sink,
+            "Fold {} along {}={} leaves {} points on a sheet of {}x{}.",
+            index + 1,
+            axis,
+            fold.position,
+            points.len(),
+            size.0,
+            size.1
+        )?;
+
+        if size.0 <= MAX_DRAWN_SIZE.0 && size.1 <= MAX_DRAWN_SIZE.1 {
+            aoc_common::explain!(
+                sink,
+                "{}",
+                GridFormatter::custom(size.0, size.1, |x, y| {
+                    if points.contains(&Point(x, y)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the points to a file, one `x,y` coordinate per line.
+pub fn export_points(path: &Path, points: &[Point]) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for point in points {
+        writeln!(writer, "{},{}", point.0, point.1)?;
+    }
+    writer.flush()
+}
+
+/// Writes the points to a file as a plain 1-bit PBM image, where every point is a black pixel.
+pub fn export_pbm(path: &Path, points: &[Point]) -> std::io::Result<()> {
+    let width = points.iter().map(|p| p.0 + 1).max().unwrap_or(0);
+    let height = points.iter().map(|p| p.1 + 1).max().unwrap_or(0);
+
+    let mut pixels = vec![b'0'; width * height];
+    for point in points {
+        pixels[point.1 * width + point.0] = b'1';
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "P1")?;
+    writeln!(writer, "{} {}", width, height)?;
+    for row in pixels.chunks(width.max(1)) {
+        for (i, &pixel) in row.iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b" ")?;
+            }
+            writer.write_all(&[pixel])?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
+/// Exports the final folded points. The format is determined by the extension of the file,
+/// `.pbm` files are written as images, and anything else as a list of points.
+pub fn export(path: &Path, input: &Input) -> std::io::Result<()> {
+    let points = fold_all(input);
+    match path.extension().and_then(|x| x.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("pbm") => export_pbm(path, &points),
+        _ => export_points(path, &points),
+    }
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day13;
+
+impl Solver for Day13 {
+    type Input = Input;
+
+    const BUDGET: Budget = Budget {
+        parse: None,
+        part1: Some(Duration::from_micros(500)),
+        part2: Some(Duration::from_micros(50)),
+    };
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+
+    fn explain(input: &Input, sink: &mut dyn Explain) -> std::io::Result<()> {
+        explain(input, sink)
+    }
+}
+
 fn main() -> std::io::Result<()> {
+    // Collect all requested exports.
+    let mut exports = Vec::new();
+    let mut strict = false;
+    let mut explain = false;
+    let mut polarity = Polarity::Auto;
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strict" => strict = true,
+            "--explain" => explain = true,
+            "--polarity" => {
+                polarity = args
+                    .next()
+                    .expect("Expected dots, holes or auto after --polarity.")
+                    .parse()
+                    .unwrap()
+            }
+            "--export" => exports.push(args.next().expect("Expected a path after --export.")),
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
     let time_parse = now.elapsed();
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
+    // Points folding off the sheet are an error in strict mode, and are clamped to the edges otherwise.
+    let errors = check_folds(&input);
+    if let Some(error) = errors.first().filter(|_| strict) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            error.to_string(),
+        ));
+    }
+    for error in errors.iter() {
+        println!("Warning: {} (clamped).", error);
+    }
+
+    if explain {
+        return self::explain(&input, &mut Narrator::new(std::io::stdout().lock()));
+    }
+
     let now = Instant::now();
     let result1 = part1(&input);
     let time1 = now.elapsed();
     println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
 
     let now = Instant::now();
-    let result2 = part2(&input);
+    let result2 = part2_with(&input, polarity);
     let time2 = now.elapsed();
     println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
 
+    for path in exports {
+        export(Path::new(&path), &input)?;
+        println!("Exported folded points to {}", path);
+    }
+
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Generates a sheet of 1 to 100 points with 1 to 8 folds. Every fold halves the remaining sheet, like in
+    /// the puzzle.
+    fn sheet() -> impl Strategy<Value = Input> {
+        let folds = prop::collection::vec(any::<bool>(), 1..=8);
+        let points = prop::collection::vec(any::<(usize, usize)>(), 1..=100);
+        (folds, points).prop_map(|(along_x, points)| {
+            let mut size = Point(1 << (along_x.len() + 1), 1 << (along_x.len() + 1));
+            let points = points
+                .into_iter()
+                .map(|(x, y)| Point(x % (size.0 - 1), y % (size.1 - 1)))
+                .collect();
+
+            let folds = along_x
+                .into_iter()
+                .map(|along_x| {
+                    let (axis, extent) = if along_x {
+                        (Axis::X, &mut size.0)
+                    } else {
+                        (Axis::Y, &mut size.1)
+                    };
+                    *extent /= 2;
+                    Fold {
+                        axis,
+                        position: *extent,
+                    }
+                })
+                .collect();
+
+            Input { points, folds }
+        })
+    }
+
+    proptest! {
+        /// Folding a point that was already folded along the same line does not move it.
+        #[test]
+        fn single_fold_is_idempotent(input in sheet()) {
+            for fold in input.folds.iter() {
+                for &point in input.points.iter() {
+                    let folded = fold.apply(point);
+                    prop_assert_eq!(fold.apply(folded), folded);
+                }
+            }
+        }
+
+        /// Folding the folded points along all folds again does not move them.
+        #[test]
+        fn all_folds_are_idempotent(input in sheet()) {
+            let folded = fold_all(&input);
+            let again = fold_all(&Input {
+                points: folded.clone(),
+                folds: input.folds,
+            });
+            prop_assert_eq!(again, folded);
+        }
+    }
+}
+
 // Parse: (time: 223us)
 // Solution 1: 788 (time: 247us)
 // Solution 2: KJBKEUBG (time: 4us)
 //
 // Benchmarked:
 // part 1 (real)           time:   [46.454 us 46.526 us 46.602 us]
-// part 2 (real)           time:   [3.6818 us 3.6940 us 3.7082 us]
\ No newline at end of file
+// part 2 (real)           time:   [3.6818 us 3.6940 us 3.7082 us]
@@ -6,6 +6,14 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
         b.iter(|| main::part1(black_box(&input)))
@@ -25,7 +33,40 @@ fn bench_main(c: &mut Criterion) {
         let input = main::parse_input("input.txt").unwrap();
         b.iter(|| main::part2(black_box(&input)))
     });
+
+    c.bench_function("part 1 (real, uncompacted)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::simulate_uncompacted(black_box(&input), 10))
+    });
+
+    c.bench_function("part 2 (real, uncompacted)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::simulate_uncompacted(black_box(&input), 40))
+    });
+
+    c.bench_function("part 1 (real, squaring)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::simulate_squaring(black_box(&input), 10))
+    });
+
+    c.bench_function("part 2 (real, squaring)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::simulate_squaring(black_box(&input), 40))
+    });
+
+    let input = main::parse_input("input.txt").unwrap();
+    for steps in [1_000_000, 10_000_000] {
+        let mut group = c.benchmark_group(format!("{} steps", steps));
+        group.sample_size(10);
+        group.bench_function("iterative", |b| {
+            b.iter(|| main::simulate(black_box(&input), steps))
+        });
+        group.bench_function("squaring", |b| {
+            b.iter(|| main::simulate_squaring(black_box(&input), steps))
+        });
+        group.finish();
+    }
 }
 
 criterion_group!(benches, bench_main);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
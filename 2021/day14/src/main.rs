@@ -1,8 +1,9 @@
+use aoc_common::{input_path, Answer, Budget, Error, GrowthStats, LinearSim, Solver, SparseMatrix};
 use std::{
     fmt::Display,
     fs::File,
     io::{BufRead, BufReader},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 pub struct InsertionRule {
@@ -13,14 +14,125 @@ pub struct InsertionRule {
 pub struct Input {
     template: Vec<u8>,
     insertion_rules: Vec<InsertionRule>,
+
+    /// The elements that actually appear in the template and insertion rules.
+    alphabet: Alphabet,
+}
+
+/// The number of letters an element can be.
+const ALPHABET_SIZE: usize = 26;
+
+/// Selects how the steps of the polymerization are computed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Algorithm {
+    /// Apply the insertion rules to the pair counts once for every step.
+    Iterative,
+
+    /// Raise the transition matrix of the insertion rules to the number of steps by repeated squaring, see
+    /// [`simulate_squaring`].
+    Squaring,
+}
+
+/// Indicates that the pair or element counts no longer fit in 64 bits.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The element counts no longer fit in 64 bits")
+    }
+}
+
+/// Marks a letter that is not part of an [`Alphabet`].
+const UNUSED_LETTER: u8 = u8::MAX;
+
+/// A compaction table that maps the letters that are in use onto a contiguous range of indices,
+/// starting at zero.
+pub struct Alphabet {
+    /// The compact index of every letter, or [`UNUSED_LETTER`] if the letter is not in use.
+    indices: [u8; ALPHABET_SIZE],
+
+    /// The letters that are in use, ordered by their compact index.
+    letters: Vec<u8>,
+}
+
+/// Describes the elements of a polymer template that do not appear in any insertion rule.
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownElementsError {
+    /// The unknown elements (`0` for `A`), together with their positions in the template (starting at 0).
+    pub elements: Vec<(usize, u8)>,
+}
+
+impl Alphabet {
+    /// Builds the compaction table for all elements used in the template and insertion rules.
+    pub fn from_elements(template: &[u8], insertion_rules: &[InsertionRule]) -> Self {
+        let mut used = [false; ALPHABET_SIZE];
+        for &element in template {
+            used[element as usize] = true;
+        }
+        for rule in insertion_rules {
+            used[rule.pair.0 as usize] = true;
+            used[rule.pair.1 as usize] = true;
+            used[rule.insertion as usize] = true;
+        }
+
+        let mut indices = [UNUSED_LETTER; ALPHABET_SIZE];
+        let mut letters = Vec::new();
+        for (letter, _) in used.iter().enumerate().filter(|(_, &x)| x) {
+            indices[letter] = letters.len() as u8;
+            letters.push(letter as u8);
+        }
+
+        Self { indices, letters }
+    }
+
+    /// Gets the number of letters in use.
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    /// Determines whether no letters are in use.
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// Gets the compact index of a letter (`0` for `A`), or [`None`] if the letter is not in use.
+    pub fn index_of(&self, letter: u8) -> Option<usize> {
+        match self.indices[letter as usize] {
+            UNUSED_LETTER => None,
+            index => Some(index as usize),
+        }
+    }
+
+    /// Gets the letter (`0` for `A`) that is assigned the provided compact index.
+    pub fn letter_at(&self, index: usize) -> u8 {
+        self.letters[index]
+    }
+}
+
+impl std::str::FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "iterative" => Ok(Algorithm::Iterative),
+            "squaring" => Ok(Algorithm::Squaring),
+            _ => Err(format!("Unknown algorithm {}.", s)),
+        }
+    }
 }
 
 impl InsertionRule {
-    pub fn from_str(s: &str) -> Self {
-        let bytes = s.as_bytes();
-        Self {
-            pair: (bytes[0] - 'A' as u8, bytes[1] - 'A' as u8),
-            insertion: bytes[6] - 'A' as u8,
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s.as_bytes() {
+            &[a, b, b' ', b'-', b'>', b' ', c] if [a, b, c].iter().all(u8::is_ascii_uppercase) => {
+                Ok(Self {
+                    pair: (a - b'A', b - b'A'),
+                    insertion: c - b'A',
+                })
+            }
+            _ => Err(format!("Expected an insertion rule, but found {}.", s)),
         }
     }
 }
@@ -30,142 +142,417 @@ impl Display for InsertionRule {
         write!(
             f,
             "{}{} -> {}",
-            (self.pair.0 + 'A' as u8) as char,
-            (self.pair.1 + 'A' as u8) as char,
-            (self.insertion + 'A' as u8) as char
+            (self.pair.0 + b'A') as char,
+            (self.pair.1 + b'A') as char,
+            (self.insertion + b'A') as char
+        )
+    }
+}
+
+impl Display for UnknownElementsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let elements: Vec<String> = self
+            .elements
+            .iter()
+            .map(|&(position, element)| {
+                format!("{} at column {}", (element + b'A') as char, position + 1)
+            })
+            .collect();
+
+        write!(
+            f,
+            "Expected elements that appear in the insertion rules, but found {}.",
+            elements.join(", ")
         )
     }
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
-    let mut lines = BufReader::new(file).lines();
+impl std::error::Error for UnknownElementsError {}
+
+/// Verifies that every element of the template appears in at least one insertion rule.
+pub fn validate_template(
+    template: &[u8],
+    insertion_rules: &[InsertionRule],
+) -> Result<(), UnknownElementsError> {
+    let mut known = [false; ALPHABET_SIZE];
+    for rule in insertion_rules {
+        known[rule.pair.0 as usize] = true;
+        known[rule.pair.1 as usize] = true;
+        known[rule.insertion as usize] = true;
+    }
 
-    let template = lines
-        .next()
-        .expect("Expected polymer template")?
-        .as_bytes()
+    let elements: Vec<(usize, u8)> = template
         .iter()
-        .map(|b| b - 'A' as u8)
+        .copied()
+        .enumerate()
+        .filter(|&(_, element)| !known[element as usize])
         .collect();
 
-    lines.next().expect("Expected blank line")?;
+    if elements.is_empty() {
+        Ok(())
+    } else {
+        Err(UnknownElementsError { elements })
+    }
+}
 
-    let insertion_rules: Vec<InsertionRule> = lines
-        .map(|line| {
-            InsertionRule::from_str(line.expect("Expected a pair insertion rule.").as_str())
-        })
-        .collect();
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let lines = reader.lines();
+
+    let mut template: Option<Vec<u8>> = None;
+    let mut template_line = 0;
+    let mut insertion_rules = Vec::new();
+    let mut line_count = 0;
+
+    // Lines are recognized by their contents rather than their position, such that the template
+    // may appear anywhere in the file.
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        line_count = index + 1;
+
+        // Tolerate Windows line endings and trailing whitespace.
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            continue;
+        } else if line.contains(" -> ") {
+            insertion_rules
+                .push(InsertionRule::from_str(line).map_err(|x| Error::at_line(index + 1, x))?);
+        } else if template.is_none() {
+            if let Some(column) = line.bytes().position(|b| !b.is_ascii_uppercase()) {
+                return Err(Error::at_column(
+                    index + 1,
+                    column + 1,
+                    format!(
+                        "Expected an element, but found '{}'.",
+                        line.as_bytes()[column].escape_ascii()
+                    ),
+                ));
+            }
+            template = Some(line.as_bytes().iter().map(|b| b - b'A').collect());
+            template_line = index + 1;
+        } else {
+            return Err(Error::at_line(
+                index + 1,
+                format!("Expected a single polymer template, but found {}.", line),
+            ));
+        }
+    }
+
+    let template =
+        template.ok_or_else(|| Error::at_line(line_count, "Expected a polymer template."))?;
+
+    validate_template(&template, &insertion_rules).map_err(|error| {
+        Error::at_column(template_line, error.elements[0].0 + 1, error.to_string())
+    })?;
+
+    let alphabet = Alphabet::from_elements(&template, &insertion_rules);
 
     Ok(Input {
         template,
         insertion_rules,
+        alphabet,
     })
 }
 
-pub fn simulate(input: &Input, steps: usize) -> usize {
-    const ALPHABET_SIZE: usize = 26;
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
 
-    fn pair_to_index(p: &(u8, u8)) -> usize {
-        p.0 as usize * ALPHABET_SIZE + p.1 as usize
-    }
+/// Simulates the provided number of steps, and returns the difference between the most and least common
+/// element. Elements are counted over the compacted alphabet of the input, such that only pairs of
+/// elements that are actually used are tracked.
+///
+/// The pair counts grow exponentially, and typically no longer fit in 64 bits after roughly 60 steps. All
+/// steps are still simulated (modulo 2^64), but the result is then reported as an [`Overflow`].
+pub fn simulate(input: &Input, steps: usize) -> Result<usize, Overflow> {
+    let alphabet = &input.alphabet;
+    simulate_with(input, steps, alphabet.len(), |letter| {
+        alphabet.index_of(letter).unwrap()
+    })
+}
 
-    // Stores the current state of the polymer as counts of every pair.
-    // A pair is referenced by the index p.0 * ALPHABET_SIZE + p.1.
-    let mut pair_counts = [0usize; ALPHABET_SIZE * ALPHABET_SIZE];
+/// Simulates the provided number of steps like [`simulate`], but tracks every pair of letters from `A`
+/// to `Z`, regardless of whether they are used.
+pub fn simulate_uncompacted(input: &Input, steps: usize) -> Result<usize, Overflow> {
+    simulate_with(input, steps, ALPHABET_SIZE, |letter| letter as usize)
+}
 
-    // Initialize pair counts with the polymer template.
-    input.template.windows(2).for_each(|p| {
-        pair_counts[pair_to_index(&(p[0], p[1]))] += 1;
-    });
+/// Simulates the provided number of steps, where every element is mapped onto an index below
+/// `alphabet_size` using the provided function.
+fn simulate_with(
+    input: &Input,
+    steps: usize,
+    alphabet_size: usize,
+    index_of: impl Fn(u8) -> usize,
+) -> Result<usize, Overflow> {
+    let mut sim = pair_system(input, alphabet_size, &index_of);
+    sim.step(steps);
 
-    // Stores a mapping from pair to a pair of new pairs that gets produced after 
-    // the insertion has taken place.
-    let mut pair_productions = [0u32; ALPHABET_SIZE * ALPHABET_SIZE];
+    if sim.overflowed() {
+        return Err(Overflow);
+    }
+    count_difference(sim.state(), alphabet_size, index_of(input.template[0]))
+}
 
-    for rule in input.insertion_rules.iter() {
-        // An insertion rule AB -> C produces from one pair AB two new pairs AC and CB.
-        let old_pair = pair_to_index(&rule.pair);
-        let new_pair_1 = pair_to_index(&(rule.pair.0, rule.insertion));
-        let new_pair_2 = pair_to_index(&(rule.insertion, rule.pair.1));
+/// Simulates the provided number of steps, and returns the length of the polymer after every step, starting
+/// with the template. Fails as soon as the length no longer fits in 64 bits.
+pub fn simulate_trace(input: &Input, steps: usize) -> Result<Vec<usize>, Overflow> {
+    let alphabet = &input.alphabet;
+    let index_of = |letter| alphabet.index_of(letter).unwrap();
+    let mut sim = pair_system(input, alphabet.len(), &index_of);
+
+    // Every element except for the last one starts exactly one pair.
+    let length = |pair_counts: &[u64]| {
+        pair_counts
+            .iter()
+            .try_fold(1usize, |acc, &x| acc.checked_add(x as usize))
+            .ok_or(Overflow)
+    };
+
+    let mut trace = Vec::with_capacity(steps + 1);
+    trace.push(length(sim.state())?);
 
-        // Register the production of the two new pairs.
-        pair_productions[old_pair] = (new_pair_1 | new_pair_2 << 16) as u32;
-    }
-    
-    // Iterate all steps.
     for _ in 0..steps {
-        // Create a new polymer.
-        let mut new_counts = [0usize; ALPHABET_SIZE * ALPHABET_SIZE];
-
-        for rule in input.insertion_rules.iter() {
-            // Get the number of current instances of the pair in the polymer.
-            let p_index = pair_to_index(&rule.pair);
-            let count = pair_counts[p_index];
-
-            // Get new pairs.
-            let new_pairs = pair_productions[p_index];
-            let new_pair1 = (new_pairs & 0xFFFF) as usize;
-            let new_pair2 = ((new_pairs >> 16) & 0xFFFF) as usize;
-
-            // Add them to the polymer.
-            new_counts[new_pair1] += count;
-            new_counts[new_pair2] += count;
+        sim.step(1);
+        if sim.overflowed() {
+            return Err(Overflow);
         }
+        trace.push(length(sim.state())?);
+    }
+
+    Ok(trace)
+}
+
+/// Simulates the provided number of steps like [`simulate`], but computes the transition matrix of all steps
+/// at once by repeated squaring. This takes `log2(steps)` matrix multiplications, which are parallelized over
+/// the rows of the matrix.
+///
+/// Like [`simulate`], all powers are still computed when the counts no longer fit in 64 bits, which keeps large
+/// step counts useful for benchmarking, but the result is then reported as an [`Overflow`]. Entries of the
+/// powers of the matrix may overflow slightly before the pair counts themselves do.
+pub fn simulate_squaring(input: &Input, steps: usize) -> Result<usize, Overflow> {
+    let alphabet = &input.alphabet;
+    let alphabet_size = alphabet.len();
+    let index_of = |letter| alphabet.index_of(letter).unwrap();
+
+    let mut sim = pair_system(input, alphabet_size, &index_of);
+    sim.step_pow(steps);
 
-        // Swap old polymer with new polymer.
-        pair_counts.copy_from_slice(&new_counts);
+    if sim.overflowed() {
+        return Err(Overflow);
     }
+    count_difference(sim.state(), alphabet_size, index_of(input.template[0]))
+}
+
+/// Builds the simulation of the pair counts of the polymer, starting at the pair counts of the template, where
+/// every element is mapped onto an index below `alphabet_size` using the provided function.
+///
+/// A pair is referenced by the index `p.0 * alphabet_size + p.1`. Every step, all instances of a pair that an
+/// insertion rule applies to are replaced by the two new pairs that get produced after the insertion has taken
+/// place. Pairs without an insertion rule do not survive the step.
+fn pair_system(input: &Input, alphabet_size: usize, index_of: &impl Fn(u8) -> usize) -> LinearSim {
+    let pair_to_index = |p: (u8, u8)| index_of(p.0) * alphabet_size + index_of(p.1);
+
+    // Initialize pair counts with the polymer template.
+    let mut pair_counts = vec![0u64; alphabet_size * alphabet_size];
+    input.template.windows(2).for_each(|p| {
+        pair_counts[pair_to_index((p[0], p[1]))] += 1;
+    });
+
+    let productions = input.insertion_rules.iter().flat_map(|rule| {
+        // An insertion rule AB -> C produces from one pair AB two new pairs AC and CB.
+        let old_pair = pair_to_index(rule.pair);
+        [
+            (pair_to_index((rule.pair.0, rule.insertion)), old_pair, 1),
+            (pair_to_index((rule.insertion, rule.pair.1)), old_pair, 1),
+        ]
+    });
+
+    let transition = SparseMatrix::from_entries(pair_counts.len(), productions);
+    LinearSim::new(pair_counts, transition)
+}
 
-    // Count all elements in the polymer, and sort them by character.
-    // We only need to count one character in the pair, since all characters
-    // are part of two pairs.
-    let mut element_counts = [0usize; ALPHABET_SIZE];
-    for p_index in 0..pair_counts.len() {
-        element_counts[p_index % ALPHABET_SIZE] += pair_counts[p_index];
+/// Counts all elements in a polymer described by its pair counts, and returns the difference between the
+/// most and least common element. `first` is the index of the first element in the polymer.
+fn count_difference(
+    pair_counts: &[u64],
+    alphabet_size: usize,
+    first: usize,
+) -> Result<usize, Overflow> {
+    // We only need to count the second character in the pair, since all characters are part of two pairs.
+    let mut element_counts = vec![0usize; alphabet_size];
+    for (p_index, &count) in pair_counts.iter().enumerate() {
+        let element_count = &mut element_counts[p_index % alphabet_size];
+        *element_count = element_count.checked_add(count as usize).ok_or(Overflow)?;
     }
 
     // Off-by-one, first character in the polymer is an exception to the counting rule.
-    element_counts[input.template[0] as usize] += 1;    
-
-    // Find min-max counts.
-    let mut min = usize::MAX;
-    let mut max = 0usize;
-    for count in element_counts {
-        if count > max {
-            max = count;
-        } else if count < min && count > 0 {
-            min = count;
-        }
-    }
+    element_counts[first] = element_counts[first].checked_add(1).ok_or(Overflow)?;
+
+    // Find min-max counts of the elements that are present.
+    let present = element_counts.iter().copied().filter(|&x| x > 0);
+    let min = present.clone().min().unwrap_or(0);
+    let max = present.max().unwrap_or(0);
 
     // Final solution.
-    max - min
+    Ok(max - min)
+}
+
+pub fn part1(input: &Input) -> Result<usize, Overflow> {
+    simulate(input, 10)
 }
 
-pub fn part1(input: &Input) -> usize {
-    simulate(&input, 10)
+pub fn part2(input: &Input) -> Result<usize, Overflow> {
+    simulate(input, 40)
 }
 
-pub fn part2(input: &Input) -> usize {
-    simulate(&input, 40)
+/// Compares the compacted simulation against the uncompacted one for every number of steps up to
+/// `max_steps`, and returns the first number of steps for which they disagree, if any.
+pub fn check_compaction(input: &Input, max_steps: usize) -> Option<usize> {
+    (0..=max_steps).find(|&steps| simulate(input, steps) != simulate_uncompacted(input, steps))
+}
+
+/// Compares the squaring simulation against the iterative one for the provided numbers of steps, and returns
+/// the first number of steps for which they disagree, if any.
+pub fn check_squaring(input: &Input, steps: &[usize]) -> Option<usize> {
+    steps
+        .iter()
+        .copied()
+        .find(|&steps| simulate(input, steps) != simulate_squaring(input, steps))
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day14;
+
+impl Solver for Day14 {
+    type Input = Input;
+
+    const BUDGET: Budget = Budget {
+        parse: None,
+        part1: Some(Duration::from_micros(50)),
+        part2: Some(Duration::from_micros(150)),
+    };
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    let mut check = false;
+    let mut check_squaring_steps = false;
+    let mut algorithm = Algorithm::Iterative;
+    let mut growth_steps = None;
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check-compaction" => check = true,
+            "--check-squaring" => check_squaring_steps = true,
+            "--algorithm" => {
+                algorithm = args
+                    .next()
+                    .expect("Expected iterative or squaring after --algorithm.")
+                    .parse()
+                    .unwrap()
+            }
+            "--growth" => {
+                growth_steps = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a number of steps after --growth."),
+                )
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
     let time_parse = now.elapsed();
+
+    if let Some(steps) = growth_steps {
+        match simulate_trace(&input, steps) {
+            Ok(trace) => {
+                return GrowthStats::from_trace(&trace).write_json(&mut std::io::stdout().lock())
+            }
+            Err(error) => {
+                eprintln!("{} within {} steps.", error, steps);
+                std::process::exit(1);
+            }
+        }
+    }
+
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
     let now = Instant::now();
-    let result1 = part1(&input);
+    let result1 = match algorithm {
+        Algorithm::Iterative => part1(&input),
+        Algorithm::Squaring => simulate_squaring(&input, 10),
+    };
     let time1 = now.elapsed();
-    println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
+    println!(
+        "Solution 1: {} (time: {}us)",
+        Answer::from(result1),
+        time1.as_micros()
+    );
 
     let now = Instant::now();
-    let result2 = part2(&input);
+    let result2 = match algorithm {
+        Algorithm::Iterative => part2(&input),
+        Algorithm::Squaring => simulate_squaring(&input, 40),
+    };
     let time2 = now.elapsed();
-    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+    println!(
+        "Solution 2: {} (time: {}us)",
+        Answer::from(result2),
+        time2.as_micros()
+    );
+
+    if check {
+        let letters: String = (0..input.alphabet.len())
+            .map(|i| (input.alphabet.letter_at(i) + b'A') as char)
+            .collect();
+        println!(
+            "Alphabet: {} ({} of {} letters)",
+            letters,
+            letters.len(),
+            ALPHABET_SIZE
+        );
+
+        match check_compaction(&input, 40) {
+            None => println!("Compacted and uncompacted simulations agree for 0..=40 steps."),
+            Some(steps) => {
+                println!("Compacted simulation disagrees after {} steps.", steps);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if check_squaring_steps {
+        match check_squaring(&input, &[10, 40]) {
+            None => println!("Squaring and iterative simulations agree for 10 and 40 steps."),
+            Some(steps) => {
+                println!("Squaring simulation disagrees after {} steps.", steps);
+                std::process::exit(1);
+            }
+        }
+    }
 
     Ok(())
 }
@@ -177,3 +564,21 @@ fn main() -> std::io::Result<()> {
 // Benchmarked:
 // part 1 (real)           time:   [3.2131 us 3.2225 us 3.2332 us]
 // part 2 (real)           time:   [10.908 us 10.937 us 10.969 us]
+//
+// Benchmarked (steps through aoc_common::LinearSim, on a slower machine that measured the bespoke loop
+// over the rules at [4.8597 us 5.0055 us 5.1784 us] and [12.620 us 13.618 us 14.992 us]):
+// part 1 (real)           time:   [9.1178 us 9.6620 us 10.147 us]
+// part 2 (real)           time:   [24.582 us 25.454 us 26.241 us]
+// Most of the difference is building the sparse transition matrix, which takes about 4 us.
+//
+// Benchmarked (transition matrix by repeated squaring, single core machine, so rows are not actually
+// computed in parallel):
+// part 1 (real, squaring) time:   [291.48 us 294.89 us 298.33 us]
+// part 2 (real, squaring) time:   [2.0883 ms 2.1217 ms 2.1558 ms]
+// 1000000 steps/iterative time:   [215.91 ms 216.95 ms 218.00 ms]
+// 1000000 steps/squaring  time:   [12.312 ms 13.754 ms 15.816 ms]
+// 10000000 steps/iterative
+//                         time:   [2.3386 s 2.4460 s 2.5295 s]
+// 10000000 steps/squaring time:   [17.246 ms 17.758 ms 18.354 ms]
+// Squaring only pays off for large step counts, for 40 steps the 6 dense-ish matrix multiplies are far more
+// expensive than 40 passes over the rules.
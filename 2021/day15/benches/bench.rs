@@ -1,31 +1,92 @@
 #![allow(dead_code)]
 
+use aoc_common::{
+    pq::{BinaryHeapQueue, BucketQueue, PairingHeap},
+    Vector2,
+};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 #[path = "../src/main.rs"]
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
-        b.iter(|| main::part1(black_box(&input)))
+        b.iter(|| main::part1(black_box(&input), None))
     });
 
     c.bench_function("part 2 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
-        b.iter(|| main::part2(black_box(&input)))
+        b.iter(|| main::part2(black_box(&input), None))
     });
 
     c.bench_function("part 1 (real)", |b| {
         let input = main::parse_input("input.txt").unwrap();
-        b.iter(|| main::part1(black_box(&input)))
+        b.iter(|| main::part1(black_box(&input), None))
     });
 
     c.bench_function("part 2 (real)", |b| {
         let input = main::parse_input("input.txt").unwrap();
-        b.iter(|| main::part2(black_box(&input)))
+        b.iter(|| main::part2(black_box(&input), None))
+    });
+
+    c.bench_function("part 2 (real, greedy bound)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        b.iter(|| main::find_shortest_path_greedy_bound(black_box(&input.grid), 5))
+    });
+
+    c.bench_function("part 2 (real, reused scratch)", |b| {
+        let input = main::parse_input("input.txt").unwrap();
+        let mut scratch = main::SearchScratch::default();
+        b.iter(|| main::part2(black_box(&input), Some(&mut scratch)))
+    });
+
+    // The same search with every priority queue of aoc_common, to pick the default agenda of the day.
+    let input = main::parse_input("input.txt").unwrap();
+    let mut group = c.benchmark_group("part 2 (real, queue)");
+    group.bench_function("binary heap", |b| {
+        b.iter(|| {
+            main::find_shortest_path_with_queue::<BinaryHeapQueue<Vector2>>(
+                black_box(&input.grid),
+                5,
+            )
+        })
+    });
+    group.bench_function("bucket queue", |b| {
+        b.iter(|| {
+            main::find_shortest_path_with_queue::<BucketQueue<Vector2>>(black_box(&input.grid), 5)
+        })
+    });
+    group.bench_function("pairing heap", |b| {
+        b.iter(|| {
+            main::find_shortest_path_with_queue::<PairingHeap<Vector2>>(black_box(&input.grid), 5)
+        })
+    });
+    group.finish();
+
+    // Without the mmap feature, the mapped parser falls back to the buffered one.
+    let path = std::env::temp_dir().join("aoc-day15-generated-4000.txt");
+    main::write_generated(&path, 4000, 1337).unwrap();
+    let file = path.to_string_lossy().into_owned();
+    let mut group = c.benchmark_group("parse (generated 4000x4000)");
+    group.sample_size(10);
+    group.bench_function("buffered", |b| {
+        b.iter(|| main::parse_input(black_box(&file)).unwrap())
+    });
+    group.bench_function("mapped", |b| {
+        b.iter(|| main::parse_input_mapped(black_box(&file)).unwrap())
     });
+    group.finish();
+    std::fs::remove_file(&path).unwrap();
 }
 
 criterion_group!(benches, bench_main);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -1,256 +1,1042 @@
+use aoc_common::{
+    byte_lines, input_path,
+    pathfinding::{self, Costs, SearchProblem, SearchStats},
+    pq::{BucketQueue, PriorityQueue},
+    tracing, Answer, Budget, Error, Grid, Rng, Scratch, Solver, Vector2,
+};
 use std::{
-    collections::BinaryHeap,
-    fmt::Display,
     fs::File,
-    io::{BufRead, BufReader},
-    ops::{Add, Div, Mul, Rem, Sub},
-    time::Instant,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
 };
 
-/// A 2 dimensional integer vector. Used for positions and directions.
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub struct Vector2(isize, isize);
-
 /// All directions that we can go in the grid.
 const DIRECTIONS: [Vector2; 4] = [Vector2(1, 0), Vector2(0, 1), Vector2(-1, 0), Vector2(0, -1)];
 
-// Some cool operator overloading in rust, for extra internet puntos :^).
+/// The puzzle input.
+pub struct Input {
+    /// The input grid.
+    pub grid: Grid<u8>,
+}
 
-impl Add for Vector2 {
-    type Output = Self;
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(mut reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    parse_bytes(&data)
+}
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Vector2(self.0 + rhs.0, self.1 + rhs.1)
-    }
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
 }
 
-impl Sub for Vector2 {
-    type Output = Self;
+/// Parses the digits of a square grid directly from the raw bytes of an input file, without allocating
+/// anything besides the grid itself.
+pub fn parse_bytes(data: &[u8]) -> aoc_common::Result<Input> {
+    let mut grid = Vec::with_capacity(data.len());
+    let mut size = 0;
+    let mut line_count = 0;
+
+    for (index, line) in byte_lines(data).enumerate() {
+        if index == 0 {
+            size = line.len();
+        } else if line.len() != size {
+            return Err(Error::at_line(
+                index + 1,
+                format!(
+                    "Expected a row of {} risk levels, but found {}.",
+                    size,
+                    line.len()
+                ),
+            ));
+        }
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Vector2(self.0 - rhs.0, self.1 - rhs.1)
+        for (column, &b) in line.iter().enumerate() {
+            if !b.is_ascii_digit() {
+                return Err(Error::at_column(
+                    index + 1,
+                    column + 1,
+                    format!("Expected a risk level, but found '{}'.", b.escape_ascii()),
+                ));
+            }
+            grid.push(b - b'0');
+        }
+        line_count = index + 1;
     }
-}
 
-impl Mul<isize> for Vector2 {
-    type Output = Vector2;
+    if size == 0 || line_count != size {
+        return Err(Error::at_line(
+            line_count,
+            format!(
+                "Expected a square grid, but found {} rows of {} risk levels.",
+                line_count, size
+            ),
+        ));
+    }
 
-    fn mul(self, rhs: isize) -> Self::Output {
-        Vector2(self.0 * rhs, self.1 * rhs)
+    Ok(Input {
+        grid: Grid {
+            grid,
+            size: size as isize,
+        },
+    })
+}
+
+/// Parses the input by mapping the file into memory and reading the digits from the mapped bytes, which
+/// avoids buffering every line of giant generated grids. Falls back to [`parse_input`] if the file cannot be
+/// mapped.
+#[cfg(feature = "mmap")]
+pub fn parse_input_mapped(file: &str) -> aoc_common::Result<Input> {
+    // Safety: the file is only read while it is mapped. Modifying it in the meantime is not supported.
+    match unsafe { memmap2::Mmap::map(&File::open(file)?) } {
+        Ok(data) => parse_bytes(&data),
+        Err(_) => parse_input(file),
     }
 }
 
-impl Div<isize> for Vector2 {
-    type Output = Vector2;
+/// Parses the input with [`parse_input`], as memory mapping is only available with the `mmap` feature.
+#[cfg(not(feature = "mmap"))]
+pub fn parse_input_mapped(file: &str) -> aoc_common::Result<Input> {
+    parse_input(file)
+}
+
+/// Writes a pseudo-random square grid of risk levels with the provided size to a file, determined by the
+/// provided seed.
+pub fn write_generated(path: &Path, size: usize, seed: u64) -> std::io::Result<()> {
+    let mut rng = Rng::new(seed);
 
-    fn div(self, rhs: isize) -> Self::Output {
-        Vector2(self.0 / rhs, self.1 / rhs)
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut line = vec![b'\n'; size + 1];
+    for _ in 0..size {
+        for c in line[..size].iter_mut() {
+            *c = b'1' + (rng.next_u64() >> 8) as u8 % 9;
+        }
+        writer.write_all(&line)?;
     }
+
+    writer.flush()
 }
 
-impl Rem<isize> for Vector2 {
-    type Output = Vector2;
+/// The priority queue that holds the agenda of the searches. Costs only grow by at most 9 per step, which
+/// suits a bucket queue. On the real input, part 2 takes about 24 ms with it, against 30 ms with a binary heap
+/// and 60 ms with a pairing heap (see the `part 2 (real, queue)` benchmarks).
+pub type Agenda = BucketQueue<Vector2>;
+
+/// Reusable buffers for [`find_shortest_path_with_scratch`], such that repeated searches do not need to
+/// allocate and clear a new distances table every time.
+#[derive(Default)]
+pub struct SearchScratch<Q = Agenda> {
+    /// The distances of the current search.
+    distances: DistanceTable,
 
-    fn rem(self, rhs: isize) -> Self::Output {
-        Vector2(self.0 % rhs, self.1 % rhs)
+    /// The agenda of the search, ordered by the cost of the route to every position.
+    agenda: Q,
+}
+
+impl<Q: PriorityQueue<Vector2>> SearchScratch<Q> {
+    /// Prepares the buffers for a new search over a map with the provided size.
+    fn begin(&mut self, size: isize) {
+        self.distances.begin(size);
+        self.agenda.clear();
     }
 }
 
-/// Represents a 2 dimensional square grid.
-pub struct Grid<T>
-where
-    T: Clone + Copy,
-{
-    /// The raw data in the grid.
-    pub grid: Vec<T>,
+/// The distances of the positions in a square map that were found by a search.
+///
+/// Every distance is tagged with the generation (search) it was written in. Starting a new search only
+/// bumps the current generation, which invalidates all distances from earlier searches at once.
+#[derive(Default)]
+struct DistanceTable {
+    /// The distances table, only valid for entries tagged with the current generation.
+    distances: Vec<usize>,
+
+    /// The generation every entry in the distances table was last written in.
+    generations: Vec<u32>,
+
+    /// The generation of the current search.
+    generation: u32,
+
+    /// The size of one of the dimensions of the map of the current search.
+    size: isize,
+}
+
+impl DistanceTable {
+    /// Prepares the table for a new search over a map with the provided size.
+    fn begin(&mut self, size: isize) {
+        let len = (size * size) as usize;
+        if self.distances.len() < len {
+            self.distances.resize(len, usize::MAX);
+            self.generations.resize(len, 0);
+        }
+        self.size = size;
+
+        // Generation 0 is never current, so all entries are invalid after a wrap around.
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            self.generations.iter_mut().for_each(|x| *x = 0);
+            self.generation = 1;
+        }
+    }
 
-    /// The size of one of the dimensions.
-    pub size: isize,
+    /// Gets the index of the provided position in the table.
+    fn index_of(&self, position: Vector2) -> usize {
+        (position.1 * self.size + position.0) as usize
+    }
 }
 
-impl<T> Grid<T>
-where
-    T: Clone + Copy,
-{
-    /// Creates a new square grid with the provided initializer value.
-    pub fn new(size: isize, init: T) -> Self {
-        Self {
-            grid: vec![init; (size * size) as usize],
-            size,
+impl Costs<Vector2> for DistanceTable {
+    /// Gets the distance of the provided position, or [`usize::MAX`] if it was not written in the current search.
+    fn get(&self, position: &Vector2) -> usize {
+        let index = self.index_of(*position);
+        if self.generations[index] == self.generation {
+            self.distances[index]
+        } else {
+            usize::MAX
         }
     }
 
-    /// Gets an element in the grid by its position.
-    pub fn get(&self, location: Vector2) -> T {
-        self.grid[(location.1 * self.size + location.0) as usize]
+    /// Sets the distance of the provided position for the current search.
+    fn set(&mut self, position: &Vector2, distance: usize) {
+        let index = self.index_of(*position);
+        self.distances[index] = distance;
+        self.generations[index] = self.generation;
     }
 
-    /// Sets an element in the grid by its position.
-    pub fn set(&mut self, location: Vector2, value: T) {
-        self.grid[(location.1 * self.size + location.0) as usize] = value;
+    /// Sets the distance of the provided position if it is shorter than any distance found before in the current
+    /// search, looking up its index only once.
+    fn improve(&mut self, position: &Vector2, distance: usize) -> bool {
+        let index = self.index_of(*position);
+        let better = self.generations[index] != self.generation || distance < self.distances[index];
+        if better {
+            self.distances[index] = distance;
+            self.generations[index] = self.generation;
+        }
+        better
     }
 }
 
-impl Display for Grid<u8> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.size {
-            for x in 0..self.size {
-                write!(f, "{}", self.get(Vector2(x as isize, y as isize)))?;
+/// Finding a path through the (repeated) map as a [`SearchProblem`]. Every state is a position, and every step
+/// costs the risk level of the position it enters. Paths start in the top-left corner.
+struct RiskMap<'a> {
+    /// The grid that the map consists of repetitions of.
+    grid: &'a Grid<u8>,
+
+    /// The size of one of the dimensions of the full map.
+    size: isize,
+
+    /// The position to find a path to.
+    end: Vector2,
+}
+
+impl SearchProblem for RiskMap<'_> {
+    type State = Vector2;
+
+    fn start(&mut self) -> Vector2 {
+        Vector2(0, 0)
+    }
+
+    fn is_goal(&self, position: &Vector2) -> bool {
+        *position == self.end
+    }
+
+    #[inline(always)]
+    fn successors(&mut self, &position: &Vector2, mut visit: impl FnMut(Vector2, usize)) {
+        // Go all possible directions.
+        for direction in DIRECTIONS {
+            // Get the neighbour position, and check if still in bounds.
+            let neighbour = position + direction;
+            if neighbour.0 < 0
+                || neighbour.0 >= self.size
+                || neighbour.1 < 0
+                || neighbour.1 >= self.size
+            {
+                continue;
             }
 
-            writeln!(f)?;
+            visit(neighbour, get_risk_level(self.grid, neighbour));
         }
+    }
+}
+
+/// Gets the risk level of a position in the full map, which consists of repetitions of the provided grid.
+fn get_risk_level(grid: &Grid<u8>, position: Vector2) -> usize {
+    // Deterine tile coordinate and the original position that this position is (potentially) a repetition of.
+    let tile = position / grid.size;
+    let reference_position = position % grid.size;
+
+    // Cost to get to the position is the number in the grid. Since all tiles are just repetitions of the first tile, but
+    // every tile coordinate increases the cost by one, we can simply calculate the new cost quickly without storing all tiles.
+    let absolute_cost = grid.get(reference_position) as isize + tile.0 + tile.1;
+    ((absolute_cost - 1) % 9 + 1) as usize
+}
+
+/// Finds the cost of a path from the top-left to the bottom-right corner that is constructed by greedily
+/// moving either right or down, whichever is cheaper. This is not necessarily the shortest path, but it is
+/// very cheap to compute and therefore a good upper bound for the actual shortest path.
+pub fn find_greedy_path_cost(grid: &Grid<u8>, scale: isize) -> usize {
+    let end = Vector2(grid.size, grid.size) * scale - Vector2(1, 1);
 
-        Ok(())
+    let mut position = Vector2(0, 0);
+    let mut cost = 0;
+
+    while position != end {
+        let right = position + Vector2(1, 0);
+        let down = position + Vector2(0, 1);
+
+        // Stay within the bounds of the map, and otherwise pick the cheapest neighbour.
+        position = if right.0 > end.0
+            || (down.1 <= end.1 && get_risk_level(grid, down) < get_risk_level(grid, right))
+        {
+            down
+        } else {
+            right
+        };
+
+        cost += get_risk_level(grid, position);
     }
+
+    cost
 }
 
-/// The puzzle input.
-pub struct Input {
-    /// The input grid.
-    pub grid: Grid<u8>,
+/// Finds the shortest path in a grid from the top-left to the bottom-right corner, using the greedy path
+/// as an upper bound to prune expensive routes early.
+pub fn find_shortest_path_greedy_bound(grid: &Grid<u8>, scale: isize) -> usize {
+    let bound = find_greedy_path_cost(grid, scale);
+
+    // The greedy path is a valid path, so a route within the bound always exists.
+    find_shortest_path(grid, scale, Some(bound)).unwrap()
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    let file = File::open(file)?;
-    let lines = BufReader::new(file).lines();
+/// Finds the shortest path in a grid from the top-left to the bottom-right corner.
+///
+/// If `max_cost` is provided, any route that costs more than this bound is abandoned. If no route
+/// to the corner exists within the bound, [`None`] is returned.
+pub fn find_shortest_path(grid: &Grid<u8>, scale: isize, max_cost: Option<usize>) -> Option<usize> {
+    find_shortest_path_with_scratch(grid, scale, max_cost, &mut SearchScratch::default())
+}
 
-    let grid: Vec<u8> = lines
-        .flat_map(|ln| {
-            ln.expect("Expected a line")
-                .as_bytes()
-                .iter()
-                .map(|b| b - '0' as u8)
-                .collect::<Vec<u8>>()
-        })
-        .collect();
+/// Finds the shortest path like [`find_shortest_path`], but reuses the buffers in the provided scratch
+/// space instead of allocating new ones.
+pub fn find_shortest_path_with_scratch(
+    grid: &Grid<u8>,
+    scale: isize,
+    max_cost: Option<usize>,
+    scratch: &mut SearchScratch,
+) -> Option<usize> {
+    let size = grid.size * scale;
+    let end = Vector2(size, size) - Vector2(1, 1);
+    search(
+        grid,
+        scale,
+        None,
+        end,
+        max_cost,
+        scratch,
+        &mut SearchStats::new(),
+    )
+}
 
-    let size = (grid.len() as f64).sqrt() as isize;
-    Ok(Input {
-        grid: Grid { grid, size },
-    })
+/// Finds the shortest path like [`find_shortest_path`] without a bound, recording what the search did in the
+/// provided statistics.
+pub fn find_shortest_path_with_stats(
+    grid: &Grid<u8>,
+    scale: isize,
+    stats: &mut SearchStats,
+) -> Option<usize> {
+    let size = grid.size * scale;
+    let end = Vector2(size, size) - Vector2(1, 1);
+    search(
+        grid,
+        scale,
+        None,
+        end,
+        None,
+        &mut SearchScratch::<Agenda>::default(),
+        stats,
+    )
 }
 
-/// Contains information on the current route that we are taking in the path finding algorithm.
-/// We implement [`Ord`] and [`PartialOrd`] to allow storing them in a [`BinaryHeap`].
-#[derive(PartialEq, Eq)]
-struct RouteInfo {
-    position: Vector2,
-    cost: usize,
+/// Finds the shortest path like [`find_shortest_path`] without a bound, but keeps the agenda in the provided
+/// type of priority queue instead of the default [`Agenda`].
+pub fn find_shortest_path_with_queue<Q: PriorityQueue<Vector2> + Default>(
+    grid: &Grid<u8>,
+    scale: isize,
+) -> Option<usize> {
+    let size = grid.size * scale;
+    let end = Vector2(size, size) - Vector2(1, 1);
+    search(
+        grid,
+        scale,
+        None,
+        end,
+        None,
+        &mut SearchScratch::<Q>::default(),
+        &mut SearchStats::new(),
+    )
 }
 
-impl Ord for RouteInfo {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position.0.cmp(&other.position.0))
-            .then_with(|| self.position.1.cmp(&other.position.1))
+/// Finds the shortest path in a grid from any of the provided start positions to the end position. The risk
+/// levels of the start positions themselves are not counted.
+///
+/// Returns an error if any of the positions lies outside of the (repeated) map. Otherwise, `max_cost` bounds
+/// the search like it does in [`find_shortest_path`].
+pub fn find_path_between(
+    grid: &Grid<u8>,
+    scale: isize,
+    starts: &[Vector2],
+    end: Vector2,
+    max_cost: Option<usize>,
+) -> Result<Option<usize>, EndpointError> {
+    let size = grid.size * scale;
+    for &endpoint in starts.iter().chain(std::iter::once(&end)) {
+        if endpoint.0 < 0 || endpoint.0 >= size || endpoint.1 < 0 || endpoint.1 >= size {
+            return Err(EndpointError::OutOfBounds { endpoint, size });
+        }
     }
+
+    Ok(search(
+        grid,
+        scale,
+        Some(starts),
+        end,
+        max_cost,
+        &mut SearchScratch::<Agenda>::default(),
+        &mut SearchStats::new(),
+    ))
 }
 
-impl PartialOrd for RouteInfo {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+/// Runs Dijkstra's algorithm from all start positions at once, or from the top-left corner if there are
+/// none, until the end position is reached. All positions must lie within the (repeated) map.
+fn search<Q: PriorityQueue<Vector2>>(
+    grid: &Grid<u8>,
+    scale: isize,
+    starts: Option<&[Vector2]>,
+    end: Vector2,
+    max_cost: Option<usize>,
+    scratch: &mut SearchScratch<Q>,
+    stats: &mut SearchStats,
+) -> Option<usize> {
+    let _span =
+        tracing::debug_span!("dijkstra", scale, starts = starts.map_or(1, |x| x.len())).entered();
+
+    let size = grid.size * scale;
+    scratch.begin(size);
+
+    let mut problem = RiskMap { grid, size, end };
+    let SearchScratch { distances, agenda } = scratch;
+    let result = match starts {
+        Some(starts) => pathfinding::shortest_path_from(
+            &mut problem,
+            starts.iter().copied(),
+            max_cost,
+            agenda,
+            distances,
+            stats,
+        ),
+        None => pathfinding::shortest_path(&mut problem, max_cost, agenda, distances, stats),
+    };
+
+    match result {
+        Some(cost) => tracing::debug!(visited = stats.expanded, cost, "reached end"),
+        None => tracing::debug!(visited = stats.expanded, "end not reachable"),
     }
+    result
 }
 
-/// Finds the shortest path in a grid from the top-left to the bottom-right corner.
-fn find_shortest_path(grid: &Grid<u8>, scale: isize) -> usize {
-    let start = Vector2(0, 0);
-    let end = Vector2(grid.size, grid.size) * scale - Vector2(1, 1);
+/// Computes the cost of the shortest path from the top-left corner to every position in the map.
+/// Unlike [`find_shortest_path`], this does not stop when the bottom-right corner is reached.
+pub fn compute_distances(grid: &Grid<u8>, scale: isize) -> Grid<usize> {
+    let distances = distances_from(grid, scale, Vector2(0, 0));
 
-    // Stores the distances table.
-    let mut distances = Grid::new(grid.size * scale, usize::MAX);
+    Grid {
+        grid: distances
+            .grid
+            .iter()
+            .map(|&x| {
+                if x == u32::MAX {
+                    usize::MAX
+                } else {
+                    x as usize
+                }
+            })
+            .collect(),
+        size: distances.size,
+    }
+}
+
+/// Computes the cost of the shortest path from the provided start position to every position in the
+/// map. The risk level of the start position itself is not counted. Positions that cannot be reached
+/// are assigned [`u32::MAX`].
+pub fn distances_from(grid: &Grid<u8>, scale: isize, start: Vector2) -> Grid<u32> {
+    let mut distances = Grid::new(grid.size * scale, u32::MAX);
     distances.set(start, 0);
 
-    // The agenda, stored as a priority queue for fast smallest element lookups (in our case lowest distance).
-    let mut agenda = BinaryHeap::with_capacity(1024);
-    agenda.push(RouteInfo {
-        position: start,
-        cost: 0,
-    });
-
-    while let Some(current) = agenda.pop() {
-        // Are we there yet?
-        if current.position == end {
-            return current.cost;
-        }
+    let mut agenda = Agenda::default();
+    agenda.push(0, start);
 
-        // Did we already find a better route for this position?
-        if current.cost > distances.get(current.position) {
+    while let Some((cost, position)) = agenda.pop() {
+        if cost > distances.get(position) as usize {
             continue;
         }
 
-        // Go all possible directions.
         for direction in DIRECTIONS {
-            // Get the neighbour position, and check if still in bounds.
-            let neighbour = current.position + direction;
-            if neighbour.0 < 0
-                || neighbour.0 >= distances.size
-                || neighbour.1 < 0
-                || neighbour.1 >= distances.size
-            {
+            let neighbour = position + direction;
+            if !distances.contains(neighbour) {
                 continue;
             }
 
-            // Deterine tile coordinate and the original neighbour position that this neighbour is  (potentially) a repetition of.
-            let tile = neighbour / grid.size;
-            let reference_neighbour = neighbour % grid.size;
-
-            // Cost to get to the neighbour is the number in the grid. Since all tiles are just repetitions of the first tile, but
-            // every tile coordinate increases the cost by one, we can simply calculate the new cost quickly without storing all tiles.
-            let absolute_cost = grid.get(reference_neighbour) as isize + tile.0 + tile.1;
-            let normalized_cost = (absolute_cost - 1) % 9 + 1;
-
-            // Compute total cost of our newly extended route.
-            let new_total_cost = current.cost + normalized_cost as usize;
-
-            // Is this actually a better route than we had before?
-            if new_total_cost < distances.get(neighbour) {
-                // Remember route, and schedule neighbour for processing.
-                distances.set(neighbour, new_total_cost);
-                agenda.push(RouteInfo {
-                    position: neighbour,
-                    cost: new_total_cost,
-                });
+            let new_total_cost = cost + get_risk_level(grid, neighbour);
+            if new_total_cost < distances.get(neighbour) as usize {
+                distances.set(neighbour, new_total_cost as u32);
+                agenda.push(new_total_cost, neighbour);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Writes a distance field to a file as a plain PGM image, where darker pixels are closer to the
+/// start and brighter pixels are further away. Unreachable positions are drawn white.
+pub fn export_heatmap(path: &Path, distances: &Grid<u32>) -> std::io::Result<()> {
+    const MAX_GRAY: u64 = 255;
+
+    let max_distance = distances
+        .grid
+        .iter()
+        .copied()
+        .filter(|&x| x != u32::MAX)
+        .max()
+        .unwrap_or(0)
+        .max(1) as u64;
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "P2")?;
+    writeln!(writer, "{} {}", distances.size, distances.size)?;
+    writeln!(writer, "{}", MAX_GRAY)?;
+
+    for row in distances.grid.chunks(distances.size as usize) {
+        let line: Vec<String> = row
+            .iter()
+            .map(|&x| {
+                let gray = if x == u32::MAX {
+                    MAX_GRAY
+                } else {
+                    x as u64 * MAX_GRAY / max_distance
+                };
+                gray.to_string()
+            })
+            .collect();
+        writeln!(writer, "{}", line.join(" "))?;
+    }
+
+    writer.flush()
+}
+
+/// Reasons why a position cannot be used as an endpoint of a path.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EndpointError {
+    /// Indicates the position is not in the format `x,y`.
+    InvalidFormat(String),
+
+    /// Indicates the position lies outside of the (repeated) map of the provided size.
+    OutOfBounds { endpoint: Vector2, size: isize },
+}
+
+impl std::fmt::Display for EndpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EndpointError::InvalidFormat(x) => write!(f, "{} is not in the format x,y", x),
+            EndpointError::OutOfBounds { endpoint, size } => write!(
+                f,
+                "{},{} lies outside of the {}x{} map",
+                endpoint.0, endpoint.1, size, size
+            ),
+        }
+    }
+}
+
+/// Parses an endpoint of a path in the format `x,y`.
+pub fn parse_endpoint(s: &str) -> Result<Vector2, EndpointError> {
+    s.split_once(',')
+        .and_then(|(x, y)| Some(Vector2(x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .ok_or_else(|| EndpointError::InvalidFormat(s.to_string()))
+}
+
+/// Reasons why a distance grid does not certify a claimed shortest path cost.
+#[derive(Debug, PartialEq, Eq)]
+pub enum CertificateError {
+    /// Indicates either grid is empty or has a size that does not match its data, or the distance grid is not
+    /// a whole multiple of the size of the risk grid.
+    SizeMismatch,
+
+    /// Indicates the distance of the top-left corner is not zero.
+    NonZeroStart(usize),
+
+    /// Indicates the distance to the first position could be improved by coming from the second position.
+    TriangleInequality(Vector2, Vector2),
+
+    /// Indicates the distance to a position cannot be achieved by coming from any of its neighbours.
+    Unreachable(Vector2),
+
+    /// Indicates the distance to the bottom-right corner differs from the claimed cost.
+    CostMismatch { claimed: usize, actual: usize },
+}
+
+/// Verifies that the provided distance grid proves that `claimed_cost` is the cost of the shortest path
+/// from the top-left to the bottom-right corner of the (repeated) risk grid.
+///
+/// The triangle inequality holding for every pair of neighbours proves no path can be cheaper than the
+/// recorded distances. Every position having a neighbour that achieves its distance exactly proves the
+/// recorded distances are actually attainable.
+pub fn verify_certificate(
+    grid: &Grid<u8>,
+    distances: &Grid<usize>,
+    claimed_cost: usize,
+) -> Result<(), CertificateError> {
+    // Grids that were deserialized may claim a size that does not match their data, which must be rejected
+    // before anything is indexed.
+    let is_square =
+        |size: isize, len: usize| size > 0 && size.checked_mul(size) == Some(len as isize);
+    if !is_square(grid.size, grid.grid.len())
+        || !is_square(distances.size, distances.grid.len())
+        || distances.size % grid.size != 0
+    {
+        return Err(CertificateError::SizeMismatch);
+    }
+
+    let start = Vector2(0, 0);
+    let end = Vector2(distances.size - 1, distances.size - 1);
+
+    if distances.get(start) != 0 {
+        return Err(CertificateError::NonZeroStart(distances.get(start)));
+    }
+
+    for y in 0..distances.size {
+        for x in 0..distances.size {
+            let position = Vector2(x, y);
+            let distance = distances.get(position);
+            let risk = get_risk_level(grid, position);
+
+            let mut tight = position == start;
+            for direction in DIRECTIONS {
+                let neighbour = position + direction;
+                if !distances.contains(neighbour) {
+                    continue;
+                }
+
+                // Entering this position from the neighbour should never be cheaper than what we recorded.
+                let via_neighbour = distances.get(neighbour).saturating_add(risk);
+                if via_neighbour < distance {
+                    return Err(CertificateError::TriangleInequality(position, neighbour));
+                }
+
+                tight |= via_neighbour == distance;
+            }
+
+            if !tight {
+                return Err(CertificateError::Unreachable(position));
+            }
+        }
+    }
+
+    let actual = distances.get(end);
+    if actual != claimed_cost {
+        return Err(CertificateError::CostMismatch {
+            claimed: claimed_cost,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+/// Serializes a distance grid into a compact byte representation.
+///
+/// The format is the size of the grid followed by the differences between consecutive distances in
+/// row-major order. All numbers are stored as LEB128 variable length integers, and the differences are
+/// zigzag encoded. Since neighbouring distances only differ by a few risk levels, most cells take up a
+/// single byte.
+pub fn serialize_distances(distances: &Grid<usize>) -> Vec<u8> {
+    fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            buffer.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        buffer.push(value as u8);
+    }
+
+    let mut buffer = Vec::with_capacity(distances.grid.len() + 8);
+    write_varint(&mut buffer, distances.size as u64);
+
+    let mut previous = 0i64;
+    for &distance in distances.grid.iter() {
+        let delta = distance as i64 - previous;
+        write_varint(&mut buffer, ((delta << 1) ^ (delta >> 63)) as u64);
+        previous = distance as i64;
+    }
+
+    buffer
+}
+
+/// Deserializes a distance grid produced by [`serialize_distances`]. Returns [`None`] if the data is
+/// malformed.
+pub fn deserialize_distances(data: &[u8]) -> Option<Grid<usize>> {
+    fn read_varint(data: &mut impl Iterator<Item = u8>) -> Option<u64> {
+        let mut result = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = data.next()?;
+            result |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(result);
             }
         }
+        None
     }
 
-    distances.get(end)
+    // Every cell takes at least one byte, so a size with more cells than there are bytes is malformed, and
+    // must be rejected before anything is allocated for it.
+    let byte_count = data.len();
+    let mut data = data.iter().copied();
+    let size = isize::try_from(read_varint(&mut data)?).ok()?;
+    let cell_count = size
+        .checked_mul(size)
+        .filter(|&x| x as usize <= byte_count)? as usize;
+
+    let mut grid = Vec::with_capacity(cell_count);
+    let mut previous = 0i64;
+    for _ in 0..cell_count {
+        let value = read_varint(&mut data)?;
+        let delta = (value >> 1) as i64 ^ -((value & 1) as i64);
+        previous = previous.checked_add(delta).filter(|&x| x >= 0)?;
+        grid.push(previous as usize);
+    }
+
+    if data.next().is_some() {
+        return None;
+    }
+
+    Some(Grid { grid, size })
+}
+
+pub fn part1(input: &Input, scratch: Option<&mut SearchScratch>) -> usize {
+    let mut owned = SearchScratch::default();
+    find_shortest_path_with_scratch(&input.grid, 1, None, scratch.unwrap_or(&mut owned)).unwrap()
 }
 
-pub fn part1(input: &Input) -> usize {
-    find_shortest_path(&input.grid, 1)
+pub fn part2(input: &Input, scratch: Option<&mut SearchScratch>) -> usize {
+    let mut owned = SearchScratch::default();
+    find_shortest_path_with_scratch(&input.grid, 5, None, scratch.unwrap_or(&mut owned)).unwrap()
 }
 
-pub fn part2(input: &Input) -> usize {
-    find_shortest_path(&input.grid, 5)
+/// The solution of this day, as it is registered with the runner.
+pub struct Day15;
+
+impl Solver for Day15 {
+    type Input = Input;
+
+    const BUDGET: Budget = Budget {
+        parse: None,
+        part1: Some(Duration::from_millis(10)),
+        part2: Some(Duration::from_millis(250)),
+    };
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input, None).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input, None).into()
+    }
+
+    fn part1_with(input: &Input, scratch: &mut Scratch) -> Answer {
+        part1(input, Some(scratch.get())).into()
+    }
+
+    fn part2_with(input: &Input, scratch: &mut Scratch) -> Answer {
+        part2(input, Some(scratch.get())).into()
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    aoc_common::init_tracing();
+
+    let mut max_cost = None;
+    let mut greedy_bound = false;
+    let mut certificate = false;
+    let mut heatmap = None;
+    let mut search_stats = false;
+    let mut frontier_plot = None;
+    let mut starts = Vec::new();
+    let mut end = None;
+    let mut mapped = false;
+    let mut generate = None;
+    let mut seed = aoc_common::seed();
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--mmap" => mapped = true,
+            "--generate" => {
+                let path = args.next().expect("Expected a file path after --generate.");
+                let size = args
+                    .next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .expect("Expected a grid size after the file path of --generate.");
+                generate = Some((path, size));
+            }
+            "--max-cost" => {
+                max_cost = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a number after --max-cost."),
+                )
+            }
+            "--greedy-bound" => greedy_bound = true,
+            "--certificate" => certificate = true,
+            "--search-stats" => search_stats = true,
+            "--frontier-plot" => {
+                frontier_plot = Some(
+                    args.next()
+                        .expect("Expected a file path after --frontier-plot."),
+                )
+            }
+            "--heatmap" => {
+                heatmap = Some(args.next().expect("Expected a file path after --heatmap."))
+            }
+            "--from" => starts.push(
+                parse_endpoint(&args.next().expect("Expected a position after --from."))
+                    .unwrap_or_else(|e| panic!("Invalid --from: {}.", e)),
+            ),
+            "--to" => {
+                end = Some(
+                    parse_endpoint(&args.next().expect("Expected a position after --to."))
+                        .unwrap_or_else(|e| panic!("Invalid --to: {}.", e)),
+                )
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --seed.")
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    if let Some((path, size)) = generate {
+        write_generated(Path::new(&path), size, seed)?;
+        println!("Generated a {}x{} grid in {}", size, size, path);
+        return Ok(());
+    }
+
+    let corners = starts.is_empty() && end.is_none();
+    if greedy_bound && !corners {
+        panic!("--greedy-bound only applies to paths between the corners of the map.");
+    }
+    if certificate && !corners {
+        panic!("--certificate only applies to paths between the corners of the map.");
+    }
+    if (search_stats || frontier_plot.is_some()) && !corners {
+        panic!("--search-stats and --frontier-plot only apply to paths between the corners of the map.");
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let file = file.unwrap_or_else(|| input_path("input.txt"));
+    let input = if mapped {
+        parse_input_mapped(&file)?
+    } else {
+        parse_input(&file)?
+    };
     let time_parse = now.elapsed();
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
+    // Determine the bound to use for a map of the provided scale.
+    let get_bound = |scale| {
+        if max_cost.is_none() && greedy_bound {
+            Some(find_greedy_path_cost(&input.grid, scale))
+        } else {
+            max_cost
+        }
+    };
+
+    // Find the path between the requested endpoints, which default to the corners of the map.
+    let find_path = |scale: isize| {
+        if corners {
+            return Ok(find_shortest_path(&input.grid, scale, get_bound(scale)));
+        }
+
+        let size = input.grid.size * scale;
+        let starts = if starts.is_empty() {
+            vec![Vector2(0, 0)]
+        } else {
+            starts.clone()
+        };
+        let end = end.unwrap_or(Vector2(size - 1, size - 1));
+        find_path_between(&input.grid, scale, &starts, end, max_cost)
+    };
+
+    /// Formats the result of a bounded search.
+    fn format_result(result: &Result<Option<usize>, EndpointError>) -> String {
+        match result {
+            Ok(Some(cost)) => cost.to_string(),
+            Ok(None) => String::from("no path within the maximum cost"),
+            Err(error) => error.to_string(),
+        }
+    }
+
     let now = Instant::now();
-    let result1 = part1(&input);
+    let result1 = find_path(1);
     let time1 = now.elapsed();
-    println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
+    println!(
+        "Solution 1: {} (time: {}us)",
+        format_result(&result1),
+        time1.as_micros()
+    );
 
     let now = Instant::now();
-    let result2 = part2(&input);
+    let result2 = find_path(5);
     let time2 = now.elapsed();
-    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+    println!(
+        "Solution 2: {} (time: {}us)",
+        format_result(&result2),
+        time2.as_micros()
+    );
+
+    if let Some(path) = heatmap {
+        // Render the distance field of the full (part 2) map.
+        let distances = distances_from(&input.grid, 5, Vector2(0, 0));
+        export_heatmap(Path::new(&path), &distances)?;
+        println!(
+            "Heatmap: {}x{} distance field written to {}",
+            distances.size, distances.size, path
+        );
+    }
+
+    // Search again with instrumentation, such that it does not slow down the timed runs above.
+    if search_stats {
+        for (part, scale) in [(1, 1), (2, 5)] {
+            let mut stats = SearchStats::new();
+            find_shortest_path_with_stats(&input.grid, scale, &mut stats);
+            println!(
+                "Search {}: {} states expanded, at most {} on the frontier",
+                part, stats.expanded, stats.max_frontier
+            );
+        }
+    }
+
+    if let Some(path) = frontier_plot {
+        let mut stats = SearchStats::recording();
+        find_shortest_path_with_stats(&input.grid, 5, &mut stats);
+        stats.write_frontier_svg(&mut BufWriter::new(File::create(&path)?))?;
+        println!("Frontier of part 2 plotted to {}", path);
+    }
+
+    if certificate {
+        // Write out the full distance grids as proof, and check them independently.
+        for (part, scale, result) in [(1, 1, result1.unwrap()), (2, 5, result2.unwrap())] {
+            let distances = compute_distances(&input.grid, scale);
+            let data = serialize_distances(&distances);
+            let file_name = format!("certificate{}.bin", part);
+            std::fs::write(&file_name, &data)?;
+
+            let status = match (deserialize_distances(&data), result) {
+                (Some(distances), Some(cost)) => {
+                    match verify_certificate(&input.grid, &distances, cost) {
+                        Ok(()) => String::from("valid"),
+                        Err(error) => format!("invalid ({:?})", error),
+                    }
+                }
+                (None, _) => String::from("malformed"),
+                (_, None) => String::from("no cost to certify"),
+            };
+
+            println!(
+                "Certificate {}: {} ({} bytes written to {})",
+                part,
+                status,
+                data.len(),
+                file_name
+            );
+        }
+    }
 
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the example input.
+    fn example() -> Input {
+        parse_reader(include_bytes!("../input2.txt").as_slice()).unwrap()
+    }
+
+    #[test]
+    fn deserialized_certificate_is_accepted() {
+        let input = example();
+        let distances = compute_distances(&input.grid, 5);
+        let distances = deserialize_distances(&serialize_distances(&distances)).unwrap();
+
+        assert_eq!(verify_certificate(&input.grid, &distances, 315), Ok(()));
+    }
+
+    #[test]
+    fn certificate_with_mismatched_size_is_rejected() {
+        let input = example();
+        let empty = Grid {
+            grid: Vec::new(),
+            size: 0,
+        };
+        let empty_risks = Grid {
+            grid: Vec::new(),
+            size: 0,
+        };
+        let truncated = Grid {
+            grid: vec![0; 3],
+            size: input.grid.size,
+        };
+
+        assert_eq!(
+            verify_certificate(&input.grid, &empty, 0),
+            Err(CertificateError::SizeMismatch)
+        );
+        assert_eq!(
+            verify_certificate(&empty_risks, &truncated, 0),
+            Err(CertificateError::SizeMismatch)
+        );
+        assert_eq!(
+            verify_certificate(&input.grid, &truncated, 0),
+            Err(CertificateError::SizeMismatch)
+        );
+    }
+}
+
 // Parse: (time: 194us)
 // Solution 1: 503 (time: 927us)
 // Solution 2: 2853 (time: 24559us)
 
 // part 1 (real)           time:   [868.96 us 872.84 us 878.01 us]
-// part 2 (real)           time:   [23.824 ms 23.855 ms 23.888 ms]
\ No newline at end of file
+// part 2 (real)           time:   [23.824 ms 23.855 ms 23.888 ms]
+// part 2 (real, greedy bound)
+//                         time:   [30.412 ms 30.873 ms 31.395 ms]
+// (plain part 2 measured at [30.714 ms 31.187 ms 31.717 ms] on the same machine. The greedy bound
+// only prunes pushes of routes that would never be popped before the end anyway, so it is a wash.)
+//
+// Benchmarked with --features mmap:
+// parse (generated 4000x4000)/buffered
+//                         time:   [56.247 ms 59.289 ms 63.551 ms]
+// parse (generated 4000x4000)/mapped
+//                         time:   [28.958 ms 29.980 ms 31.256 ms]
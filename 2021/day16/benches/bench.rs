@@ -6,6 +6,14 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 mod main;
 
 fn bench_main(c: &mut Criterion) {
+    c.bench_function("parse (sample)", |b| {
+        b.iter(|| main::parse_input(black_box("input2.txt")).unwrap())
+    });
+
+    c.bench_function("parse (real)", |b| {
+        b.iter(|| main::parse_input(black_box("input.txt")).unwrap())
+    });
+
     c.bench_function("part 1 (sample)", |b| {
         let input = main::parse_input("input2.txt").unwrap();
         b.iter(|| main::part1(black_box(&input)))
@@ -25,7 +33,58 @@ fn bench_main(c: &mut Criterion) {
         let input = main::parse_input("input.txt").unwrap();
         b.iter(|| main::part2(black_box(&input)))
     });
+
+    // A transmission of 16 sums of 2000 large literals each, where decoding is dominated by reading bits.
+    let transmission = main::PacketBuilder::sum((0..16).map(|i| {
+        main::PacketBuilder::sum(
+            (0..2000u128).map(|j| main::lit((i << 64 | j) * 0x9E37_79B9_7F4A_7C15)),
+        )
+        .by_packet_count()
+    }))
+    .by_packet_count()
+    .encode()
+    .unwrap();
+
+    c.bench_function("decode (generated literals)", |b| {
+        b.iter(|| {
+            let mut reader = main::BitReader::new(black_box(&transmission));
+            main::Packet::read(&mut reader, &main::Limits::default()).unwrap()
+        })
+    });
+
+    let mut group = c.benchmark_group("read bits (generated literals)");
+    group.bench_function("5 bits", |b| {
+        b.iter(|| {
+            let mut reader = main::BitReader::new(black_box(&transmission));
+            let mut sum = 0u32;
+            while reader.position + 5 <= 8 * transmission.len() {
+                sum += reader.read_bits(5).unwrap() as u32;
+            }
+            sum
+        })
+    });
+    group.bench_function("5 bits (const)", |b| {
+        b.iter(|| {
+            let mut reader = main::BitReader::new(black_box(&transmission));
+            let mut sum = 0u32;
+            while reader.position + 5 <= 8 * transmission.len() {
+                sum += reader.read_bits_const::<5>().unwrap() as u32;
+            }
+            sum
+        })
+    });
+    group.bench_function("16 bits (aligned)", |b| {
+        b.iter(|| {
+            let mut reader = main::BitReader::new(black_box(&transmission));
+            let mut sum = 0u32;
+            while reader.position + 16 <= 8 * transmission.len() {
+                sum += reader.read_bits(16).unwrap() as u32;
+            }
+            sum
+        })
+    });
+    group.finish();
 }
 
 criterion_group!(benches, bench_main);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -1,12 +1,33 @@
+use aoc_common::{input_path, Answer, Budget, Scanner, Solver};
 use std::{
+    convert::Infallible,
+    fmt::Display,
     fs::File,
     io::{BufRead, BufReader},
-    time::Instant,
+    ops::Range,
+    time::{Duration, Instant},
 };
 
 /// The puzzle input.
 pub struct Input {
     data: Vec<u8>,
+
+    /// The limits the transmission is decoded with.
+    pub limits: Limits,
+}
+
+/// Limits on the size of a decoded transmission, such that malformed transmissions are rejected with an
+/// error before they can exhaust the stack or memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum number of packets in the whole packet tree.
+    pub max_packets: usize,
+
+    /// The maximum number of nested packets on any path through the tree, including the outermost packet.
+    pub max_depth: usize,
+
+    /// The maximum number of 4-bit groups in a single literal, including leading zero groups.
+    pub max_literal_nibbles: usize,
 }
 
 /// A structure that reads individual bits from a byte stream.
@@ -18,9 +39,26 @@ pub struct BitReader<'a> {
     pub position: usize,
 }
 
+/// A structure that writes individual bits to a byte stream.
+#[derive(Default)]
+pub struct BitWriter {
+    /// The raw data written so far. The last byte is padded with zero bits.
+    pub data: Vec<u8>,
+
+    /// The current bit index.
+    pub position: usize,
+}
+
 /// Errors that can occur during the reading and evaluation of a packet.
 type Result<T> = std::result::Result<T, Error>;
 
+/// Errors that can occur during the reading and evaluation of a packet, annotated with the
+/// location in the transmission at which they occurred.
+type LocatedResult<T> = std::result::Result<T, LocatedError>;
+
+/// The maximum number of bytes that are included in the context of a located error.
+pub const ERROR_CONTEXT_SIZE: usize = 4;
+
 #[derive(Debug)]
 pub enum Error {
     /// Indicates an incorrect amount of bits was specified for reading.
@@ -31,30 +69,125 @@ pub enum Error {
 
     /// Indicates a packet had an invalid type ID.
     InvalidTypeId(u16),
+
+    /// Indicates an operator packet had an unsupported amount of operands.
+    InvalidOperandCount(usize),
+
+    /// Indicates a literal value does not fit in 128 bits.
+    LiteralOverflow,
+
+    /// Indicates the transmission contains more packets than the limit.
+    TooManyPackets(usize),
+
+    /// Indicates packets are nested deeper than the limit.
+    TooDeep(usize),
+
+    /// Indicates a literal consists of more 4-bit groups than the limit.
+    LiteralTooLong(usize),
+
+    /// Indicates the result of an operator does not fit in 128 bits.
+    Overflow,
+}
+
+/// An error that occurred at a specific location in the transmission.
+#[derive(Debug)]
+pub struct LocatedError {
+    /// The error that occurred.
+    pub error: Error,
+
+    /// The bit index at which the error occurred.
+    pub position: usize,
+
+    /// The bytes of the transmission starting at the byte containing the offending bit.
+    pub context: Vec<u8>,
+
+    /// Indicates whether there is more data after the bytes in the context.
+    pub truncated: bool,
 }
 
-pub fn parse_input(file: &str) -> std::io::Result<Input> {
-    fn hex_value(c: u8) -> u8 {
-        match c {
-            b'A'..=b'F' => c - b'A' + 10,
-            b'a'..=b'f' => c - b'a' + 10,
-            b'0'..=b'9' => c - b'0',
-            _ => panic!("Non hexadecimal digit."),
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidBitCount(count) => write!(f, "invalid bit count {}", count),
+            Error::Eof => write!(f, "unexpected end of transmission"),
+            Error::InvalidTypeId(type_id) => write!(f, "invalid type id {}", type_id),
+            Error::InvalidOperandCount(count) => write!(f, "invalid operand count {}", count),
+            Error::LiteralOverflow => write!(f, "literal value does not fit in 128 bits"),
+            Error::TooManyPackets(limit) => write!(f, "more than {} packets", limit),
+            Error::TooDeep(limit) => write!(f, "packets nested deeper than {}", limit),
+            Error::LiteralTooLong(limit) => write!(f, "literal longer than {} groups", limit),
+            Error::Overflow => write!(f, "operator result does not fit in 128 bits"),
         }
     }
+}
+
+impl Display for LocatedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at bit {} (", self.error, self.position)?;
+
+        if self.context.is_empty() {
+            write!(f, "end of data")?;
+        } else {
+            write!(f, "byte {}:", self.position / 8)?;
+            for byte in &self.context {
+                write!(f, " 0x{:02X}", byte)?;
+            }
+            if self.truncated {
+                write!(f, " ...")?;
+            }
+        }
+
+        write!(f, ")")
+    }
+}
+
+impl std::error::Error for LocatedError {}
+
+impl Default for Limits {
+    /// Gets limits that comfortably fit any puzzle input, and only allow literals that fit in 128 bits.
+    fn default() -> Self {
+        Self {
+            max_packets: 1 << 16,
+            max_depth: 256,
+            max_literal_nibbles: 32,
+        }
+    }
+}
+
+/// Gets the value of a single hexadecimal digit.
+fn hex_value(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|x| x as u8)
+}
 
-    let file = File::open(file)?;
-    let data: Vec<u8> = BufReader::new(file)
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let line = reader
         .lines()
         .next()
-        .expect("Expected a line.")
-        .unwrap()
-        .as_bytes()
-        .chunks(2)
-        .map(|pair| (hex_value(pair[0]) << 4) | hex_value(pair[1]))
-        .collect();
+        .ok_or_else(|| aoc_common::Error::at_line(1, "Expected a line."))??;
 
-    Ok(Input { data })
+    let line = line.trim_end().as_bytes();
+    let mut scanner = Scanner::new(line);
+    let mut data = Vec::with_capacity(line.len() / 2);
+    while !scanner.is_eof() {
+        let high = scanner
+            .expect("a hexadecimal digit", hex_value)
+            .map_err(|x| x.at_line(1))?;
+        let low = scanner
+            .expect("a second hexadecimal digit", hex_value)
+            .map_err(|x| x.at_line(1))?;
+        data.push((high << 4) | low);
+    }
+
+    Ok(Input {
+        data,
+        limits: Limits::default(),
+    })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
 }
 
 impl<'a> BitReader<'a> {
@@ -63,12 +196,41 @@ impl<'a> BitReader<'a> {
         Self { data, position: 0 }
     }
 
+    /// Annotates the provided error with the specified bit index and the bytes surrounding it.
+    pub fn error_at(&self, position: usize, error: Error) -> LocatedError {
+        let start = std::cmp::min(position / 8, self.data.len());
+        let end = std::cmp::min(start + ERROR_CONTEXT_SIZE, self.data.len());
+
+        LocatedError {
+            error,
+            position,
+            context: self.data[start..end].to_vec(),
+            truncated: end < self.data.len(),
+        }
+    }
+
+    /// Annotates the provided error with the current bit index and the bytes surrounding it.
+    pub fn error(&self, error: Error) -> LocatedError {
+        self.error_at(self.position, error)
+    }
+
     /// Consumes the specified amount of bits from the input stream.
-    pub fn read_bits(&mut self, mut count: usize) -> Result<u16> {
+    pub fn read_bits(&mut self, mut count: usize) -> LocatedResult<u16> {
         if count > 16 {
-            return Err(Error::InvalidBitCount(count));
+            return Err(self.error(Error::InvalidBitCount(count)));
         } else if self.position + count > 8 * self.data.len() {
-            return Err(Error::Eof);
+            return Err(self.error(Error::Eof));
+        }
+
+        // Whole bytes at a byte boundary do not need any shifting or masking.
+        if self.position.is_multiple_of(8) && (count == 8 || count == 16) {
+            let byte_index = self.position / 8;
+            self.position += count;
+            return Ok(if count == 8 {
+                self.data[byte_index] as u16
+            } else {
+                u16::from_be_bytes([self.data[byte_index], self.data[byte_index + 1]])
+            });
         }
 
         let mut result = 0u16;
@@ -98,14 +260,48 @@ impl<'a> BitReader<'a> {
         Ok(result)
     }
 
-    /// Consumes a compressed literal value from the input stream.
-    pub fn read_compressed_literal(&mut self) -> Result<usize> {
-        let mut result = 0usize;
+    /// Consumes `N` bits from the input stream, like [`BitReader::read_bits`]. Because the number of bits is
+    /// known at compile time, the bits are extracted from a 3-byte window with a single shift and mask,
+    /// instead of one chunk per byte.
+    pub fn read_bits_const<const N: usize>(&mut self) -> LocatedResult<u16> {
+        if N > 16 {
+            return Err(self.error(Error::InvalidBitCount(N)));
+        } else if self.position + N > 8 * self.data.len() {
+            return Err(self.error(Error::Eof));
+        }
+
+        // The bits span at most 3 bytes, since they start at most 7 bits into the first one. Bytes past the
+        // end of the data are never part of the result, so they are read as zero.
+        let byte_index = self.position / 8;
+        let bit_index = self.position % 8;
+        let window = (0..3).fold(0u32, |window, i| {
+            window << 8 | *self.data.get(byte_index + i).unwrap_or(&0) as u32
+        });
+
+        self.position += N;
+        Ok(((window >> (24 - bit_index - N)) & ((1 << N) - 1)) as u16)
+    }
+
+    /// Consumes a compressed literal value of at most `max_nibbles` 4-bit groups from the input stream.
+    pub fn read_compressed_literal(&mut self, max_nibbles: usize) -> LocatedResult<u128> {
+        let start = self.position;
+        let mut result = 0u128;
+
+        for nibbles in 1.. {
+            // Reject long runs of (zero) groups early, even if the value itself would fit.
+            if nibbles > max_nibbles {
+                return Err(self.error_at(start, Error::LiteralTooLong(max_nibbles)));
+            }
+
+            let chunk = self.read_bits_const::<5>()?;
+
+            // Make sure we are not shifting out any significant bits.
+            if result >> (u128::BITS - 4) != 0 {
+                return Err(self.error_at(start, Error::LiteralOverflow));
+            }
 
-        loop {
-            let chunk = self.read_bits(5)?;
             result <<= 4;
-            result |= (chunk & 0b1111) as usize;
+            result |= (chunk & 0b1111) as u128;
             if chunk & 0b10000 == 0 {
                 break;
             }
@@ -115,6 +311,57 @@ impl<'a> BitReader<'a> {
     }
 }
 
+impl BitWriter {
+    /// Creates a new empty bit writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the lowest `count` bits of the provided value to the output stream.
+    pub fn write_bits(&mut self, value: u16, count: usize) -> Result<()> {
+        if count > 16 {
+            return Err(Error::InvalidBitCount(count));
+        }
+
+        for i in (0..count).rev() {
+            // Start a new byte when we crossed a byte boundary.
+            if self.position.is_multiple_of(8) {
+                self.data.push(0);
+            }
+
+            let bit = ((value >> i) & 1) as u8;
+            *self.data.last_mut().unwrap() |= bit << (7 - self.position % 8);
+            self.position += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Appends a value to the output stream as a compressed literal, using as few groups as possible.
+    pub fn write_compressed_literal(&mut self, value: u128) -> Result<()> {
+        let group_count = literal_group_count(value);
+
+        for i in (0..group_count).rev() {
+            let chunk = ((value >> (i * 4)) & 0b1111) as u16;
+            let continuation = if i > 0 { 0b10000 } else { 0 };
+            self.write_bits(continuation | chunk, 5)?;
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the writer, and returns the written bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Determines the number of 4-bit groups needed to encode the provided value as a compressed literal.
+fn literal_group_count(value: u128) -> usize {
+    let significant_bits = (u128::BITS - value.leading_zeros()) as usize;
+    std::cmp::max(1, significant_bits.div_ceil(4))
+}
+
 pub const TYPE_ID_SUM: u16 = 0;
 pub const TYPE_ID_PRODUCT: u16 = 1;
 pub const TYPE_ID_MIN: u16 = 2;
@@ -127,124 +374,1057 @@ pub const TYPE_ID_EQ: u16 = 7;
 pub const LENGTH_TYPE_ID_BIT_COUNT: u16 = 0;
 pub const LENGTH_TYPE_ID_PACKET_COUNT: u16 = 1;
 
-pub fn part1(input: &Input) -> Result<usize> {
-    fn read_packet(mut reader: &mut BitReader) -> Result<usize> {
-        let mut version = reader.read_bits(3)? as usize;
-        let type_id = reader.read_bits(3)?;
+pub fn part1(input: &Input) -> LocatedResult<usize> {
+    let packet = Packet::parse(input)?;
+
+    let mut visitor = VersionSum::default();
+    let Ok(()) = walk(&packet, &mut visitor);
+    Ok(visitor.sum)
+}
+
+/// A function that computes the value of an operator packet from its evaluated operands.
+pub type Operator = fn(&[u128]) -> Result<u128>;
+
+/// A dispatch table mapping packet type IDs to the operators they represent.
+pub struct OperatorRegistry {
+    /// The registered operators, indexed by type ID.
+    operators: [Option<Operator>; 8],
+}
+
+impl OperatorRegistry {
+    /// Creates a new registry without any operators.
+    pub fn empty() -> Self {
+        Self {
+            operators: [None; 8],
+        }
+    }
+
+    /// Creates a new registry containing all operators defined by the BITS specification.
+    /// Every operator checks its operands, and reports an error rather than panicking or wrapping around if
+    /// its result does not fit in 128 bits.
+    pub fn standard() -> Self {
+        fn fold(operands: &[u128], operation: fn(u128, u128) -> Option<u128>) -> Result<u128> {
+            let (&first, rest) = operands
+                .split_first()
+                .ok_or(Error::InvalidOperandCount(0))?;
+            rest.iter()
+                .try_fold(first, |a, &b| operation(a, b).ok_or(Error::Overflow))
+        }
+
+        fn compare(operands: &[u128], predicate: fn(u128, u128) -> bool) -> Result<u128> {
+            match operands {
+                [a, b] => Ok(predicate(*a, *b) as u128),
+                _ => Err(Error::InvalidOperandCount(operands.len())),
+            }
+        }
+
+        let mut result = Self::empty();
+        result.register(TYPE_ID_SUM, |x| fold(x, u128::checked_add));
+        result.register(TYPE_ID_PRODUCT, |x| fold(x, u128::checked_mul));
+        result.register(TYPE_ID_MIN, |x| fold(x, |a, b| Some(a.min(b))));
+        result.register(TYPE_ID_MAX, |x| fold(x, |a, b| Some(a.max(b))));
+        result.register(TYPE_ID_GT, |x| compare(x, |a, b| a > b));
+        result.register(TYPE_ID_LT, |x| compare(x, |a, b| a < b));
+        result.register(TYPE_ID_EQ, |x| compare(x, |a, b| a == b));
+        result
+    }
+
+    /// Assigns an operator to the provided type ID, replacing any previously registered operator.
+    /// The type ID must fit in 3 bits and cannot be the literal type ID.
+    pub fn register(&mut self, type_id: u16, operator: Operator) -> Option<Operator> {
+        assert!(
+            (type_id as usize) < self.operators.len() && type_id != TYPE_ID_LITERAL,
+            "Type ID {} cannot be assigned an operator.",
+            type_id
+        );
+        self.operators[type_id as usize].replace(operator)
+    }
+
+    /// Gets the operator registered for the provided type ID, if any.
+    pub fn get(&self, type_id: u16) -> Option<Operator> {
+        self.operators.get(type_id as usize).copied().flatten()
+    }
+}
+
+/// A packet decoded from a transmission. Packets do not hold on to any bytes of the transmission, but refer
+/// back into it with bit offsets, such that the raw bits of a packet can be read again with a [`BitReader`]
+/// over the original data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Packet {
+    /// The bit index in the transmission at which the packet starts.
+    pub position: usize,
+
+    /// The number of bits the packet takes up in the transmission, including its operands.
+    pub length: usize,
+
+    /// The version of the packet.
+    pub version: u16,
+
+    /// The type ID of the packet.
+    pub type_id: u16,
+
+    /// The contents of the packet.
+    pub kind: PacketKind,
+}
+
+/// The contents of a packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PacketKind {
+    /// The packet is a literal value.
+    Literal(u128),
+
+    /// The packet is an operator applied to a list of operands.
+    Operator(Vec<Packet>),
+}
+
+impl Packet {
+    /// Decodes the full packet tree of a transmission, within the limits of the input.
+    pub fn parse(input: &Input) -> LocatedResult<Self> {
+        Self::read(&mut BitReader::new(input.data.as_slice()), &input.limits)
+    }
+
+    /// Reads a single packet (including all its operands) from the reader, within the provided limits.
+    pub fn read(reader: &mut BitReader, limits: &Limits) -> LocatedResult<Self> {
+        let mut packet_count = 0;
+        Self::read_limited(reader, limits, 1, &mut packet_count)
+    }
+
+    /// Reads a single packet at the provided nesting depth, and keeps count of the number of packets read
+    /// so far in the whole tree.
+    fn read_limited(
+        reader: &mut BitReader,
+        limits: &Limits,
+        depth: usize,
+        packet_count: &mut usize,
+    ) -> LocatedResult<Self> {
+        if depth > limits.max_depth {
+            return Err(reader.error(Error::TooDeep(limits.max_depth)));
+        }
+
+        *packet_count += 1;
+        if *packet_count > limits.max_packets {
+            return Err(reader.error(Error::TooManyPackets(limits.max_packets)));
+        }
+
+        let position = reader.position;
+        let version = reader.read_bits_const::<3>()?;
+        let type_id = reader.read_bits_const::<3>()?;
 
         if type_id == TYPE_ID_LITERAL {
-            // Literal, just return the version.
-            let _literal = reader.read_compressed_literal()?;
-            Ok(version)
+            let value = reader.read_compressed_literal(limits.max_literal_nibbles)?;
+            return Ok(Self {
+                position,
+                length: reader.position - position,
+                version,
+                type_id,
+                kind: PacketKind::Literal(value),
+            });
+        }
+
+        let length_type_id = reader.read_bits_const::<1>()?;
+        let mut operands = Vec::new();
+
+        if length_type_id == LENGTH_TYPE_ID_BIT_COUNT {
+            let total_bit_length = reader.read_bits_const::<15>()? as usize;
+            let end_index = reader.position + total_bit_length;
+
+            while reader.position < end_index {
+                operands.push(Self::read_limited(reader, limits, depth + 1, packet_count)?);
+            }
         } else {
-            let length_type_id = reader.read_bits(1)?;
+            let operand_count = reader.read_bits_const::<11>()? as usize;
+            for _ in 0..operand_count {
+                operands.push(Self::read_limited(reader, limits, depth + 1, packet_count)?);
+            }
+        }
 
-            // Read arguments and sum their versions.
-            if length_type_id == LENGTH_TYPE_ID_BIT_COUNT {
-                let total_bit_length = reader.read_bits(15)? as usize;
-                let end_index = reader.position + total_bit_length;
+        Ok(Self {
+            position,
+            length: reader.position - position,
+            version,
+            type_id,
+            kind: PacketKind::Operator(operands),
+        })
+    }
 
-                while reader.position < end_index {
-                    version += read_packet(&mut reader)?;
-                }
-            } else {
-                let operand_count = reader.read_bits(11)? as usize;
-                for _ in 0..operand_count {
-                    version += read_packet(&mut reader)?;
+    /// Gets the bit range in the transmission that the packet was decoded from.
+    pub fn span(&self) -> Range<usize> {
+        self.position..self.position + self.length
+    }
+
+    /// Gets the bit range of the 5-bit groups that encode the value of the packet if it is a literal, which
+    /// follow the 6 header bits.
+    pub fn literal_span(&self) -> Option<Range<usize>> {
+        match self.kind {
+            PacketKind::Literal(_) => Some(self.position + 6..self.position + self.length),
+            PacketKind::Operator(_) => None,
+        }
+    }
+
+    /// Gets the value of the packet if it is a literal.
+    pub fn literal_value(&self) -> Option<u128> {
+        match self.kind {
+            PacketKind::Literal(value) => Some(value),
+            PacketKind::Operator(_) => None,
+        }
+    }
+
+    /// Simplifies the packet tree. Operands of nested sums, products, minimums and maximums are pulled
+    /// into their parent, and every operator whose operands are all literals is replaced with the literal
+    /// it evaluates to. Operators that are not in the registry, or that fail to evaluate, are kept as-is.
+    /// Folded packets take the version and span of the operator they replace.
+    pub fn fold_constants(&self, registry: &OperatorRegistry) -> Packet {
+        let operands = match &self.kind {
+            PacketKind::Literal(_) => return self.clone(),
+            PacketKind::Operator(operands) => operands,
+        };
+
+        let is_associative = matches!(
+            self.type_id,
+            TYPE_ID_SUM | TYPE_ID_PRODUCT | TYPE_ID_MIN | TYPE_ID_MAX
+        );
+
+        // Simplify all operands first, and flatten nested associative operators of the same kind.
+        let mut folded = Vec::with_capacity(operands.len());
+        for operand in operands {
+            let operand = operand.fold_constants(registry);
+            match operand.kind {
+                PacketKind::Operator(nested)
+                    if is_associative && operand.type_id == self.type_id =>
+                {
+                    folded.extend(nested)
                 }
+                _ => folded.push(operand),
+            }
+        }
+
+        // Replace the operator with its result if all operands are known.
+        let values: Option<Vec<u128>> = folded.iter().map(|x| x.literal_value()).collect();
+        if let (Some(values), Some(operator)) = (values, registry.get(self.type_id)) {
+            if let Ok(value) = operator(&values) {
+                return Packet {
+                    position: self.position,
+                    length: self.length,
+                    version: self.version,
+                    type_id: TYPE_ID_LITERAL,
+                    kind: PacketKind::Literal(value),
+                };
             }
+        }
 
-            Ok(version)
+        Packet {
+            position: self.position,
+            length: self.length,
+            version: self.version,
+            type_id: self.type_id,
+            kind: PacketKind::Operator(folded),
         }
     }
+}
 
-    let mut reader = BitReader::new(input.data.as_slice());
-    read_packet(&mut reader)
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        walk(
+            self,
+            &mut Printer {
+                f,
+                first_operand: Vec::new(),
+            },
+        )
+    }
 }
 
-pub fn part2(input: &Input) -> Result<usize> {
-    fn evaluate(mut reader: &mut BitReader, mut eval_stack: &mut Vec<usize>) -> Result<usize> {
-        let _version = reader.read_bits(3)? as usize;
-        let type_id = reader.read_bits(3)?;
+/// Receives callbacks for every packet while a packet tree is walked with [`walk`]. Operands are visited
+/// in order, in between the calls to [`PacketVisitor::enter_operator`] and
+/// [`PacketVisitor::leave_operator`] of the operator they belong to.
+pub trait PacketVisitor {
+    /// The error that aborts the walk.
+    type Error;
 
-        if type_id == TYPE_ID_LITERAL {
-            // Literal, just return the result.
-            Ok(reader.read_compressed_literal()?)
+    /// Visits a literal packet.
+    fn visit_literal(
+        &mut self,
+        packet: &Packet,
+        value: u128,
+    ) -> std::result::Result<(), Self::Error>;
+
+    /// Visits an operator packet, before any of its operands are visited.
+    fn enter_operator(
+        &mut self,
+        _packet: &Packet,
+        _operands: &[Packet],
+    ) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Visits an operator packet, after all of its operands are visited.
+    fn leave_operator(
+        &mut self,
+        _packet: &Packet,
+        _operands: &[Packet],
+    ) -> std::result::Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Walks the packet tree depth-first, and reports every packet to the visitor. Stops at the first error
+/// returned by the visitor.
+pub fn walk<V: PacketVisitor>(
+    packet: &Packet,
+    visitor: &mut V,
+) -> std::result::Result<(), V::Error> {
+    match &packet.kind {
+        PacketKind::Literal(value) => visitor.visit_literal(packet, *value),
+        PacketKind::Operator(operands) => {
+            visitor.enter_operator(packet, operands)?;
+            for operand in operands {
+                walk(operand, visitor)?;
+            }
+            visitor.leave_operator(packet, operands)
+        }
+    }
+}
+
+/// A visitor that sums the versions of all packets.
+#[derive(Default)]
+pub struct VersionSum {
+    /// The sum of all versions visited so far.
+    pub sum: usize,
+}
+
+impl PacketVisitor for VersionSum {
+    type Error = Infallible;
+
+    fn visit_literal(
+        &mut self,
+        packet: &Packet,
+        _value: u128,
+    ) -> std::result::Result<(), Self::Error> {
+        self.sum += packet.version as usize;
+        Ok(())
+    }
+
+    fn enter_operator(
+        &mut self,
+        packet: &Packet,
+        _operands: &[Packet],
+    ) -> std::result::Result<(), Self::Error> {
+        self.sum += packet.version as usize;
+        Ok(())
+    }
+}
+
+/// A visitor that collects statistics on the shape of a packet tree.
+#[derive(Default, Debug)]
+pub struct PacketStats {
+    /// The number of literal packets.
+    pub literals: usize,
+
+    /// The number of operator packets, indexed by type ID.
+    pub operators: [usize; 8],
+
+    /// The largest number of operands of a single operator.
+    pub max_operands: usize,
+
+    /// The number of nested packets on the deepest path through the tree, including the outermost packet.
+    pub max_depth: usize,
+
+    /// The number of operators that are currently entered.
+    depth: usize,
+}
+
+impl PacketVisitor for PacketStats {
+    type Error = Infallible;
+
+    fn visit_literal(
+        &mut self,
+        _packet: &Packet,
+        _value: u128,
+    ) -> std::result::Result<(), Self::Error> {
+        self.literals += 1;
+        self.max_depth = self.max_depth.max(self.depth + 1);
+        Ok(())
+    }
+
+    fn enter_operator(
+        &mut self,
+        packet: &Packet,
+        operands: &[Packet],
+    ) -> std::result::Result<(), Self::Error> {
+        self.operators[packet.type_id as usize] += 1;
+        self.max_operands = self.max_operands.max(operands.len());
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+        Ok(())
+    }
+
+    fn leave_operator(
+        &mut self,
+        _packet: &Packet,
+        _operands: &[Packet],
+    ) -> std::result::Result<(), Self::Error> {
+        self.depth -= 1;
+        Ok(())
+    }
+}
+
+/// A visitor that writes a packet tree as an expression, e.g. `sum(1, product(2, 3))`.
+struct Printer<'a, 'b> {
+    /// The formatter to write to.
+    f: &'a mut std::fmt::Formatter<'b>,
+
+    /// For every entered operator, whether none of its operands were written yet.
+    first_operand: Vec<bool>,
+}
+
+impl Printer<'_, '_> {
+    /// Writes the separator that goes in front of an operand, if it is not the first one.
+    fn separate(&mut self) -> std::fmt::Result {
+        match self.first_operand.last_mut() {
+            Some(first) if !*first => write!(self.f, ", "),
+            Some(first) => {
+                *first = false;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl PacketVisitor for Printer<'_, '_> {
+    type Error = std::fmt::Error;
+
+    fn visit_literal(&mut self, _packet: &Packet, value: u128) -> std::fmt::Result {
+        self.separate()?;
+        write!(self.f, "{}", value)
+    }
+
+    fn enter_operator(&mut self, packet: &Packet, _operands: &[Packet]) -> std::fmt::Result {
+        self.separate()?;
+        self.first_operand.push(true);
+
+        match packet.type_id {
+            TYPE_ID_SUM => write!(self.f, "sum("),
+            TYPE_ID_PRODUCT => write!(self.f, "product("),
+            TYPE_ID_MIN => write!(self.f, "min("),
+            TYPE_ID_MAX => write!(self.f, "max("),
+            TYPE_ID_GT => write!(self.f, "gt("),
+            TYPE_ID_LT => write!(self.f, "lt("),
+            TYPE_ID_EQ => write!(self.f, "eq("),
+            type_id => write!(self.f, "op{}(", type_id),
+        }
+    }
+
+    fn leave_operator(&mut self, _packet: &Packet, _operands: &[Packet]) -> std::fmt::Result {
+        self.first_operand.pop();
+        write!(self.f, ")")
+    }
+}
+
+/// A visitor that evaluates a packet tree using the operators defined in a registry.
+pub struct Evaluator<'a> {
+    /// The operators to evaluate with.
+    registry: &'a OperatorRegistry,
+
+    /// The transmission the packet tree was decoded from, used to give errors their context.
+    data: &'a [u8],
+
+    /// The values of all evaluated packets whose operator was not left yet.
+    eval_stack: Vec<u128>,
+
+    /// For every entered operator, the index in the eval stack at which its operands start.
+    operand_starts: Vec<usize>,
+}
+
+impl<'a> Evaluator<'a> {
+    /// Creates a new evaluator for a packet tree decoded from the provided transmission.
+    pub fn new(registry: &'a OperatorRegistry, data: &'a [u8]) -> Self {
+        Self {
+            registry,
+            data,
+            eval_stack: Vec::with_capacity(128),
+            operand_starts: Vec::new(),
+        }
+    }
+
+    /// Gets the value of the packet tree that was walked.
+    pub fn result(&self) -> Option<u128> {
+        self.eval_stack.last().copied()
+    }
+
+    /// Annotates an error with the location in the transmission at which it occurred.
+    fn error_at(&self, position: usize, error: Error) -> LocatedError {
+        BitReader::new(self.data).error_at(position, error)
+    }
+}
+
+impl PacketVisitor for Evaluator<'_> {
+    type Error = LocatedError;
+
+    fn visit_literal(&mut self, _packet: &Packet, value: u128) -> LocatedResult<()> {
+        self.eval_stack.push(value);
+        Ok(())
+    }
+
+    fn enter_operator(&mut self, packet: &Packet, _operands: &[Packet]) -> LocatedResult<()> {
+        // Look up the operation before we start evaluating any operands. The type ID follows the
+        // 3 version bits.
+        if self.registry.get(packet.type_id).is_none() {
+            return Err(self.error_at(packet.position + 3, Error::InvalidTypeId(packet.type_id)));
+        }
+
+        self.operand_starts.push(self.eval_stack.len());
+        Ok(())
+    }
+
+    fn leave_operator(&mut self, packet: &Packet, _operands: &[Packet]) -> LocatedResult<()> {
+        // Slice out operands and compute result based on operation.
+        let operands_start = self.operand_starts.pop().unwrap();
+        let operator = self.registry.get(packet.type_id).unwrap();
+        let result = operator(&self.eval_stack[operands_start..])
+            .map_err(|error| self.error_at(packet.position, error))?;
+
+        // Replace the operands with the result.
+        self.eval_stack.truncate(operands_start);
+        self.eval_stack.push(result);
+        Ok(())
+    }
+}
+
+/// Evaluates the transmission using the operators defined in the provided registry.
+pub fn evaluate(input: &Input, registry: &OperatorRegistry) -> LocatedResult<u128> {
+    let packet = Packet::parse(input)?;
+
+    let mut evaluator = Evaluator::new(registry, input.data.as_slice());
+    walk(&packet, &mut evaluator)?;
+    Ok(evaluator.result().unwrap())
+}
+
+/// A high-level description of a packet, that can be encoded into a transmission.
+#[derive(Clone, Debug)]
+pub struct PacketBuilder {
+    /// The version of the packet.
+    version: u16,
+
+    /// The type ID of the packet.
+    type_id: u16,
+
+    /// The value of the packet, if it is a literal.
+    value: u128,
+
+    /// The length type ID to use for encoding the operands, if it is an operator.
+    length_type_id: u16,
+
+    /// The operands of the packet, if it is an operator.
+    operands: Vec<PacketBuilder>,
+}
+
+/// Creates a new literal packet with the provided value.
+pub fn lit(value: u128) -> PacketBuilder {
+    PacketBuilder::literal(value)
+}
+
+impl PacketBuilder {
+    /// Creates a new literal packet with the provided value.
+    pub fn literal(value: u128) -> Self {
+        Self {
+            version: 0,
+            type_id: TYPE_ID_LITERAL,
+            value,
+            length_type_id: LENGTH_TYPE_ID_BIT_COUNT,
+            operands: Vec::new(),
+        }
+    }
+
+    /// Creates a new operator packet with the provided type ID and operands. By default, the operands
+    /// are encoded with their total length in bits.
+    pub fn operator(type_id: u16, operands: impl IntoIterator<Item = PacketBuilder>) -> Self {
+        assert!(
+            type_id < 8 && type_id != TYPE_ID_LITERAL,
+            "Type ID {} is not an operator.",
+            type_id
+        );
+
+        Self {
+            version: 0,
+            type_id,
+            value: 0,
+            length_type_id: LENGTH_TYPE_ID_BIT_COUNT,
+            operands: operands.into_iter().collect(),
+        }
+    }
+
+    /// Creates a new sum packet.
+    pub fn sum(operands: impl IntoIterator<Item = PacketBuilder>) -> Self {
+        Self::operator(TYPE_ID_SUM, operands)
+    }
+
+    /// Creates a new product packet.
+    pub fn product(operands: impl IntoIterator<Item = PacketBuilder>) -> Self {
+        Self::operator(TYPE_ID_PRODUCT, operands)
+    }
+
+    /// Creates a new minimum packet.
+    pub fn min(operands: impl IntoIterator<Item = PacketBuilder>) -> Self {
+        Self::operator(TYPE_ID_MIN, operands)
+    }
+
+    /// Creates a new maximum packet.
+    pub fn max(operands: impl IntoIterator<Item = PacketBuilder>) -> Self {
+        Self::operator(TYPE_ID_MAX, operands)
+    }
+
+    /// Creates a new greater-than packet.
+    pub fn gt(left: PacketBuilder, right: PacketBuilder) -> Self {
+        Self::operator(TYPE_ID_GT, [left, right])
+    }
+
+    /// Creates a new less-than packet.
+    pub fn lt(left: PacketBuilder, right: PacketBuilder) -> Self {
+        Self::operator(TYPE_ID_LT, [left, right])
+    }
+
+    /// Creates a new equal-to packet.
+    pub fn eq(left: PacketBuilder, right: PacketBuilder) -> Self {
+        Self::operator(TYPE_ID_EQ, [left, right])
+    }
+
+    /// Sets the version of the packet. The version must fit in 3 bits.
+    pub fn version(mut self, version: u16) -> Self {
+        assert!(version < 8, "Version {} does not fit in 3 bits.", version);
+        self.version = version;
+        self
+    }
+
+    /// Encodes the operands of the packet with their total length in bits.
+    pub fn by_bit_count(mut self) -> Self {
+        self.length_type_id = LENGTH_TYPE_ID_BIT_COUNT;
+        self
+    }
+
+    /// Encodes the operands of the packet with the number of operands.
+    pub fn by_packet_count(mut self) -> Self {
+        self.length_type_id = LENGTH_TYPE_ID_PACKET_COUNT;
+        self
+    }
+
+    /// Computes the number of bits the packet takes up when encoded.
+    pub fn bit_length(&self) -> usize {
+        if self.type_id == TYPE_ID_LITERAL {
+            6 + 5 * literal_group_count(self.value)
         } else {
-            let length_type_id = reader.read_bits(1)?;
-            let mut operand_count = 0;
-
-            // Read operands and push onto the eval stack.
-            if length_type_id == LENGTH_TYPE_ID_BIT_COUNT {
-                let total_bit_length = reader.read_bits(15)? as usize;
-                let end_index = reader.position + total_bit_length;
-
-                while reader.position < end_index {
-                    // Recursively evaluate child packet.
-                    let result = evaluate(&mut reader, &mut eval_stack)?;
-                    eval_stack.push(result);
-                    operand_count += 1;
-                }
+            let header_length = if self.length_type_id == LENGTH_TYPE_ID_BIT_COUNT {
+                7 + 15
             } else {
-                operand_count = reader.read_bits(11)? as usize;
+                7 + 11
+            };
+            header_length + self.operands.iter().map(|x| x.bit_length()).sum::<usize>()
+        }
+    }
 
-                for _ in 0..operand_count {
-                    // Recursively evaluate child packet.
-                    let result = evaluate(&mut reader, &mut eval_stack)?;
-                    eval_stack.push(result);
-                }
+    /// Writes the packet to the provided bit writer.
+    pub fn write(&self, writer: &mut BitWriter) -> Result<()> {
+        writer.write_bits(self.version, 3)?;
+        writer.write_bits(self.type_id, 3)?;
+
+        if self.type_id == TYPE_ID_LITERAL {
+            return writer.write_compressed_literal(self.value);
+        }
+
+        writer.write_bits(self.length_type_id, 1)?;
+        if self.length_type_id == LENGTH_TYPE_ID_BIT_COUNT {
+            let total_bit_length: usize = self.operands.iter().map(|x| x.bit_length()).sum();
+            if total_bit_length >= 1 << 15 {
+                return Err(Error::InvalidOperandCount(self.operands.len()));
             }
+            writer.write_bits(total_bit_length as u16, 15)?;
+        } else {
+            if self.operands.len() >= 1 << 11 {
+                return Err(Error::InvalidOperandCount(self.operands.len()));
+            }
+            writer.write_bits(self.operands.len() as u16, 11)?;
+        }
 
-            // Slice out operands.
-            let operands = &eval_stack[eval_stack.len() - operand_count..];
+        for operand in self.operands.iter() {
+            operand.write(writer)?;
+        }
 
-            // Compute result based on operation.
-            let result = match type_id {
-                TYPE_ID_SUM => Ok(operands.iter().sum::<usize>()),
-                TYPE_ID_PRODUCT => Ok(operands.iter().product::<usize>()),
-                TYPE_ID_MIN => Ok(*operands.iter().min().unwrap()),
-                TYPE_ID_MAX => Ok(*operands.iter().max().unwrap()),
-                TYPE_ID_GT => Ok((operands[0] > operands[1]) as usize),
-                TYPE_ID_LT => Ok((operands[0] < operands[1]) as usize),
-                TYPE_ID_EQ => Ok((operands[0] == operands[1]) as usize),
-                _ => Err(Error::InvalidTypeId(type_id)),
-            };
+        Ok(())
+    }
 
-            // Pop operands from stack.
-            eval_stack.resize(eval_stack.len() - operand_count, 0);
+    /// Encodes the packet into a transmission, padded with zero bits up to a whole byte.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut writer = BitWriter::new();
+        self.write(&mut writer)?;
+        Ok(writer.into_bytes())
+    }
 
-            // Return result.
-            result
+    /// Determines whether the provided decoded packet describes exactly this packet tree.
+    pub fn matches(&self, packet: &Packet) -> bool {
+        if packet.version != self.version
+            || packet.type_id != self.type_id
+            || packet.length != self.bit_length()
+        {
+            return false;
+        }
+
+        match &packet.kind {
+            PacketKind::Literal(value) => *value == self.value,
+            PacketKind::Operator(operands) => {
+                operands.len() == self.operands.len()
+                    && self
+                        .operands
+                        .iter()
+                        .zip(operands.iter())
+                        .all(|(expected, actual)| expected.matches(actual))
+            }
         }
     }
+}
+
+pub fn part2(input: &Input) -> LocatedResult<u128> {
+    evaluate(input, &OperatorRegistry::standard())
+}
 
-    let mut reader = BitReader::new(input.data.as_slice());
-    let mut eval_stack = Vec::with_capacity(128);
-    evaluate(&mut reader, &mut eval_stack)
+/// Converts a located error into an I/O error carrying its description.
+fn to_io_error(error: LocatedError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day16;
+
+impl Solver for Day16 {
+    type Input = Input;
+
+    const BUDGET: Budget = Budget {
+        parse: None,
+        part1: Some(Duration::from_micros(50)),
+        part2: Some(Duration::from_micros(50)),
+    };
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
 }
 
 fn main() -> std::io::Result<()> {
+    let mut optimize = false;
+    let mut stats = false;
+    let mut file = None;
+    let mut limits = Limits::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut number = |name: &str| {
+            args.next()
+                .and_then(|x| x.parse::<usize>().ok())
+                .unwrap_or_else(|| panic!("Expected a number after {}.", name))
+        };
+
+        match arg.as_str() {
+            "--stats" => stats = true,
+            "--optimize" => optimize = true,
+            "--max-packets" => limits.max_packets = number("--max-packets"),
+            "--max-depth" => limits.max_depth = number("--max-depth"),
+            "--max-literal-nibbles" => limits.max_literal_nibbles = number("--max-literal-nibbles"),
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
     let now = Instant::now();
-    let input = parse_input("input.txt")?;
+    let mut input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    input.limits = limits;
     let time_parse = now.elapsed();
     println!("Parse: (time: {}us)", time_parse.as_micros());
 
     let now = Instant::now();
-    let result1 = part1(&input).unwrap();
+    let result1 = part1(&input).map_err(to_io_error)?;
     let time1 = now.elapsed();
     println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
 
     let now = Instant::now();
-    let result2 = part2(&input).unwrap();
+    let result2 = part2(&input).map_err(to_io_error)?;
     let time2 = now.elapsed();
     println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
 
+    if stats {
+        let packet = Packet::parse(&input).map_err(to_io_error)?;
+        let mut visitor = PacketStats::default();
+        let Ok(()) = walk(&packet, &mut visitor);
+        println!(
+            "Stats: {} literals, {} operators (by type ID: {:?}), at most {} operands, depth {}",
+            visitor.literals,
+            visitor.operators.iter().sum::<usize>(),
+            visitor.operators,
+            visitor.max_operands,
+            visitor.max_depth
+        );
+    }
+
+    if optimize {
+        // Only fold the comparisons, such that the arithmetic structure of the transmission remains
+        // visible next to the fully simplified expression.
+        let mut comparisons = OperatorRegistry::empty();
+        for type_id in [TYPE_ID_GT, TYPE_ID_LT, TYPE_ID_EQ] {
+            if let Some(operator) = OperatorRegistry::standard().get(type_id) {
+                comparisons.register(type_id, operator);
+            }
+        }
+
+        let packet = Packet::parse(&input).map_err(to_io_error)?;
+        println!("Expression: {}", packet);
+        println!(
+            "Folded comparisons: {}",
+            packet.fold_constants(&comparisons)
+        );
+        println!(
+            "Folded: {}",
+            packet.fold_constants(&OperatorRegistry::standard())
+        );
+    }
+
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Generates packet trees of at most 5 levels. Versions, type IDs, length type IDs and literal sizes are
+    /// all mixed.
+    fn packet_tree() -> impl Strategy<Value = PacketBuilder> {
+        // Shift the value by a random amount, such that small and large literals are equally common.
+        let literal = (any::<u128>(), 0..128u32, 0..8u16)
+            .prop_map(|(value, shift, version)| lit(value >> shift).version(version));
+
+        literal.prop_recursive(4, 256, 4, |operand| {
+            let operands = prop_oneof![
+                (
+                    prop_oneof![
+                        Just(TYPE_ID_SUM),
+                        Just(TYPE_ID_PRODUCT),
+                        Just(TYPE_ID_MIN),
+                        Just(TYPE_ID_MAX)
+                    ],
+                    prop::collection::vec(operand.clone(), 1..=4)
+                ),
+                (
+                    prop_oneof![Just(TYPE_ID_GT), Just(TYPE_ID_LT), Just(TYPE_ID_EQ)],
+                    prop::collection::vec(operand, 2)
+                ),
+            ];
+
+            (operands, any::<bool>(), 0..8u16).prop_map(
+                |((type_id, operands), by_bit_count, version)| {
+                    let packet = PacketBuilder::operator(type_id, operands);
+                    let packet = if by_bit_count {
+                        packet.by_bit_count()
+                    } else {
+                        packet.by_packet_count()
+                    };
+                    packet.version(version)
+                },
+            )
+        })
+    }
+
+    /// Gets the example transmissions from the puzzle description, rebuilt with a [`PacketBuilder`], along
+    /// with their published encoding, and their expected version sum or value (when given).
+    fn published_examples() -> Vec<(&'static str, PacketBuilder, Option<usize>, Option<u128>)> {
+        use PacketBuilder as P;
+
+        vec![
+            ("D2FE28", lit(2021).version(6), Some(6), Some(2021)),
+            (
+                "38006F45291200",
+                P::lt(lit(10).version(6), lit(20).version(2)).version(1),
+                Some(9),
+                Some(1),
+            ),
+            (
+                "EE00D40C823060",
+                P::max([lit(1).version(2), lit(2).version(4), lit(3).version(1)])
+                    .version(7)
+                    .by_packet_count(),
+                Some(14),
+                Some(3),
+            ),
+            (
+                "8A004A801A8002F478",
+                P::min([P::min([P::min([lit(15).version(6)]).version(5)])
+                    .version(1)
+                    .by_packet_count()])
+                .version(4)
+                .by_packet_count(),
+                Some(16),
+                None,
+            ),
+            (
+                "620080001611562C8802118E34",
+                P::sum([
+                    P::sum([lit(10), lit(11).version(5)]),
+                    P::sum([lit(12), lit(13).version(3)])
+                        .version(1)
+                        .by_packet_count(),
+                ])
+                .version(3)
+                .by_packet_count(),
+                Some(12),
+                None,
+            ),
+            (
+                "C0015000016115A2E0802F182340",
+                P::sum([
+                    P::sum([lit(10), lit(11).version(6)]),
+                    P::sum([lit(12).version(7), lit(13)])
+                        .version(4)
+                        .by_packet_count(),
+                ])
+                .version(6),
+                Some(23),
+                None,
+            ),
+            (
+                "A0016C880162017C3686B18A3D4780",
+                P::sum([P::sum([P::sum([
+                    lit(6).version(7),
+                    lit(6).version(6),
+                    lit(12).version(5),
+                    lit(15).version(2),
+                    lit(15).version(2),
+                ])
+                .version(3)
+                .by_packet_count()])
+                .version(1)
+                .by_packet_count()])
+                .version(5),
+                Some(31),
+                None,
+            ),
+            (
+                "C200B40A82",
+                P::sum([lit(1).version(6), lit(2).version(2)])
+                    .version(6)
+                    .by_packet_count(),
+                None,
+                Some(3),
+            ),
+            (
+                "04005AC33890",
+                P::product([lit(6).version(5), lit(9).version(3)]),
+                None,
+                Some(54),
+            ),
+            (
+                "880086C3E88112",
+                P::min([lit(7).version(5), lit(8).version(6), lit(9)]).version(4),
+                None,
+                Some(7),
+            ),
+            (
+                "CE00C43D881120",
+                P::max([lit(7), lit(8).version(5), lit(9)])
+                    .version(6)
+                    .by_packet_count(),
+                None,
+                Some(9),
+            ),
+            (
+                "D8005AC2A8F0",
+                P::lt(lit(5).version(5), lit(15).version(2)).version(6),
+                None,
+                Some(1),
+            ),
+            (
+                "F600BC2D8F",
+                P::gt(lit(5).version(7), lit(15).version(5))
+                    .version(7)
+                    .by_packet_count(),
+                None,
+                Some(0),
+            ),
+            (
+                "9C005AC2F8F0",
+                P::eq(lit(5).version(5), lit(15).version(7)).version(4),
+                None,
+                Some(0),
+            ),
+            (
+                "9C0141080250320F1802104A08",
+                P::eq(
+                    P::sum([lit(1).version(2), lit(3).version(4)])
+                        .version(2)
+                        .by_packet_count(),
+                    P::product([lit(2), lit(2).version(2)])
+                        .version(6)
+                        .by_packet_count(),
+                )
+                .version(4),
+                None,
+                Some(1),
+            ),
+        ]
+    }
+
+    /// The published examples encode to their published transmissions, and give their published answers.
+    #[test]
+    fn published_examples_match() {
+        for (expected_hex, builder, version_sum, value) in published_examples() {
+            let data = builder.encode().unwrap();
+            let hex: String = data.iter().map(|b| format!("{:02X}", b)).collect();
+            assert_eq!(hex, expected_hex);
+
+            let input = Input {
+                data,
+                limits: Limits::default(),
+            };
+            if let Some(version_sum) = version_sum {
+                assert_eq!(part1(&input).ok(), Some(version_sum), "{}", expected_hex);
+            }
+            if let Some(value) = value {
+                assert_eq!(part2(&input).ok(), Some(value), "{}", expected_hex);
+            }
+        }
+    }
+
+    proptest! {
+        /// Decoding an encoded packet tree gives back the same tree.
+        #[test]
+        fn round_trip(builder in packet_tree()) {
+            let input = Input {
+                data: builder.encode().unwrap(),
+                limits: Limits::default(),
+            };
+            let packet = Packet::parse(&input).unwrap();
+            prop_assert!(builder.matches(&packet), "decodes to a different tree {}", packet);
+        }
+    }
+}
+
 // Parse: (time: 80us)
 // Solution 1: 897 (time: 10us)
 // Solution 2: 9485076995911 (time: 11us)
 
 // part 1 (real)           time:   [4.4253 us 4.4294 us 4.4338 us]
 // part 2 (real)           time:   [4.6803 us 4.6849 us 4.6900 us]
+//
+// Generated transmission of 32000 large literals, before and after the byte-aligned and const reads:
+// decode (generated literals)     time:   [9.8094 ms 10.253 ms 10.716 ms]
+//                                 time:   [6.6198 ms 6.9082 ms 7.2617 ms]
+// read bits/16 bits (aligned)     time:   [2.4239 ms 2.5270 ms 2.6369 ms]
+//                                 time:   [1.5321 ms 1.5720 ms 1.6170 ms]
+// read bits/5 bits (const)        time:   [2.3648 ms 2.4033 ms 2.4437 ms]
@@ -0,0 +1,190 @@
+use aoc_common::{input_path, Answer, Error, Solver};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    time::Instant,
+};
+
+/// The largest distance of the target area from the launcher along either axis. This keeps the search over
+/// all initial velocities bounded.
+const MAX_DISTANCE: isize = 10_000;
+
+/// The target area the probe needs to be in after any step, with inclusive bounds.
+pub struct Input {
+    x_min: isize,
+    x_max: isize,
+    y_min: isize,
+    y_max: isize,
+}
+
+/// An initial velocity that brings the probe into the target area.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Shot {
+    /// The initial horizontal velocity.
+    pub vx: isize,
+
+    /// The initial vertical velocity.
+    pub vy: isize,
+
+    /// The highest position the probe reaches.
+    pub max_height: isize,
+}
+
+/// Parses a range of the form `a..b`.
+fn parse_range(s: &str) -> Option<(isize, isize)> {
+    let (low, high) = s.split_once("..")?;
+    Some((low.parse().ok()?, high.parse().ok()?))
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(mut reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let line = line.trim_end();
+
+    let (x_min, x_max, y_min, y_max) = line
+        .strip_prefix("target area: x=")
+        .and_then(|x| x.split_once(", y="))
+        .and_then(|(x, y)| {
+            let (x_min, x_max) = parse_range(x)?;
+            let (y_min, y_max) = parse_range(y)?;
+            Some((x_min, x_max, y_min, y_max))
+        })
+        .ok_or_else(|| Error::at_line(1, format!("Expected a target area, but found {}.", line)))?;
+
+    // Velocities are only searched for targets ahead of and below the launcher, which all inputs are.
+    if x_min > x_max || y_min > y_max {
+        return Err(Error::at_line(1, "Expected ranges from low to high."));
+    }
+    if x_min <= 0 || y_max >= 0 || x_max > MAX_DISTANCE || y_min < -MAX_DISTANCE {
+        return Err(Error::at_line(
+            1,
+            format!(
+                "Expected a target area ahead of and below the launcher, within {} steps.",
+                MAX_DISTANCE
+            ),
+        ));
+    }
+
+    Ok(Input {
+        x_min,
+        x_max,
+        y_min,
+        y_max,
+    })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Launches the probe with the provided initial velocity, and returns the highest position it reaches if it
+/// is in the target area after any step.
+pub fn launch(input: &Input, mut vx: isize, mut vy: isize) -> Option<isize> {
+    let (mut x, mut y) = (0, 0);
+    let mut max_height = 0;
+
+    // The probe only moves away from the launcher horizontally, and keeps falling once it is below the target.
+    while x <= input.x_max && (vy >= 0 || y >= input.y_min) {
+        x += vx;
+        y += vy;
+        vx -= vx.signum();
+        vy -= 1;
+        max_height = max_height.max(y);
+
+        if (input.x_min..=input.x_max).contains(&x) && (input.y_min..=input.y_max).contains(&y) {
+            return Some(max_height);
+        }
+    }
+
+    None
+}
+
+/// Finds every initial velocity that brings the probe into the target area.
+pub fn find_shots(input: &Input) -> Vec<Shot> {
+    // Any faster horizontal velocity overshoots the target in the first step. The same holds for any lower
+    // vertical velocity. A probe that is shot up with velocity vy comes down at y = 0 again with velocity
+    // -vy - 1, so any higher vertical velocity overshoots the target in the step after.
+    let mut shots = Vec::new();
+    for vx in 1..=input.x_max {
+        for vy in input.y_min..-input.y_min {
+            if let Some(max_height) = launch(input, vx, vy) {
+                shots.push(Shot { vx, vy, max_height });
+            }
+        }
+    }
+    shots
+}
+
+pub fn part1(input: &Input) -> usize {
+    find_shots(input)
+        .iter()
+        .map(|shot| shot.max_height)
+        .max()
+        .unwrap_or(0) as usize
+}
+
+pub fn part2(input: &Input) -> usize {
+    find_shots(input).len()
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day17;
+
+impl Solver for Day17 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut list = false;
+    let mut file = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--list" => list = true,
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    let now = Instant::now();
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    let time_parse = now.elapsed();
+
+    if list {
+        for shot in find_shots(&input) {
+            println!("{},{} (height: {})", shot.vx, shot.vy, shot.max_height);
+        }
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let result1 = part1(&input);
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let result2 = part2(&input);
+    let time2 = now.elapsed();
+
+    println!("Parse: (time: {}us)", time_parse.as_micros());
+    println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
+    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+
+    Ok(())
+}
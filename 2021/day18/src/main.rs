@@ -0,0 +1,321 @@
+use aoc_common::{input_path, Answer, Rng, Scanner, Solver};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufRead, BufReader},
+    time::Instant,
+};
+
+/// A regular number within a snailfish number, together with the number of pairs it is nested in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Element {
+    pub value: u32,
+    pub depth: u8,
+}
+
+/// A snailfish number, stored as its regular numbers from left to right. The tree of pairs is implied by the
+/// depths of the regular numbers, which makes finding the neighbours of a pair that explodes trivial.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Number {
+    elements: Vec<Element>,
+}
+
+pub struct Input {
+    numbers: Vec<Number>,
+}
+
+/// Pairs nested inside this many pairs explode.
+const MAX_DEPTH: u8 = 4;
+
+/// Regular numbers of at least this value split.
+const SPLIT_THRESHOLD: u32 = 10;
+
+impl Number {
+    /// Reads a pair or a regular number at the provided depth.
+    fn read_element(
+        scanner: &mut Scanner,
+        depth: u8,
+        elements: &mut Vec<Element>,
+    ) -> Result<(), aoc_common::ScanError> {
+        if scanner.next_if(|c| c == b'[').is_some() {
+            if depth == MAX_DEPTH {
+                return Err(scanner.error("a regular number in a reduced number"));
+            }
+            Self::read_element(scanner, depth + 1, elements)?;
+            scanner.expect("','", |c| (c == b',').then_some(()))?;
+            Self::read_element(scanner, depth + 1, elements)?;
+            scanner.expect("']'", |c| (c == b']').then_some(()))?;
+        } else {
+            let value = scanner.expect("a pair or a digit", |c| {
+                c.is_ascii_digit().then_some((c - b'0') as u32)
+            })?;
+            elements.push(Element { value, depth });
+        }
+        Ok(())
+    }
+
+    /// Adds two numbers and reduces the result.
+    pub fn add(&self, other: &Number) -> Number {
+        let mut elements = Vec::with_capacity(self.elements.len() + other.elements.len());
+        elements.extend(
+            self.elements
+                .iter()
+                .chain(other.elements.iter())
+                .map(|x| Element {
+                    value: x.value,
+                    depth: x.depth + 1,
+                }),
+        );
+
+        let mut result = Number { elements };
+        result.reduce();
+        result
+    }
+
+    /// Reduces the number by exploding all pairs that are nested too deeply first, and then splitting the
+    /// leftmost regular number that is too large until there is nothing left to do.
+    fn reduce(&mut self) {
+        // Only the sum of two reduced numbers needs reducing, so the only pairs that explode are nested in
+        // exactly one more pair than allowed. Exploding never creates deeper pairs, so all of them can be
+        // exploded in a single pass. A split creates at most one pair to explode, which is exploded right away.
+        let mut i = 0;
+        while i < self.elements.len() {
+            if self.elements[i].depth > MAX_DEPTH {
+                self.explode(i);
+            }
+            i += 1;
+        }
+
+        while let Some(i) = self
+            .elements
+            .iter()
+            .position(|x| x.value >= SPLIT_THRESHOLD)
+        {
+            let Element { value, depth } = self.elements[i];
+            let left = Element {
+                value: value / 2,
+                depth: depth + 1,
+            };
+            let right = Element {
+                value: value - value / 2,
+                depth: depth + 1,
+            };
+            self.elements[i] = left;
+            self.elements.insert(i + 1, right);
+
+            if depth + 1 > MAX_DEPTH {
+                self.explode(i);
+            }
+        }
+    }
+
+    /// Explodes the pair whose left regular number is at the provided index.
+    fn explode(&mut self, i: usize) {
+        let left = self.elements[i];
+        let right = self.elements.remove(i + 1);
+        if i > 0 {
+            self.elements[i - 1].value += left.value;
+        }
+        if let Some(next) = self.elements.get_mut(i + 1) {
+            next.value += right.value;
+        }
+        self.elements[i] = Element {
+            value: 0,
+            depth: left.depth - 1,
+        };
+    }
+
+    /// Computes the magnitude of the number.
+    pub fn magnitude(&self) -> u32 {
+        fn magnitude_at(elements: &[Element], index: &mut usize, depth: u8) -> u32 {
+            if elements[*index].depth == depth {
+                *index += 1;
+                return elements[*index - 1].value;
+            }
+
+            let left = magnitude_at(elements, index, depth + 1);
+            let right = magnitude_at(elements, index, depth + 1);
+            3 * left + 2 * right
+        }
+
+        magnitude_at(&self.elements, &mut 0, 0)
+    }
+}
+
+impl std::str::FromStr for Number {
+    type Err = aoc_common::ScanError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut scanner = Scanner::new(s.as_bytes());
+        if scanner.peek() != Some(b'[') {
+            return Err(scanner.error("'['"));
+        }
+
+        let mut elements = Vec::new();
+        Number::read_element(&mut scanner, 0, &mut elements)?;
+        scanner.expect_eof()?;
+        Ok(Number { elements })
+    }
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Rebuild the brackets by tracking which pairs still need their right element.
+        let mut open: Vec<bool> = Vec::new();
+        for element in self.elements.iter() {
+            while open.len() < element.depth as usize {
+                write!(f, "[")?;
+                open.push(false);
+            }
+            write!(f, "{}", element.value)?;
+
+            while open.last() == Some(&true) {
+                write!(f, "]")?;
+                open.pop();
+            }
+            if let Some(last) = open.last_mut() {
+                *last = true;
+                write!(f, ",")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut numbers = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number: Number = line
+            .trim_end()
+            .parse()
+            .map_err(|error: aoc_common::ScanError| error.at_line(index + 1))?;
+        numbers.push(number);
+    }
+
+    if numbers.is_empty() {
+        return Err(aoc_common::Error::at_line(
+            1,
+            "Expected a snailfish number.",
+        ));
+    }
+
+    Ok(Input { numbers })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Adds all numbers of the input from top to bottom.
+pub fn sum(input: &Input) -> Number {
+    let mut numbers = input.numbers.iter();
+    let first = numbers.next().unwrap().clone();
+    numbers.fold(first, |acc, x| acc.add(x))
+}
+
+pub fn part1(input: &Input) -> usize {
+    sum(input).magnitude() as usize
+}
+
+pub fn part2(input: &Input) -> usize {
+    let mut max = 0;
+    for (i, a) in input.numbers.iter().enumerate() {
+        for (j, b) in input.numbers.iter().enumerate() {
+            if i != j {
+                max = max.max(a.add(b).magnitude());
+            }
+        }
+    }
+    max as usize
+}
+
+/// Generates a pseudo-random list of reduced snailfish numbers, determined by the provided seed.
+pub fn generate(count: usize, seed: u64) -> Input {
+    let mut rng = Rng::new(seed);
+    let mut next = move || rng.next_usize();
+
+    fn generate_element(next: &mut impl FnMut() -> usize, depth: u8, elements: &mut Vec<Element>) {
+        // Make pairs less likely the deeper we go, such that numbers look like the ones of the puzzle.
+        if depth < MAX_DEPTH && next() % (depth as usize + 2) < 2 {
+            generate_element(next, depth + 1, elements);
+            generate_element(next, depth + 1, elements);
+        } else {
+            elements.push(Element {
+                value: (next() % 10) as u32,
+                depth,
+            });
+        }
+    }
+
+    let numbers = (0..count)
+        .map(|_| {
+            let mut elements = Vec::new();
+            generate_element(&mut next, 1, &mut elements);
+            generate_element(&mut next, 1, &mut elements);
+            Number { elements }
+        })
+        .collect();
+
+    Input { numbers }
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day18;
+
+impl Solver for Day18 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut show_sum = false;
+    let mut file = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--sum" => show_sum = true,
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    let now = Instant::now();
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    let time_parse = now.elapsed();
+
+    if show_sum {
+        println!("{}", sum(&input));
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let result1 = part1(&input);
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let result2 = part2(&input);
+    let time2 = now.elapsed();
+
+    println!("Parse: (time: {}us)", time_parse.as_micros());
+    println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
+    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+
+    Ok(())
+}
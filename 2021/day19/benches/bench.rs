@@ -0,0 +1,28 @@
+#![allow(dead_code)]
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[path = "../src/main.rs"]
+mod main;
+
+fn bench_main(c: &mut Criterion) {
+    // There is no sample input small enough to be interesting, so use a generated one of the size of a real input.
+    let text = main::generate(30, 1337);
+
+    c.bench_function("parse (generated)", |b| {
+        b.iter(|| main::parse_reader(black_box(text.as_bytes())).unwrap())
+    });
+
+    c.bench_function("part 1 (generated)", |b| {
+        let input = main::parse_reader(text.as_bytes()).unwrap();
+        b.iter(|| main::part1(black_box(&input)))
+    });
+
+    c.bench_function("part 2 (generated)", |b| {
+        let input = main::parse_reader(text.as_bytes()).unwrap();
+        b.iter(|| main::part2(black_box(&input)))
+    });
+}
+
+criterion_group!(benches, bench_main);
+criterion_main!(benches);
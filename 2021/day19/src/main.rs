@@ -0,0 +1,379 @@
+use aoc_common::{input_path, Answer, Error, Rng, Solver};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::{Display, Write as _},
+    fs::File,
+    io::{BufRead, BufReader},
+    time::Instant,
+};
+
+/// A position in 3D space, relative to a scanner or relative to the first scanner.
+pub type Point = [i32; 3];
+
+/// The number of beacons two scanners need to have in common to be able to locate one from the other.
+const MIN_OVERLAP: usize = 12;
+
+/// The distance along every axis up to which a scanner detects beacons.
+const RANGE: i32 = 1000;
+
+/// The number of rotations a scanner can be in.
+const ROTATION_COUNT: usize = 24;
+
+pub struct Input {
+    /// The beacons detected by every scanner, relative to that scanner.
+    scans: Vec<Vec<Point>>,
+}
+
+/// The positions of all scanners and beacons, relative to the first scanner.
+pub struct Map {
+    pub scanners: Vec<Point>,
+    pub beacons: HashSet<Point>,
+}
+
+/// Indicates that a scanner could not be located, as it does not detect enough beacons in common with any
+/// scanner that could be located.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UnlocatedScanner {
+    pub scanner: usize,
+}
+
+impl Display for UnlocatedScanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Scanner {} does not overlap with any other scanner",
+            self.scanner
+        )
+    }
+}
+
+/// A rotation, as a permutation of the axes together with the sign of every axis.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Rotation {
+    axes: [usize; 3],
+    signs: [i32; 3],
+}
+
+impl Rotation {
+    /// Gets all 24 rotations, which are exactly the axis permutations and sign flips that do not mirror.
+    fn all() -> Vec<Rotation> {
+        let permutations = [
+            ([0, 1, 2], 1),
+            ([1, 2, 0], 1),
+            ([2, 0, 1], 1),
+            ([0, 2, 1], -1),
+            ([2, 1, 0], -1),
+            ([1, 0, 2], -1),
+        ];
+
+        let mut rotations = Vec::with_capacity(ROTATION_COUNT);
+        for (axes, parity) in permutations {
+            for flips in 0..8 {
+                let signs = [0, 1, 2].map(|i| if flips & (1 << i) == 0 { 1 } else { -1 });
+                if signs[0] * signs[1] * signs[2] == parity {
+                    rotations.push(Rotation { axes, signs });
+                }
+            }
+        }
+        rotations
+    }
+
+    fn apply(&self, p: Point) -> Point {
+        [0, 1, 2].map(|i| p[self.axes[i]] * self.signs[i])
+    }
+}
+
+fn add(a: Point, b: Point) -> Point {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Gets the squared distances between all pairs of beacons of a scan. These do not depend on the position
+/// or rotation of the scanner, so two scanners can only overlap if they share enough of them.
+fn fingerprint(beacons: &[Point]) -> HashSet<i64> {
+    let mut distances = HashSet::new();
+    for (i, a) in beacons.iter().enumerate() {
+        for b in beacons[i + 1..].iter() {
+            let d = sub(*a, *b);
+            distances.insert(d.iter().map(|&x| x as i64 * x as i64).sum());
+        }
+    }
+    distances
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut scans: Vec<Vec<Point>> = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        let invalid = |message: String| Error::at_line(index + 1, message);
+
+        if line.is_empty() {
+            continue;
+        } else if line.starts_with("---") {
+            let expected = format!("--- scanner {} ---", scans.len());
+            if line != expected {
+                return Err(invalid(format!(
+                    "Expected {}, but found {}.",
+                    expected, line
+                )));
+            }
+            scans.push(Vec::new());
+        } else {
+            let scan = scans
+                .last_mut()
+                .ok_or_else(|| invalid(String::from("Expected a scanner header.")))?;
+
+            let coordinates = line
+                .split(',')
+                .map(|x| x.parse::<i32>().ok().filter(|x| x.abs() <= RANGE))
+                .collect::<Option<Vec<i32>>>();
+            match coordinates.as_deref() {
+                Some(&[x, y, z]) => scan.push([x, y, z]),
+                _ => {
+                    return Err(invalid(format!(
+                        "Expected a position within a distance of {}, but found {}.",
+                        RANGE, line
+                    )))
+                }
+            }
+        }
+    }
+
+    if scans.is_empty() {
+        return Err(Error::at_line(1, "Expected a scanner header."));
+    }
+
+    Ok(Input { scans })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Tries to place the provided scan next to beacons that are already located. Returns the position of the
+/// scanner and its beacons, relative to the first scanner, if there are enough beacons in common.
+fn align(located: &[Point], scan: &[Point], rotations: &[Rotation]) -> Option<(Point, Vec<Point>)> {
+    let mut offsets = HashMap::new();
+    for rotation in rotations {
+        let rotated: Vec<Point> = scan.iter().map(|&p| rotation.apply(p)).collect();
+
+        // If the scanner is rotated correctly, the overlapping beacons all agree on where the scanner is.
+        offsets.clear();
+        for &a in located {
+            for &b in rotated.iter() {
+                let count = offsets.entry(sub(a, b)).or_insert(0);
+                *count += 1;
+                if *count >= MIN_OVERLAP {
+                    let position = sub(a, b);
+                    let beacons = rotated.iter().map(|&p| add(p, position)).collect();
+                    return Some((position, beacons));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Locates all scanners and beacons relative to the first scanner, by repeatedly aligning the scans that are
+/// not located yet with the scans that were located last.
+pub fn locate(input: &Input) -> Result<Map, UnlocatedScanner> {
+    let rotations = Rotation::all();
+    let fingerprints: Vec<HashSet<i64>> = input.scans.iter().map(|x| fingerprint(x)).collect();
+    let min_shared = MIN_OVERLAP * (MIN_OVERLAP - 1) / 2;
+
+    let mut scanners: Vec<Option<Point>> = vec![None; input.scans.len()];
+    let mut beacons: Vec<Vec<Point>> = vec![Vec::new(); input.scans.len()];
+    scanners[0] = Some([0, 0, 0]);
+    beacons[0] = input.scans[0].clone();
+
+    let mut queue = vec![0];
+    while let Some(origin) = queue.pop() {
+        for target in 0..input.scans.len() {
+            if scanners[target].is_some()
+                || fingerprints[origin]
+                    .intersection(&fingerprints[target])
+                    .count()
+                    < min_shared
+            {
+                continue;
+            }
+
+            if let Some((position, located)) =
+                align(&beacons[origin], &input.scans[target], &rotations)
+            {
+                scanners[target] = Some(position);
+                beacons[target] = located;
+                queue.push(target);
+            }
+        }
+    }
+
+    let scanners = scanners
+        .iter()
+        .enumerate()
+        .map(|(scanner, x)| x.ok_or(UnlocatedScanner { scanner }))
+        .collect::<Result<Vec<Point>, UnlocatedScanner>>()?;
+
+    Ok(Map {
+        scanners,
+        beacons: beacons.into_iter().flatten().collect(),
+    })
+}
+
+pub fn part1(input: &Input) -> Result<usize, UnlocatedScanner> {
+    Ok(locate(input)?.beacons.len())
+}
+
+pub fn part2(input: &Input) -> Result<usize, UnlocatedScanner> {
+    let map = locate(input)?;
+    let mut max = 0;
+    for a in map.scanners.iter() {
+        for b in map.scanners.iter() {
+            max = max.max(sub(*a, *b).iter().map(|x| x.unsigned_abs() as usize).sum());
+        }
+    }
+    Ok(max)
+}
+
+/// Generates the text of a pseudo-random puzzle input with the provided number of scanners in a row,
+/// determined by the provided seed. Every scanner shares at least 12 beacons with the next one, and every
+/// scan is rotated randomly.
+pub fn generate(scanner_count: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut next = move || rng.next_usize();
+    let mut coordinate = |low: i32, high: i32| low + (next() % (high - low + 1) as usize) as i32;
+
+    // Neighbouring scanners are 1000 apart, so they share half of their range along the x axis.
+    let positions: Vec<Point> = (0..scanner_count)
+        .map(|i| {
+            [
+                i as i32 * RANGE,
+                coordinate(-100, 100),
+                coordinate(-100, 100),
+            ]
+        })
+        .collect();
+
+    // An ordered set, such that the same seed always gives the same text.
+    let mut beacons = BTreeSet::new();
+    for (i, position) in positions.iter().enumerate() {
+        let x_low = if i == 0 { -RANGE } else { 0 };
+        for count in 0..MIN_OVERLAP + 8 {
+            // Put a few beacons behind this scanner, and the rest also within range of the next.
+            let (x_low, x_high) = if count < 4 {
+                (x_low, 0)
+            } else {
+                (0, RANGE - 1)
+            };
+            beacons.insert([
+                position[0] + coordinate(x_low, x_high),
+                position[1] + coordinate(-RANGE + 200, RANGE - 200),
+                position[2] + coordinate(-RANGE + 200, RANGE - 200),
+            ]);
+        }
+    }
+
+    let rotations = Rotation::all();
+    let mut text = String::new();
+    for (i, &position) in positions.iter().enumerate() {
+        let rotation = rotations[coordinate(0, ROTATION_COUNT as i32 - 1) as usize];
+        writeln!(text, "--- scanner {} ---", i).unwrap();
+        for &beacon in beacons.iter() {
+            let relative = sub(beacon, position);
+            if relative.iter().all(|x| x.abs() <= RANGE) {
+                let [x, y, z] = rotation.apply(relative);
+                writeln!(text, "{},{},{}", x, y, z).unwrap();
+            }
+        }
+        writeln!(text).unwrap();
+    }
+    text
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day19;
+
+impl Solver for Day19 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut generate_scanners = None;
+    let mut seed = aoc_common::seed();
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--generate" => {
+                generate_scanners = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a scanner count after --generate."),
+                )
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --seed.")
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    if let Some(scanner_count) = generate_scanners {
+        print!("{}", generate(scanner_count, seed));
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    let time_parse = now.elapsed();
+
+    let now = Instant::now();
+    let result1 = part1(&input);
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let result2 = part2(&input);
+    let time2 = now.elapsed();
+
+    println!("Parse: (time: {}us)", time_parse.as_micros());
+    println!(
+        "Solution 1: {} (time: {}us)",
+        Answer::from(result1),
+        time1.as_micros()
+    );
+    println!(
+        "Solution 2: {} (time: {}us)",
+        Answer::from(result2),
+        time2.as_micros()
+    );
+
+    Ok(())
+}
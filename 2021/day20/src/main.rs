@@ -0,0 +1,283 @@
+use aoc_common::{input_path, Answer, Error, GridFormatter, Solver};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufRead, BufReader},
+    time::Instant,
+};
+
+/// The number of entries in the image enhancement algorithm, one for every 3x3 square of pixels.
+const ALGORITHM_LENGTH: usize = 512;
+
+/// A finite image on an infinite background.
+#[derive(Clone)]
+pub struct Image {
+    width: usize,
+    height: usize,
+
+    /// The pixels of the image, row by row.
+    pixels: Vec<bool>,
+
+    /// The value of every pixel outside of the image.
+    background: bool,
+}
+
+pub struct Input {
+    algorithm: [bool; ALGORITHM_LENGTH],
+    image: Image,
+}
+
+/// Indicates that the image has infinitely many lit pixels, as the background of the image is lit.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InfinitelyLit;
+
+impl Display for InfinitelyLit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The background of the image is lit, so infinitely many pixels are lit"
+        )
+    }
+}
+
+impl Image {
+    /// Gets the pixel at the provided location, which may be outside of the image.
+    pub fn get(&self, x: isize, y: isize) -> bool {
+        if x < 0 || y < 0 || x >= self.width as isize || y >= self.height as isize {
+            self.background
+        } else {
+            self.pixels[y as usize * self.width + x as usize]
+        }
+    }
+
+    /// Applies the enhancement algorithm once. The image grows by one pixel on every side, as those are the
+    /// only pixels outside of the image that can differ from the new background.
+    pub fn enhance(&self, algorithm: &[bool; ALGORITHM_LENGTH]) -> Image {
+        let width = self.width + 2;
+        let height = self.height + 2;
+        let mut pixels = Vec::with_capacity(width * height);
+
+        for y in 0..height as isize {
+            for x in 0..width as isize {
+                let mut index = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        index = (index << 1) | self.get(x + dx - 1, y + dy - 1) as usize;
+                    }
+                }
+                pixels.push(algorithm[index]);
+            }
+        }
+
+        let background = algorithm[if self.background {
+            ALGORITHM_LENGTH - 1
+        } else {
+            0
+        }];
+        Image {
+            width,
+            height,
+            pixels,
+            background,
+        }
+    }
+
+    /// Counts the lit pixels.
+    pub fn count_lit(&self) -> Result<usize, InfinitelyLit> {
+        if self.background {
+            Err(InfinitelyLit)
+        } else {
+            Ok(self.pixels.iter().filter(|&&x| x).count())
+        }
+    }
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        GridFormatter::custom(self.width, self.height, |x, y| {
+            if self.pixels[y * self.width + x] {
+                '#'
+            } else {
+                '.'
+            }
+        })
+        .fmt(f)
+    }
+}
+
+/// Parses a row of pixels.
+fn parse_pixels(line: &str, line_number: usize) -> aoc_common::Result<Vec<bool>> {
+    line.bytes()
+        .enumerate()
+        .map(|(column, c)| match c {
+            b'#' => Ok(true),
+            b'.' => Ok(false),
+            _ => Err(Error::at_column(
+                line_number,
+                column + 1,
+                format!("Expected '#' or '.', but found '{}'.", c.escape_ascii()),
+            )),
+        })
+        .collect()
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut lines = reader.lines();
+
+    let line = lines.next().transpose()?.unwrap_or_default();
+    let algorithm: [bool; ALGORITHM_LENGTH] = parse_pixels(line.trim_end(), 1)?
+        .try_into()
+        .map_err(|x: Vec<bool>| {
+            Error::at_line(
+                1,
+                format!(
+                    "Expected an algorithm of {} pixels, but found {}.",
+                    ALGORITHM_LENGTH,
+                    x.len()
+                ),
+            )
+        })?;
+
+    // A lit background would stay lit forever, so the number of lit pixels would never be finite.
+    if algorithm[0] && algorithm[ALGORITHM_LENGTH - 1] {
+        return Err(Error::at_line(
+            1,
+            "Expected an algorithm that does not light up the entire background.",
+        ));
+    }
+
+    let mut width = 0;
+    let mut pixels = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        if line.is_empty() && pixels.is_empty() {
+            continue;
+        }
+
+        let row = parse_pixels(line, index + 2)?;
+        if pixels.is_empty() {
+            width = row.len();
+        } else if row.len() != width {
+            return Err(Error::at_line(
+                index + 2,
+                format!(
+                    "Expected a row of {} pixels, but found {}.",
+                    width,
+                    row.len()
+                ),
+            ));
+        }
+        pixels.extend(row);
+    }
+
+    if pixels.is_empty() {
+        return Err(Error::at_line(2, "Expected an image."));
+    }
+
+    Ok(Input {
+        algorithm,
+        image: Image {
+            width,
+            height: pixels.len() / width,
+            pixels,
+            background: false,
+        },
+    })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Applies the enhancement algorithm the provided number of times.
+pub fn enhance(input: &Input, steps: usize) -> Image {
+    let mut image = input.image.clone();
+    for _ in 0..steps {
+        image = image.enhance(&input.algorithm);
+    }
+    image
+}
+
+pub fn part1(input: &Input) -> Result<usize, InfinitelyLit> {
+    enhance(input, 2).count_lit()
+}
+
+pub fn part2(input: &Input) -> Result<usize, InfinitelyLit> {
+    enhance(input, 50).count_lit()
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day20;
+
+impl Solver for Day20 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut print_steps = None;
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--print" => {
+                print_steps = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a number of steps after --print."),
+                )
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    let now = Instant::now();
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    let time_parse = now.elapsed();
+
+    if let Some(steps) = print_steps {
+        print!("{}", enhance(&input, steps));
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let result1 = part1(&input);
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let result2 = part2(&input);
+    let time2 = now.elapsed();
+
+    println!("Parse: (time: {}us)", time_parse.as_micros());
+    println!(
+        "Solution 1: {} (time: {}us)",
+        Answer::from(result1),
+        time1.as_micros()
+    );
+    println!(
+        "Solution 2: {} (time: {}us)",
+        Answer::from(result2),
+        time2.as_micros()
+    );
+
+    Ok(())
+}
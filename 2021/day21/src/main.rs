@@ -0,0 +1,190 @@
+use aoc_common::{input_path, Answer, Error, Solver};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    time::Instant,
+};
+
+/// The number of spaces on the game board.
+const BOARD_SIZE: usize = 10;
+
+/// The number of sides of the deterministic die of part 1.
+const DETERMINISTIC_SIDES: usize = 100;
+
+/// The score a player needs to win with the deterministic die.
+const DETERMINISTIC_TARGET: usize = 1000;
+
+/// The score a player needs to win with the Dirac die.
+const DIRAC_TARGET: usize = 21;
+
+/// The number of universes every sum of three rolls of the Dirac die occurs in, as `(sum, universes)` pairs.
+const DIRAC_ROLLS: [(usize, usize); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+pub struct Input {
+    /// The starting positions of both players, from 1 to 10.
+    positions: [usize; 2],
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut positions = [0; 2];
+    let mut lines = reader.lines();
+
+    for (player, position) in positions.iter_mut().enumerate() {
+        let line = lines
+            .next()
+            .transpose()?
+            .ok_or_else(|| Error::at_line(player + 1, "Expected a starting position."))?;
+
+        let prefix = format!("Player {} starting position: ", player + 1);
+        *position = line
+            .trim_end()
+            .strip_prefix(&prefix)
+            .and_then(|x| x.parse().ok())
+            .filter(|x| (1..=BOARD_SIZE).contains(x))
+            .ok_or_else(|| {
+                Error::at_line(
+                    player + 1,
+                    format!(
+                        "Expected the starting position of player {} from 1 to {}, but found {}.",
+                        player + 1,
+                        BOARD_SIZE,
+                        line.trim_end()
+                    ),
+                )
+            })?;
+    }
+
+    Ok(Input { positions })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Moves a pawn the provided number of spaces forward, wrapping around from 10 to 1.
+fn advance(position: usize, spaces: usize) -> usize {
+    (position + spaces - 1) % BOARD_SIZE + 1
+}
+
+/// Plays with the deterministic die until a player wins, and returns the score of the losing player
+/// multiplied by the number of rolls.
+pub fn part1(input: &Input) -> usize {
+    let mut positions = input.positions;
+    let mut scores = [0; 2];
+    let mut rolls = 0;
+
+    for player in (0..2).cycle() {
+        let spaces: usize = (0..3).map(|i| (rolls + i) % DETERMINISTIC_SIDES + 1).sum();
+        rolls += 3;
+
+        positions[player] = advance(positions[player], spaces);
+        scores[player] += positions[player];
+        if scores[player] >= DETERMINISTIC_TARGET {
+            return scores[1 - player] * rolls;
+        }
+    }
+
+    unreachable!()
+}
+
+/// Counts the universes in which each player wins with the Dirac die, where the first player is the one
+/// that moves next. Results are cached by positions and scores, of which there are only a few thousand.
+fn count_wins(
+    positions: [usize; 2],
+    scores: [usize; 2],
+    cache: &mut [Option<[usize; 2]>],
+) -> [usize; 2] {
+    let key = (((positions[0] - 1) * BOARD_SIZE + positions[1] - 1) * DIRAC_TARGET + scores[0])
+        * DIRAC_TARGET
+        + scores[1];
+    if let Some(wins) = cache[key] {
+        return wins;
+    }
+
+    let mut wins = [0; 2];
+    for (sum, universes) in DIRAC_ROLLS {
+        let position = advance(positions[0], sum);
+        let score = scores[0] + position;
+        if score >= DIRAC_TARGET {
+            wins[0] += universes;
+        } else {
+            // Continue with the other player to move.
+            let other = count_wins([positions[1], position], [scores[1], score], cache);
+            wins[0] += universes * other[1];
+            wins[1] += universes * other[0];
+        }
+    }
+
+    cache[key] = Some(wins);
+    wins
+}
+
+/// Counts the universes in which each player wins with the Dirac die.
+pub fn dirac_wins(input: &Input) -> [usize; 2] {
+    let mut cache = vec![None; BOARD_SIZE * BOARD_SIZE * DIRAC_TARGET * DIRAC_TARGET];
+    count_wins(input.positions, [0, 0], &mut cache)
+}
+
+pub fn part2(input: &Input) -> usize {
+    dirac_wins(input).into_iter().max().unwrap()
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day21;
+
+impl Solver for Day21 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut file = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    let now = Instant::now();
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    let time_parse = now.elapsed();
+
+    let now = Instant::now();
+    let result1 = part1(&input);
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let wins = dirac_wins(&input);
+    let result2 = wins[0].max(wins[1]);
+    let time2 = now.elapsed();
+
+    println!("Parse: (time: {}us)", time_parse.as_micros());
+    println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
+    println!(
+        "Solution 2: {} (player 1 wins in {}, player 2 wins in {} universes) (time: {}us)",
+        result2,
+        wins[0],
+        wins[1],
+        time2.as_micros()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use aoc_common::{input_path, parse_lines, Answer, Rng, Solver};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    time::Instant,
+};
+
+/// The largest distance of a cube from the origin along any axis. This keeps the volumes well within 64 bits.
+const MAX_COORDINATE: i64 = 1_000_000;
+
+/// The distance from the origin along every axis of the cubes that the initialization procedure considers.
+const INITIALIZATION_RANGE: i64 = 50;
+
+/// A cuboid, with inclusive bounds along every axis.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cuboid {
+    pub min: [i64; 3],
+    pub max: [i64; 3],
+}
+
+/// A single step of the reboot procedure.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Step {
+    pub on: bool,
+    pub cuboid: Cuboid,
+}
+
+pub struct Input {
+    steps: Vec<Step>,
+}
+
+impl Cuboid {
+    /// Gets the cubes that are in both cuboids, or [`None`] if the cuboids do not overlap.
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let min = [0, 1, 2].map(|i| self.min[i].max(other.min[i]));
+        let max = [0, 1, 2].map(|i| self.max[i].min(other.max[i]));
+        (0..3)
+            .all(|i| min[i] <= max[i])
+            .then_some(Cuboid { min, max })
+    }
+
+    /// Counts the cubes in the cuboid.
+    pub fn volume(&self) -> i64 {
+        (0..3).map(|i| self.max[i] - self.min[i] + 1).product()
+    }
+}
+
+impl std::str::FromStr for Step {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Expected a reboot step, but found {}.", s);
+
+        let (on, ranges) = s.split_once(' ').ok_or_else(invalid)?;
+        let on = match on {
+            "on" => true,
+            "off" => false,
+            _ => return Err(invalid()),
+        };
+
+        let mut min = [0; 3];
+        let mut max = [0; 3];
+        let mut ranges = ranges.split(',');
+        for (axis, name) in ["x=", "y=", "z="].iter().enumerate() {
+            let (low, high) = ranges
+                .next()
+                .and_then(|x| x.strip_prefix(name))
+                .and_then(|x| x.split_once(".."))
+                .ok_or_else(invalid)?;
+            min[axis] = low.parse().map_err(|_| invalid())?;
+            max[axis] = high.parse().map_err(|_| invalid())?;
+
+            if min[axis] > max[axis] {
+                return Err(format!(
+                    "Expected a range from low to high, but found {}.",
+                    s
+                ));
+            }
+            if min[axis] < -MAX_COORDINATE || max[axis] > MAX_COORDINATE {
+                return Err(format!(
+                    "Expected coordinates from -{0} to {0}, but found {1}.",
+                    MAX_COORDINATE, s
+                ));
+            }
+        }
+        if ranges.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(Step {
+            on,
+            cuboid: Cuboid { min, max },
+        })
+    }
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let steps = parse_lines(reader, |line| line.parse())?;
+    Ok(Input { steps })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Counts the cubes that are on after all steps, only considering the cubes within the provided region.
+///
+/// Rather than splitting cuboids into pieces, the cubes that are on are described by a list of cuboids that
+/// are either added or subtracted. Every new step cancels out its overlap with every cuboid in the list, by
+/// adding the overlap with the opposite sign. Cuboids that are turned on are then added as a whole.
+pub fn count_on(input: &Input, region: &Cuboid) -> i64 {
+    let mut cuboids: Vec<(Cuboid, i64)> = Vec::new();
+
+    for step in input.steps.iter() {
+        let cuboid = match step.cuboid.intersection(region) {
+            Some(cuboid) => cuboid,
+            None => continue,
+        };
+
+        let overlaps: Vec<(Cuboid, i64)> = cuboids
+            .iter()
+            .filter_map(|(other, sign)| Some((other.intersection(&cuboid)?, -sign)))
+            .collect();
+        cuboids.extend(overlaps);
+
+        if step.on {
+            cuboids.push((cuboid, 1));
+        }
+    }
+
+    cuboids
+        .iter()
+        .map(|(cuboid, sign)| cuboid.volume() * sign)
+        .sum()
+}
+
+pub fn part1(input: &Input) -> usize {
+    let region = Cuboid {
+        min: [-INITIALIZATION_RANGE; 3],
+        max: [INITIALIZATION_RANGE; 3],
+    };
+    count_on(input, &region) as usize
+}
+
+pub fn part2(input: &Input) -> usize {
+    let region = Cuboid {
+        min: [-MAX_COORDINATE; 3],
+        max: [MAX_COORDINATE; 3],
+    };
+    count_on(input, &region) as usize
+}
+
+/// Generates a pseudo-random reboot procedure with the provided number of steps, determined by the provided
+/// seed. Like the puzzle input, the first steps initialize the region around the origin.
+pub fn generate(step_count: usize, seed: u64) -> Input {
+    let mut rng = Rng::new(seed);
+    let mut next = move || rng.next_u64() as i64 & i64::MAX;
+
+    let steps = (0..step_count)
+        .map(|i| {
+            let (range, size) = if i < step_count / 20 {
+                (INITIALIZATION_RANGE, INITIALIZATION_RANGE)
+            } else {
+                (MAX_COORDINATE / 10, MAX_COORDINATE / 40)
+            };
+
+            let min = [0; 3].map(|_| next() % (2 * range) - range);
+            let max = [0, 1, 2].map(|i| (min[i] + next() % size).min(range));
+            Step {
+                on: next() % 2 == 0,
+                cuboid: Cuboid { min, max },
+            }
+        })
+        .collect();
+
+    Input { steps }
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day22;
+
+impl Solver for Day22 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut generate_steps = None;
+    let mut seed = aoc_common::seed();
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--generate" => {
+                generate_steps = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected a step count after --generate."),
+                )
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --seed.")
+            }
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    let now = Instant::now();
+    let input = match generate_steps {
+        Some(step_count) => generate(step_count, seed),
+        None => parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?,
+    };
+    let time_parse = now.elapsed();
+
+    let now = Instant::now();
+    let result1 = part1(&input);
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let result2 = part2(&input);
+    let time2 = now.elapsed();
+
+    println!("Parse: (time: {}us)", time_parse.as_micros());
+    println!("Solution 1: {} (time: {}us)", result1, time1.as_micros());
+    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+
+    Ok(())
+}
@@ -0,0 +1,348 @@
+use aoc_common::{input_path, Answer, Error, Solver};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::Display,
+    fs::File,
+    io::{BufRead, BufReader},
+    time::Instant,
+};
+
+/// The number of spaces in the hallway.
+const HALLWAY_LENGTH: usize = 11;
+
+/// The number of side rooms, one for every type of amphipod.
+const ROOM_COUNT: usize = 4;
+
+/// The number of amphipods that fit in a side room once the diagram is unfolded.
+const MAX_DEPTH: usize = 4;
+
+/// The energy a single step takes, for every type of amphipod.
+const ENERGY: [usize; ROOM_COUNT] = [1, 10, 100, 1000];
+
+/// The rows of amphipods that are hidden in the folded diagram of part 1.
+const HIDDEN_ROWS: [[u8; ROOM_COUNT]; 2] = [[3, 2, 1, 0], [3, 1, 0, 2]];
+
+/// Marks a space without an amphipod.
+const EMPTY: u8 = u8::MAX;
+
+/// The positions of all amphipods. Amphipods are identified by their type, `0` for `A` up to `3` for `D`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Burrow {
+    hallway: [u8; HALLWAY_LENGTH],
+
+    /// The amphipods in every side room, from the hallway down.
+    rooms: [[u8; MAX_DEPTH]; ROOM_COUNT],
+
+    /// The number of amphipods that fit in every side room.
+    depth: usize,
+}
+
+/// Indicates that the amphipods cannot be organized.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Unsolvable;
+
+impl Display for Unsolvable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The amphipods cannot be organized")
+    }
+}
+
+pub struct Input {
+    burrow: Burrow,
+}
+
+/// Gets the hallway space right outside of a side room.
+fn entrance(room: usize) -> usize {
+    2 + 2 * room
+}
+
+/// Determines whether nothing blocks the way through the hallway from one space to another, not including the
+/// space of origin.
+fn is_clear(hallway: &[u8; HALLWAY_LENGTH], from: usize, to: usize) -> bool {
+    let range = if from < to {
+        from + 1..=to
+    } else {
+        to..=from - 1
+    };
+    hallway[range].iter().all(|&x| x == EMPTY)
+}
+
+impl Burrow {
+    /// Inserts the hidden rows of amphipods between the first and the last row of every side room.
+    pub fn unfold(&self) -> Burrow {
+        let mut burrow = *self;
+        for (room, slots) in burrow.rooms.iter_mut().enumerate() {
+            *slots = [
+                slots[0],
+                HIDDEN_ROWS[0][room],
+                HIDDEN_ROWS[1][room],
+                slots[1],
+            ];
+        }
+        burrow.depth = MAX_DEPTH;
+        burrow
+    }
+
+    /// Determines whether all amphipods are in their own side room.
+    pub fn is_organized(&self) -> bool {
+        (0..ROOM_COUNT).all(|room| {
+            self.rooms[room][..self.depth]
+                .iter()
+                .all(|&x| x == room as u8)
+        })
+    }
+
+    /// Determines whether amphipods can move into a side room, which is only the case if all amphipods in it
+    /// already belong there.
+    fn accepts(&self, room: usize) -> bool {
+        self.rooms[room][..self.depth]
+            .iter()
+            .all(|&x| x == EMPTY || x == room as u8)
+    }
+
+    /// Gets every state that can be reached with a single move, together with the energy it takes.
+    fn moves(&self) -> Vec<(Burrow, usize)> {
+        let mut moves = Vec::new();
+
+        // Moving into a side room is never worse than any other move, so take it right away if possible.
+        for x in 0..HALLWAY_LENGTH {
+            let kind = self.hallway[x];
+            if kind == EMPTY || !self.accepts(kind as usize) {
+                continue;
+            }
+
+            let room = kind as usize;
+            if is_clear(&self.hallway, x, entrance(room)) {
+                let slot = self.rooms[room][..self.depth]
+                    .iter()
+                    .rposition(|&x| x == EMPTY)
+                    .unwrap();
+
+                let mut next = *self;
+                next.hallway[x] = EMPTY;
+                next.rooms[room][slot] = kind;
+                let steps = x.abs_diff(entrance(room)) + slot + 1;
+                return vec![(next, steps * ENERGY[room])];
+            }
+        }
+
+        for room in 0..ROOM_COUNT {
+            if self.accepts(room) {
+                continue;
+            }
+
+            let slot = match self.rooms[room][..self.depth]
+                .iter()
+                .position(|&x| x != EMPTY)
+            {
+                Some(slot) => slot,
+                None => continue,
+            };
+            let kind = self.rooms[room][slot];
+
+            // Amphipods never stop right outside of a side room.
+            for x in
+                (0..HALLWAY_LENGTH).filter(|&x| (0..ROOM_COUNT).all(|room| entrance(room) != x))
+            {
+                if !is_clear(&self.hallway, entrance(room), x) || self.hallway[x] != EMPTY {
+                    continue;
+                }
+
+                let mut next = *self;
+                next.rooms[room][slot] = EMPTY;
+                next.hallway[x] = kind;
+                let steps = slot + 1 + x.abs_diff(entrance(room));
+                moves.push((next, steps * ENERGY[kind as usize]));
+            }
+        }
+
+        moves
+    }
+
+    /// Finds the least energy needed to organize the amphipods.
+    pub fn organize(&self) -> Result<usize, Unsolvable> {
+        let mut best = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        best.insert(*self, 0);
+        queue.push(Reverse((0, *self)));
+
+        while let Some(Reverse((energy, burrow))) = queue.pop() {
+            if burrow.is_organized() {
+                return Ok(energy);
+            }
+            if best.get(&burrow).is_some_and(|&x| x < energy) {
+                continue;
+            }
+
+            for (next, cost) in burrow.moves() {
+                let energy = energy + cost;
+                if best.get(&next).is_none_or(|&x| energy < x) {
+                    best.insert(next, energy);
+                    queue.push(Reverse((energy, next)));
+                }
+            }
+        }
+
+        Err(Unsolvable)
+    }
+}
+
+impl Display for Burrow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cell = |x: u8| if x == EMPTY { '.' } else { (b'A' + x) as char };
+
+        writeln!(f, "#############")?;
+        write!(f, "#")?;
+        for &x in self.hallway.iter() {
+            write!(f, "{}", cell(x))?;
+        }
+        writeln!(f, "#")?;
+
+        for slot in 0..self.depth {
+            write!(f, "{}", if slot == 0 { "###" } else { "  #" })?;
+            for room in 0..ROOM_COUNT {
+                write!(f, "{}#", cell(self.rooms[room][slot]))?;
+            }
+            writeln!(f, "{}", if slot == 0 { "##" } else { "" })?;
+        }
+        writeln!(f, "  #########")
+    }
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut rooms = [[EMPTY; MAX_DEPTH]; ROOM_COUNT];
+    let mut counts = [0; ROOM_COUNT];
+    let mut depth = 0;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end().as_bytes();
+
+        // The side rooms are the only lines with amphipods in them. Only the folded diagram is accepted, as
+        // part 2 unfolds it.
+        if !line.iter().any(u8::is_ascii_uppercase) {
+            continue;
+        }
+        if depth == MAX_DEPTH / 2 {
+            return Err(Error::at_line(
+                index + 1,
+                "Expected two amphipods per side room.",
+            ));
+        }
+
+        for (room, slots) in rooms.iter_mut().enumerate() {
+            let column = 3 + 2 * room;
+            match line.get(column) {
+                Some(&c @ b'A'..=b'D') => {
+                    slots[depth] = c - b'A';
+                    counts[(c - b'A') as usize] += 1;
+                }
+                _ => {
+                    return Err(Error::at_column(
+                        index + 1,
+                        column + 1,
+                        "Expected an amphipod from A to D.",
+                    ))
+                }
+            }
+        }
+        depth += 1;
+    }
+
+    if counts.iter().any(|&x| x != MAX_DEPTH / 2) {
+        return Err(Error::at_line(
+            1,
+            "Expected two amphipods of every type in two rows of side rooms.",
+        ));
+    }
+
+    Ok(Input {
+        burrow: Burrow {
+            hallway: [EMPTY; HALLWAY_LENGTH],
+            rooms,
+            depth,
+        },
+    })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+pub fn part1(input: &Input) -> Result<usize, Unsolvable> {
+    input.burrow.organize()
+}
+
+pub fn part2(input: &Input) -> Result<usize, Unsolvable> {
+    input.burrow.unfold().organize()
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day23;
+
+impl Solver for Day23 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut print = false;
+    let mut file = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--print" => print = true,
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    let now = Instant::now();
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    let time_parse = now.elapsed();
+
+    if print {
+        println!("{}", input.burrow);
+        print!("{}", input.burrow.unfold());
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let result1 = part1(&input);
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let result2 = part2(&input);
+    let time2 = now.elapsed();
+
+    println!("Parse: (time: {}us)", time_parse.as_micros());
+    println!(
+        "Solution 1: {} (time: {}us)",
+        Answer::from(result1),
+        time1.as_micros()
+    );
+    println!(
+        "Solution 2: {} (time: {}us)",
+        Answer::from(result2),
+        time2.as_micros()
+    );
+
+    Ok(())
+}
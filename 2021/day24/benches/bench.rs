@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[path = "../src/main.rs"]
+mod main;
+
+fn bench_main(c: &mut Criterion) {
+    let input = main::generate(1337);
+
+    c.bench_function("part 1 (generated)", |b| {
+        b.iter(|| main::part1(black_box(&input)))
+    });
+
+    c.bench_function("part 2 (generated)", |b| {
+        b.iter(|| main::part2(black_box(&input)))
+    });
+}
+
+criterion_group!(benches, bench_main);
+criterion_main!(benches);
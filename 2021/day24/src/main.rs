@@ -0,0 +1,505 @@
+use aoc_common::{input_path, Answer, Error, Rng, Solver};
+use std::{
+    fmt::{Display, Write as _},
+    fs::File,
+    io::{BufRead, BufReader},
+    time::Instant,
+};
+
+/// The number of digits in a model number.
+const DIGIT_COUNT: usize = 14;
+
+/// The registers of the ALU, in the order they are stored in.
+const REGISTERS: [&str; 4] = ["w", "x", "y", "z"];
+
+const W: usize = 0;
+const X: usize = 1;
+const Y: usize = 2;
+const Z: usize = 3;
+
+/// The instructions that check a single digit of the model number. The MONAD repeats this block for every
+/// digit, only changing the operands of the instructions at [`BLOCK_DIVISOR`], [`BLOCK_CHECK`] and
+/// [`BLOCK_OFFSET`].
+const BLOCK: &str = "inp w
+mul x 0
+add x z
+mod x 26
+div z 1
+add x 0
+eql x w
+eql x 0
+mul y 0
+add y 25
+mul y x
+add y 1
+mul z y
+mul y 0
+add y w
+add y 0
+mul y x
+add z y";
+
+/// The index of the instruction in [`BLOCK`] that pops a digit off the stack encoded in `z` if it divides by
+/// 26, or keeps the stack as is if it divides by 1.
+const BLOCK_DIVISOR: usize = 4;
+
+/// The index of the instruction in [`BLOCK`] that adds the number the digit is compared with to the top of
+/// the stack.
+const BLOCK_CHECK: usize = 5;
+
+/// The index of the instruction in [`BLOCK`] that adds the number to the digit before pushing it.
+const BLOCK_OFFSET: usize = 15;
+
+/// The number of distinct values that fit in one element of the stack encoded in `z`.
+const STACK_BASE: i64 = 26;
+
+/// The second operand of an instruction.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Operand {
+    Register(usize),
+    Value(i64),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Instruction {
+    Inp(usize),
+    Add(usize, Operand),
+    Mul(usize, Operand),
+    Div(usize, Operand),
+    Mod(usize, Operand),
+    Eql(usize, Operand),
+}
+
+pub struct Input {
+    program: Vec<Instruction>,
+}
+
+/// The parameters of the block of the MONAD that checks one digit.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Block {
+    /// Whether the block pops the top of the stack encoded in `z`.
+    pub pops: bool,
+
+    /// The number added to the top of the stack before comparing it with the digit.
+    pub check: i64,
+
+    /// The number added to the digit before pushing it onto the stack.
+    pub offset: i64,
+}
+
+/// Describes why no model number could be found.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MonadError {
+    /// The instructions checking the provided digit (starting at 0) differ from the ones of the MONAD.
+    UnexpectedStructure(usize),
+
+    /// Pairs of digits cannot be chosen such that the MONAD accepts them.
+    NoModelNumber,
+}
+
+/// Describes why the ALU stopped.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AluError {
+    /// The instruction at the provided index divides by zero, or takes a remainder of a negative number or by a
+    /// number that is not positive.
+    InvalidOperands(usize),
+
+    /// The instruction at the provided index reads more digits than were provided.
+    MissingInput(usize),
+}
+
+impl Display for MonadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonadError::UnexpectedStructure(digit) => write!(
+                f,
+                "The instructions checking digit {} are not the ones of a MONAD",
+                digit + 1
+            ),
+            MonadError::NoModelNumber => write!(f, "The MONAD does not accept any model number"),
+        }
+    }
+}
+
+impl Display for AluError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AluError::InvalidOperands(index) => {
+                write!(f, "Instruction {} has invalid operands", index + 1)
+            }
+            AluError::MissingInput(index) => {
+                write!(
+                    f,
+                    "Instruction {} reads past the end of the input",
+                    index + 1
+                )
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let register = |x: &str| REGISTERS.iter().position(|&r| r == x);
+        let invalid = || format!("Expected an instruction, but found {}.", s);
+
+        let mut parts = s.split(' ');
+        let opcode = parts.next().ok_or_else(invalid)?;
+        let a = parts.next().and_then(register).ok_or_else(invalid)?;
+
+        let instruction = if opcode == "inp" {
+            Instruction::Inp(a)
+        } else {
+            let b = parts.next().ok_or_else(invalid)?;
+            let b = match register(b) {
+                Some(b) => Operand::Register(b),
+                None => Operand::Value(b.parse().map_err(|_| invalid())?),
+            };
+
+            match opcode {
+                "add" => Instruction::Add(a, b),
+                "mul" => Instruction::Mul(a, b),
+                "div" => Instruction::Div(a, b),
+                "mod" => Instruction::Mod(a, b),
+                "eql" => Instruction::Eql(a, b),
+                _ => return Err(invalid()),
+            }
+        };
+
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(instruction)
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (opcode, a, b) = match *self {
+            Instruction::Inp(a) => return write!(f, "inp {}", REGISTERS[a]),
+            Instruction::Add(a, b) => ("add", a, b),
+            Instruction::Mul(a, b) => ("mul", a, b),
+            Instruction::Div(a, b) => ("div", a, b),
+            Instruction::Mod(a, b) => ("mod", a, b),
+            Instruction::Eql(a, b) => ("eql", a, b),
+        };
+
+        match b {
+            Operand::Register(b) => write!(f, "{} {} {}", opcode, REGISTERS[a], REGISTERS[b]),
+            Operand::Value(b) => write!(f, "{} {} {}", opcode, REGISTERS[a], b),
+        }
+    }
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut program = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        if !line.is_empty() {
+            program.push(
+                line.parse()
+                    .map_err(|x: String| Error::at_line(index + 1, x))?,
+            );
+        }
+    }
+
+    Ok(Input { program })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Runs the program on the provided digits, and returns the final values of the registers.
+pub fn run(program: &[Instruction], digits: &[i64]) -> Result<[i64; 4], AluError> {
+    let mut registers = [0i64; 4];
+    let mut digits = digits.iter();
+
+    for (index, &instruction) in program.iter().enumerate() {
+        let value = |b: Operand, registers: &[i64; 4]| match b {
+            Operand::Register(b) => registers[b],
+            Operand::Value(b) => b,
+        };
+
+        match instruction {
+            Instruction::Inp(a) => {
+                registers[a] = *digits.next().ok_or(AluError::MissingInput(index))?;
+            }
+            Instruction::Add(a, b) => {
+                registers[a] = registers[a].wrapping_add(value(b, &registers))
+            }
+            Instruction::Mul(a, b) => {
+                registers[a] = registers[a].wrapping_mul(value(b, &registers))
+            }
+            Instruction::Div(a, b) => {
+                registers[a] = registers[a]
+                    .checked_div(value(b, &registers))
+                    .ok_or(AluError::InvalidOperands(index))?
+            }
+            Instruction::Mod(a, b) => {
+                let b = value(b, &registers);
+                if registers[a] < 0 || b <= 0 {
+                    return Err(AluError::InvalidOperands(index));
+                }
+                registers[a] %= b;
+            }
+            Instruction::Eql(a, b) => registers[a] = (registers[a] == value(b, &registers)) as i64,
+        }
+    }
+
+    Ok(registers)
+}
+
+/// Extracts the parameters of every block of the MONAD.
+pub fn blocks(program: &[Instruction]) -> Result<Vec<Block>, MonadError> {
+    let template: Vec<Instruction> = BLOCK.lines().map(|x| x.parse().unwrap()).collect();
+    if program.len() != template.len() * DIGIT_COUNT {
+        return Err(MonadError::UnexpectedStructure(
+            (program.len() / template.len()).min(DIGIT_COUNT - 1),
+        ));
+    }
+
+    program
+        .chunks(template.len())
+        .enumerate()
+        .map(|(digit, block)| {
+            let unexpected = MonadError::UnexpectedStructure(digit);
+
+            let matches_template =
+                block
+                    .iter()
+                    .zip(template.iter())
+                    .enumerate()
+                    .all(|(i, (a, b))| {
+                        matches!(i, BLOCK_DIVISOR | BLOCK_CHECK | BLOCK_OFFSET) || a == b
+                    });
+            if !matches_template {
+                return Err(unexpected);
+            }
+
+            let pops = match block[BLOCK_DIVISOR] {
+                Instruction::Div(Z, Operand::Value(1)) => false,
+                Instruction::Div(Z, Operand::Value(STACK_BASE)) => true,
+                _ => return Err(unexpected),
+            };
+            let (check, offset) = match (block[BLOCK_CHECK], block[BLOCK_OFFSET]) {
+                (
+                    Instruction::Add(X, Operand::Value(check)),
+                    Instruction::Add(Y, Operand::Value(offset)),
+                ) => (check, offset),
+                _ => return Err(unexpected),
+            };
+
+            Ok(Block {
+                pops,
+                check,
+                offset,
+            })
+        })
+        .collect()
+}
+
+/// Finds the largest or smallest model number the MONAD accepts.
+///
+/// Every block either pushes its digit plus an offset onto a stack encoded in base 26 in `z`, or pops the
+/// top of the stack and compares it plus a number with its digit. If the comparison fails, the digit is pushed
+/// anyway. As `z` needs to be zero at the end, half of the blocks need to pop, and all of their comparisons need
+/// to succeed. This pairs every pushing block with a popping block, which puts a constraint on the difference
+/// between their digits that is easily satisfied by hand.
+pub fn find_model_number(input: &Input, largest: bool) -> Result<usize, MonadError> {
+    let blocks = blocks(&input.program)?;
+
+    let mut digits = [0i64; DIGIT_COUNT];
+    let mut stack = Vec::new();
+    for (j, block) in blocks.iter().enumerate() {
+        if !block.pops {
+            stack.push(j);
+            continue;
+        }
+
+        let i = stack.pop().ok_or(MonadError::NoModelNumber)?;
+        let difference = blocks[i].offset + block.check;
+        digits[i] = if largest {
+            9.min(9 - difference)
+        } else {
+            1.max(1 - difference)
+        };
+        digits[j] = digits[i] + difference;
+    }
+
+    if !stack.is_empty() || digits.iter().any(|x| !(1..=9).contains(x)) {
+        return Err(MonadError::NoModelNumber);
+    }
+
+    // Make sure the digits are accepted, in case a pushing block could also have popped.
+    match run(&input.program, &digits) {
+        Ok(registers) if registers[Z] == 0 => {
+            Ok(digits.iter().fold(0, |acc, &x| acc * 10 + x as usize))
+        }
+        _ => Err(MonadError::NoModelNumber),
+    }
+}
+
+pub fn part1(input: &Input) -> Result<usize, MonadError> {
+    find_model_number(input, true)
+}
+
+pub fn part2(input: &Input) -> Result<usize, MonadError> {
+    find_model_number(input, false)
+}
+
+/// Generates a pseudo-random MONAD that accepts at least one model number, determined by the provided seed.
+pub fn generate(seed: u64) -> Input {
+    let mut rng = Rng::new(seed);
+    let mut next = move || (rng.next_u64() >> 1) as i64;
+
+    // Shuffle the order of pushing and popping blocks, while making sure every pop has a matching push.
+    let mut blocks = Vec::with_capacity(DIGIT_COUNT);
+    let mut stack = Vec::new();
+    let mut pushes = DIGIT_COUNT / 2;
+    while blocks.len() < DIGIT_COUNT {
+        if pushes > 0 && (stack.is_empty() || next() % 2 == 0) {
+            pushes -= 1;
+            stack.push(blocks.len());
+            blocks.push(Block {
+                pops: false,
+                check: 10 + next() % 6,
+                offset: next() % 16,
+            });
+        } else {
+            let i: usize = stack.pop().unwrap();
+            let difference = next() % 17 - 8;
+            blocks.push(Block {
+                pops: true,
+                check: difference - blocks[i].offset,
+                offset: next() % 16,
+            });
+        }
+    }
+
+    let mut text = String::new();
+    for block in blocks {
+        for (i, line) in BLOCK.lines().enumerate() {
+            match i {
+                BLOCK_DIVISOR => {
+                    writeln!(text, "div z {}", if block.pops { STACK_BASE } else { 1 })
+                }
+                BLOCK_CHECK => writeln!(text, "add x {}", block.check),
+                BLOCK_OFFSET => writeln!(text, "add y {}", block.offset),
+                _ => writeln!(text, "{}", line),
+            }
+            .unwrap();
+        }
+    }
+
+    parse_reader(text.as_bytes()).unwrap()
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day24;
+
+impl Solver for Day24 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut generate_seed = None;
+    let mut check = None;
+    let mut file = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--generate" => {
+                generate_seed = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<u64>().ok())
+                        .expect("Expected a seed after --generate."),
+                )
+            }
+            "--check" => check = Some(args.next().expect("Expected a model number after --check.")),
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    if let Some(seed) = generate_seed {
+        for instruction in generate(seed).program {
+            println!("{}", instruction);
+        }
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    let time_parse = now.elapsed();
+
+    if let Some(number) = check {
+        let digits: Vec<i64> = number
+            .bytes()
+            .map(|x| match x {
+                b'1'..=b'9' => (x - b'0') as i64,
+                _ => panic!("Expected a model number of digits from 1 to 9."),
+            })
+            .collect();
+
+        match run(&input.program, &digits) {
+            Ok(registers) => println!(
+                "w: {}, x: {}, y: {}, z: {} ({})",
+                registers[W],
+                registers[X],
+                registers[Y],
+                registers[Z],
+                if registers[Z] == 0 {
+                    "valid"
+                } else {
+                    "invalid"
+                }
+            ),
+            Err(error) => println!("{}.", error),
+        }
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let result1 = part1(&input);
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let result2 = part2(&input);
+    let time2 = now.elapsed();
+
+    println!("Parse: (time: {}us)", time_parse.as_micros());
+    println!(
+        "Solution 1: {} (time: {}us)",
+        Answer::from(result1),
+        time1.as_micros()
+    );
+    println!(
+        "Solution 2: {} (time: {}us)",
+        Answer::from(result2),
+        time2.as_micros()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use aoc_common::{input_path, Answer, Error, GridFormatter, Solver};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufRead, BufReader},
+    time::Instant,
+};
+
+/// The number of steps after which the sea cucumbers are assumed to keep moving forever. A herd that stops
+/// moving at all does so long before this.
+const MAX_STEPS: usize = 1_000_000;
+
+const EMPTY: u8 = b'.';
+const EAST: u8 = b'>';
+const SOUTH: u8 = b'v';
+
+/// The locations of all sea cucumbers. The sea floor wraps around on both axes.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SeaFloor {
+    width: usize,
+    height: usize,
+
+    /// The cells of the sea floor, row by row.
+    cells: Vec<u8>,
+}
+
+pub struct Input {
+    floor: SeaFloor,
+}
+
+/// Indicates that the sea cucumbers never stop moving.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NeverStops;
+
+impl Display for NeverStops {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The sea cucumbers keep moving after {} steps", MAX_STEPS)
+    }
+}
+
+impl SeaFloor {
+    /// Moves every sea cucumber of the provided herd that faces an empty cell, all at the same time. Returns
+    /// whether any of them moved.
+    fn move_herd(&mut self, herd: u8) -> bool {
+        let (dx, dy) = if herd == EAST { (1, 0) } else { (0, 1) };
+
+        let moves: Vec<(usize, usize)> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter_map(|(x, y)| {
+                let from = y * self.width + x;
+                let to = (y + dy) % self.height * self.width + (x + dx) % self.width;
+                (self.cells[from] == herd && self.cells[to] == EMPTY).then_some((from, to))
+            })
+            .collect();
+
+        for &(from, to) in moves.iter() {
+            self.cells[from] = EMPTY;
+            self.cells[to] = herd;
+        }
+        !moves.is_empty()
+    }
+
+    /// Moves the east-facing herd followed by the south-facing herd. Returns whether any sea cucumber moved.
+    pub fn step(&mut self) -> bool {
+        let east = self.move_herd(EAST);
+        let south = self.move_herd(SOUTH);
+        east || south
+    }
+}
+
+impl Display for SeaFloor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        GridFormatter::custom(self.width, self.height, |x, y| {
+            self.cells[y * self.width + x] as char
+        })
+        .fmt(f)
+    }
+}
+
+/// Parses the puzzle input from the provided reader.
+pub fn parse_reader(reader: impl BufRead) -> aoc_common::Result<Input> {
+    let mut width = 0;
+    let mut cells = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end().as_bytes();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(column) = line
+            .iter()
+            .position(|&c| !matches!(c, EMPTY | EAST | SOUTH))
+        {
+            return Err(Error::at_column(
+                index + 1,
+                column + 1,
+                format!(
+                    "Expected '.', '>' or 'v', but found '{}'.",
+                    line[column].escape_ascii()
+                ),
+            ));
+        }
+
+        if cells.is_empty() {
+            width = line.len();
+        } else if line.len() != width {
+            return Err(Error::at_line(
+                index + 1,
+                format!(
+                    "Expected a row of {} cells, but found {}.",
+                    width,
+                    line.len()
+                ),
+            ));
+        }
+        cells.extend_from_slice(line);
+    }
+
+    if cells.is_empty() {
+        return Err(Error::at_line(1, "Expected a map of the sea floor."));
+    }
+
+    Ok(Input {
+        floor: SeaFloor {
+            width,
+            height: cells.len() / width,
+            cells,
+        },
+    })
+}
+
+/// Parses the puzzle input from the provided file.
+pub fn parse_input(file: &str) -> aoc_common::Result<Input> {
+    parse_reader(BufReader::new(File::open(file)?))
+}
+
+/// Moves the sea cucumbers until they stop, and returns the first step in which none of them moved together
+/// with their final locations.
+pub fn settle(input: &Input) -> Result<(usize, SeaFloor), NeverStops> {
+    let mut floor = input.floor.clone();
+    for step in 1..=MAX_STEPS {
+        if !floor.step() {
+            return Ok((step, floor));
+        }
+    }
+    Err(NeverStops)
+}
+
+pub fn part1(input: &Input) -> Result<usize, NeverStops> {
+    settle(input).map(|(step, _)| step)
+}
+
+/// There is no second puzzle on the last day, the final star is awarded for all the others.
+pub fn part2(_input: &Input) -> String {
+    String::from("Remotely start the sleigh.")
+}
+
+/// The solution of this day, as it is registered with the runner.
+pub struct Day25;
+
+impl Solver for Day25 {
+    type Input = Input;
+
+    fn parse(file: &str) -> aoc_common::Result<Input> {
+        parse_input(file)
+    }
+
+    fn parse_str(input: &str) -> aoc_common::Result<Input> {
+        parse_reader(input.as_bytes())
+    }
+
+    fn part1(input: &Input) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Input) -> Answer {
+        part2(input).into()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let mut print = false;
+    let mut file = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--print" => print = true,
+            _ if !arg.starts_with("--") => file = Some(arg),
+            _ => panic!("Unknown argument {}.", arg),
+        }
+    }
+
+    let now = Instant::now();
+    let input = parse_input(&file.unwrap_or_else(|| input_path("input.txt")))?;
+    let time_parse = now.elapsed();
+
+    if print {
+        match settle(&input) {
+            Ok((_, floor)) => print!("{}", floor),
+            Err(error) => println!("{}.", error),
+        }
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let result1 = part1(&input);
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let result2 = part2(&input);
+    let time2 = now.elapsed();
+
+    println!("Parse: (time: {}us)", time_parse.as_micros());
+    println!(
+        "Solution 1: {} (time: {}us)",
+        Answer::from(result1),
+        time1.as_micros()
+    );
+    println!("Solution 2: {} (time: {}us)", result2, time2.as_micros());
+
+    Ok(())
+}
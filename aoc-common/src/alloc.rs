@@ -0,0 +1,100 @@
+//! An instrumented global allocator that counts allocations and tracks the peak heap usage.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The number of allocations (including reallocations) made since the start of the process.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of bytes that are currently allocated.
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+/// The largest number of bytes that were allocated at once since the last call to [`measure_allocations`].
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Forwards all requests to the system allocator, while keeping track of the number of allocations and the
+/// number of allocated bytes. Nothing is counted unless it is installed by the binary:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: aoc_common::CountingAllocator = aoc_common::CountingAllocator;
+/// ```
+pub struct CountingAllocator;
+
+/// The allocations made while running a single phase of a solver.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// The number of allocations and reallocations.
+    pub allocations: usize,
+
+    /// The largest number of bytes that were allocated at once on top of what was allocated before the phase.
+    pub peak_bytes: usize,
+}
+
+impl CountingAllocator {
+    fn grow(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            if new_size > layout.size() {
+                Self::grow(new_size - layout.size());
+            } else {
+                Self::shrink(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Runs the provided function and counts the allocations it makes. The counters are shared by all threads, so
+/// allocations made concurrently by other threads are included. All statistics are zero if the
+/// [`CountingAllocator`] is not installed.
+pub fn measure_allocations<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+
+    let result = f();
+
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(baseline),
+    };
+    (result, stats)
+}
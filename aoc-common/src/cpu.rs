@@ -0,0 +1,101 @@
+//! Runtime selection of the instruction set that hot loops are compiled for.
+//!
+//! Binaries are built for the baseline of their target, such that timings do not depend on the machine they were
+//! built on. Days with loops that benefit from wider vectors compile them a second time with the features of
+//! [`Isa::Avx2`] enabled, and pick one of both at runtime through [`isa`]:
+//!
+//! ```ignore
+//! match aoc_common::isa() {
+//!     #[cfg(target_arch = "x86_64")]
+//!     Isa::Avx2 => unsafe { kernel_avx2(input) },
+//!     _ => kernel(input),
+//! }
+//! ```
+//!
+//! Harnesses can limit the selection with [`set_isa`], such that timings can be compared between machines that
+//! support different instruction sets.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The instruction sets that hot loops can be compiled for, from least to most capable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Isa {
+    /// The baseline of the target.
+    Scalar,
+
+    /// AVX2 with POPCNT, available on x86-64 processors since 2013.
+    Avx2,
+}
+
+/// Marks that the instruction set has not been determined yet.
+const UNDETERMINED: u8 = u8::MAX;
+
+/// The instruction set that was selected, or [`UNDETERMINED`].
+static SELECTED: AtomicU8 = AtomicU8::new(UNDETERMINED);
+
+impl Isa {
+    /// All instruction sets, from least to most capable.
+    pub const ALL: [Isa; 2] = [Isa::Scalar, Isa::Avx2];
+
+    /// Gets the name of the instruction set, as it is parsed by [`FromStr`](std::str::FromStr).
+    pub fn name(self) -> &'static str {
+        match self {
+            Isa::Scalar => "scalar",
+            Isa::Avx2 => "avx2",
+        }
+    }
+
+    /// Detects the most capable instruction set the processor supports.
+    pub fn detect() -> Isa {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("popcnt") {
+            return Isa::Avx2;
+        }
+
+        Isa::Scalar
+    }
+}
+
+impl std::fmt::Display for Isa {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for Isa {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Isa::ALL
+            .into_iter()
+            .find(|x| x.name() == s)
+            .ok_or_else(|| format!("Unknown instruction set {} (expected scalar or avx2).", s))
+    }
+}
+
+/// Gets the instruction set that hot loops run with: the most capable one the processor supports, unless it was
+/// limited with [`set_isa`].
+pub fn isa() -> Isa {
+    match SELECTED.load(Ordering::Relaxed) {
+        UNDETERMINED => {
+            let isa = Isa::detect();
+            SELECTED.store(isa as u8, Ordering::Relaxed);
+            isa
+        }
+        x => Isa::ALL[x as usize],
+    }
+}
+
+/// Runs hot loops with the provided instruction set. Fails if the processor does not support it.
+pub fn set_isa(isa: Isa) -> Result<(), String> {
+    let supported = Isa::detect();
+    if isa > supported {
+        return Err(format!(
+            "The processor does not support {} (at most {}).",
+            isa, supported
+        ));
+    }
+
+    SELECTED.store(isa as u8, Ordering::Relaxed);
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! The error type of the puzzle input parsers, which locates malformed input by its line and column.
+
+use std::{fmt::Display, io::BufRead};
+
+/// Describes why a puzzle input could not be parsed.
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be read.
+    Io(std::io::Error),
+
+    /// The input is malformed.
+    Parse {
+        /// The line of the input that is malformed, starting at 1.
+        line: usize,
+
+        /// The column within the line at which the input is malformed, starting at 1, or [`None`] if the
+        /// line is malformed as a whole.
+        column: Option<usize>,
+
+        /// A description of the problem.
+        message: String,
+    },
+}
+
+/// The result of parsing a puzzle input.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Creates an error describing a malformed line.
+    pub fn at_line(line: usize, message: impl Into<String>) -> Self {
+        Self::Parse {
+            line,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error describing malformed input at a specific column of a line.
+    pub fn at_column(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self::Parse {
+            line,
+            column: Some(column),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(error) => error.fmt(f),
+            Error::Parse {
+                line,
+                column: Some(column),
+                message,
+            } => write!(f, "Line {}, column {}: {}", line, column, message),
+            Error::Parse {
+                line,
+                column: None,
+                message,
+            } => write!(f, "Line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Io(error) => error,
+            Error::Parse { .. } => std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+/// Parses every line of the provided reader with the provided function, and annotates the first failure
+/// with its line number.
+pub fn parse_lines<T>(
+    reader: impl BufRead,
+    mut parse: impl FnMut(&str) -> std::result::Result<T, String>,
+) -> Result<Vec<T>> {
+    reader
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse(line?.trim_end()).map_err(|x| Error::at_line(index + 1, x)))
+        .collect()
+}
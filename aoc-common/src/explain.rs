@@ -0,0 +1,116 @@
+//! Human-readable descriptions of the intermediate steps of a solver, such as the state of a puzzle right before
+//! the answer is read from it.
+
+use std::{
+    fmt::Arguments,
+    io::{self, Write},
+};
+
+/// Receives the intermediate steps of a solver.
+///
+/// Steps are passed as [`Arguments`], so nothing is formatted unless the sink writes it. Use [`explain!`] to
+/// record a step, which also skips evaluating the arguments for sinks that are not enabled.
+///
+/// [`explain!`]: crate::explain!
+pub trait Explain {
+    /// Indicates whether the sink records steps at all.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Records a single step. A step may span multiple lines, e.g. if it draws a grid.
+    fn step(&mut self, args: Arguments) -> io::Result<()>;
+}
+
+/// Discards every step without formatting it.
+pub struct Silent;
+
+impl Explain for Silent {
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    fn step(&mut self, _args: Arguments) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes every step to a writer, with every line of the step prefixed by a fixed indentation.
+pub struct Narrator<W> {
+    writer: W,
+    indent: &'static str,
+}
+
+impl<W: Write> Narrator<W> {
+    /// Creates a new narrator that writes steps as-is.
+    pub fn new(writer: W) -> Self {
+        Self::indented(writer, "")
+    }
+
+    /// Creates a new narrator that prefixes every line with the provided indentation.
+    pub fn indented(writer: W, indent: &'static str) -> Self {
+        Self { writer, indent }
+    }
+}
+
+/// Inserts the indentation at the start of every line while a step is being formatted.
+struct IndentWriter<'a, W> {
+    writer: &'a mut W,
+    indent: &'static str,
+    line_start: bool,
+    error: io::Result<()>,
+}
+
+impl<W: Write> std::fmt::Write for IndentWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for line in s.split_inclusive('\n') {
+            if self.line_start {
+                self.error = self.writer.write_all(self.indent.as_bytes());
+            }
+            if self.error.is_ok() {
+                self.error = self.writer.write_all(line.as_bytes());
+            }
+            if self.error.is_err() {
+                return Err(std::fmt::Error);
+            }
+            self.line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Explain for Narrator<W> {
+    fn step(&mut self, args: Arguments) -> io::Result<()> {
+        let mut writer = IndentWriter {
+            writer: &mut self.writer,
+            indent: self.indent,
+            line_start: true,
+            error: Ok(()),
+        };
+
+        if std::fmt::Write::write_fmt(&mut writer, args).is_err() {
+            // Formatting only fails on write errors, unless a `Display` implementation is broken.
+            return writer
+                .error
+                .and(Err(io::Error::other("Formatting a step failed.")));
+        }
+        if !writer.line_start {
+            self.writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Records a step in an [`Explain`] sink, with the same arguments as [`format!`]. The arguments are only evaluated
+/// if the sink is enabled. Evaluates to the [`io::Result`] of writing the step.
+#[macro_export]
+macro_rules! explain {
+    ($sink:expr, $($arg:tt)*) => {{
+        let sink: &mut dyn $crate::Explain = $sink;
+        if sink.is_enabled() {
+            sink.step(format_args!($($arg)*))
+        } else {
+            Ok(())
+        }
+    }};
+}
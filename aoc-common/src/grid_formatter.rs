@@ -0,0 +1,130 @@
+//! A configurable text renderer for 2 dimensional grids, shared by the `Display` implementations of all days.
+
+use std::fmt::{Display, Formatter, Result};
+
+/// Renders a grid of cells as text, one row per line.
+///
+/// All cells are padded to the width of the widest cell, and separated by a space if that is wider than one
+/// character. Axis labels can be added with [`GridFormatter::with_axes`], or by formatting with the alternate
+/// flag (`{:#}`).
+pub struct GridFormatter<'a> {
+    /// The number of columns in the grid.
+    width: usize,
+
+    /// The number of rows in the grid.
+    height: usize,
+
+    /// Turns the cell at the provided column and row into text.
+    render: Box<dyn Fn(usize, usize) -> String + 'a>,
+
+    /// Indicates whether the column and row indices are written along the top and left of the grid.
+    axes: bool,
+}
+
+impl<'a> GridFormatter<'a> {
+    /// Creates a new formatter for a grid of the provided size, that writes the value of every cell as-is.
+    pub fn values<T: Display>(
+        width: usize,
+        height: usize,
+        cell: impl Fn(usize, usize) -> T + 'a,
+    ) -> Self {
+        Self::with_renderer(width, height, move |x, y| cell(x, y).to_string())
+    }
+
+    /// Creates a new formatter for a grid of the provided size, that writes the value of every cell, except for
+    /// cells with the default value (zero). These are drawn as `.`, such that only the interesting cells stand
+    /// out.
+    pub fn sparse<T>(width: usize, height: usize, cell: impl Fn(usize, usize) -> T + 'a) -> Self
+    where
+        T: Display + Default + PartialEq,
+    {
+        Self::with_renderer(width, height, move |x, y| {
+            let value = cell(x, y);
+            if value == T::default() {
+                String::from(".")
+            } else {
+                value.to_string()
+            }
+        })
+    }
+
+    /// Creates a new formatter for a grid of the provided size, that draws every cell as the character produced
+    /// by the provided function.
+    pub fn custom(width: usize, height: usize, cell: impl Fn(usize, usize) -> char + 'a) -> Self {
+        Self::with_renderer(width, height, move |x, y| cell(x, y).to_string())
+    }
+
+    fn with_renderer(
+        width: usize,
+        height: usize,
+        render: impl Fn(usize, usize) -> String + 'a,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            render: Box::new(render),
+            axes: false,
+        }
+    }
+
+    /// Writes the column and row indices along the top and left of the grid.
+    pub fn with_axes(mut self) -> Self {
+        self.axes = true;
+        self
+    }
+}
+
+impl Display for GridFormatter<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let cells: Vec<String> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| (self.render)(x, y))
+            .collect();
+
+        let mut cell_width = cells.iter().map(|x| x.chars().count()).max().unwrap_or(1);
+        let axes = self.axes || f.alternate();
+        if axes {
+            // Columns need to be wide enough for their labels, unless we only write the last digit.
+            if cell_width > 1 {
+                cell_width = cell_width.max(self.width.saturating_sub(1).to_string().len());
+            }
+        }
+
+        let separator = if cell_width > 1 { " " } else { "" };
+        let label_width = self.height.saturating_sub(1).to_string().len();
+
+        if axes {
+            write!(f, "{:>1$} ", "", label_width)?;
+            for x in 0..self.width {
+                if x > 0 {
+                    write!(f, "{}", separator)?;
+                }
+
+                if cell_width == 1 {
+                    write!(f, "{}", x % 10)?;
+                } else {
+                    write!(f, "{:>1$}", x, cell_width)?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        for (y, row) in cells.chunks(self.width.max(1)).enumerate() {
+            if axes {
+                write!(f, "{:>1$} ", y, label_width)?;
+            }
+
+            for (x, cell) in row.iter().enumerate() {
+                if x > 0 {
+                    write!(f, "{}", separator)?;
+                }
+
+                write!(f, "{:>1$}", cell, cell_width)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
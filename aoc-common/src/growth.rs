@@ -0,0 +1,85 @@
+//! Growth statistics of simulations that produce a population or length per step.
+
+use std::io::Write;
+
+/// Describes how fast the values in a trace of a simulation grow.
+#[derive(Debug, Clone)]
+pub struct GrowthStats {
+    /// The values of the trace, starting with the initial state.
+    pub values: Vec<usize>,
+
+    /// For every step, the factor by which the value grew compared to the previous step, or [`None`] if the
+    /// previous value was zero.
+    pub factors: Vec<Option<f64>>,
+
+    /// The geometric mean of the growth factors over the whole trace, or [`None`] if it started at zero or
+    /// contains no steps.
+    pub mean_factor: Option<f64>,
+
+    /// The estimated number of steps it takes for the value to double, derived from the mean growth factor.
+    /// This is [`None`] if the value does not grow.
+    pub doubling_time: Option<f64>,
+}
+
+impl GrowthStats {
+    /// Computes the growth statistics of the provided trace of values, one per step.
+    pub fn from_trace(values: &[usize]) -> Self {
+        let factors = values
+            .windows(2)
+            .map(|w| (w[0] != 0).then(|| w[1] as f64 / w[0] as f64))
+            .collect();
+
+        let mean_factor = match (values.first(), values.last()) {
+            (Some(&first), Some(&last)) if first != 0 && values.len() > 1 => {
+                Some((last as f64 / first as f64).powf(1.0 / (values.len() - 1) as f64))
+            }
+            _ => None,
+        };
+
+        let doubling_time = mean_factor
+            .filter(|&x| x > 1.0)
+            .map(|x| std::f64::consts::LN_2 / x.ln());
+
+        Self {
+            values: values.to_vec(),
+            factors,
+            mean_factor,
+            doubling_time,
+        }
+    }
+
+    /// Writes the statistics as a single JSON object.
+    pub fn write_json(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        fn number(x: Option<f64>) -> String {
+            match x {
+                Some(x) if x.is_finite() => x.to_string(),
+                _ => "null".to_string(),
+            }
+        }
+
+        let steps: Vec<String> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(step, value)| {
+                let factor = step.checked_sub(1).and_then(|i| self.factors[i]);
+                format!(
+                    "{{\"step\":{},\"value\":{},\"factor\":{}}}",
+                    step,
+                    value,
+                    number(factor)
+                )
+            })
+            .collect();
+
+        writeln!(
+            writer,
+            "{{\"steps\":[{}],\"mean_factor\":{},\"doubling_time\":{}}}",
+            steps.join(","),
+            number(self.mean_factor),
+            number(self.doubling_time)
+        )?;
+
+        writer.flush()
+    }
+}
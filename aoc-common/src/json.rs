@@ -0,0 +1,177 @@
+//! Machine readable reports of the answers and resource usage of runs, written as JSON.
+//!
+//! Numeric answers are written as strings by default, as several answers (e.g. those of days 6, 14 and 16)
+//! exceed 2^53, beyond which JavaScript and many other JSON consumers silently round numbers.
+
+use crate::{AllocStats, Answer, Run};
+use std::{io::Write, time::Duration};
+
+/// The resources a single phase of a run (parsing or solving a part) used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceReport {
+    /// The time the phase took.
+    pub time: Duration,
+
+    /// The allocations made during the phase, or [`None`] if allocations were not counted.
+    pub allocations: Option<AllocStats>,
+}
+
+/// The run of a single day, as it appears in a [`JsonReport`].
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    /// The year of the puzzle.
+    pub year: u16,
+
+    /// The day of the puzzle.
+    pub day: u8,
+
+    /// The answers and timings of the run.
+    pub run: Run,
+
+    /// A JSON object with further statistics of the input that the day computed, written as is in the
+    /// `analysis` field. Only the binaries of individual days fill this in.
+    pub analysis: Option<String>,
+}
+
+/// A report of the runs of any number of days.
+#[derive(Debug, Clone, Default)]
+pub struct JsonReport {
+    /// The runs of all days, in the order they are written.
+    pub days: Vec<DayReport>,
+
+    /// The workspace-wide seed of the randomized components of the run (see [`crate::seed`]), if it is
+    /// recorded.
+    pub seed: Option<u64>,
+
+    /// Whether numeric answers are written as JSON numbers rather than strings. Numbers are exact in the
+    /// document, but consumers may lose precision when reading answers above 2^53.
+    pub numbers: bool,
+
+    /// Whether the allocations of the runs were counted, i.e. whether a [`crate::CountingAllocator`] is
+    /// installed. Otherwise, the allocations in the resource reports are `null`.
+    pub allocations: bool,
+}
+
+/// Writes a string as a JSON string literal, escaping quotes, backslashes and control characters.
+pub fn write_json_string(writer: &mut impl Write, s: &str) -> std::io::Result<()> {
+    write!(writer, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(writer, "\\\"")?,
+            '\\' => write!(writer, "\\\\")?,
+            '\n' => write!(writer, "\\n")?,
+            '\r' => write!(writer, "\\r")?,
+            '\t' => write!(writer, "\\t")?,
+            c if (c as u32) < 0x20 => write!(writer, "\\u{:04x}", c as u32)?,
+            c => write!(writer, "{}", c)?,
+        }
+    }
+    write!(writer, "\"")
+}
+
+impl Answer {
+    /// Writes the answer as a JSON value. Textual answers are always strings, numeric answers are only
+    /// written as numbers if `numbers` is set.
+    pub fn write_json(&self, writer: &mut impl Write, numbers: bool) -> std::io::Result<()> {
+        match self {
+            Answer::Number(x) if numbers => write!(writer, "{}", x),
+            Answer::Number(x) => write!(writer, "\"{}\"", x),
+            Answer::Text(x) => write_json_string(writer, x),
+        }
+    }
+}
+
+impl ResourceReport {
+    /// Writes the resources as a single JSON object. Allocation counts are `null` if they were not counted.
+    pub fn write_json(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        write!(writer, "{{\"time_ns\":{}", self.time.as_nanos())?;
+        match self.allocations {
+            Some(stats) => write!(
+                writer,
+                ",\"allocations\":{},\"peak_bytes\":{}",
+                stats.allocations, stats.peak_bytes
+            )?,
+            None => write!(writer, ",\"allocations\":null,\"peak_bytes\":null")?,
+        }
+        write!(writer, "}}")
+    }
+}
+
+impl DayReport {
+    /// Gets the resources used by parsing the input and by solving the first and second part.
+    pub fn resources(&self, allocations: bool) -> [ResourceReport; 3] {
+        let run = &self.run;
+        let report = |time, stats| ResourceReport {
+            time,
+            allocations: allocations.then_some(stats),
+        };
+
+        [
+            report(run.time_parse, run.alloc_parse),
+            report(run.time1, run.alloc1),
+            report(run.time2, run.alloc2),
+        ]
+    }
+
+    /// Writes the run of the day as a single JSON object, with the resources of every phase next to its
+    /// answer.
+    fn write_json(
+        &self,
+        writer: &mut impl Write,
+        numbers: bool,
+        allocations: bool,
+    ) -> std::io::Result<()> {
+        let [parse, part1, part2] = self.resources(allocations);
+
+        write!(
+            writer,
+            "{{\"year\":{},\"day\":{},\"parse\":{{\"resources\":",
+            self.year, self.day
+        )?;
+        parse.write_json(writer)?;
+        write!(writer, "}}")?;
+
+        for (name, answer, resources) in [
+            ("part1", &self.run.answer1, part1),
+            ("part2", &self.run.answer2, part2),
+        ] {
+            write!(writer, ",\"{}\":{{\"answer\":", name)?;
+            answer.write_json(writer, numbers)?;
+            write!(writer, ",\"resources\":")?;
+            resources.write_json(writer)?;
+            write!(writer, "}}")?;
+        }
+
+        if let Some(analysis) = &self.analysis {
+            write!(writer, ",\"analysis\":{}", analysis)?;
+        }
+
+        write!(writer, "}}")
+    }
+}
+
+impl JsonReport {
+    /// Writes the report as a single JSON object, with the runs of all days in its `results` array. The seed
+    /// is written in the `seed` field if it is recorded. Like the numeric answers, it is a string unless numbers
+    /// are requested, as seeds span all 64 bits.
+    pub fn write_json(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        write!(writer, "{{")?;
+        if let Some(seed) = self.seed {
+            if self.numbers {
+                write!(writer, "\"seed\":{},", seed)?;
+            } else {
+                write!(writer, "\"seed\":\"{}\",", seed)?;
+            }
+        }
+        write!(writer, "\"results\":[")?;
+        for (index, day) in self.days.iter().enumerate() {
+            if index > 0 {
+                write!(writer, ",")?;
+            }
+            day.write_json(writer, self.numbers, self.allocations)?;
+        }
+        writeln!(writer, "]}}")?;
+
+        writer.flush()
+    }
+}
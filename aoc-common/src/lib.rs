@@ -0,0 +1,228 @@
+//! Types and helpers that are shared between the solutions of all days.
+
+mod alloc;
+mod cpu;
+mod error;
+mod explain;
+mod grid_formatter;
+mod growth;
+mod json;
+mod linear_sim;
+pub mod pathfinding;
+mod profile;
+pub mod pq;
+mod rng;
+mod scanner;
+mod scratch;
+mod select;
+mod solver;
+mod strategy;
+mod trace;
+
+pub use alloc::*;
+pub use cpu::*;
+pub use error::*;
+pub use explain::*;
+pub use grid_formatter::*;
+pub use growth::*;
+pub use json::*;
+pub use linear_sim::*;
+pub use profile::set_profile_prefix;
+pub use rng::*;
+pub use scanner::*;
+pub use scratch::*;
+pub use select::*;
+pub use solver::*;
+pub use strategy::*;
+pub use trace::*;
+pub use tracing;
+
+use std::{
+    fmt::Display,
+    ops::{Add, Div, Mul, Rem, Sub},
+};
+
+/// A 2 dimensional integer vector. Used for positions and directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Vector2(pub isize, pub isize);
+
+// Some cool operator overloading in rust, for extra internet puntos :^).
+
+impl Add for Vector2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Vector2(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Sub for Vector2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vector2(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl Mul<isize> for Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Vector2(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+impl Div<isize> for Vector2 {
+    type Output = Vector2;
+
+    fn div(self, rhs: isize) -> Self::Output {
+        Vector2(self.0 / rhs, self.1 / rhs)
+    }
+}
+
+impl Rem<isize> for Vector2 {
+    type Output = Vector2;
+
+    fn rem(self, rhs: isize) -> Self::Output {
+        Vector2(self.0 % rhs, self.1 % rhs)
+    }
+}
+
+/// A 2 dimensional position that cannot be negative, such as a cell in a map whose origin is its top left
+/// corner. Points are ordered by their X component first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point(pub usize, pub usize);
+
+impl Point {
+    /// Reads a point in the format "x,y" from the start of the provided bytes, and advances the slice past
+    /// it. Returns [`None`] if the bytes do not start with a point.
+    pub fn read(s: &mut &[u8]) -> Option<Point> {
+        let x = read_uint(s)? as usize;
+        read_literal(s, b",")?;
+        let y = read_uint(s)? as usize;
+        Some(Point(x, y))
+    }
+}
+
+impl std::str::FromStr for Point {
+    type Err = String;
+
+    /// Parses a point in the format "x,y".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| format!("Expected a coordinate, but found {}.", s))?;
+
+        let x = x
+            .parse::<usize>()
+            .map_err(|_| format!("Expected an X component, but found {}.", x))?;
+        let y = y
+            .parse::<usize>()
+            .map_err(|_| format!("Expected an Y component, but found {}.", y))?;
+
+        Ok(Point(x, y))
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(x: {}, y: {})", self.0, self.1)
+    }
+}
+
+/// Represents a 2 dimensional square grid.
+#[derive(Clone)]
+pub struct Grid<T>
+where
+    T: Clone + Copy,
+{
+    /// The raw data in the grid.
+    pub grid: Vec<T>,
+
+    /// The size of one of the dimensions.
+    pub size: isize,
+}
+
+impl<T> Grid<T>
+where
+    T: Clone + Copy,
+{
+    /// Creates a new square grid with the provided initializer value.
+    pub fn new(size: isize, init: T) -> Self {
+        Self {
+            grid: vec![init; (size * size) as usize],
+            size,
+        }
+    }
+
+    /// Gets an element in the grid by its position.
+    pub fn get(&self, location: Vector2) -> T {
+        self.grid[(location.1 * self.size + location.0) as usize]
+    }
+
+    /// Sets an element in the grid by its position.
+    pub fn set(&mut self, location: Vector2, value: T) {
+        self.grid[(location.1 * self.size + location.0) as usize] = value;
+    }
+
+    /// Determines whether the provided position lies within the bounds of the grid.
+    pub fn contains(&self, location: Vector2) -> bool {
+        location.0 >= 0 && location.0 < self.size && location.1 >= 0 && location.1 < self.size
+    }
+
+    /// Creates a new grid of the same size, where every element is moved to the position given by `f`.
+    fn remap(&self, f: impl Fn(Vector2) -> Vector2) -> Self {
+        let mut result = self.clone();
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let location = Vector2(x, y);
+                result.set(f(location), self.get(location));
+            }
+        }
+        result
+    }
+
+    /// Creates a copy of the grid that is mirrored along its main diagonal.
+    pub fn transpose(&self) -> Self {
+        self.remap(|p| Vector2(p.1, p.0))
+    }
+
+    /// Creates a copy of the grid that is rotated 90 degrees clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        self.remap(|p| Vector2(self.size - 1 - p.1, p.0))
+    }
+
+    /// Creates a copy of the grid that is rotated 90 degrees counter-clockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        self.remap(|p| Vector2(p.1, self.size - 1 - p.0))
+    }
+
+    /// Creates a copy of the grid where the columns are in reverse order (mirrored horizontally).
+    pub fn flip_x(&self) -> Self {
+        self.remap(|p| Vector2(self.size - 1 - p.0, p.1))
+    }
+
+    /// Creates a copy of the grid where the rows are in reverse order (mirrored vertically).
+    pub fn flip_y(&self) -> Self {
+        self.remap(|p| Vector2(p.0, self.size - 1 - p.1))
+    }
+}
+
+impl Display for Grid<u8> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let size = self.size as usize;
+        GridFormatter::values(size, size, |x, y| self.get(Vector2(x as isize, y as isize))).fmt(f)
+    }
+}
+
+/// Resolves a file in the crate directory when run through cargo, such that the program can be started
+/// from any working directory. Otherwise, the path is resolved relative to the working directory.
+pub fn input_path(file: &str) -> String {
+    match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => std::path::Path::new(&dir)
+            .join(file)
+            .to_string_lossy()
+            .into_owned(),
+        None => file.to_string(),
+    }
+}
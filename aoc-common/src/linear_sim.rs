@@ -0,0 +1,239 @@
+//! Simulations whose state is a vector of counts, and where every step maps the counts linearly onto the counts
+//! of the next step, such as the lanternfish of day 6 and the polymer pairs of day 14.
+//!
+//! All arithmetic is wrapping, i.e. performed modulo 2^64, but simulations and matrices remember whether any
+//! count ever exceeded 64 bits.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A square matrix that only stores the entries it was built from (typically the non-zero ones), row by row.
+#[derive(Debug, Clone)]
+pub struct SparseMatrix {
+    /// The columns and values of the stored entries, ordered by row and then by column.
+    entries: Vec<(usize, u64)>,
+
+    /// For every row, the index of its first entry in `entries`, followed by the total number of entries.
+    row_starts: Vec<usize>,
+
+    /// Whether any entry no longer fits in 64 bits, in which case the entries are only correct modulo 2^64.
+    overflowed: bool,
+}
+
+impl SparseMatrix {
+    /// Builds a matrix of the provided size from `(row, column, value)` entries. Entries at the same position
+    /// are added up. Entries that are zero are stored all the same, so they are best left out.
+    pub fn from_entries(
+        size: usize,
+        entries: impl IntoIterator<Item = (usize, usize, u64)>,
+    ) -> Self {
+        let entries: Vec<(usize, usize, u64)> = entries.into_iter().collect();
+
+        // Bucket the entries by row, which is a lot cheaper than sorting all of them.
+        let mut row_starts = vec![0; size + 1];
+        for &(row, _, _) in entries.iter() {
+            row_starts[row + 1] += 1;
+        }
+        for row in 0..size {
+            row_starts[row + 1] += row_starts[row];
+        }
+
+        let mut next = row_starts.clone();
+        let mut bucketed = vec![(0, 0); entries.len()];
+        for (row, column, value) in entries {
+            bucketed[next[row]] = (column, value);
+            next[row] += 1;
+        }
+
+        // Merge the entries at the same position within every row, moving the rows to the front as we go.
+        let mut overflowed = false;
+        let mut length = 0;
+        for row in 0..size {
+            let (start, end) = (row_starts[row], row_starts[row + 1]);
+            bucketed[start..end].sort_unstable_by_key(|&(column, _)| column);
+
+            row_starts[row] = length;
+            for index in start..end {
+                let (column, value) = bucketed[index];
+                if length > row_starts[row] && bucketed[length - 1].0 == column {
+                    overflowed |= multiply_add(&mut bucketed[length - 1].1, value, 1);
+                } else {
+                    bucketed[length] = (column, value);
+                    length += 1;
+                }
+            }
+        }
+        row_starts[size] = length;
+        bucketed.truncate(length);
+
+        Self {
+            entries: bucketed,
+            row_starts,
+            overflowed,
+        }
+    }
+
+    /// Gets the number of rows (and columns) of the matrix.
+    pub fn size(&self) -> usize {
+        self.row_starts.len() - 1
+    }
+
+    /// Determines whether any entry no longer fits in 64 bits.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// Gets the columns and values of the stored entries in the provided row.
+    fn row(&self, row: usize) -> &[(usize, u64)] {
+        &self.entries[self.row_starts[row]..self.row_starts[row + 1]]
+    }
+
+    /// Multiplies this matrix with another matrix of the same size. Every row of the result is computed
+    /// on its own, so rows are computed in parallel when the `parallel` feature is enabled.
+    pub fn multiply(&self, other: &Self) -> Self {
+        let size = self.size();
+        let multiply_row = |dense: &mut Vec<u64>, row: usize| {
+            dense.iter_mut().for_each(|x| *x = 0);
+            let mut overflowed = false;
+            for &(k, a) in self.row(row) {
+                for &(j, b) in other.row(k) {
+                    overflowed |= multiply_add(&mut dense[j], a, b);
+                }
+            }
+            (compress_row(dense), overflowed)
+        };
+
+        #[cfg(feature = "parallel")]
+        let (rows, overflows): (Vec<_>, Vec<_>) = (0..size)
+            .into_par_iter()
+            .map_init(|| vec![0u64; size], multiply_row)
+            .unzip();
+
+        #[cfg(not(feature = "parallel"))]
+        let (rows, overflows): (Vec<_>, Vec<_>) = {
+            let mut dense = vec![0u64; size];
+            (0..size).map(|row| multiply_row(&mut dense, row)).unzip()
+        };
+
+        let mut row_starts = Vec::with_capacity(size + 1);
+        row_starts.push(0);
+        for row in rows.iter() {
+            row_starts.push(row_starts[row_starts.len() - 1] + row.len());
+        }
+
+        Self {
+            entries: rows.concat(),
+            row_starts,
+            overflowed: self.overflowed || other.overflowed || overflows.contains(&true),
+        }
+    }
+
+    /// Multiplies this matrix with a column vector, and writes the product into `result`. Returns whether any
+    /// entry of the matrix or the product no longer fits in 64 bits.
+    pub fn apply(&self, vector: &[u64], result: &mut [u64]) -> bool {
+        let mut overflowed = self.overflowed;
+        for (target, bounds) in result.iter_mut().zip(self.row_starts.windows(2)) {
+            let mut sum = 0;
+            for &(j, a) in &self.entries[bounds[0]..bounds[1]] {
+                overflowed |= multiply_add(&mut sum, a, vector[j]);
+            }
+            *target = sum;
+        }
+        overflowed
+    }
+}
+
+/// Adds `a * b` to `target` modulo 2^64, and returns whether the exact result no longer fits in 64 bits.
+fn multiply_add(target: &mut u64, a: u64, b: u64) -> bool {
+    let (product, overflow1) = a.overflowing_mul(b);
+    let (sum, overflow2) = target.overflowing_add(product);
+    *target = sum;
+    overflow1 || overflow2
+}
+
+/// Turns a dense row into a list of the columns and values of its non-zero entries.
+fn compress_row(row: &[u64]) -> Vec<(usize, u64)> {
+    row.iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, x)| x != 0)
+        .collect()
+}
+
+/// A vector of counts together with the matrix that maps the counts of one step onto the counts of the next.
+#[derive(Debug, Clone)]
+pub struct LinearSim {
+    /// The counts after the steps simulated so far.
+    state: Vec<u64>,
+
+    /// The matrix that maps the counts of one step onto those of the next.
+    transition: SparseMatrix,
+
+    /// Scratch space for the counts of the next step.
+    next: Vec<u64>,
+
+    /// Whether any count no longer fits in 64 bits.
+    overflowed: bool,
+}
+
+impl LinearSim {
+    /// Creates a simulation that starts at the provided counts. The transition matrix must have a row and a
+    /// column for every count.
+    pub fn new(state: Vec<u64>, transition: SparseMatrix) -> Self {
+        assert_eq!(
+            state.len(),
+            transition.size(),
+            "Expected a transition matrix of the same size as the state."
+        );
+
+        Self {
+            next: vec![0; state.len()],
+            state,
+            transition,
+            overflowed: false,
+        }
+    }
+
+    /// Gets the counts after the steps simulated so far. If the simulation [overflowed](Self::overflowed),
+    /// they are only correct modulo 2^64.
+    pub fn state(&self) -> &[u64] {
+        &self.state
+    }
+
+    /// Determines whether any count (or any entry of a power of the transition matrix) no longer fits in 64
+    /// bits.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// Simulates `n` steps by applying the transition matrix once for every step.
+    pub fn step(&mut self, n: usize) {
+        for _ in 0..n {
+            self.overflowed |= self.transition.apply(&self.state, &mut self.next);
+            std::mem::swap(&mut self.state, &mut self.next);
+        }
+    }
+
+    /// Simulates `n` steps by raising the transition matrix to the power `n` by repeated squaring. This takes
+    /// `log2(n)` matrix multiplications, which pays off over [`step`](Self::step) for large `n` only.
+    ///
+    /// Entries of the powers of the matrix may overflow slightly before the counts themselves do, in which
+    /// case the simulation is reported as overflowed a few steps early.
+    pub fn step_pow(&mut self, n: usize) {
+        // Powers of the same matrix commute, so we can apply the power of every set bit in the number of
+        // steps as soon as it is available.
+        let mut power = self.transition.clone();
+        let mut remaining = n;
+        while remaining > 0 {
+            if remaining & 1 != 0 {
+                self.overflowed |= power.apply(&self.state, &mut self.next);
+                std::mem::swap(&mut self.state, &mut self.next);
+            }
+
+            remaining >>= 1;
+            if remaining > 0 {
+                power = power.multiply(&power);
+            }
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Search algorithms that are written once against [`SearchProblem`], such that days with different state spaces
+//! share them, along with their instrumentation.
+//!
+//! A problem only describes its state space: where to start, which states are goals, and which states follow
+//! from a state. The algorithms decide the order in which states are expanded, and record what they do in a
+//! [`SearchStats`]:
+//!
+//! - [`count_paths`] enumerates the paths to the goals depth-first, which requires a state space without cycles.
+//! - [`shortest_path`] and [`shortest_path_from`] run Dijkstra's algorithm over any [`PriorityQueue`].
+
+use crate::pq::PriorityQueue;
+use std::io::Write;
+
+/// The width of a frontier plot in pixels.
+const PLOT_WIDTH: usize = 800;
+
+/// The height of a frontier plot in pixels, excluding the caption.
+const PLOT_HEIGHT: f64 = 300.0;
+
+/// The height of the caption below a frontier plot in pixels.
+const CAPTION_HEIGHT: f64 = 24.0;
+
+/// A state space to search through.
+///
+/// Problems are borrowed mutably while they are searched, such that they can keep bookkeeping of their own,
+/// like a tree of the paths that were explored so far.
+pub trait SearchProblem {
+    /// A single state in the search space.
+    type State;
+
+    /// Gets the state that the search starts in.
+    fn start(&mut self) -> Self::State;
+
+    /// Determines whether the provided state is a goal. Searches do not expand goals any further.
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// Calls `visit` with every state that follows from the provided state, along with the cost of the step
+    /// to it.
+    fn successors(&mut self, state: &Self::State, visit: impl FnMut(Self::State, usize));
+}
+
+/// The costs of the cheapest routes to the states of a [`SearchProblem`] that were found so far.
+pub trait Costs<S> {
+    /// Gets the cost of the cheapest route to the provided state, or [`usize::MAX`] if none was found yet.
+    fn get(&self, state: &S) -> usize;
+
+    /// Sets the cost of the cheapest route to the provided state.
+    fn set(&mut self, state: &S, cost: usize);
+
+    /// Sets the cost of the route to the provided state if it is cheaper than any route found before, and
+    /// returns whether it was.
+    fn improve(&mut self, state: &S, cost: usize) -> bool {
+        let better = cost < self.get(state);
+        if better {
+            self.set(state, cost);
+        }
+        better
+    }
+}
+
+/// What a search did, for comparing algorithms and problems beyond their running time.
+#[derive(Debug, Clone, Default)]
+pub struct SearchStats {
+    /// The number of states that were taken from the agenda, including goals and outdated entries.
+    pub expanded: usize,
+
+    /// The largest number of states that were left on the agenda at once.
+    pub max_frontier: usize,
+
+    /// The number of states left on the agenda after every expansion, if requested with
+    /// [`SearchStats::recording`].
+    pub frontier: Option<Vec<usize>>,
+}
+
+impl SearchStats {
+    /// Creates new statistics that only keep totals.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates new statistics that also record the size of the frontier after every expansion.
+    pub fn recording() -> Self {
+        Self {
+            frontier: Some(Vec::new()),
+            ..Self::default()
+        }
+    }
+
+    /// Registers the expansion of a state, which left the provided number of states on the agenda.
+    #[inline(always)]
+    fn expand(&mut self, frontier: usize) {
+        self.expanded += 1;
+        self.max_frontier = self.max_frontier.max(frontier);
+        if let Some(sizes) = &mut self.frontier {
+            sizes.push(frontier);
+        }
+    }
+
+    /// Plots the recorded sizes of the frontier against the number of expanded states as an SVG image. Long
+    /// searches are drawn with the largest frontier of every column of pixels.
+    pub fn write_frontier_svg(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let sizes = self.frontier.as_deref().unwrap_or(&[]);
+        let per_column = sizes.len().div_ceil(PLOT_WIDTH).max(1);
+        let max_frontier = self.max_frontier.max(1) as f64;
+
+        writeln!(
+            writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"14\">",
+            PLOT_WIDTH,
+            PLOT_HEIGHT + CAPTION_HEIGHT
+        )?;
+        writeln!(
+            writer,
+            "<rect width=\"{}\" height=\"{}\" fill=\"#f4f4f4\"/>",
+            PLOT_WIDTH, PLOT_HEIGHT
+        )?;
+
+        // The frontier, closed along the bottom of the plot.
+        write!(
+            writer,
+            "<polygon fill=\"#3c78b4\" points=\"0,{}",
+            PLOT_HEIGHT
+        )?;
+        let columns = sizes.chunks(per_column);
+        let column_width = PLOT_WIDTH as f64 / columns.len().max(1) as f64;
+        for (index, column) in columns.enumerate() {
+            let y = PLOT_HEIGHT - *column.iter().max().unwrap() as f64 / max_frontier * PLOT_HEIGHT;
+            write!(
+                writer,
+                " {:.1},{:.1} {:.1},{:.1}",
+                index as f64 * column_width,
+                y,
+                (index + 1) as f64 * column_width,
+                y
+            )?;
+        }
+        writeln!(writer, " {},{}\"/>", PLOT_WIDTH, PLOT_HEIGHT)?;
+
+        writeln!(
+            writer,
+            "<text x=\"4\" y=\"{}\">{} states expanded, at most {} on the frontier</text>",
+            PLOT_HEIGHT + CAPTION_HEIGHT - 6.0,
+            self.expanded,
+            self.max_frontier
+        )?;
+        writeln!(writer, "</svg>")?;
+
+        writer.flush()
+    }
+}
+
+/// Counts the distinct paths from the start of the problem to any of its goals, by enumerating them depth-first.
+/// Every path ends at the first goal it reaches. The state space must not contain cycles, or the search never
+/// ends, so states typically remember the path that led to them.
+///
+/// The agenda is cleared before the search, and only passed in such that its allocation can be reused.
+pub fn count_paths<P: SearchProblem>(
+    problem: &mut P,
+    agenda: &mut Vec<P::State>,
+    stats: &mut SearchStats,
+) -> usize {
+    let mut count = 0;
+
+    agenda.clear();
+    agenda.push(problem.start());
+
+    while let Some(state) = agenda.pop() {
+        stats.expand(agenda.len());
+
+        // Paths end at the first goal they reach.
+        if problem.is_goal(&state) {
+            count += 1;
+            continue;
+        }
+
+        problem.successors(&state, |next, _| agenda.push(next));
+    }
+
+    count
+}
+
+/// Finds the cost of the cheapest path from the start of the problem to any of its goals with Dijkstra's
+/// algorithm. See [`shortest_path_from`] for the other parameters.
+pub fn shortest_path<P, Q, C>(
+    problem: &mut P,
+    max_cost: Option<usize>,
+    agenda: &mut Q,
+    costs: &mut C,
+    stats: &mut SearchStats,
+) -> Option<usize>
+where
+    P: SearchProblem,
+    P::State: Copy,
+    Q: PriorityQueue<P::State>,
+    C: Costs<P::State>,
+{
+    let start = problem.start();
+    shortest_path_from(problem, [start], max_cost, agenda, costs, stats)
+}
+
+/// Finds the cost of the cheapest path from any of the provided states to any goal of the problem with
+/// Dijkstra's algorithm, ignoring the start of the problem itself. Steps must not have negative costs.
+///
+/// If `max_cost` is provided, any route that costs more than this bound is abandoned, and [`None`] is returned
+/// if no goal can be reached within it. The agenda and the costs must not contain any states of earlier
+/// searches.
+pub fn shortest_path_from<P, Q, C>(
+    problem: &mut P,
+    starts: impl IntoIterator<Item = P::State>,
+    max_cost: Option<usize>,
+    agenda: &mut Q,
+    costs: &mut C,
+    stats: &mut SearchStats,
+) -> Option<usize>
+where
+    P: SearchProblem,
+    P::State: Copy,
+    Q: PriorityQueue<P::State>,
+    C: Costs<P::State>,
+{
+    let max_cost = max_cost.unwrap_or(usize::MAX);
+
+    for start in starts {
+        costs.set(&start, 0);
+        agenda.push(0, start);
+    }
+
+    while let Some((cost, state)) = agenda.pop() {
+        stats.expand(agenda.len());
+
+        // Are we there yet?
+        if problem.is_goal(&state) {
+            return Some(cost);
+        }
+
+        // Did we already find a better route for this state?
+        if cost > costs.get(&state) {
+            continue;
+        }
+
+        problem.successors(&state, |next, step| {
+            // Abandon routes that are too expensive, and only schedule the ones that are better than before.
+            let new_cost = cost + step;
+            if new_cost <= max_cost && costs.improve(&next, new_cost) {
+                agenda.push(new_cost, next);
+            }
+        });
+    }
+
+    None
+}
@@ -0,0 +1,303 @@
+//! Min-priority queues for shortest path searches and other solvers that repeatedly take the cheapest item.
+//!
+//! All queues implement [`PriorityQueue`], so solvers can be written once and switched between them:
+//!
+//! - [`BinaryHeapQueue`] is a thin wrapper around [`std::collections::BinaryHeap`] and works for any priorities.
+//! - [`BucketQueue`] keeps a bucket of items per priority. Pushing and popping are (amortized) constant time,
+//!   but priorities must be small, and an item may never be pushed with a lower priority than the last item
+//!   that was popped. Both hold for Dijkstra's algorithm with small non-negative edge costs.
+//! - [`PairingHeap`] pushes in constant time and pops in amortized logarithmic time, and stores its nodes in
+//!   an arena that is reused between pushes and pops.
+//!
+//! Items of equal priority may be popped in any order.
+
+use std::collections::BinaryHeap;
+
+/// A queue that pops its items in order of increasing priority.
+pub trait PriorityQueue<T> {
+    /// Adds an item with the provided priority to the queue.
+    fn push(&mut self, priority: usize, item: T);
+
+    /// Removes and returns the item with the lowest priority, along with its priority.
+    fn pop(&mut self) -> Option<(usize, T)>;
+
+    /// Gets the number of items in the queue.
+    fn len(&self) -> usize;
+
+    /// Removes all items from the queue, but keeps its allocations for reuse.
+    fn clear(&mut self);
+
+    /// Determines whether the queue contains no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// An item in a [`BinaryHeapQueue`], ordered by its priority only, and reversed such that the max-heap of the
+/// standard library pops the lowest priority first.
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    priority: usize,
+    item: T,
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A priority queue backed by the binary heap of the standard library.
+#[derive(Debug, Clone)]
+pub struct BinaryHeapQueue<T> {
+    heap: BinaryHeap<Entry<T>>,
+}
+
+impl<T> Default for BinaryHeapQueue<T> {
+    fn default() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+        }
+    }
+}
+
+impl<T> PriorityQueue<T> for BinaryHeapQueue<T> {
+    fn push(&mut self, priority: usize, item: T) {
+        self.heap.push(Entry { priority, item });
+    }
+
+    fn pop(&mut self) -> Option<(usize, T)> {
+        self.heap.pop().map(|x| (x.priority, x.item))
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn clear(&mut self) {
+        self.heap.clear();
+    }
+}
+
+/// A priority queue with a bucket of items for every priority, also known as Dial's queue. It only works for
+/// monotone use: an item may never be pushed with a lower priority than the last item that was popped.
+#[derive(Debug, Clone)]
+pub struct BucketQueue<T> {
+    /// The items of every priority.
+    buckets: Vec<Vec<T>>,
+
+    /// The lowest priority that may still have items. All buckets before it are empty.
+    current: usize,
+
+    /// The total number of items in all buckets.
+    len: usize,
+}
+
+impl<T> Default for BucketQueue<T> {
+    fn default() -> Self {
+        Self {
+            buckets: Vec::new(),
+            current: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<T> PriorityQueue<T> for BucketQueue<T> {
+    fn push(&mut self, priority: usize, item: T) {
+        debug_assert!(
+            priority >= self.current,
+            "Pushed priority {} after popping priority {}.",
+            priority,
+            self.current
+        );
+
+        if priority >= self.buckets.len() {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+        self.buckets[priority].push(item);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<(usize, T)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        while self.buckets[self.current].is_empty() {
+            self.current += 1;
+        }
+
+        self.len -= 1;
+        let item = self.buckets[self.current].pop()?;
+        Some((self.current, item))
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        for bucket in self.buckets.iter_mut() {
+            bucket.clear();
+        }
+        self.current = 0;
+        self.len = 0;
+    }
+}
+
+/// Marks the absence of a node in a [`PairingHeap`].
+const NIL: usize = usize::MAX;
+
+/// A node of a [`PairingHeap`]. Children are stored as a linked list through their siblings.
+#[derive(Debug, Clone)]
+struct Node<T> {
+    priority: usize,
+    item: Option<T>,
+    child: usize,
+    sibling: usize,
+}
+
+/// A pairing heap, whose nodes live in an arena such that popped nodes are reused by later pushes.
+#[derive(Debug, Clone)]
+pub struct PairingHeap<T> {
+    /// All nodes, including the free ones.
+    nodes: Vec<Node<T>>,
+
+    /// The nodes that are not part of the heap.
+    free: Vec<usize>,
+
+    /// The node with the lowest priority, or [`NIL`] if the heap is empty.
+    root: usize,
+
+    /// The number of items in the heap.
+    len: usize,
+
+    /// Scratch space for the children of a popped root.
+    children: Vec<usize>,
+}
+
+impl<T> Default for PairingHeap<T> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: NIL,
+            len: 0,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<T> PairingHeap<T> {
+    /// Merges two heaps by making the root with the higher priority the first child of the other root, and
+    /// returns the root of the merged heap.
+    fn meld(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+
+        let (parent, child) = if self.nodes[a].priority <= self.nodes[b].priority {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.nodes[child].sibling = self.nodes[parent].child;
+        self.nodes[parent].child = child;
+        parent
+    }
+}
+
+impl<T> PriorityQueue<T> for PairingHeap<T> {
+    fn push(&mut self, priority: usize, item: T) {
+        let node = Node {
+            priority,
+            item: Some(item),
+            child: NIL,
+            sibling: NIL,
+        };
+
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+
+        self.root = self.meld(self.root, index);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<(usize, T)> {
+        if self.root == NIL {
+            return None;
+        }
+
+        let root = self.root;
+        let priority = self.nodes[root].priority;
+        let item = self.nodes[root].item.take()?;
+
+        // Detach the children of the root.
+        let mut children = std::mem::take(&mut self.children);
+        let mut child = self.nodes[root].child;
+        while child != NIL {
+            children.push(child);
+            let next = self.nodes[child].sibling;
+            self.nodes[child].sibling = NIL;
+            child = next;
+        }
+
+        // Meld the children in pairs from left to right, and then the pairs from right to left.
+        let mut pairs = 0;
+        for index in (0..children.len()).step_by(2) {
+            let second = children.get(index + 1).copied().unwrap_or(NIL);
+            children[pairs] = self.meld(children[index], second);
+            pairs += 1;
+        }
+
+        let mut new_root = NIL;
+        for &pair in children[..pairs].iter().rev() {
+            new_root = self.meld(pair, new_root);
+        }
+
+        children.clear();
+        self.children = children;
+
+        self.root = new_root;
+        self.free.push(root);
+        self.len -= 1;
+        Some((priority, item))
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = NIL;
+        self.len = 0;
+    }
+}
@@ -0,0 +1,65 @@
+//! Optional CPU profiling of the parts of a solver, which writes a flame graph per part. Only available with
+//! the `profile` feature; without it, profiling is a no-op.
+
+#[cfg(feature = "profile")]
+use std::sync::Mutex;
+
+/// The number of samples taken per second while profiling.
+#[cfg(feature = "profile")]
+const PROFILE_FREQUENCY: i32 = 10_000;
+
+/// The path prefix of the flame graphs of the next run, or an empty string if profiling is disabled.
+#[cfg(feature = "profile")]
+static PREFIX: Mutex<String> = Mutex::new(String::new());
+
+/// Enables profiling of the next runs, writing a flame graph of every part to `<prefix>-part1.svg` and
+/// `<prefix>-part2.svg`. An empty prefix disables profiling again.
+pub fn set_profile_prefix(prefix: &str) {
+    #[cfg(feature = "profile")]
+    {
+        *PREFIX.lock().unwrap() = prefix.to_string();
+    }
+
+    #[cfg(not(feature = "profile"))]
+    let _ = prefix;
+}
+
+/// Runs the provided part of a solver, and writes a flame graph of it if profiling is enabled.
+#[cfg(feature = "profile")]
+pub(crate) fn profile<T>(part: &str, f: impl FnOnce() -> T) -> T {
+    let prefix = PREFIX.lock().unwrap().clone();
+    if prefix.is_empty() {
+        return f();
+    }
+
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(PROFILE_FREQUENCY)
+        .build();
+    let result = f();
+
+    let path = format!("{}-{}.svg", prefix, part);
+    let written = guard
+        .and_then(|guard| guard.report().build())
+        .map_err(|error| error.to_string())
+        .and_then(|report| {
+            // Parts that finish before the first sample have nothing to draw.
+            if report.data.is_empty() {
+                return Err("no samples were taken".to_string());
+            }
+
+            let file = std::fs::File::create(&path).map_err(|error| error.to_string())?;
+            report.flamegraph(file).map_err(|error| error.to_string())
+        });
+
+    if let Err(error) = written {
+        eprintln!("Could not write profile {}: {}.", path, error);
+    }
+
+    result
+}
+
+/// Runs the provided part of a solver. Profiling is not available without the `profile` feature.
+#[cfg(not(feature = "profile"))]
+pub(crate) fn profile<T>(_part: &str, f: impl FnOnce() -> T) -> T {
+    f()
+}
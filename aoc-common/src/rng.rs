@@ -0,0 +1,59 @@
+//! A small deterministic pseudo-random number generator for the randomized parts of the solutions, such as input
+//! generators, stress tests and property checks, and the workspace-wide seed they start from.
+//!
+//! The seed is taken from the last call to [`set_seed`] (the runner's `--seed`), or else from the `AOC_SEED`
+//! environment variable, so a run of any binary in the workspace can be reproduced by passing the same seed.
+
+use std::sync::Mutex;
+
+/// The seed used if none was set and `AOC_SEED` is not set either.
+pub const DEFAULT_SEED: u64 = 1;
+
+/// The seed set through [`set_seed`], if any.
+static SEED: Mutex<Option<u64>> = Mutex::new(None);
+
+/// Sets the seed that randomized components use unless they are given one explicitly.
+pub fn set_seed(seed: u64) {
+    *SEED.lock().unwrap() = Some(seed);
+}
+
+/// Gets the seed that randomized components use unless they are given one explicitly. This is the seed passed
+/// to [`set_seed`], the value of the `AOC_SEED` environment variable, or [`DEFAULT_SEED`], in that order.
+pub fn seed() -> u64 {
+    if let Some(seed) = *SEED.lock().unwrap() {
+        return seed;
+    }
+
+    std::env::var("AOC_SEED")
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(DEFAULT_SEED)
+}
+
+/// A xorshift generator. We do not need anything fancy, but every randomized component should draw from the
+/// same generator, such that a seed means the same thing everywhere.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator from the provided seed. Xorshift gets stuck on a state of zero, so a seed of zero
+    /// behaves like a seed of one.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    /// Gets the next pseudo-random 64-bit number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Gets the next pseudo-random number as a `usize`.
+    pub fn next_usize(&mut self) -> usize {
+        self.next_u64() as usize
+    }
+}
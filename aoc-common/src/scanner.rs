@@ -0,0 +1,186 @@
+//! A byte-level scanner for parsers that report the exact position of malformed input.
+
+use std::fmt::Display;
+
+/// A cursor over a byte slice that keeps track of its position, for parsers that want to report exactly
+/// where their input went wrong.
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+/// Describes an unexpected byte (or the unexpected end of the data) encountered by a [`Scanner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError {
+    /// The index of the offending byte.
+    pub position: usize,
+
+    /// A description of what was expected at the position.
+    pub expected: &'static str,
+
+    /// The byte that was found instead, or [`None`] if the end of the data was reached.
+    pub found: Option<u8>,
+}
+
+impl<'a> Scanner<'a> {
+    /// Creates a new scanner at the start of the provided data.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    /// Gets the index of the next byte that will be read.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Determines whether all bytes have been read.
+    pub fn is_eof(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    /// Gets the number of bytes that have not been read yet.
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    /// Gets the next byte without advancing the scanner.
+    pub fn peek(&self) -> Option<u8> {
+        self.data.get(self.position).copied()
+    }
+
+    /// Reads the next byte if it satisfies the provided predicate.
+    pub fn next_if(&mut self, predicate: impl FnOnce(u8) -> bool) -> Option<u8> {
+        let c = self.peek().filter(|&c| predicate(c))?;
+        self.position += 1;
+        Some(c)
+    }
+
+    /// Reads the next byte and converts it with the provided function. Returns an error pointing at the byte
+    /// if there is no next byte or the conversion returns [`None`], in which case the scanner is not advanced.
+    pub fn expect<T>(
+        &mut self,
+        expected: &'static str,
+        convert: impl FnOnce(u8) -> Option<T>,
+    ) -> Result<T, ScanError> {
+        match self.peek().map(convert) {
+            Some(Some(value)) => {
+                self.position += 1;
+                Ok(value)
+            }
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    /// Checks that all bytes have been read.
+    pub fn expect_eof(&self) -> Result<(), ScanError> {
+        if self.is_eof() {
+            Ok(())
+        } else {
+            Err(self.error("the end of the line"))
+        }
+    }
+
+    /// Creates an error describing that something else was expected at the current position.
+    pub fn error(&self, expected: &'static str) -> ScanError {
+        ScanError {
+            position: self.position,
+            expected,
+            found: self.peek(),
+        }
+    }
+}
+
+/// Reads an unsigned decimal integer from the start of the provided bytes, and advances the slice past its
+/// digits. Returns [`None`] (leaving the slice untouched) if the bytes do not start with a digit, or the
+/// number does not fit in 64 bits.
+pub fn read_uint(input: &mut &[u8]) -> Option<u64> {
+    let digits = input.iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+
+    let value = input[..digits].iter().try_fold(0u64, |acc, &b| {
+        acc.checked_mul(10)?.checked_add((b - b'0') as u64)
+    })?;
+    *input = &input[digits..];
+    Some(value)
+}
+
+/// Advances the provided bytes past the provided literal. Returns [`None`] (leaving the slice untouched) if
+/// the bytes do not start with it.
+pub fn read_literal(input: &mut &[u8], literal: &[u8]) -> Option<()> {
+    *input = input.strip_prefix(literal)?;
+    Some(())
+}
+
+/// Splits the provided bytes into lines without allocating, with trailing whitespace (including the `\r` of
+/// Windows line endings) removed. Like [`std::io::BufRead::lines`], a final line ending does not start an
+/// extra empty line.
+pub fn byte_lines(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let count = if data.is_empty() { 0 } else { usize::MAX };
+    data.strip_suffix(b"\n")
+        .unwrap_or(data)
+        .split(|&b| b == b'\n')
+        .take(count)
+        .map(|line| line.trim_ascii_end())
+}
+
+impl<'a> Iterator for Scanner<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining(), Some(self.remaining()))
+    }
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.found {
+            Some(c) if c.is_ascii_graphic() => write!(
+                f,
+                "Expected {}, but found '{}' at position {}",
+                self.expected, c as char, self.position
+            ),
+            Some(c) => write!(
+                f,
+                "Expected {}, but found byte 0x{:02X} at position {}",
+                self.expected, c, self.position
+            ),
+            None => write!(
+                f,
+                "Expected {}, but the line ended at position {}",
+                self.expected, self.position
+            ),
+        }
+    }
+}
+
+impl ScanError {
+    /// Locates the error on the provided line of the input (starting at 1), with its position as the
+    /// column.
+    pub fn at_line(&self, line: usize) -> crate::Error {
+        let message = match self.found {
+            Some(c) if c.is_ascii_graphic() => {
+                format!("Expected {}, but found '{}'.", self.expected, c as char)
+            }
+            Some(c) => format!("Expected {}, but found byte 0x{:02X}.", self.expected, c),
+            None => format!("Expected {}, but the line ended.", self.expected),
+        };
+        crate::Error::at_column(line, self.position + 1, message)
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+impl From<ScanError> for std::io::Error {
+    fn from(error: ScanError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+    }
+}
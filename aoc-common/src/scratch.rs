@@ -0,0 +1,53 @@
+//! Buffers that solvers keep between runs, such that repeated runs of the same day (as in benchmarks) measure the
+//! algorithm rather than the allocation of its agendas and tables.
+//!
+//! Every day defines its own scratch type with the buffers it reuses, which must implement [`Default`]. The
+//! harness owns a [`Scratch`] per day and passes it to [`Solver::part1_with`](crate::Solver::part1_with) and
+//! [`Solver::part2_with`](crate::Solver::part2_with), which look up their scratch type in it:
+//!
+//! ```ignore
+//! fn part1_with(input: &Input, scratch: &mut Scratch) -> Answer {
+//!     part1(input, Some(scratch.get::<StepScratch>())).into()
+//! }
+//! ```
+//!
+//! The part functions of a day take their scratch type as an optional parameter, and start from empty buffers
+//! without one.
+
+use std::any::{Any, TypeId};
+
+/// The scratch types of a single day, each created the first time it is requested.
+#[derive(Default)]
+pub struct Scratch {
+    /// The scratch types requested so far.
+    buffers: Vec<(TypeId, Box<dyn Any + Send>)>,
+}
+
+impl Scratch {
+    /// Creates a new scratch space without any buffers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the scratch type `T`, creating it if it was not requested before. Buffers are handed out as they
+    /// were left by the previous run, so solvers must clear them before use.
+    pub fn get<T: Default + Send + 'static>(&mut self) -> &mut T {
+        let index = match self.buffers.iter().position(|x| x.0 == TypeId::of::<T>()) {
+            Some(index) => index,
+            None => {
+                self.buffers.push((TypeId::of::<T>(), Box::<T>::default()));
+                self.buffers.len() - 1
+            }
+        };
+
+        self.buffers[index]
+            .1
+            .downcast_mut()
+            .expect("Scratch buffers are stored with their own type id.")
+    }
+
+    /// Drops all buffers.
+    pub fn clear(&mut self) {
+        self.buffers.clear();
+    }
+}
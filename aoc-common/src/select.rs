@@ -0,0 +1,47 @@
+//! Order statistics by selection rather than fully sorting, for days that only need a few of the values in
+//! order. Selection takes linear time, where sorting takes `O(n log n)`.
+
+/// Finds the middle element of the provided values, or [`None`] if there are none. For an even number of
+/// values, this is the upper one of the two middle elements. The order of the values is changed in the
+/// process.
+pub fn median<T: Ord + Copy>(values: &mut [T]) -> Option<T> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let middle = values.len() / 2;
+    Some(*values.select_nth_unstable(middle).1)
+}
+
+/// Gets the `k` largest values in no particular order, or [`None`] if there are fewer than `k` values. The
+/// order of the values is changed in the process.
+pub fn top_k<T: Ord>(values: &mut [T], k: usize) -> Option<&[T]> {
+    if k > values.len() {
+        return None;
+    } else if k > 0 && k < values.len() {
+        values.select_nth_unstable_by(k - 1, |a, b| b.cmp(a));
+    }
+
+    Some(&values[..k])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_no_values_is_none() {
+        assert_eq!(median::<u32>(&mut []), None);
+        assert_eq!(median(&mut [3, 1, 2]), Some(2));
+    }
+
+    #[test]
+    fn top_k_of_too_few_values_is_none() {
+        assert_eq!(top_k(&mut [1, 2], 3), None);
+
+        let mut values = [4, 1, 5, 2, 3];
+        let mut top = top_k(&mut values, 3).unwrap().to_vec();
+        top.sort();
+        assert_eq!(top, [3, 4, 5]);
+    }
+}
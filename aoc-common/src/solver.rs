@@ -0,0 +1,310 @@
+//! A uniform interface for the solutions of all days, such that they can be enumerated and run by a single
+//! harness.
+
+use crate::{measure_allocations, profile::profile, AllocStats, Explain, Scratch};
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    /// A numeric answer.
+    Number(u128),
+
+    /// A textual answer, such as letters read from a grid, or a description of why there is no answer.
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(x) => write!(f, "{}", x),
+            Answer::Text(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(x: usize) -> Self {
+        Answer::Number(x as u128)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(x: u128) -> Self {
+        Answer::Number(x)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(x: String) -> Self {
+        Answer::Text(x)
+    }
+}
+
+impl<T, E> From<Result<T, E>> for Answer
+where
+    T: Into<Answer>,
+    E: Display,
+{
+    fn from(x: Result<T, E>) -> Self {
+        match x {
+            Ok(x) => x.into(),
+            Err(error) => Answer::Text(error.to_string()),
+        }
+    }
+}
+
+/// The expected running time of every phase of a solver, as checked by the runner's `--budgets`. Budgets are
+/// meant for optimized builds, and phases without a budget are never checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Budget {
+    /// The longest time parsing the input should take.
+    pub parse: Option<Duration>,
+
+    /// The longest time solving the first part should take.
+    pub part1: Option<Duration>,
+
+    /// The longest time solving the second part should take.
+    pub part2: Option<Duration>,
+}
+
+impl Budget {
+    /// No budget for any phase.
+    pub const NONE: Budget = Budget {
+        parse: None,
+        part1: None,
+        part2: None,
+    };
+}
+
+/// The solution of a single day.
+pub trait Solver {
+    /// The parsed puzzle input.
+    type Input;
+
+    /// Parses the puzzle input from the provided file.
+    fn parse(file: &str) -> crate::Result<Self::Input>;
+
+    /// Parses the puzzle input from the provided text, for hosts without a file system.
+    fn parse_str(input: &str) -> crate::Result<Self::Input>;
+
+    /// Solves the first part of the puzzle.
+    fn part1(input: &Self::Input) -> Answer;
+
+    /// Solves the second part of the puzzle.
+    fn part2(input: &Self::Input) -> Answer;
+
+    /// Solves the first part of the puzzle like [`Solver::part1`], but reuses the buffers in the provided
+    /// scratch space. Days without buffers worth reusing ignore it.
+    fn part1_with(input: &Self::Input, scratch: &mut Scratch) -> Answer {
+        let _ = scratch;
+        Self::part1(input)
+    }
+
+    /// Solves the second part of the puzzle like [`Solver::part2`], but reuses the buffers in the provided
+    /// scratch space. Days without buffers worth reusing ignore it.
+    fn part2_with(input: &Self::Input, scratch: &mut Scratch) -> Answer {
+        let _ = scratch;
+        Self::part2(input)
+    }
+
+    /// The expected running time of the solver. Days without performance goals have no budget.
+    const BUDGET: Budget = Budget::NONE;
+
+    /// The names of the strategies the solver can be switched to with [`crate::set_strategy`], the default
+    /// first. Days that solve their puzzle in one way only have no strategies.
+    const STRATEGIES: &'static [&'static str] = &[];
+
+    /// Describes the intermediate steps that lead to the answers. Days without interesting steps describe
+    /// nothing.
+    fn explain(input: &Self::Input, sink: &mut dyn Explain) -> std::io::Result<()> {
+        let _ = (input, sink);
+        Ok(())
+    }
+}
+
+/// The answers and timings of running a solver on one input.
+#[derive(Debug, Clone)]
+pub struct Run {
+    /// The answer to the first part.
+    pub answer1: Answer,
+
+    /// The answer to the second part.
+    pub answer2: Answer,
+
+    /// The time it took to parse the input.
+    pub time_parse: Duration,
+
+    /// The time it took to solve the first part.
+    pub time1: Duration,
+
+    /// The time it took to solve the second part.
+    pub time2: Duration,
+
+    /// The allocations made while parsing the input.
+    pub alloc_parse: AllocStats,
+
+    /// The allocations made while solving the first part.
+    pub alloc1: AllocStats,
+
+    /// The allocations made while solving the second part.
+    pub alloc2: AllocStats,
+}
+
+impl Run {
+    /// Gets the total time it took to parse the input and solve both parts.
+    pub fn total_time(&self) -> Duration {
+        self.time_parse + self.time1 + self.time2
+    }
+}
+
+/// Parses the provided file and solves both parts with the provided solver, timing every phase and counting
+/// its allocations.
+pub fn run<S: Solver>(file: &str) -> std::io::Result<Run> {
+    run_with::<S>(file, &mut Scratch::new())
+}
+
+/// Runs the provided solver like [`run`], but reuses the buffers in the provided scratch space, such that
+/// repeated runs only allocate them once.
+pub fn run_with<S: Solver>(file: &str, scratch: &mut Scratch) -> std::io::Result<Run> {
+    // Querying the processor is slow the first time, which should not count towards any phase.
+    crate::isa();
+
+    let now = Instant::now();
+    let (input, alloc_parse) =
+        measure_allocations(|| tracing::info_span!("parse", file).in_scope(|| S::parse(file)));
+    let input = input?;
+    let time_parse = now.elapsed();
+
+    let now = Instant::now();
+    let (answer1, alloc1) = measure_allocations(|| {
+        tracing::info_span!("part1")
+            .in_scope(|| profile("part1", || S::part1_with(&input, scratch)))
+    });
+    let time1 = now.elapsed();
+
+    let now = Instant::now();
+    let (answer2, alloc2) = measure_allocations(|| {
+        tracing::info_span!("part2")
+            .in_scope(|| profile("part2", || S::part2_with(&input, scratch)))
+    });
+    let time2 = now.elapsed();
+
+    Ok(Run {
+        answer1,
+        answer2,
+        time_parse,
+        time1,
+        time2,
+        alloc_parse,
+        alloc1,
+        alloc2,
+    })
+}
+
+/// Parses the provided puzzle input text and solves one part (1 or 2) with the provided solver. Unlike [`run`],
+/// this does not touch the file system or the clock, so it can be used from WebAssembly.
+pub fn solve<S: Solver>(input: &str, part: u8) -> std::io::Result<Answer> {
+    let input = S::parse_str(input)?;
+    match part {
+        1 => Ok(S::part1(&input)),
+        2 => Ok(S::part2(&input)),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Unknown part {}.", part),
+        )),
+    }
+}
+
+/// Parses the provided file and describes the intermediate steps of the provided solver to the provided sink.
+pub fn explain<S: Solver>(file: &str, sink: &mut dyn Explain) -> std::io::Result<()> {
+    let input = S::parse(file)?;
+    S::explain(&input, sink)
+}
+
+/// A solver that is registered for a specific day.
+#[derive(Clone, Copy)]
+pub struct Registration {
+    /// The year of the puzzle.
+    pub year: u16,
+
+    /// The day of the puzzle (starting at 1).
+    pub day: u8,
+
+    /// Runs the solver on the provided file.
+    pub run: fn(&str) -> std::io::Result<Run>,
+
+    /// Runs the solver on the provided file, reusing the buffers in the provided scratch space.
+    pub run_with: fn(&str, &mut Scratch) -> std::io::Result<Run>,
+
+    /// Solves one part of the puzzle for the provided input text.
+    pub solve: fn(&str, u8) -> std::io::Result<Answer>,
+
+    /// Describes the intermediate steps of the solver for the provided file.
+    pub explain: fn(&str, &mut dyn Explain) -> std::io::Result<()>,
+
+    /// The expected running time of the solver.
+    pub budget: Budget,
+
+    /// The names of the strategies the solver can be switched to.
+    pub strategies: &'static [&'static str],
+}
+
+/// Maps days onto their solvers.
+#[derive(Default)]
+pub struct Registry {
+    /// All registered solvers, ordered by year and day.
+    registrations: Vec<Registration>,
+}
+
+impl Registry {
+    /// Creates a new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the solver for the provided day, replacing any solver that was registered for it before.
+    pub fn register<S: Solver>(&mut self, year: u16, day: u8) {
+        let registration = Registration {
+            year,
+            day,
+            run: run::<S>,
+            run_with: run_with::<S>,
+            solve: solve::<S>,
+            explain: explain::<S>,
+            budget: S::BUDGET,
+            strategies: S::STRATEGIES,
+        };
+
+        match self
+            .registrations
+            .binary_search_by_key(&(year, day), |x| (x.year, x.day))
+        {
+            Ok(index) => self.registrations[index] = registration,
+            Err(index) => self.registrations.insert(index, registration),
+        }
+    }
+
+    /// Gets the solver registered for the provided day, if any.
+    pub fn get(&self, year: u16, day: u8) -> Option<&Registration> {
+        self.registrations
+            .binary_search_by_key(&(year, day), |x| (x.year, x.day))
+            .ok()
+            .map(|index| &self.registrations[index])
+    }
+
+    /// Iterates all registered solvers, ordered by year and day.
+    pub fn iter(&self) -> impl Iterator<Item = &Registration> {
+        self.registrations.iter()
+    }
+
+    /// Gets every year that has at least one registered solver, in ascending order.
+    pub fn years(&self) -> Vec<u16> {
+        let mut years: Vec<u16> = self.registrations.iter().map(|x| x.year).collect();
+        years.dedup();
+        years
+    }
+}
@@ -0,0 +1,30 @@
+//! Strategies selected for days that can solve their puzzle in several ways, such that a harness can switch a
+//! day to another strategy without going through the command line of its binary.
+
+use std::{str::FromStr, sync::Mutex};
+
+/// The strategies selected so far, as `(year, day, name)` triples.
+static SELECTED: Mutex<Vec<(u16, u8, String)>> = Mutex::new(Vec::new());
+
+/// Selects the named strategy for the provided day, replacing any strategy selected for it before. Harnesses
+/// check the name against the [`STRATEGIES`](crate::Solver::STRATEGIES) of the day first.
+pub fn set_strategy(year: u16, day: u8, name: &str) {
+    let mut selected = SELECTED.lock().unwrap();
+    selected.retain(|x| (x.0, x.1) != (year, day));
+    selected.push((year, day, name.to_string()));
+}
+
+/// Gets the strategy selected for the provided day, or `default` if none was selected.
+///
+/// # Panics
+///
+/// Panics if the selected name is not a strategy the day understands.
+pub fn strategy<T: FromStr<Err = String>>(year: u16, day: u8, default: T) -> T {
+    let selected = SELECTED.lock().unwrap();
+    match selected.iter().find(|x| (x.0, x.1) == (year, day)) {
+        Some((_, _, name)) => name
+            .parse()
+            .unwrap_or_else(|error| panic!("Invalid strategy for {} day {}: {}", year, day, error)),
+        None => default,
+    }
+}
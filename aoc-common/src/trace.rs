@@ -0,0 +1,22 @@
+//! Structured timing and debugging output through `tracing` spans. Spans are always recorded, but only written
+//! with the `trace` feature; without it, installing the subscriber is a no-op.
+
+#[cfg(feature = "trace")]
+use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+/// Installs a subscriber that writes events and closed spans (including their duration) to the standard error
+/// stream. What is written is controlled by the `RUST_LOG` environment variable, e.g. `RUST_LOG=trace` or
+/// `RUST_LOG=day15=debug`. Nothing is written if it is not set.
+pub fn init_tracing() {
+    #[cfg(feature = "trace")]
+    {
+        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("off"));
+
+        // Ignore the error if a subscriber was installed already, e.g. by another harness in the same process.
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
+            .try_init();
+    }
+}
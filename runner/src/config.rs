@@ -0,0 +1,249 @@
+//! Defaults for the flags of the runner, read from an `aoc.toml` file such that they do not have to be repeated
+//! on every run, and such that automation can pin the behavior of the runner. A config file looks like this:
+//!
+//! ```toml
+//! # The year that single days are run from.
+//! year = 2021
+//!
+//! # The format of benchmark reports and of answers: "markdown", "csv" or "json".
+//! format = "json"
+//!
+//! # The file that contains the session cookie of adventofcode.com, if AOC_SESSION is not set.
+//! session_file = "~/.config/aoc/session"
+//!
+//! # The directory that contains the inputs, and where the input of a day is within it.
+//! inputs = "../aoc-inputs"
+//! input_file = "{year}/day{day}/input.txt"
+//!
+//! # The instruction set that hot loops run with: "scalar" or "avx2". Defaults to the best one available.
+//! isa = "scalar"
+//!
+//! # The strategies of days that can solve their puzzle in several ways.
+//! [strategies]
+//! 2021-07 = "histogram"
+//! ```
+//!
+//! Only this subset of TOML is understood: comments, tables, and keys with string, integer or boolean values.
+//! Relative paths are relative to the directory of the config file. Flags on the command line take precedence
+//! over the config file.
+
+use crate::report::Format;
+use aoc_common::Isa;
+use std::path::{Path, PathBuf};
+
+/// The name of the config file that is looked up if no config file is given with `--config`.
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// The layout of the input directory if the config file does not specify one.
+const DEFAULT_INPUT_FILE: &str = "{year}/day{day}/input.txt";
+
+/// The defaults read from a config file.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// The file that contains the session cookie of adventofcode.com.
+    pub session_file: Option<PathBuf>,
+
+    /// The year that single days are run from.
+    pub year: Option<u16>,
+
+    /// The format of benchmark reports and answers.
+    pub format: Option<Format>,
+
+    /// The directory that contains the inputs.
+    pub inputs: Option<PathBuf>,
+
+    /// The path of the input of a day within the input directory, where `{year}` is replaced by the year and
+    /// `{day}` by the day padded to two digits.
+    pub input_file: Option<String>,
+
+    /// The instruction set that hot loops run with.
+    pub isa: Option<Isa>,
+
+    /// The strategies selected for days, as `(year, day, name)` triples.
+    pub strategies: Vec<(u16, u8, String)>,
+}
+
+/// A value in a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+impl Config {
+    /// Reads the config file at the provided path, or if no path is provided, the `aoc.toml` in the working
+    /// directory or else in the root of the repository. Without any config file, nothing has a default.
+    pub fn load(path: Option<&str>) -> std::io::Result<Self> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => {
+                let candidates = [
+                    PathBuf::from(CONFIG_FILE),
+                    Path::new(env!("CARGO_MANIFEST_DIR"))
+                        .join("..")
+                        .join(CONFIG_FILE),
+                ];
+                match candidates.into_iter().find(|x| x.is_file()) {
+                    Some(path) => path,
+                    None => return Ok(Self::default()),
+                }
+            }
+        };
+
+        let text = std::fs::read_to_string(&path)?;
+        let base = path.parent().unwrap_or(Path::new("."));
+        Self::parse(&text, base).map_err(|error| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), error),
+            )
+        })
+    }
+
+    /// Parses the text of a config file. Relative paths are resolved against the provided directory.
+    pub fn parse(text: &str, base: &Path) -> Result<Self, String> {
+        let mut config = Self::default();
+        let mut table = String::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let error = |message: String| format!("line {}: {}", index + 1, message);
+
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name
+                    .strip_suffix(']')
+                    .ok_or_else(|| error(format!("Expected ] after table {}.", name)))?;
+                table = name.trim().to_string();
+                if table != "strategies" {
+                    return Err(error(format!("Unknown table {}.", table)));
+                }
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("Expected key = value, but found {}.", line)))?;
+            let key = key.trim().trim_matches('"');
+            let value = parse_value(value.trim()).map_err(&error)?;
+
+            match (table.as_str(), key, value) {
+                ("", "session_file", Value::String(x)) => {
+                    config.session_file = Some(resolve(base, &x))
+                }
+                ("", "year", Value::Integer(x)) => {
+                    config.year =
+                        Some(u16::try_from(x).map_err(|_| error(format!("Invalid year {}.", x)))?)
+                }
+                ("", "format", Value::String(x)) => {
+                    config.format = Some(x.parse().map_err(&error)?)
+                }
+                ("", "isa", Value::String(x)) => config.isa = Some(x.parse().map_err(&error)?),
+                ("", "inputs", Value::String(x)) => config.inputs = Some(resolve(base, &x)),
+                ("", "input_file", Value::String(x)) => config.input_file = Some(x),
+                ("strategies", key, Value::String(x)) => {
+                    let (year, day) = key
+                        .split_once('-')
+                        .and_then(|(year, day)| Some((year.parse().ok()?, day.parse().ok()?)))
+                        .ok_or_else(|| error(format!("Expected year-day, but found {}.", key)))?;
+                    config.strategies.push((year, day, x));
+                }
+                (_, key, value) => {
+                    return Err(error(format!(
+                        "Unexpected key {} with value {:?}.",
+                        key, value
+                    )))
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Gets the path of the input of a day relative to the input directory, with the `{year}` and `{day}`
+    /// placeholders.
+    pub fn input_layout(&self) -> &str {
+        self.input_file.as_deref().unwrap_or(DEFAULT_INPUT_FILE)
+    }
+
+    /// Gets the path of the input of the provided day relative to the input directory.
+    pub fn input_file(&self, year: u16, day: u8) -> String {
+        self.input_layout()
+            .replace("{year}", &year.to_string())
+            .replace("{day}", &format!("{:02}", day))
+    }
+}
+
+/// Removes a comment from the end of a line, unless the `#` is part of a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parses the value of a key.
+fn parse_value(s: &str) -> Result<Value, String> {
+    match s {
+        "true" => return Ok(Value::Boolean(true)),
+        "false" => return Ok(Value::Boolean(false)),
+        _ => {}
+    }
+
+    if let Some(rest) = s.strip_prefix('"') {
+        let rest = rest
+            .strip_suffix('"')
+            .ok_or_else(|| format!("Expected \" at the end of {}.", s))?;
+
+        let mut value = String::with_capacity(rest.len());
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                x => {
+                    return Err(format!(
+                        "Unknown escape sequence \\{} in {}.",
+                        x.unwrap_or(' '),
+                        s
+                    ))
+                }
+            }
+        }
+        return Ok(Value::String(value));
+    }
+
+    s.replace('_', "")
+        .parse()
+        .map(Value::Integer)
+        .map_err(|_| format!("Expected a string, integer or boolean, but found {}.", s))
+}
+
+/// Resolves a path in the config file, expanding a leading `~` to the home directory.
+fn resolve(base: &Path, path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => match std::env::var_os("HOME") {
+            Some(home) => Path::new(&home).join(rest),
+            None => base.join(path),
+        },
+        None => base.join(path),
+    }
+}
@@ -0,0 +1,426 @@
+//! Diagnoses the environment of the runner: the session cookie, the layout of the input directory, missing
+//! inputs, stale cached files and answers that are not verified on any sample. Every problem comes with a
+//! suggestion to fix it, which helps setting up a fresh clone of the repository on a new machine.
+
+use crate::samples;
+use aoc_common::Registry;
+use std::{path::Path, time::SystemTime};
+
+/// The first words of the page the website serves instead of an input if the session cookie is invalid.
+const INPUT_ERROR_PAGE: &str = "Puzzle inputs differ by user.";
+
+/// How serious a finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The check passed.
+    Ok,
+
+    /// Something works, but may not work as expected.
+    Warning,
+
+    /// Something does not work.
+    Error,
+}
+
+/// The outcome of a single check.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// How serious the finding is.
+    pub severity: Severity,
+
+    /// What was found.
+    pub message: String,
+
+    /// How to fix it, if anything is to be fixed.
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    /// Creates a finding of a check that passed.
+    fn ok(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    /// Creates a finding of something that may not work as expected.
+    fn warning(message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    /// Creates a finding of something that does not work.
+    fn error(message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Checks the session cookie that is used to download inputs and to submit answers. With the `fetch` feature,
+/// the cookie is tried by downloading the input of the first day of the provided year.
+pub fn check_session(year: u16) -> Vec<Finding> {
+    let (source, session) = match std::env::var("AOC_SESSION") {
+        Ok(session) => (String::from("AOC_SESSION"), session),
+        Err(_) => match &crate::config().session_file {
+            Some(path) => match std::fs::read_to_string(path) {
+                Ok(session) => (path.display().to_string(), session),
+                Err(error) => {
+                    return vec![Finding::error(
+                        format!("The session file {} cannot be read: {}", path.display(), error),
+                        "Save the session cookie of adventofcode.com in it, or fix session_file in aoc.toml.",
+                    )]
+                }
+            },
+            None => {
+                return vec![Finding::warning(
+                    "No session cookie is set, so inputs cannot be downloaded and answers cannot be submitted.",
+                    "Set AOC_SESSION or the session_file in aoc.toml to the session cookie of adventofcode.com.",
+                )]
+            }
+        },
+    };
+
+    let session = session.trim();
+    if session.is_empty() {
+        return vec![Finding::error(
+            format!("The session cookie in {} is empty.", source),
+            "Copy the value of the session cookie of adventofcode.com from the browser after logging in.",
+        )];
+    }
+    if session.starts_with("session=") {
+        return vec![Finding::error(
+            format!(
+                "The session cookie in {} starts with the name of the cookie.",
+                source
+            ),
+            "Remove `session=`, only the value of the cookie is needed.",
+        )];
+    }
+    if !session.bytes().all(|x| x.is_ascii_hexdigit()) {
+        return vec![Finding::warning(
+            format!(
+                "The session cookie in {} does not look like one, as it is not hexadecimal.",
+                source
+            ),
+            "Copy the value of the session cookie of adventofcode.com from the browser after logging in.",
+        )];
+    }
+
+    #[cfg(feature = "fetch")]
+    match crate::fetch::fetch_input(year, 1) {
+        Ok(input) if !input.starts_with(INPUT_ERROR_PAGE) => {
+            vec![Finding::ok(format!(
+                "The session cookie in {} is accepted by adventofcode.com.",
+                source
+            ))]
+        }
+        Ok(_) => vec![Finding::error(
+            format!("The session cookie in {} is not accepted by adventofcode.com.", source),
+            "Log in again and copy the new session cookie, as cookies expire after about a month.",
+        )],
+        Err(error) => vec![Finding::error(
+            format!(
+                "The session cookie in {} could not be checked with adventofcode.com: {}",
+                source, error
+            ),
+            "Log in again and copy the new session cookie if it expired, or check the network connection.",
+        )],
+    }
+
+    #[cfg(not(feature = "fetch"))]
+    {
+        let _ = year;
+        vec![Finding::ok(format!(
+            "The session cookie in {} is set. It is only checked with adventofcode.com with the fetch feature.",
+            source
+        ))]
+    }
+}
+
+/// Checks that the input directory exists, and that its layout separates the inputs of different days.
+pub fn check_layout() -> Vec<Finding> {
+    let directory = crate::input_directory();
+    let layout = crate::config().input_layout();
+    let mut findings = Vec::new();
+
+    if directory.is_dir() {
+        findings.push(Finding::ok(format!(
+            "Inputs are read from {}, laid out as {}.",
+            directory.display(),
+            layout
+        )));
+    } else {
+        findings.push(Finding::error(
+            format!(
+                "The input directory {} does not exist.",
+                directory.display()
+            ),
+            "Create it, or point --inputs or inputs in aoc.toml to the directory with the inputs.",
+        ));
+    }
+
+    if !layout.contains("{day}") {
+        findings.push(Finding::error(
+            format!("The layout {} reads the same input for every day.", layout),
+            "Add `{day}` to input_file in aoc.toml.",
+        ));
+    }
+
+    findings
+}
+
+/// Checks that the input of every registered day exists, and that none of them is an error page that was
+/// downloaded with an invalid session cookie.
+pub fn check_inputs(registry: &Registry) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for year in registry.years() {
+        let mut missing = Vec::new();
+        let mut count = 0;
+
+        for registration in registry.iter().filter(|x| x.year == year) {
+            count += 1;
+            let path = crate::input_file(year, registration.day);
+            match std::fs::read_to_string(&path) {
+                Ok(text) if text.trim().is_empty() => findings.push(Finding::error(
+                    format!("The input of {} day {:02} at {} is empty.", year, registration.day, path),
+                    "Delete it and download it again.",
+                )),
+                Ok(text) if text.starts_with(INPUT_ERROR_PAGE) => findings.push(Finding::error(
+                    format!(
+                        "The input of {} day {:02} at {} is the error page of an invalid session cookie.",
+                        year, registration.day, path
+                    ),
+                    "Delete it and download it again after fixing the session cookie.",
+                )),
+                Ok(_) => {}
+                Err(_) => missing.push(registration.day),
+            }
+        }
+
+        if missing.is_empty() {
+            findings.push(Finding::ok(format!(
+                "All {} inputs of {} are present.",
+                count, year
+            )));
+            continue;
+        }
+
+        let days: Vec<String> = missing.iter().map(|x| x.to_string()).collect();
+        let fix = if cfg!(feature = "fetch") {
+            format!("Download them with --fetch <day> --year {}.", year)
+        } else {
+            format!(
+                "Download them from https://adventofcode.com/{}/day/<day>/input to {}, or build with the fetch \
+                 feature and use --fetch <day>.",
+                year,
+                crate::input_directory().join(crate::config().input_layout()).display()
+            )
+        };
+        findings.push(Finding::warning(
+            format!(
+                "{} of {} inputs of {} are missing, of days {}. These days are skipped.",
+                missing.len(),
+                count,
+                year,
+                days.join(", ")
+            ),
+            fix,
+        ));
+    }
+
+    findings
+}
+
+/// Gets the time the most recently modified file in the provided directory was modified.
+fn newest_modification(directory: &Path) -> Option<SystemTime> {
+    std::fs::read_dir(directory)
+        .ok()?
+        .filter_map(|x| x.ok()?.metadata().ok()?.modified().ok())
+        .max()
+}
+
+/// Checks for cached puzzle descriptions that were downloaded before part 2 was unlocked, and for flame
+/// graphs in the provided directory that are older than the sources of their day.
+pub fn check_caches(registry: &Registry, repository: &Path, profiles: &Path) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for registration in registry.iter() {
+        let path = crate::day_file(registration.year, registration.day, "puzzle.md");
+        if let Ok(markdown) = std::fs::read_to_string(&path) {
+            if !markdown.contains("Part Two") {
+                findings.push(Finding::warning(
+                    format!(
+                        "{} was cached before part 1 was solved, so it lacks part 2.",
+                        path
+                    ),
+                    "Delete it, --read downloads it again.",
+                ));
+            }
+        }
+    }
+
+    let mut stale = Vec::new();
+    for entry in std::fs::read_dir(profiles).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // Flame graphs are named after the year and day they belong to, e.g. 2021-day07-part1.svg.
+        let Some((year, day)) = name
+            .split_once("-day")
+            .and_then(|(year, rest)| Some((year, rest.get(..2)?)))
+        else {
+            continue;
+        };
+
+        let sources = repository.join(format!("{}/day{}/src", year, day));
+        let modified = entry.metadata().and_then(|x| x.modified()).ok();
+        if let (Some(modified), Some(source)) = (modified, newest_modification(&sources)) {
+            if modified < source {
+                stale.push(name);
+            }
+        }
+    }
+
+    if !stale.is_empty() {
+        stale.sort();
+        findings.push(Finding::warning(
+            format!(
+                "{} flame graphs in {} are older than the sources of their day: {}",
+                stale.len(),
+                profiles.display(),
+                stale.join(", ")
+            ),
+            "Run the days again with the profile feature, or delete the flame graphs.",
+        ));
+    }
+
+    if findings.is_empty() {
+        findings.push(Finding::ok(
+            "No cached puzzle descriptions or flame graphs are stale.",
+        ));
+    }
+
+    findings
+}
+
+/// Checks which parts of the registered days have no sample with an expected answer in the manifest of their
+/// year, such that a wrong answer on the real input would go unnoticed.
+pub fn check_samples(registry: &Registry, repository: &Path) -> std::io::Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+
+    for year in registry.years() {
+        let path = repository.join(format!("{}/{}", year, samples::MANIFEST));
+        let manifest = samples::read_manifest(&path)?;
+
+        let mut unverified = Vec::new();
+        for registration in registry.iter().filter(|x| x.year == year) {
+            let samples: Vec<&samples::Sample> = manifest
+                .iter()
+                .filter(|x| x.day == registration.day)
+                .collect();
+
+            for sample in samples.iter() {
+                let file =
+                    repository.join(format!("{}/day{:02}/{}", year, sample.day, sample.file));
+                if !file.exists() {
+                    findings.push(Finding::error(
+                        format!(
+                            "The sample {} of {} day {:02} in {} does not exist.",
+                            sample.file,
+                            year,
+                            sample.day,
+                            path.display()
+                        ),
+                        "Save the example of the puzzle description under that name, or remove it from the manifest.",
+                    ));
+                }
+            }
+
+            let verified: Vec<bool> = (0..2)
+                .map(|part| samples.iter().any(|x| x.answers[part].is_some()))
+                .collect();
+            match (verified[0], verified[1]) {
+                (true, true) => {}
+                (false, false) => unverified.push(format!("day {:02}", registration.day)),
+                (true, false) => unverified.push(format!("day {:02} part 2", registration.day)),
+                (false, true) => unverified.push(format!("day {:02} part 1", registration.day)),
+            }
+        }
+
+        if unverified.is_empty() {
+            findings.push(Finding::ok(format!(
+                "The answers of every day of {} are verified on samples.",
+                year
+            )));
+        } else {
+            findings.push(Finding::warning(
+                format!(
+                    "Answers of {} that are not verified on any sample: {}",
+                    year,
+                    unverified.join(", ")
+                ),
+                format!(
+                    "Add the answers of the examples in the puzzle descriptions to {}, and check them with \
+                     --with-samples.",
+                    path.display()
+                ),
+            ));
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Runs all checks and prints their findings, grouped by check. Returns the number of errors.
+pub fn run_doctor(
+    registry: &Registry,
+    repository: &Path,
+    profiles: &Path,
+    year: u16,
+) -> std::io::Result<usize> {
+    let sections = [
+        ("Session cookie", check_session(year)),
+        ("Input directory", check_layout()),
+        ("Inputs", check_inputs(registry)),
+        ("Cached files", check_caches(registry, repository, profiles)),
+        ("Samples", check_samples(registry, repository)?),
+    ];
+
+    let mut errors = 0;
+    let mut warnings = 0;
+    for (index, (title, findings)) in sections.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!("{}", title);
+
+        for finding in findings {
+            let label = match finding.severity {
+                Severity::Ok => "ok",
+                Severity::Warning => {
+                    warnings += 1;
+                    "warning"
+                }
+                Severity::Error => {
+                    errors += 1;
+                    "error"
+                }
+            };
+            println!("  {:<8}{}", label, finding.message);
+            if let Some(fix) = &finding.fix {
+                println!("  {:<8}{}", "", fix);
+            }
+        }
+    }
+
+    println!();
+    println!("{} errors, {} warnings.", errors, warnings);
+    Ok(errors)
+}
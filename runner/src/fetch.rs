@@ -0,0 +1,298 @@
+//! Downloads puzzle inputs and descriptions from the Advent of Code website, and submits answers to it.
+//! Requests are authenticated with the session cookie of the website, which is read from the `AOC_SESSION`
+//! environment variable, or else from the `session_file` of the config file.
+//!
+//! The website is run by a single person, so we try hard not to hammer it: server errors are retried with
+//! exponential backoff, submissions that are rejected for coming too soon are retried only once the wait the
+//! website asks for has passed, and every request is appended to a log file that is kept across runs.
+
+use std::{
+    fs::OpenOptions,
+    io::{Error, ErrorKind, Write},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The website to download from.
+const SITE: &str = "https://adventofcode.com";
+
+/// Identifies this tool to the website, as requested by its maintainer.
+const USER_AGENT: &str = "github.com/Washi1337/advent-of-code";
+
+/// The file in the input directory that every request is appended to.
+const LOG_FILE: &str = "requests.log";
+
+/// The number of times a request is sent before a server error is given up on.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// The time to wait before retrying a request after the first server error. The wait doubles after every
+/// further error.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The wait assumed when the website rejects a submission for coming too soon without saying how long to wait.
+const DEFAULT_SUBMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// The response of the website to a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// The answer is correct.
+    Correct,
+
+    /// The answer is wrong. Contains the hint of the website, if any (e.g. "your answer is too low").
+    Incorrect(Option<String>),
+
+    /// The answer was not checked because the previous answer was submitted too recently. Contains the time
+    /// left to wait.
+    TooRecent(Duration),
+
+    /// The answer was not checked because the part is already solved or not unlocked yet.
+    WrongLevel,
+}
+
+/// Gets the session cookie from the environment or the session file of the config file, if either is set.
+fn session() -> Option<String> {
+    std::env::var("AOC_SESSION")
+        .ok()
+        .or_else(|| std::fs::read_to_string(crate::config().session_file.as_ref()?).ok())
+        .map(|x| x.trim().to_string())
+        .filter(|x| !x.is_empty())
+}
+
+/// Gets the session cookie from the environment, or an error explaining what the cookie is needed for.
+fn require_session(purpose: &str) -> std::io::Result<String> {
+    session().ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            format!(
+                "Set AOC_SESSION or the session_file in aoc.toml to the session cookie of adventofcode.com to {}.",
+                purpose
+            ),
+        )
+    })
+}
+
+/// Appends a request and its outcome to the request log. Failing to write the log does not fail the request.
+fn log_request(method: &str, path: &str, outcome: &str, elapsed: Duration) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let _ = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(crate::input_directory().join(LOG_FILE))
+        .and_then(|mut file| {
+            writeln!(
+                file,
+                "{} {} {} {} {}ms",
+                timestamp,
+                method,
+                path,
+                outcome,
+                elapsed.as_millis()
+            )
+        });
+}
+
+/// Sends a request to the provided path of the website and returns the body of the response as text. Bodies
+/// with form fields are sent as a POST request, others as a GET request. Server errors and connection
+/// failures are retried with exponential backoff, client errors are returned immediately.
+fn request(
+    path: &str,
+    session: Option<&str>,
+    form: Option<&[(&str, &str)]>,
+) -> std::io::Result<String> {
+    let method = if form.is_some() { "POST" } else { "GET" };
+    let mut backoff = INITIAL_BACKOFF;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request =
+            ureq::request(method, &format!("{}{}", SITE, path)).set("User-Agent", USER_AGENT);
+        if let Some(session) = session {
+            request = request.set("Cookie", &format!("session={}", session));
+        }
+
+        let start = Instant::now();
+        let result = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+
+        let error = match result {
+            Ok(response) => {
+                log_request(
+                    method,
+                    path,
+                    &response.status().to_string(),
+                    start.elapsed(),
+                );
+                return response.into_string();
+            }
+            Err(ureq::Error::Status(status, _)) if status < 500 => {
+                log_request(method, path, &status.to_string(), start.elapsed());
+                return Err(Error::other(format!(
+                    "{} {} returned status {}.",
+                    method, path, status
+                )));
+            }
+            Err(ureq::Error::Status(status, _)) => {
+                log_request(method, path, &status.to_string(), start.elapsed());
+                format!("status {}", status)
+            }
+            Err(ureq::Error::Transport(transport)) => {
+                log_request(method, path, "transport-error", start.elapsed());
+                transport.to_string()
+            }
+        };
+
+        if attempt == MAX_ATTEMPTS {
+            return Err(Error::other(format!(
+                "{} {} failed {} times, last with {}.",
+                method, path, MAX_ATTEMPTS, error
+            )));
+        }
+
+        eprintln!(
+            "{} {} failed with {}, retrying in {:?}.",
+            method, path, error, backoff
+        );
+        std::thread::sleep(backoff);
+        backoff *= 2;
+    }
+
+    unreachable!()
+}
+
+/// Downloads the HTML page with the description of the provided puzzle. The description of part 2 is only
+/// included if `AOC_SESSION` is set and part 1 was solved.
+pub fn fetch_puzzle(year: u16, day: u8) -> std::io::Result<String> {
+    request(
+        &format!("/{}/day/{}", year, day),
+        session().as_deref(),
+        None,
+    )
+}
+
+/// Downloads the personal puzzle input of the provided puzzle. Requires `AOC_SESSION` to be set.
+pub fn fetch_input(year: u16, day: u8) -> std::io::Result<String> {
+    let session = require_session("download inputs")?;
+    request(
+        &format!("/{}/day/{}/input", year, day),
+        Some(&session),
+        None,
+    )
+}
+
+/// Downloads the JSON of the private leaderboard with the provided ID. Requires `AOC_SESSION` to be set to
+/// the session of a member of the leaderboard.
+pub fn fetch_leaderboard(year: u16, id: u64) -> std::io::Result<String> {
+    let session = require_session("download leaderboards")?;
+    request(
+        &format!("/{}/leaderboard/private/view/{}.json", year, id),
+        Some(&session),
+        None,
+    )
+}
+
+/// Submits an answer to the provided part of a puzzle once, and returns the verdict of the website. Requires
+/// `AOC_SESSION` to be set.
+pub fn submit(year: u16, day: u8, part: u8, answer: &str) -> std::io::Result<Verdict> {
+    let session = require_session("submit answers")?;
+    let html = request(
+        &format!("/{}/day/{}/answer", year, day),
+        Some(&session),
+        Some(&[("level", &part.to_string()), ("answer", answer)]),
+    )?;
+
+    // The verdict is the only article on the page.
+    let message = html
+        .split_once("<article>")
+        .and_then(|(_, x)| x.split_once("</article>"))
+        .map_or(html.as_str(), |(x, _)| x);
+    Ok(parse_verdict(&strip_tags(message)))
+}
+
+/// Submits an answer to the provided part of a puzzle, and if the website asks to wait before submitting
+/// again, waits as long as it asks and submits again.
+pub fn submit_when_allowed(year: u16, day: u8, part: u8, answer: &str) -> std::io::Result<Verdict> {
+    loop {
+        match submit(year, day, part, answer)? {
+            Verdict::TooRecent(wait) => {
+                // Some slack, as the website rounds the time left down to whole seconds.
+                let wait = wait + Duration::from_secs(1);
+                println!(
+                    "Answered too recently, submitting again in {}s.",
+                    wait.as_secs()
+                );
+                std::thread::sleep(wait);
+            }
+            verdict => return Ok(verdict),
+        }
+    }
+}
+
+/// Removes all HTML tags from the provided text.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Interprets the message the website responds to a submission with.
+fn parse_verdict(message: &str) -> Verdict {
+    if message.contains("That's the right answer") {
+        Verdict::Correct
+    } else if message.contains("answer too recently") {
+        Verdict::TooRecent(parse_wait(message).unwrap_or(DEFAULT_SUBMIT_WAIT))
+    } else if message.contains("That's not the right answer") {
+        let hint = ["your answer is too low", "your answer is too high"]
+            .into_iter()
+            .find(|x| message.contains(x))
+            .map(String::from);
+        Verdict::Incorrect(hint)
+    } else {
+        Verdict::WrongLevel
+    }
+}
+
+/// Parses the time left to wait from a message that rejects a submission for coming too soon. The website
+/// either says "You have 1m 23s left to wait" or "please wait 5 minutes".
+fn parse_wait(message: &str) -> Option<Duration> {
+    if let Some((_, rest)) = message.split_once("You have ") {
+        let (left, _) = rest.split_once(" left to wait")?;
+        let mut seconds = 0;
+        for part in left.split_whitespace() {
+            let unit = match part.chars().last()? {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            seconds += part[..part.len() - 1].parse::<u64>().ok()? * unit;
+        }
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let (_, rest) = message.split_once("wait ")?;
+    let mut words = rest.split_whitespace();
+    let count = match words.next()? {
+        "one" | "a" | "an" => 1,
+        "five" => 5,
+        x => x.parse().ok()?,
+    };
+    let unit = match words.next()?.trim_end_matches(['.', ',']) {
+        "second" | "seconds" => 1,
+        "minute" | "minutes" => 60,
+        "hour" | "hours" => 3600,
+        _ => return None,
+    };
+    Some(Duration::from_secs(count * unit))
+}
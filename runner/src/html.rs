@@ -0,0 +1,125 @@
+//! A static HTML page that summarizes the answers and timings of all days.
+
+use crate::report::Timing;
+use aoc_common::{Registration, Run};
+use std::io::Write;
+
+/// The width of a sparkline in pixels.
+const SPARKLINE_WIDTH: f64 = 120.0;
+
+/// The height of a sparkline in pixels.
+const SPARKLINE_HEIGHT: f64 = 24.0;
+
+/// Escapes the characters that have a special meaning in HTML.
+fn escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Renders the provided total times as an inline SVG line chart, scaled such that the slowest time touches the
+/// top. Returns an empty string if there are fewer than two points to draw.
+fn sparkline(totals: &[f64]) -> String {
+    if totals.len() < 2 {
+        return String::new();
+    }
+
+    let max = totals.iter().cloned().fold(f64::MIN_POSITIVE, f64::max);
+    let step = SPARKLINE_WIDTH / (totals.len() - 1) as f64;
+    let points: Vec<String> = totals
+        .iter()
+        .enumerate()
+        .map(|(i, &total)| {
+            let y = SPARKLINE_HEIGHT - 1.0 - total / max * (SPARKLINE_HEIGHT - 2.0);
+            format!("{:.1},{:.1}", i as f64 * step, y)
+        })
+        .collect();
+
+    format!(
+        "<svg width=\"{}\" height=\"{}\"><polyline fill=\"none\" stroke=\"currentColor\" points=\"{}\"/></svg>",
+        SPARKLINE_WIDTH,
+        SPARKLINE_HEIGHT,
+        points.join(" ")
+    )
+}
+
+/// Writes a single HTML page with a table of the answers and timings of the provided runs. Every entry in
+/// `history` is an earlier benchmark report (oldest first), which is drawn together with the current run as a
+/// sparkline of the total time of every day.
+pub fn write_html(
+    writer: &mut impl Write,
+    runs: &[(&Registration, Run)],
+    history: &[Vec<Timing>],
+) -> std::io::Result<()> {
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, "<html>")?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, "<meta charset=\"utf-8\">")?;
+    writeln!(writer, "<title>Advent of Code results</title>")?;
+    writeln!(
+        writer,
+        "<style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }} \
+         td, th {{ padding: 4px 8px; border-bottom: 1px solid #ccc; }} \
+         td.time {{ text-align: right; }} pre {{ margin: 0; }}</style>"
+    )?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>Advent of Code results</h1>")?;
+    writeln!(writer, "<table>")?;
+    writeln!(
+        writer,
+        "<tr><th>Day</th><th>Part 1</th><th>Part 2</th><th>Parse</th><th>Part 1</th><th>Part 2</th>\
+         <th>Total</th><th>History</th></tr>"
+    )?;
+
+    let mut total = 0;
+    for (registration, run) in runs {
+        let mut totals: Vec<f64> = history
+            .iter()
+            .filter_map(|timings| {
+                timings
+                    .iter()
+                    .find(|x| x.year == registration.year && x.day == registration.day)
+            })
+            .map(|x| x.total().as_micros() as f64)
+            .collect();
+        totals.push(run.total_time().as_micros() as f64);
+        total += run.total_time().as_micros();
+
+        writeln!(
+            writer,
+            "<tr><td>{} day {:02}</td><td><pre>{}</pre></td><td><pre>{}</pre></td>\
+             <td class=\"time\">{}us</td><td class=\"time\">{}us</td><td class=\"time\">{}us</td>\
+             <td class=\"time\">{}us</td><td>{}</td></tr>",
+            registration.year,
+            registration.day,
+            escape(&run.answer1.to_string()),
+            escape(&run.answer2.to_string()),
+            run.time_parse.as_micros(),
+            run.time1.as_micros(),
+            run.time2.as_micros(),
+            run.total_time().as_micros(),
+            sparkline(&totals)
+        )?;
+    }
+
+    writeln!(writer, "</table>")?;
+    writeln!(
+        writer,
+        "<p>Total: {}us parsing and solving {} days.</p>",
+        total,
+        runs.len()
+    )?;
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")?;
+
+    writer.flush()
+}
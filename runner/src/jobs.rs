@@ -0,0 +1,59 @@
+//! Runs days on a pool of threads. Every day collects its output into a value that is handed to the main thread,
+//! which prints it, such that the output of days that run at the same time never interleaves.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// Runs `work` on every item on up to `jobs` threads, and calls `emit` on the calling thread with the index of
+/// every item and its result. Results are emitted in the order of the items, or if `stream` is set, as soon as
+/// they are done.
+pub fn run_jobs<I: Sync, T: Send>(
+    items: &[I],
+    jobs: usize,
+    stream: bool,
+    work: impl Fn(&I) -> T + Sync,
+    mut emit: impl FnMut(usize, T),
+) {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let sender = sender.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                if sender.send((index, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+
+        // Only the workers may keep the channel open, so receiving ends once all of them are done.
+        drop(sender);
+
+        let mut pending: Vec<Option<T>> = items.iter().map(|_| None).collect();
+        let mut emitted = 0;
+        for (index, result) in receiver {
+            if stream {
+                emit(index, result);
+                continue;
+            }
+
+            // Hold results back until all earlier items are emitted.
+            pending[index] = Some(result);
+            while let Some(result) = pending.get_mut(emitted).and_then(Option::take) {
+                emit(emitted, result);
+                emitted += 1;
+            }
+        }
+    });
+}
@@ -0,0 +1,367 @@
+//! A minimal JSON reader for the documents the runner consumes, such as leaderboards of the Advent of Code
+//! API and the JSON output of earlier runs.
+
+use std::time::Duration;
+
+/// A value of a JSON document.
+#[derive(Debug)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// A number, kept as its literal text such that integers beyond 2^53 stay exact.
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parses a complete JSON document.
+    pub fn parse(json: &str) -> Result<Json, String> {
+        let mut parser = Parser {
+            data: json.as_bytes(),
+            position: 0,
+        };
+        let root = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position != parser.data.len() {
+            return Err(parser.error("the end of the document"));
+        }
+        Ok(root)
+    }
+
+    /// Gets the value of the provided field if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an unsigned integer if it is a non-negative number.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Json::Number(x) => x.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a string if it is one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as the text of an answer, which is either a string or a number.
+    pub fn as_answer(&self) -> Option<&str> {
+        match self {
+            Json::String(x) | Json::Number(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Gets the items of the value if it is an array.
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+/// A recursive descent parser for JSON documents.
+struct Parser<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, expected: &str) -> String {
+        format!("Expected {} at position {}.", expected, self.position)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .data
+            .get(self.position)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.position += 1;
+        }
+    }
+
+    /// Skips whitespace and consumes the provided byte if it is next.
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        if self.data.get(self.position) == Some(&c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", c as char)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        let rest = &self.data[self.position..];
+        for (literal, value) in [
+            (&b"null"[..], Json::Null),
+            (b"true", Json::Bool(true)),
+            (b"false", Json::Bool(false)),
+        ] {
+            if rest.starts_with(literal) {
+                self.position += literal.len();
+                return Ok(value);
+            }
+        }
+
+        match rest.first() {
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                if !self.eat(b']') {
+                    loop {
+                        items.push(self.value()?);
+                        if self.eat(b']') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Ok(Json::Array(items))
+            }
+            Some(b'{') => {
+                self.position += 1;
+                let mut fields = Vec::new();
+                if !self.eat(b'}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(b':')?;
+                        fields.push((key, self.value()?));
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Ok(Json::Object(fields))
+            }
+            _ => {
+                let length = rest
+                    .iter()
+                    .take_while(|&&c| c.is_ascii_digit() || b"+-.eE".contains(&c))
+                    .count();
+                let number = std::str::from_utf8(&rest[..length])
+                    .ok()
+                    .filter(|x| x.parse::<f64>().is_ok())
+                    .ok_or_else(|| self.error("a value"))?
+                    .to_string();
+                self.position += length;
+                Ok(Json::Number(number))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.data.get(self.position) != Some(&b'"') {
+            return Err(self.error("a string"));
+        }
+        self.position += 1;
+
+        let mut result = Vec::new();
+        loop {
+            let c = *self
+                .data
+                .get(self.position)
+                .ok_or_else(|| self.error("the end of the string"))?;
+            self.position += 1;
+
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = *self
+                        .data
+                        .get(self.position)
+                        .ok_or_else(|| self.error("an escape sequence"))?;
+                    self.position += 1;
+
+                    let decoded = match escaped {
+                        b'n' => '\n',
+                        b't' => '\t',
+                        b'r' => '\r',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'u' => {
+                            // Names outside of the basic multilingual plane are rare enough to replace.
+                            let code = self
+                                .data
+                                .get(self.position..self.position + 4)
+                                .and_then(|x| std::str::from_utf8(x).ok())
+                                .and_then(|x| u32::from_str_radix(x, 16).ok())
+                                .ok_or_else(|| self.error("four hexadecimal digits"))?;
+                            self.position += 4;
+                            char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        x => x as char,
+                    };
+                    result.extend_from_slice(decoded.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                x => result.push(x),
+            }
+        }
+
+        String::from_utf8(result).map_err(|_| self.error("a UTF-8 string"))
+    }
+}
+
+/// The answers and timings of a single day, as written by the runner with `--format json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayResult {
+    /// The year of the puzzle.
+    pub year: u16,
+
+    /// The day of the puzzle.
+    pub day: u8,
+
+    /// The answers to the first and second part.
+    pub answers: [String; 2],
+
+    /// The time it took to parse the input and to solve both parts, if the document has them.
+    pub times: Option<[Duration; 3]>,
+}
+
+/// Reads the results of a document in the shape of the runner's JSON output, i.e.
+/// `{"results":[{"year":..,"day":..,"part1":{"answer":..},"part2":{"answer":..}}]}`. Answers may be strings or
+/// numbers, and the resource reports of the phases are optional.
+pub fn parse_results(json: &str) -> Result<Vec<DayResult>, String> {
+    let root = Json::parse(json)?;
+    let results = root
+        .get("results")
+        .and_then(Json::as_array)
+        .ok_or("Expected a results array.")?;
+
+    results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| {
+            let field = |name: &str| {
+                result
+                    .get(name)
+                    .and_then(Json::as_u64)
+                    .ok_or_else(|| format!("Expected a {} in result {}.", name, index + 1))
+            };
+            let year = field("year")? as u16;
+            let day = field("day")? as u8;
+
+            let answer = |part: &str| {
+                result
+                    .get(part)
+                    .and_then(|x| x.get("answer"))
+                    .and_then(Json::as_answer)
+                    .map(String::from)
+                    .ok_or_else(|| {
+                        format!("Expected an answer to {} of {} day {}.", part, year, day)
+                    })
+            };
+            let time = |phase: &str| {
+                result
+                    .get(phase)?
+                    .get("resources")?
+                    .get("time_ns")?
+                    .as_u64()
+                    .map(Duration::from_nanos)
+            };
+
+            Ok(DayResult {
+                year,
+                day,
+                answers: [answer("part1")?, answer("part2")?],
+                times: time("parse")
+                    .zip(time("part1"))
+                    .zip(time("part2"))
+                    .map(|((parse, part1), part2)| [parse, part1, part2]),
+            })
+        })
+        .collect()
+}
+
+/// The answers of a single day in an answers file from another repository, as read by [`parse_answers`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayAnswers {
+    /// The year of the puzzle, if the file says.
+    pub year: Option<u16>,
+
+    /// The day of the puzzle.
+    pub day: u8,
+
+    /// The answers to the first and second part, if the file has them.
+    pub answers: [Option<String>; 2],
+}
+
+/// Reads the answers of an answers file from another repository. Besides the runner's own JSON output, this
+/// accepts a plain array of days, in which every answer is either the value itself or an object with an
+/// `answer` field, and the year and either answer may be left out or `null`:
+///
+/// ```json
+/// [{"year":2021,"day":1,"part1":1616,"part2":"1645"},{"day":2,"part1":1762050}]
+/// ```
+pub fn parse_answers(json: &str) -> Result<Vec<DayAnswers>, String> {
+    let root = Json::parse(json)?;
+    let days = root
+        .get("results")
+        .unwrap_or(&root)
+        .as_array()
+        .ok_or("Expected an array of days, or an object with a results array.")?;
+
+    days.iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let day = entry
+                .get("day")
+                .and_then(Json::as_u64)
+                .ok_or_else(|| format!("Expected a day in entry {}.", index + 1))?;
+            let year = match entry.get("year") {
+                None | Some(Json::Null) => None,
+                Some(year) => Some(
+                    year.as_u64()
+                        .ok_or_else(|| format!("Expected a year in entry {}.", index + 1))?
+                        as u16,
+                ),
+            };
+
+            let answer = |part: &str| -> Result<Option<String>, String> {
+                let value = match entry.get(part) {
+                    None | Some(Json::Null) => return Ok(None),
+                    Some(value) => value.get("answer").unwrap_or(value),
+                };
+                match value {
+                    Json::Null => Ok(None),
+                    value => value
+                        .as_answer()
+                        .map(|x| Some(x.to_string()))
+                        .ok_or_else(|| {
+                            format!("Expected an answer to {} in entry {}.", part, index + 1)
+                        }),
+                }
+            };
+
+            Ok(DayAnswers {
+                year,
+                day: day as u8,
+                answers: [answer("part1")?, answer("part2")?],
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,133 @@
+//! Private leaderboards of the Advent of Code website, read from the JSON of their API and written as Markdown
+//! tables.
+
+use crate::json::Json;
+use std::{collections::HashMap, io::Write};
+
+/// A member of a private leaderboard.
+#[derive(Clone, Debug)]
+pub struct Member {
+    /// The user ID of the member.
+    pub id: u64,
+
+    /// The display name of the member. Anonymous members have no name.
+    pub name: Option<String>,
+
+    /// The number of stars the member collected.
+    pub stars: u64,
+
+    /// The score of the member on the leaderboard.
+    pub local_score: u64,
+}
+
+impl Member {
+    /// Gets the name to display for the member, which falls back to the user ID for anonymous members.
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+}
+
+/// A private leaderboard of a single event.
+#[derive(Clone, Debug)]
+pub struct Leaderboard {
+    /// The year of the event.
+    pub event: String,
+
+    /// The members of the leaderboard, ordered by descending score.
+    pub members: Vec<Member>,
+}
+
+/// Parses the JSON of a private leaderboard, as returned by `/<year>/leaderboard/private/view/<id>.json`.
+pub fn parse(json: &str) -> Result<Leaderboard, String> {
+    let root = Json::parse(json)?;
+
+    let event = root
+        .get("event")
+        .and_then(Json::as_str)
+        .ok_or("Expected an event in the leaderboard.")?
+        .to_string();
+
+    let mut members = match root.get("members") {
+        Some(Json::Object(members)) => members
+            .iter()
+            .map(|(key, member)| {
+                let field = |name: &str| {
+                    member
+                        .get(name)
+                        .and_then(Json::as_u64)
+                        .ok_or_else(|| format!("Expected {} for member {}.", name, key))
+                };
+
+                Ok(Member {
+                    id: field("id")?,
+                    name: member.get("name").and_then(Json::as_str).map(String::from),
+                    stars: field("stars")?,
+                    local_score: field("local_score")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        _ => return Err("Expected members in the leaderboard.".to_string()),
+    };
+
+    members.sort_by(|a, b| {
+        b.local_score
+            .cmp(&a.local_score)
+            .then(b.stars.cmp(&a.stars))
+            .then(a.id.cmp(&b.id))
+    });
+
+    Ok(Leaderboard { event, members })
+}
+
+/// Writes the leaderboard as a Markdown table. If an earlier snapshot of the leaderboard is provided, the
+/// stars and score every member gained since then are added, and members that joined since are marked.
+pub fn write_table(
+    writer: &mut impl Write,
+    leaderboard: &Leaderboard,
+    previous: Option<&Leaderboard>,
+) -> std::io::Result<()> {
+    let previous: Option<HashMap<u64, &Member>> =
+        previous.map(|x| x.members.iter().map(|m| (m.id, m)).collect());
+
+    writeln!(writer, "Leaderboard {}", leaderboard.event)?;
+    writeln!(writer)?;
+    write!(writer, "| Rank | Member | Stars | Score |")?;
+    if previous.is_some() {
+        write!(writer, " Change |")?;
+    }
+    writeln!(writer)?;
+    write!(writer, "|--:|---|--:|--:|")?;
+    if previous.is_some() {
+        write!(writer, "---|")?;
+    }
+    writeln!(writer)?;
+
+    for (rank, member) in leaderboard.members.iter().enumerate() {
+        write!(
+            writer,
+            "| {} | {} | {} | {} |",
+            rank + 1,
+            member.display_name().replace('|', "\\|"),
+            member.stars,
+            member.local_score
+        )?;
+
+        if let Some(previous) = &previous {
+            match previous.get(&member.id) {
+                Some(old) => write!(
+                    writer,
+                    " {:+} stars, {:+} points |",
+                    member.stars as i64 - old.stars as i64,
+                    member.local_score as i64 - old.local_score as i64
+                )?,
+                None => write!(writer, " new |")?,
+            }
+        }
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
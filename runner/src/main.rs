@@ -0,0 +1,759 @@
+#![allow(dead_code)]
+
+use aoc_common::{tracing, Budget, DayReport, Isa, JsonReport, Narrator, Registration, Registry};
+use config::Config;
+use report::Format;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: aoc_common::CountingAllocator = aoc_common::CountingAllocator;
+
+mod config;
+mod doctor;
+#[cfg(feature = "fetch")]
+mod fetch;
+mod html;
+mod jobs;
+mod json;
+mod leaderboard;
+mod order;
+mod puzzle;
+mod report;
+mod samples;
+mod watch;
+
+/// The directory the flame graphs are written to when the `profile` feature is enabled.
+const PROFILE_DIRECTORY: &str = "profiles";
+
+/// The directory that contains a directory per year with the crate directories of its days. Set by `--inputs`,
+/// and defaults to the root of the repository the runner was built from, such that the built binary finds the
+/// inputs regardless of the directory it is started from.
+static INPUT_DIRECTORY: OnceLock<PathBuf> = OnceLock::new();
+
+/// The defaults read from the config file. Set once the flags are parsed, as `--config` may point elsewhere.
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Builds a registry containing the solvers of all years.
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    aoc_2021::register_2021(&mut registry);
+    registry
+}
+
+/// Gets the directory that contains the crate directories of all days, see [`INPUT_DIRECTORY`].
+fn input_directory() -> &'static Path {
+    INPUT_DIRECTORY.get_or_init(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(".."))
+}
+
+/// Gets the path to the provided file in the crate directory of a day.
+fn day_file(year: u16, day: u8, file: &str) -> String {
+    input_directory()
+        .join(format!("{}/day{:02}/{}", year, day, file))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Gets the defaults read from the config file.
+fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// Gets the path to the input file of the provided day, laid out as the config file specifies.
+fn input_file(year: u16, day: u8) -> String {
+    input_directory()
+        .join(config().input_file(year, day))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Gets the path to the input file of the provided day.
+fn day_input(registration: &Registration) -> String {
+    input_file(registration.year, registration.day)
+}
+
+/// Gets the description of the provided day as Markdown. Descriptions are downloaded once and then read from
+/// `puzzle.md` next to the input of the day.
+fn read_puzzle(year: u16, day: u8) -> std::io::Result<String> {
+    let path = day_file(year, day, "puzzle.md");
+    if let Ok(markdown) = std::fs::read_to_string(&path) {
+        return Ok(markdown);
+    }
+
+    #[cfg(feature = "fetch")]
+    {
+        let markdown = puzzle::html_to_markdown(&fetch::fetch_puzzle(year, day)?);
+        std::fs::write(&path, &markdown)?;
+        Ok(markdown)
+    }
+
+    #[cfg(not(feature = "fetch"))]
+    Err(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("{} does not exist, and downloading it requires the fetch feature.", path),
+    ))
+}
+
+/// Downloads the input of the provided day, unless it already exists.
+#[cfg(feature = "fetch")]
+fn fetch_input(year: u16, day: u8) -> std::io::Result<()> {
+    let path = input_file(year, day);
+    if Path::new(&path).exists() {
+        println!("{} already exists.", path);
+    } else {
+        let input = fetch::fetch_input(year, day)?;
+        if let Some(directory) = Path::new(&path).parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(&path, input)?;
+        println!("Input written to {}.", path);
+    }
+    Ok(())
+}
+
+/// Solves the provided part of a day on its input, and submits the answer. If the website asks to wait before
+/// submitting again, waits as long as it asks.
+#[cfg(feature = "fetch")]
+fn submit_answer(registration: &Registration, part: u8) -> std::io::Result<()> {
+    let run = (registration.run)(&day_input(registration))?;
+    let answer = match part {
+        1 => run.answer1.to_string(),
+        2 => run.answer2.to_string(),
+        _ => panic!("Expected part 1 or 2 after --submit."),
+    };
+
+    println!("Submitting {} for {} day {:02} part {}.", answer, registration.year, registration.day, part);
+    match fetch::submit_when_allowed(registration.year, registration.day, part, &answer)? {
+        fetch::Verdict::Correct => println!("That's the right answer."),
+        fetch::Verdict::Incorrect(Some(hint)) => println!("That's not the right answer; {}.", hint),
+        fetch::Verdict::Incorrect(None) => println!("That's not the right answer."),
+        fetch::Verdict::WrongLevel => println!("The part is already solved or not unlocked yet."),
+        fetch::Verdict::TooRecent(_) => unreachable!(),
+    }
+    Ok(())
+}
+
+/// Gets the JSON of a private leaderboard, either downloaded by its ID for the provided year or read from a
+/// snapshot saved earlier.
+fn read_leaderboard(source: &str, year: u16) -> std::io::Result<String> {
+    if Path::new(source).is_file() {
+        return std::fs::read_to_string(source);
+    }
+
+    let id: u64 = source.parse().map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{} is neither a leaderboard ID nor a snapshot file.", source),
+        )
+    })?;
+
+    #[cfg(feature = "fetch")]
+    return fetch::fetch_leaderboard(year, id);
+
+    #[cfg(not(feature = "fetch"))]
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!(
+            "Downloading leaderboard {} of {} requires the fetch feature.",
+            id, year
+        ),
+    ))
+}
+
+/// The output of running a single day, collected such that days that run in parallel do not interleave.
+struct DayOutput {
+    /// The answers and timings, followed by the explanation of the answers if requested, as printed.
+    text: Vec<u8>,
+
+    /// The total time the solver took.
+    time: Duration,
+}
+
+/// Runs the provided solver on the input file of its day, and collects the answers and timings, and if
+/// requested, the explanation of the answers.
+fn run(registration: &Registration, explain: bool) -> std::io::Result<DayOutput> {
+    let _span = tracing::info_span!("day", year = registration.year, day = registration.day).entered();
+
+    if cfg!(feature = "profile") {
+        std::fs::create_dir_all(PROFILE_DIRECTORY)?;
+        aoc_common::set_profile_prefix(&format!(
+            "{}/{}-day{:02}",
+            PROFILE_DIRECTORY, registration.year, registration.day
+        ));
+    }
+
+    let run = (registration.run)(&day_input(registration))?;
+
+    let mut text = Vec::new();
+    writeln!(
+        text,
+        "{} day {:02}: {} / {} (parse: {}us, part 1: {}us, part 2: {}us)",
+        registration.year,
+        registration.day,
+        run.answer1,
+        run.answer2,
+        run.time_parse.as_micros(),
+        run.time1.as_micros(),
+        run.time2.as_micros()
+    )?;
+
+    if cfg!(feature = "alloc-stats") {
+        writeln!(
+            text,
+            "            allocations (parse: {} / {} bytes peak, part 1: {} / {} bytes peak, part 2: {} / {} bytes peak)",
+            run.alloc_parse.allocations,
+            run.alloc_parse.peak_bytes,
+            run.alloc1.allocations,
+            run.alloc1.peak_bytes,
+            run.alloc2.allocations,
+            run.alloc2.peak_bytes
+        )?;
+    }
+
+    if explain {
+        let mut sink = Narrator::indented(&mut text, "    ");
+        (registration.explain)(&day_input(registration), &mut sink)?;
+    }
+
+    Ok(DayOutput {
+        text,
+        time: run.total_time(),
+    })
+}
+
+fn main() -> std::io::Result<()> {
+    aoc_common::init_tracing();
+    let registry = registry();
+
+    let mut year = None;
+    let mut days = Vec::new();
+    let mut all = false;
+    let mut bench = false;
+    let mut budgets = false;
+    let mut with_samples = false;
+    let mut explain = false;
+    let mut iterations = 10;
+    let mut jobs = 1;
+    let mut stream = false;
+    let mut isa = None;
+    let mut format = None;
+    let mut json_numbers = false;
+    let mut compare = None;
+    let mut against = None;
+    let mut threshold = 10.0;
+    let mut html = None;
+    let mut history = Vec::new();
+    let mut shuffle_rounds = None;
+    let mut seed = aoc_common::seed();
+    let mut read = None;
+    let mut markdown = false;
+    let mut fetch = None;
+    let mut leaderboard = None;
+    let mut snapshot = None;
+    let mut diff = None;
+    let mut watch = None;
+    let mut analyze = None;
+    let mut doctor = false;
+    let mut submit = None;
+    let mut config_file = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--list" => {
+                for registration in registry.iter() {
+                    println!("{} day {:02}", registration.year, registration.day);
+                }
+                return Ok(());
+            }
+            "--all" => all = true,
+            "--year" => {
+                year = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<u16>().ok())
+                        .expect("Expected year after --year."),
+                )
+            }
+            "--bench" => bench = true,
+            "--budgets" => budgets = true,
+            "--with-samples" => with_samples = true,
+            "--explain" => explain = true,
+            "--iterations" => {
+                iterations = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected number of iterations after --iterations.")
+            }
+            "--jobs" => {
+                jobs = args
+                    .next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .filter(|&x| x > 0)
+                    .expect("Expected number of threads after --jobs.")
+            }
+            "--stream" => stream = true,
+            "--isa" => {
+                isa = Some(
+                    args.next()
+                        .expect("Expected instruction set after --isa.")
+                        .parse::<Isa>()
+                        .unwrap(),
+                )
+            }
+            "--format" => {
+                format = Some(
+                    args.next()
+                        .expect("Expected format after --format.")
+                        .parse()
+                        .unwrap(),
+                )
+            }
+            "--json-numbers" => json_numbers = true,
+            "--compare" => {
+                compare = Some(args.next().expect("Expected CSV file after --compare."))
+            }
+            "--against" => {
+                against = Some(args.next().expect("Expected answers file after --against."))
+            }
+            "--threshold" => {
+                threshold = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected percentage after --threshold.")
+            }
+            "--html" => html = Some(args.next().expect("Expected output directory after --html.")),
+            "--history" => history.push(args.next().expect("Expected CSV file after --history.")),
+            "--check-order" => {
+                shuffle_rounds = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<usize>().ok())
+                        .expect("Expected number of rounds after --check-order."),
+                )
+            }
+            "--read" => {
+                read = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<u8>().ok())
+                        .expect("Expected day after --read."),
+                )
+            }
+            "--markdown" => markdown = true,
+            "--fetch" => {
+                fetch = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<u8>().ok())
+                        .expect("Expected day after --fetch."),
+                )
+            }
+            "--leaderboard" => {
+                leaderboard = Some(
+                    args.next()
+                        .expect("Expected leaderboard ID or snapshot file after --leaderboard."),
+                )
+            }
+            "--snapshot" => {
+                snapshot = Some(args.next().expect("Expected JSON file after --snapshot."))
+            }
+            "--diff" => diff = Some(args.next().expect("Expected JSON file after --diff.")),
+            "--submit" => {
+                submit = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<u8>().ok())
+                        .expect("Expected part after --submit."),
+                )
+            }
+            "--doctor" => doctor = true,
+            "--analyze" => {
+                analyze = Some(args.next().expect("Expected analysis after --analyze."))
+            }
+            "--watch" => {
+                watch = Some(
+                    args.next()
+                        .and_then(|x| x.parse::<u8>().ok())
+                        .expect("Expected day after --watch."),
+                )
+            }
+            "--config" => {
+                config_file = Some(args.next().expect("Expected config file after --config."))
+            }
+            "--inputs" => {
+                let directory = args.next().expect("Expected input directory after --inputs.");
+                INPUT_DIRECTORY.set(PathBuf::from(directory)).expect("Expected --inputs only once.");
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .expect("Expected a number after --seed.");
+                aoc_common::set_seed(seed);
+            }
+            _ => days.push(
+                arg.parse::<u8>()
+                    .unwrap_or_else(|_| panic!("Unknown argument {}.", arg)),
+            ),
+        }
+    }
+
+    // Flags take precedence over the config file, so its defaults only fill in what the flags left open.
+    let loaded = Config::load(config_file.as_deref())?;
+    let config = CONFIG.get_or_init(|| loaded);
+    if let Some(directory) = &config.inputs {
+        let _ = INPUT_DIRECTORY.set(directory.clone());
+    }
+    let year = year.or(config.year);
+    let format = format.or(config.format).unwrap_or(Format::Markdown);
+    if let Some(isa) = isa.or(config.isa) {
+        aoc_common::set_isa(isa).unwrap_or_else(|error| panic!("{}", error));
+    }
+    for (year, day, name) in &config.strategies {
+        let registration = registry
+            .get(*year, *day)
+            .unwrap_or_else(|| panic!("No solver registered for {} day {}.", year, day));
+        if !registration.strategies.contains(&name.as_str()) {
+            panic!(
+                "Unknown strategy {} for {} day {} (expected one of: {}).",
+                name,
+                year,
+                day,
+                registration.strategies.join(", ")
+            );
+        }
+        aoc_common::set_strategy(*year, *day, name);
+    }
+
+    // Allocations are counted and profiles are written for the whole process, so days cannot share it.
+    if jobs > 1 && (cfg!(feature = "alloc-stats") || cfg!(feature = "profile")) {
+        eprintln!("Allocation counts and profiles are per process, so days run one at a time.");
+        jobs = 1;
+    }
+
+    // Single days default to the latest year, while running without days runs every year unless one is given.
+    let latest_year = registry.years().last().copied().unwrap_or(aoc_2021::YEAR);
+
+    if doctor {
+        let repository = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let profiles = Path::new(PROFILE_DIRECTORY);
+        if doctor::run_doctor(&registry, &repository, profiles, year.unwrap_or(latest_year))? > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(analysis) = analyze {
+        match analysis.as_str() {
+            "submarine" => {
+                let depths = input_file(aoc_2021::YEAR, 1);
+                let course = input_file(aoc_2021::YEAR, 2);
+                let trip = aoc_2021::submarine::Trip::read(&depths, &course)?;
+                trip.write_svg(&mut std::io::stdout().lock())?;
+            }
+            _ => panic!("Unknown analysis {} (expected submarine).", analysis),
+        }
+        return Ok(());
+    }
+
+    if let Some(day) = watch {
+        let year = year.unwrap_or(latest_year);
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").canonicalize()?;
+        let input = input_file(year, day);
+        return watch::watch(&root, input_directory(), Path::new(&input), year, day);
+    }
+
+    if let Some(part) = submit {
+        let year = year.unwrap_or(latest_year);
+        let [day] = days[..] else {
+            panic!("Expected a single day to submit with --submit.");
+        };
+        let registration = registry
+            .get(year, day)
+            .unwrap_or_else(|| panic!("No solver registered for {} day {}.", year, day));
+
+        #[cfg(feature = "fetch")]
+        return submit_answer(registration, part);
+        #[cfg(not(feature = "fetch"))]
+        panic!("The --submit flag requires the fetch feature ({} day {} part {}).", registration.year, day, part);
+    }
+
+    if let Some(day) = fetch {
+        #[cfg(feature = "fetch")]
+        fetch_input(year.unwrap_or(latest_year), day)?;
+        #[cfg(not(feature = "fetch"))]
+        panic!("The --fetch flag requires the fetch feature (day {}).", day);
+    }
+
+    if let Some(day) = read {
+        let text = read_puzzle(year.unwrap_or(latest_year), day)?;
+        if markdown {
+            print!("{}", text);
+        } else {
+            print!("{}", puzzle::render_terminal(&text));
+        }
+    }
+
+    if let Some(source) = leaderboard {
+        let invalid_data =
+            |error: String| std::io::Error::new(std::io::ErrorKind::InvalidData, error);
+
+        let json = read_leaderboard(&source, year.unwrap_or(latest_year))?;
+        let current = leaderboard::parse(&json).map_err(invalid_data)?;
+        let previous = diff
+            .map(|x| leaderboard::parse(&std::fs::read_to_string(x)?).map_err(invalid_data))
+            .transpose()?;
+
+        leaderboard::write_table(&mut std::io::stdout(), &current, previous.as_ref())?;
+        if let Some(path) = snapshot {
+            std::fs::write(&path, json)?;
+            println!("Snapshot written to {}.", path);
+        }
+        return Ok(());
+    }
+
+    if fetch.is_some() || read.is_some() {
+        return Ok(());
+    }
+
+    // Days that are solved but whose input was never downloaded would fail the whole run, so they are skipped.
+    let mut missing = Vec::new();
+    let selected: Vec<&Registration> = if all || days.is_empty() {
+        registry
+            .iter()
+            .filter(|x| year.is_none_or(|year| x.year == year))
+            .filter(|x| {
+                let exists = Path::new(&day_input(x)).exists();
+                if !exists {
+                    missing.push(format!("{} day {:02}", x.year, x.day));
+                }
+                exists
+            })
+            .collect()
+    } else {
+        let year = year.unwrap_or(latest_year);
+        days.iter()
+            .map(|&day| {
+                registry
+                    .get(year, day)
+                    .unwrap_or_else(|| panic!("No solver registered for {} day {}.", year, day))
+            })
+            .collect()
+    };
+
+    if !missing.is_empty() {
+        eprintln!("Input missing for {}.", missing.join(", "));
+    }
+
+    if selected.is_empty() {
+        if missing.is_empty() {
+            eprintln!("No solvers registered for {}.", year.unwrap_or(latest_year));
+        } else {
+            eprintln!("None of the selected days has an input, so there is nothing to run.");
+        }
+        std::process::exit(1);
+    }
+
+    if let Some(rounds) = shuffle_rounds {
+        let mut stable = true;
+        for registration in selected.into_iter().filter(|x| order::is_shufflable(x)) {
+            match order::check_order(registration, &day_input(registration), rounds, seed)? {
+                None => println!(
+                    "{} day {:02}: answers stable over {} shuffled inputs",
+                    registration.year, registration.day, rounds
+                ),
+                Some((round, file)) => {
+                    stable = false;
+                    println!(
+                        "{} day {:02}: answers differ for shuffled input {} ({})",
+                        registration.year, registration.day, round, file
+                    );
+                }
+            }
+        }
+
+        if !stable {
+            std::process::exit(1);
+        }
+    } else if let Some(directory) = html {
+        let history = history
+            .iter()
+            .map(|x| report::read_csv(x))
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        let mut runs = Vec::new();
+        for registration in selected {
+            runs.push((registration, (registration.run)(&day_input(registration))?));
+        }
+
+        std::fs::create_dir_all(&directory)?;
+        let path = Path::new(&directory).join("index.html");
+        html::write_html(&mut std::fs::File::create(&path)?, &runs, &history)?;
+        println!("Report written to {}.", path.display());
+    } else if budgets {
+        if cfg!(debug_assertions) {
+            eprintln!("Budgets are meant for optimized builds, so timings of this debug build will likely exceed them.");
+        }
+
+        let mut checked = 0;
+        let mut overruns = Vec::new();
+        for registration in selected.into_iter().filter(|x| x.budget != Budget::NONE) {
+            let timing = report::measure(registration, &day_input(registration), iterations)?;
+            overruns.extend(report::overruns(&timing, &registration.budget));
+            checked += 1;
+        }
+
+        for overrun in overruns.iter() {
+            println!(
+                "{} day {:02}: {} took {}, over its budget of {}",
+                overrun.year,
+                overrun.day,
+                overrun.phase,
+                report::format_duration(overrun.time),
+                report::format_duration(overrun.budget)
+            );
+        }
+
+        if !overruns.is_empty() {
+            std::process::exit(1);
+        }
+        println!("All {} days with a budget stayed within it.", checked);
+    } else if with_samples {
+        // The samples are committed to the repository, while the real inputs may live elsewhere.
+        let repository = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+
+        let mut manifests: Vec<(u16, Vec<samples::Sample>)> = Vec::new();
+        let mut mismatches = 0;
+        for registration in selected {
+            let year = registration.year;
+            if !manifests.iter().any(|x| x.0 == year) {
+                let path = repository.join(format!("{}/{}", year, samples::MANIFEST));
+                manifests.push((year, samples::read_manifest(&path)?));
+            }
+
+            let manifest = &manifests.iter().find(|x| x.0 == year).unwrap().1;
+            let directory = repository.join(format!("{}/day{:02}", year, registration.day));
+            mismatches += samples::run_with_samples(registration, manifest, &directory, &day_input(registration));
+        }
+
+        if mismatches > 0 {
+            println!("{} sample answers did not match the manifest.", mismatches);
+            std::process::exit(1);
+        }
+    } else if let Some(path) = against {
+        let theirs = json::parse_answers(&std::fs::read_to_string(&path)?)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+
+        let (mut agreements, mut disagreements, mut unknown) = (0, 0, 0);
+        for registration in selected {
+            let (year, day) = (registration.year, registration.day);
+            let entry = theirs
+                .iter()
+                .find(|x| x.day == day && x.year.is_none_or(|x| x == year));
+            let Some(entry) = entry else {
+                println!("{} day {:02}: not in {}", year, day, path);
+                unknown += 2;
+                continue;
+            };
+
+            let run = (registration.run)(&day_input(registration))?;
+            let ours = [run.answer1.to_string(), run.answer2.to_string()];
+            for (part, (ours, theirs)) in ours.iter().zip(entry.answers.iter()).enumerate() {
+                match theirs {
+                    Some(theirs) if theirs == ours => {
+                        println!("{} day {:02} part {}: {} (agree)", year, day, part + 1, ours);
+                        agreements += 1;
+                    }
+                    Some(theirs) => {
+                        println!(
+                            "{} day {:02} part {}: {} (disagree, theirs is {})",
+                            year,
+                            day,
+                            part + 1,
+                            ours,
+                            theirs
+                        );
+                        disagreements += 1;
+                    }
+                    None => {
+                        println!("{} day {:02} part {}: {} (no answer in {})", year, day, part + 1, ours, path);
+                        unknown += 1;
+                    }
+                }
+            }
+        }
+
+        println!(
+            "{} agreements, {} disagreements, {} parts without an answer to compare with.",
+            agreements, disagreements, unknown
+        );
+        if disagreements > 0 {
+            std::process::exit(1);
+        }
+    } else if bench || compare.is_some() {
+        let baseline = compare.map(|x| report::read_csv(&x)).transpose()?;
+
+        let mut timings = Vec::new();
+        for registration in selected {
+            timings.push(report::measure(
+                registration,
+                &day_input(registration),
+                iterations,
+            )?);
+        }
+
+        report::write_report(
+            &mut std::io::stdout(),
+            &timings,
+            baseline.as_deref(),
+            threshold,
+            format,
+            aoc_common::isa(),
+        )?;
+    } else if format == Format::Json {
+        let mut report = JsonReport {
+            seed: Some(seed),
+            numbers: json_numbers,
+            allocations: cfg!(feature = "alloc-stats"),
+            ..JsonReport::default()
+        };
+        let mut runs = Vec::with_capacity(selected.len());
+        jobs::run_jobs(&selected, jobs, false, |x| (x.run)(&day_input(x)), |_, run| runs.push(run));
+        for (registration, run) in selected.iter().zip(runs) {
+            report.days.push(DayReport {
+                year: registration.year,
+                day: registration.day,
+                run: run?,
+                analysis: None,
+            });
+        }
+        report.write_json(&mut std::io::stdout().lock())?;
+    } else {
+        let now = Instant::now();
+        let mut total = Duration::ZERO;
+        let mut error = None;
+        jobs::run_jobs(&selected, jobs, stream, |x| run(x, explain), |_, output| match output {
+            Ok(output) => {
+                let _ = std::io::stdout().lock().write_all(&output.text);
+                total += output.time;
+            }
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        });
+        if let Some(error) = error {
+            return Err(error);
+        }
+        let wall_time = now.elapsed();
+
+        println!(
+            "Total: {}us wall time ({}us parsing and solving {} days)",
+            wall_time.as_micros(),
+            total.as_micros(),
+            selected.len()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! Checks that the answers of days whose input lines can be reordered without changing the puzzle, do not
+//! change when the lines are shuffled.
+
+use aoc_common::{Registration, Rng};
+use std::io::Write;
+
+/// The days for which every permutation of the input lines describes the same puzzle: the line segments of
+/// day 5, the entries of day 8 and the edges of day 12.
+pub const SHUFFLABLE_DAYS: [(u16, u8); 3] = [(2021, 5), (2021, 8), (2021, 12)];
+
+/// Determines whether the input lines of the provided day can be shuffled.
+pub fn is_shufflable(registration: &Registration) -> bool {
+    SHUFFLABLE_DAYS.contains(&(registration.year, registration.day))
+}
+
+/// Runs the solver on `rounds` shuffled copies of the provided input file, and compares the answers with the
+/// answers for the original file. Returns the number of the first round (starting at 1) that gave different
+/// answers along with the shuffled file that reproduces it, or [`None`] if all answers agree.
+pub fn check_order(
+    registration: &Registration,
+    file: &str,
+    rounds: usize,
+    seed: u64,
+) -> std::io::Result<Option<(usize, String)>> {
+    let mut rng = Rng::new(seed);
+    let mut next = move || rng.next_usize();
+
+    let expected = (registration.run)(file)?;
+    let contents = std::fs::read_to_string(file)?;
+    let mut lines: Vec<&str> = contents.lines().filter(|x| !x.trim().is_empty()).collect();
+
+    let shuffled_file = std::env::temp_dir().join(format!(
+        "aoc-shuffled-{}-{:02}.txt",
+        registration.year, registration.day
+    ));
+    let shuffled_path = shuffled_file.to_string_lossy().into_owned();
+
+    for round in 1..=rounds {
+        // Fisher-Yates shuffle of the lines.
+        for i in (1..lines.len()).rev() {
+            lines.swap(i, next() % (i + 1));
+        }
+
+        let mut writer = std::fs::File::create(&shuffled_file)?;
+        for line in lines.iter() {
+            writeln!(writer, "{}", line)?;
+        }
+        drop(writer);
+
+        let actual = (registration.run)(&shuffled_path)?;
+        if actual.answer1 != expected.answer1 || actual.answer2 != expected.answer2 {
+            return Ok(Some((round, shuffled_path)));
+        }
+    }
+
+    std::fs::remove_file(&shuffled_file)?;
+    Ok(None)
+}
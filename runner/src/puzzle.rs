@@ -0,0 +1,210 @@
+//! Puzzle descriptions, converted from the HTML of their page on the Advent of Code website to Markdown, and
+//! rendered as styled text in the terminal.
+
+/// The website that relative links in puzzle descriptions point to.
+const SITE: &str = "https://adventofcode.com";
+
+/// Decodes the character references that occur in puzzle descriptions.
+fn decode_entities(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = match rest.find(';') {
+            Some(end) if end <= 10 => end,
+            _ => {
+                result.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+
+        let decoded = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            x if x.starts_with("#x") => u32::from_str_radix(&x[2..], 16)
+                .ok()
+                .and_then(char::from_u32),
+            x if x.starts_with('#') => x[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+
+        match decoded {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Gets the value of the provided attribute from the contents of a start tag, if present.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{}=\"", name))? + name.len() + 2;
+    let length = tag[start..].find('"')?;
+    Some(&tag[start..start + length])
+}
+
+/// Extracts the descriptions of the parts of a puzzle (the `<article>` elements) from the HTML of its page, and
+/// converts them to Markdown.
+pub fn html_to_markdown(html: &str) -> String {
+    let mut result = String::new();
+    let mut links = Vec::new();
+    let mut in_article = false;
+    let mut in_pre = false;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let text_end = rest.find('<').unwrap_or(rest.len());
+        if in_article {
+            let text = decode_entities(&rest[..text_end]);
+            if in_pre {
+                result.push_str(&text);
+            } else {
+                // Whitespace in the HTML source is insignificant outside of preformatted blocks.
+                let mut words = text.split_whitespace().peekable();
+                if text.starts_with(char::is_whitespace) && words.peek().is_some() {
+                    result.push(' ');
+                }
+                result.push_str(&words.collect::<Vec<_>>().join(" "));
+                if text.ends_with(char::is_whitespace) && !text.trim().is_empty() {
+                    result.push(' ');
+                }
+            }
+        }
+        rest = &rest[text_end..];
+        if rest.is_empty() {
+            break;
+        }
+
+        let tag_end = rest.find('>').map_or(rest.len(), |x| x + 1);
+        let tag = rest[1..tag_end].trim_end_matches('>');
+        rest = &rest[tag_end..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("");
+
+        match (closing, name) {
+            (false, "article") => in_article = true,
+            (true, "article") => {
+                in_article = false;
+                result.push_str("\n\n");
+            }
+            _ if !in_article => {}
+            (false, "h2") => result.push_str("## "),
+            (false, "pre") => {
+                in_pre = true;
+                result.push_str("```\n");
+            }
+            (true, "pre") => {
+                in_pre = false;
+                if !result.ends_with('\n') {
+                    result.push('\n');
+                }
+                result.push_str("```\n\n");
+            }
+            (_, "code") if !in_pre => result.push('`'),
+            (_, "em") => result.push_str("**"),
+            (false, "li") => result.push_str("- "),
+            (true, "h2" | "p" | "ul") => result.push_str("\n\n"),
+            (true, "li") => result.push('\n'),
+            (false, "a") => {
+                links.push(attribute(tag, "href").unwrap_or("").to_string());
+                result.push('[');
+            }
+            (true, "a") => {
+                let href = links.pop().unwrap_or_default();
+                let site = if href.starts_with('/') { SITE } else { "" };
+                result.push_str(&format!("]({}{})", site, href));
+            }
+            _ => {}
+        }
+    }
+
+    // Collapse the blank lines that pile up between consecutive blocks.
+    let mut markdown = String::with_capacity(result.len());
+    for line in result.lines() {
+        let line = line.trim_end();
+        if !line.is_empty() || !markdown.ends_with("\n\n") {
+            markdown.push_str(line);
+            markdown.push('\n');
+        }
+    }
+    markdown.trim().to_string() + "\n"
+}
+
+/// Renders Markdown produced by [`html_to_markdown`] as text with ANSI escape sequences: headings in bold
+/// yellow, emphasis in bold white, and code in cyan.
+pub fn render_terminal(markdown: &str) -> String {
+    const RESET: &str = "\x1b[0m";
+    const HEADING: &str = "\x1b[1;33m";
+    const EMPHASIS: &str = "\x1b[1;97m";
+    const CODE: &str = "\x1b[36m";
+
+    let mut result = String::with_capacity(markdown.len() * 2);
+    let mut in_fence = false;
+
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+
+        if in_fence {
+            result.push_str(&format!("    {}{}{}\n", CODE, line, RESET));
+            continue;
+        }
+
+        if let Some(heading) = line.strip_prefix("## ") {
+            result.push_str(&format!("{}{}{}\n", HEADING, heading, RESET));
+            continue;
+        }
+
+        // Emphasis and code can be nested, so restore the style of whatever is still open after every marker.
+        let mut bold = false;
+        let mut code = false;
+        let mut rest = line;
+        while let Some(index) = rest.find(['*', '`']) {
+            result.push_str(&rest[..index]);
+            if rest[index..].starts_with("**") {
+                bold = !bold;
+                rest = &rest[index + 2..];
+            } else if rest[index..].starts_with('`') {
+                code = !code;
+                rest = &rest[index + 1..];
+            } else {
+                result.push('*');
+                rest = &rest[index + 1..];
+                continue;
+            }
+
+            result.push_str(RESET);
+            if bold {
+                result.push_str(EMPHASIS);
+            }
+            if code {
+                result.push_str(CODE);
+            }
+        }
+        result.push_str(rest);
+        result.push('\n');
+    }
+
+    result
+}
@@ -0,0 +1,334 @@
+//! Benchmark reports over all days, written as Markdown or CSV tables, or as JSON.
+
+use aoc_common::{Budget, Isa, Registration, Scratch};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    time::Duration,
+};
+
+/// The formats a benchmark report can be written in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    /// A Markdown table with human readable durations.
+    Markdown,
+
+    /// A CSV table with durations in nanoseconds, which can be read back with [`read_csv`].
+    Csv,
+
+    /// A JSON document with durations in nanoseconds. Outside of benchmarks, the answers of every day are
+    /// written as an [`aoc_common::JsonReport`] instead.
+    Json,
+}
+
+/// The median timings of a single day over a number of runs.
+#[derive(Clone, Copy, Debug)]
+pub struct Timing {
+    /// The year of the puzzle.
+    pub year: u16,
+
+    /// The day of the puzzle.
+    pub day: u8,
+
+    /// The time it took to parse the input.
+    pub parse: Duration,
+
+    /// The time it took to solve the first part.
+    pub part1: Duration,
+
+    /// The time it took to solve the second part.
+    pub part2: Duration,
+}
+
+impl Timing {
+    /// Gets the total time of all phases.
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1 + self.part2
+    }
+}
+
+/// A phase of a day whose median time exceeded the budget of the day.
+#[derive(Clone, Copy, Debug)]
+pub struct Overrun {
+    /// The year of the puzzle.
+    pub year: u16,
+
+    /// The day of the puzzle.
+    pub day: u8,
+
+    /// The name of the phase.
+    pub phase: &'static str,
+
+    /// The median time the phase took.
+    pub time: Duration,
+
+    /// The budget of the phase.
+    pub budget: Duration,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" => Ok(Format::Markdown),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown format {}.", s)),
+        }
+    }
+}
+
+/// Runs the solver on the provided file the provided number of times, and returns the median time of every
+/// phase. The solver keeps its scratch buffers between runs, so only the first run allocates them.
+pub fn measure(
+    registration: &Registration,
+    file: &str,
+    iterations: usize,
+) -> std::io::Result<Timing> {
+    let mut parse = Vec::with_capacity(iterations);
+    let mut part1 = Vec::with_capacity(iterations);
+    let mut part2 = Vec::with_capacity(iterations);
+
+    let mut scratch = Scratch::new();
+    for _ in 0..iterations.max(1) {
+        let run = (registration.run_with)(file, &mut scratch)?;
+        parse.push(run.time_parse);
+        part1.push(run.time1);
+        part2.push(run.time2);
+    }
+
+    fn median(values: &mut [Duration]) -> Duration {
+        let middle = values.len() / 2;
+        *values.select_nth_unstable(middle).1
+    }
+
+    Ok(Timing {
+        year: registration.year,
+        day: registration.day,
+        parse: median(&mut parse),
+        part1: median(&mut part1),
+        part2: median(&mut part2),
+    })
+}
+
+/// Reads the timings of an earlier run that were written with [`Format::Csv`].
+pub fn read_csv(file: &str) -> std::io::Result<Vec<Timing>> {
+    let invalid_data = |line: usize| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Line {} is not a valid timing.", line + 1),
+        )
+    };
+
+    let mut timings = Vec::new();
+    for (index, line) in BufReader::new(File::open(file)?)
+        .lines()
+        .enumerate()
+        .skip(1)
+    {
+        let line = line?;
+        let fields: Vec<u64> = line
+            .trim_end()
+            .split(',')
+            .take(5)
+            .map(|x| x.parse::<u64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid_data(index))?;
+
+        if fields.len() != 5 {
+            return Err(invalid_data(index));
+        }
+
+        timings.push(Timing {
+            year: fields[0] as u16,
+            day: fields[1] as u8,
+            parse: Duration::from_nanos(fields[2]),
+            part1: Duration::from_nanos(fields[3]),
+            part2: Duration::from_nanos(fields[4]),
+        });
+    }
+
+    Ok(timings)
+}
+
+/// Gets every phase of the timing that took longer than the provided budget allows.
+pub fn overruns(timing: &Timing, budget: &Budget) -> Vec<Overrun> {
+    [
+        ("parse", timing.parse, budget.parse),
+        ("part 1", timing.part1, budget.part1),
+        ("part 2", timing.part2, budget.part2),
+    ]
+    .into_iter()
+    .filter_map(|(phase, time, budget)| {
+        let budget = budget.filter(|&x| time > x)?;
+        Some(Overrun {
+            year: timing.year,
+            day: timing.day,
+            phase,
+            time,
+            budget,
+        })
+    })
+    .collect()
+}
+
+/// Formats a duration with a unit that keeps the number readable.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+    if nanos < 1e3 {
+        format!("{} ns", nanos)
+    } else if nanos < 1e6 {
+        format!("{:.1} us", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.1} ms", nanos / 1e6)
+    } else {
+        format!("{:.2} s", nanos / 1e9)
+    }
+}
+
+/// Writes the timings as a table in the provided format. If baseline timings are provided, the total time of
+/// every day is compared against its baseline, and days that got slower by more than `threshold` percent are
+/// marked as a regression. Markdown and JSON reports name the instruction set the days ran with, as timings
+/// with different instruction sets cannot be compared.
+pub fn write_report(
+    writer: &mut impl Write,
+    timings: &[Timing],
+    baseline: Option<&[Timing]>,
+    threshold: f64,
+    format: Format,
+    isa: Isa,
+) -> std::io::Result<()> {
+    let baseline: Option<HashMap<(u16, u8), Timing>> =
+        baseline.map(|x| x.iter().map(|t| ((t.year, t.day), *t)).collect());
+
+    // Gets the baseline total and relative change in percent of a day, if there is a baseline for it.
+    let compare = |timing: &Timing| {
+        let old = baseline.as_ref()?.get(&(timing.year, timing.day))?.total();
+        let change = (timing.total().as_secs_f64() / old.as_secs_f64() - 1.0) * 100.0;
+        Some((old, change))
+    };
+
+    match format {
+        Format::Markdown => {
+            writeln!(writer, "Instruction set: {}", isa)?;
+            writeln!(writer)?;
+
+            write!(writer, "| Day | Parse | Part 1 | Part 2 | Total |")?;
+            if baseline.is_some() {
+                write!(writer, " Baseline | Change |")?;
+            }
+            writeln!(writer)?;
+
+            write!(writer, "|---|--:|--:|--:|--:|")?;
+            if baseline.is_some() {
+                write!(writer, "--:|--:|")?;
+            }
+            writeln!(writer)?;
+
+            for timing in timings {
+                write!(
+                    writer,
+                    "| {} day {:02} | {} | {} | {} | {} |",
+                    timing.year,
+                    timing.day,
+                    format_duration(timing.parse),
+                    format_duration(timing.part1),
+                    format_duration(timing.part2),
+                    format_duration(timing.total())
+                )?;
+
+                if baseline.is_some() {
+                    match compare(timing) {
+                        Some((old, change)) => write!(
+                            writer,
+                            " {} | {:+.1}%{} |",
+                            format_duration(old),
+                            change,
+                            if change > threshold {
+                                " (regression)"
+                            } else {
+                                ""
+                            }
+                        )?,
+                        None => write!(writer, " | |")?,
+                    }
+                }
+
+                writeln!(writer)?;
+            }
+        }
+
+        Format::Csv => {
+            write!(writer, "year,day,parse_ns,part1_ns,part2_ns,total_ns")?;
+            if baseline.is_some() {
+                write!(writer, ",baseline_total_ns,change_percent,regression")?;
+            }
+            writeln!(writer)?;
+
+            for timing in timings {
+                write!(
+                    writer,
+                    "{},{},{},{},{},{}",
+                    timing.year,
+                    timing.day,
+                    timing.parse.as_nanos(),
+                    timing.part1.as_nanos(),
+                    timing.part2.as_nanos(),
+                    timing.total().as_nanos()
+                )?;
+
+                if baseline.is_some() {
+                    match compare(timing) {
+                        Some((old, change)) => write!(
+                            writer,
+                            ",{},{:.1},{}",
+                            old.as_nanos(),
+                            change,
+                            change > threshold
+                        )?,
+                        None => write!(writer, ",,,")?,
+                    }
+                }
+
+                writeln!(writer)?;
+            }
+        }
+
+        Format::Json => {
+            write!(writer, "{{\"isa\":\"{}\",\"timings\":[", isa)?;
+            for (index, timing) in timings.iter().enumerate() {
+                write!(
+                    writer,
+                    "{}{{\"year\":{},\"day\":{},\"parse_ns\":{},\"part1_ns\":{},\"part2_ns\":{},\"total_ns\":{}",
+                    if index > 0 { "," } else { "" },
+                    timing.year,
+                    timing.day,
+                    timing.parse.as_nanos(),
+                    timing.part1.as_nanos(),
+                    timing.part2.as_nanos(),
+                    timing.total().as_nanos()
+                )?;
+
+                if baseline.is_some() {
+                    match compare(timing) {
+                        Some((old, change)) => write!(
+                            writer,
+                            ",\"baseline_total_ns\":{},\"change_percent\":{:.1},\"regression\":{}",
+                            old.as_nanos(),
+                            change,
+                            change > threshold
+                        )?,
+                        None => write!(writer, ",\"baseline_total_ns\":null")?,
+                    }
+                }
+
+                write!(writer, "}}")?;
+            }
+            writeln!(writer, "]}}")?;
+        }
+    }
+
+    writer.flush()
+}
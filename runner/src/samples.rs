@@ -0,0 +1,191 @@
+//! Runs days on the sample inputs of their puzzle descriptions next to their real inputs, and checks the
+//! answers to the samples against a manifest.
+//!
+//! Every year has a manifest `samples.txt` in its directory of the repository. Every line holds the day, the
+//! sample file in the crate directory of the day, the part and the expected answer, separated by spaces:
+//!
+//! ```text
+//! # day file part answer
+//! 1 input2.txt 1 7
+//! 1 input2.txt 2 5
+//! ```
+//!
+//! Answers extend to the end of the line, so they may contain spaces. Parts without an expected answer are
+//! run, but not checked.
+
+use aoc_common::Registration;
+use std::{
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+/// The name of the manifest in the directory of every year.
+pub const MANIFEST: &str = "samples.txt";
+
+/// A sample input of a day and its expected answers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    /// The day of the puzzle.
+    pub day: u8,
+
+    /// The name of the sample file in the crate directory of the day.
+    pub file: String,
+
+    /// The expected answers to both parts, if known.
+    pub answers: [Option<String>; 2],
+}
+
+/// The outcome of running a day on a single input.
+enum Outcome {
+    /// Both parts were solved.
+    Solved([String; 2]),
+
+    /// The input could not be read or parsed, or the solver panicked.
+    Failed(String),
+}
+
+/// Reads the manifest with the samples of a year. A year without a manifest has no samples.
+pub fn read_manifest(path: &Path) -> std::io::Result<Vec<Sample>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    let mut samples: Vec<Sample> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid_data = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "{}: line {} is not of the form `day file part answer`.",
+                    path.display(),
+                    index + 1
+                ),
+            )
+        };
+
+        let mut fields = line.splitn(4, ' ');
+        let day: u8 = fields
+            .next()
+            .and_then(|x| x.parse().ok())
+            .ok_or_else(invalid_data)?;
+        let file = fields.next().ok_or_else(invalid_data)?;
+        let part: usize = fields
+            .next()
+            .and_then(|x| x.parse().ok())
+            .filter(|x| (1..=2).contains(x))
+            .ok_or_else(invalid_data)?;
+        let answer = fields.next().map(str::trim).ok_or_else(invalid_data)?;
+
+        let sample = match samples.iter_mut().find(|x| x.day == day && x.file == file) {
+            Some(sample) => sample,
+            None => {
+                samples.push(Sample {
+                    day,
+                    file: file.to_string(),
+                    answers: [None, None],
+                });
+                samples.last_mut().unwrap()
+            }
+        };
+        sample.answers[part - 1] = Some(answer.to_string());
+    }
+
+    Ok(samples)
+}
+
+/// Runs the day on the provided file, catching panics such that one broken sample does not end the whole run.
+fn solve(registration: &Registration, file: &str) -> Outcome {
+    let run = registration.run;
+    match std::panic::catch_unwind(|| run(file)) {
+        Ok(Ok(run)) => Outcome::Solved([run.answer1.to_string(), run.answer2.to_string()]),
+        Ok(Err(error)) => Outcome::Failed(error.to_string()),
+        Err(_) => Outcome::Failed(String::from("panicked")),
+    }
+}
+
+/// Runs the day on all of its samples and on its real input, and prints the answers next to each other, one
+/// column per input. Returns the number of samples whose answers did not match the manifest.
+pub fn run_with_samples(
+    registration: &Registration,
+    samples: &[Sample],
+    sample_directory: &Path,
+    input: &str,
+) -> usize {
+    let mut mismatches = 0;
+    let mut columns = Vec::new();
+
+    for sample in samples.iter().filter(|x| x.day == registration.day) {
+        let file = sample_directory.join(&sample.file);
+        let cells = match solve(registration, &file.to_string_lossy()) {
+            Outcome::Solved(answers) => {
+                let mut cells = Vec::new();
+                for (answer, expected) in answers.iter().zip(sample.answers.iter()) {
+                    cells.push(match expected {
+                        Some(expected) if expected == answer => format!("{} (ok)", answer),
+                        Some(expected) => {
+                            mismatches += 1;
+                            format!("{} (expected {})", answer, expected)
+                        }
+                        None => answer.clone(),
+                    });
+                }
+                cells
+            }
+            Outcome::Failed(error) => {
+                mismatches += 1;
+                vec![format!("error: {}", error); 2]
+            }
+        };
+        columns.push((sample.file.clone(), cells));
+    }
+
+    if columns.is_empty() {
+        columns.push((String::from("samples"), vec![String::from("none"); 2]));
+    }
+
+    let real = if Path::new(input).exists() {
+        match solve(registration, input) {
+            Outcome::Solved(answers) => answers.to_vec(),
+            Outcome::Failed(error) => vec![format!("error: {}", error); 2],
+        }
+    } else {
+        vec![String::from("missing"); 2]
+    };
+    columns.push((String::from("input"), real));
+
+    let title = format!("{} day {:02}", registration.year, registration.day);
+    let widths: Vec<usize> = columns
+        .iter()
+        .map(|(header, cells)| {
+            cells
+                .iter()
+                .chain([header])
+                .map(|x| x.len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut line = format!("{:<12}", title);
+    for ((header, _), width) in columns.iter().zip(widths.iter()) {
+        line += &format!("  {:<width$}", header, width = width);
+    }
+    println!("{}", line.trim_end());
+
+    for part in 0..2 {
+        let mut line = format!("{:<12}", format!("  part {}", part + 1));
+        for ((_, cells), width) in columns.iter().zip(widths.iter()) {
+            line += &format!("  {:<width$}", cells[part], width = width);
+        }
+        println!("{}", line.trim_end());
+    }
+
+    mismatches
+}
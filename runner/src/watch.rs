@@ -0,0 +1,126 @@
+//! A tight loop for solving new puzzles: watches the sources and input of a single day, and whenever they
+//! change, rebuilds the runner, reruns the day and compares its answers and timings with the previous run.
+
+use crate::{
+    json::{self, DayResult},
+    report::format_duration,
+};
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, SystemTime},
+};
+
+/// How often the watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The names of the phases of a run, in the order of [`DayResult::times`].
+const PHASES: [&str; 3] = ["parse", "part 1", "part 2"];
+
+/// Collects the provided files, and all files below the provided directories.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    match std::fs::read_dir(path) {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                collect_files(&entry.path(), files);
+            }
+        }
+        Err(_) => files.push(path.to_path_buf()),
+    }
+}
+
+/// Gets the modification time of every watched file, or [`None`] for files that do not exist.
+fn modification_times(watched: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut files = Vec::new();
+    for path in watched {
+        collect_files(path, &mut files);
+    }
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|file| {
+            let modified = std::fs::metadata(&file).and_then(|x| x.modified()).ok();
+            (file, modified)
+        })
+        .collect()
+}
+
+/// Builds the runner in release mode and runs the provided day, or returns [`None`] if building or running
+/// failed. Compiler errors and panics go straight to the standard error stream.
+fn run_day(root: &Path, inputs: &Path, year: u16, day: u8) -> std::io::Result<Option<DayResult>> {
+    let output = Command::new(std::env::var("CARGO").unwrap_or_else(|_| String::from("cargo")))
+        .args(["run", "--quiet", "--release", "--manifest-path"])
+        .arg(root.join("Cargo.toml"))
+        .args(["-p", "aoc-runner", "--", "--format", "json", "--inputs"])
+        .arg(inputs)
+        .args(["--year", &year.to_string(), &day.to_string()])
+        .stderr(Stdio::inherit())
+        .output()?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let results = json::parse_results(&String::from_utf8_lossy(&output.stdout))
+        .map_err(|x| std::io::Error::new(std::io::ErrorKind::InvalidData, x))?;
+    Ok(results.into_iter().next())
+}
+
+/// Prints the answers and timings of a run, along with the changes since the previous run.
+fn print_run(result: &DayResult, previous: Option<&DayResult>) {
+    let times = result.times.unwrap_or_default();
+    let previous_times = previous.and_then(|x| x.times);
+
+    for (phase, name) in PHASES.iter().enumerate() {
+        let mut line = format!("  {:<7}", format!("{}:", name));
+        if phase > 0 {
+            let answer = &result.answers[phase - 1];
+            line += &format!(" {}", answer);
+            match previous.map(|x| &x.answers[phase - 1]) {
+                Some(old) if old != answer => line += &format!(" (was {})", old),
+                _ => {}
+            }
+            line += ",";
+        }
+
+        line += &format!(" {}", format_duration(times[phase]));
+        if let Some(old) = previous_times.map(|x| x[phase]) {
+            let change = (times[phase].as_secs_f64() / old.as_secs_f64() - 1.0) * 100.0;
+            line += &format!(" (was {}, {:+.1}%)", format_duration(old), change);
+        }
+
+        println!("{}", line);
+    }
+}
+
+/// Watches the crate directory of the provided day and its input file, and reruns the day every time any of
+/// them changes. `root` is the root of the workspace, and `inputs` the directory that is passed to the runner
+/// as `--inputs`. Only returns on errors.
+pub fn watch(root: &Path, inputs: &Path, input: &Path, year: u16, day: u8) -> std::io::Result<()> {
+    let directory = root.join(format!("{}/day{:02}", year, day));
+    let watched = [
+        directory.join("src"),
+        directory.join("Cargo.toml"),
+        input.to_path_buf(),
+    ];
+
+    let mut previous = None;
+    loop {
+        let times = modification_times(&watched);
+
+        println!("Running {} day {:02}...", year, day);
+        match run_day(root, inputs, year, day)? {
+            Some(result) => {
+                print_run(&result, previous.as_ref());
+                previous = Some(result);
+            }
+            None => println!("The run failed."),
+        }
+
+        println!("Waiting for changes in {}...", directory.display());
+        while modification_times(&watched) == times {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}